@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use everscale_types::cell::*;
 use everscale_types::dict::*;
@@ -64,5 +66,220 @@ fn build_dict_group(c: &mut Criterion) {
     ];
 }
 
+fn extend_dict_impl<K, V>(name: String, num_elements: usize, num_updates: usize, c: &mut Criterion)
+where
+    Standard: Distribution<K> + Distribution<V>,
+    K: Store + DictKey + Clone,
+    V: Store + Clone,
+{
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([0u8; 16]);
+
+    let mut dict = Dict::<K, V>::new();
+    for _ in 0..num_elements {
+        dict.set(rng.gen::<K>(), rng.gen::<V>()).unwrap();
+    }
+
+    let updates = (0..num_updates)
+        .map(|_| (rng.gen::<K>(), rng.gen::<V>()))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group(name);
+    group.bench_with_input("set", &updates, |b, updates| {
+        b.iter(|| {
+            let mut result = dict.clone();
+            for (key, value) in updates {
+                result.set(key, value).unwrap();
+            }
+            black_box(result);
+        });
+    });
+    group.bench_with_input("extend", &updates, |b, updates| {
+        b.iter(|| {
+            let mut result = dict.clone();
+            result.extend(updates.iter().cloned()).unwrap();
+            black_box(result);
+        });
+    });
+    group.finish();
+}
+
+fn extend_dict_group(c: &mut Criterion) {
+    macro_rules! decl_dict_benches {
+        ($({ $n:literal, $u:literal, $k:ty, $v:ident }),*$(,)?) => {
+            $({
+                let name = format!(
+                    "extend_dict/size={}; updates={}; key={}; value={}",
+                    $n, $u, stringify!($k), stringify!($v)
+                );
+                extend_dict_impl::<$k, $v>(name, $n, $u, c);
+            });*
+        };
+    }
+
+    decl_dict_benches![
+        { 1000, 100, u32, u64 },
+        { 100000, 1000, u32, u64 },
+        { 100000, 1000, u64, u64 },
+    ];
+}
+
+fn diff_dict_impl<K, V>(name: String, num_elements: usize, num_changes: usize, c: &mut Criterion)
+where
+    Standard: Distribution<K> + Distribution<V>,
+    K: Store + DictKey + Ord,
+    for<'a> V: Store + Load<'a> + PartialEq,
+{
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([0u8; 16]);
+
+    let mut left = Dict::<K, V>::new();
+    for _ in 0..num_elements {
+        left.set(rng.gen::<K>(), rng.gen::<V>()).unwrap();
+    }
+
+    let mut right = left.clone();
+    for _ in 0..num_changes {
+        right.set(rng.gen::<K>(), rng.gen::<V>()).unwrap();
+    }
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("iter", |b| {
+        b.iter(|| {
+            let left = left.iter().collect::<Result<BTreeMap<K, V>, _>>().unwrap();
+            let right = right.iter().collect::<Result<BTreeMap<K, V>, _>>().unwrap();
+
+            let mut changes = 0usize;
+            for (key, value) in &left {
+                changes += (right.get(key) != Some(value)) as usize;
+            }
+            for key in right.keys() {
+                changes += !left.contains_key(key) as usize;
+            }
+            black_box(changes);
+        });
+    });
+    group.bench_function("diff", |b| {
+        b.iter(|| {
+            let changes = left.diff(&right).unwrap().count();
+            black_box(changes);
+        });
+    });
+    group.finish();
+}
+
+fn diff_dict_group(c: &mut Criterion) {
+    macro_rules! decl_dict_benches {
+        ($({ $n:literal, $u:literal, $k:ty, $v:ident }),*$(,)?) => {
+            $({
+                let name = format!(
+                    "diff_dict/size={}; changes={}; key={}; value={}",
+                    $n, $u, stringify!($k), stringify!($v)
+                );
+                diff_dict_impl::<$k, $v>(name, $n, $u, c);
+            });*
+        };
+    }
+
+    decl_dict_benches![
+        { 10000, 100, u32, u64 },
+        { 100000, 1000, u64, u64 },
+    ];
+}
+
+fn iter_dict_impl<K, V>(name: String, num_elements: usize, c: &mut Criterion)
+where
+    Standard: Distribution<K> + Distribution<V>,
+    K: Store + DictKey,
+    for<'a> V: Store + Load<'a>,
+{
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([0u8; 16]);
+
+    let mut dict = Dict::<K, V>::new();
+    for _ in 0..num_elements {
+        dict.set(rng.gen::<K>(), rng.gen::<V>()).unwrap();
+    }
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("iter", |b| {
+        b.iter(|| {
+            for entry in dict.iter() {
+                black_box(entry.unwrap());
+            }
+        });
+    });
+    group.bench_function("values", |b| {
+        b.iter(|| {
+            for value in dict.values() {
+                black_box(value.unwrap());
+            }
+        });
+    });
+    group.bench_function("raw_values", |b| {
+        b.iter(|| {
+            for value in dict.raw_values() {
+                black_box(value.unwrap());
+            }
+        });
+    });
+    group.finish();
+}
+
+fn iter_dict_group(c: &mut Criterion) {
+    macro_rules! decl_dict_benches {
+        ($({ $n:literal, $k:ty, $v:ident }),*$(,)?) => {
+            $({
+                let name = format!(
+                    "iter_dict/size={}; key={}; value={}",
+                    $n, stringify!($k), stringify!($v)
+                );
+                iter_dict_impl::<$k, $v>(name, $n, c);
+            });*
+        };
+    }
+
+    decl_dict_benches![
+        { 10000, u32, u64 },
+        { 10000, (u128, u128), u64 },
+        { 100000, (u128, u128), u64 },
+    ];
+}
+
+fn map_dict_impl(name: String, num_elements: usize, c: &mut Criterion) {
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([0u8; 16]);
+
+    let mut dict = Dict::<u64, u64>::new();
+    for _ in 0..num_elements {
+        dict.set(rng.gen::<u64>(), rng.gen::<u64>()).unwrap();
+    }
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("map_values", |b| {
+        b.iter(|| {
+            let result = dict.map_values(|_, value| Ok((value, 0u32))).unwrap();
+            black_box(result);
+        });
+    });
+    group.bench_function("rebuild", |b| {
+        b.iter(|| {
+            let mut result = Dict::<u64, (u64, u32)>::new();
+            for entry in dict.iter() {
+                let (key, value) = entry.unwrap();
+                result.set(key, (value, 0u32)).unwrap();
+            }
+            black_box(result);
+        });
+    });
+    group.finish();
+}
+
+fn map_dict_group(c: &mut Criterion) {
+    for n in [10000, 100000] {
+        map_dict_impl(format!("map_dict/size={n}; key=u64; value=u64"), n, c);
+    }
+}
+
 criterion_group!(build_dict, build_dict_group);
-criterion_main!(build_dict);
+criterion_group!(extend_dict, extend_dict_group);
+criterion_group!(diff_dict, diff_dict_group);
+criterion_group!(iter_dict, iter_dict_group);
+criterion_group!(map_dict, map_dict_group);
+criterion_main!(build_dict, extend_dict, diff_dict, iter_dict, map_dict);
@@ -0,0 +1,71 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use everscale_types::cell::*;
+use everscale_types::dict::*;
+use rand::distributions::{Distribution, Standard};
+use rand::{Rng, SeedableRng};
+
+fn build_dict_par_impl<K, V>(name: &str, num_elements: usize, c: &mut Criterion)
+where
+    Standard: Distribution<K> + Distribution<V>,
+    K: Store + DictKey + Clone + Send + Sync,
+    V: Store + Clone + Send + Sync,
+{
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([0u8; 16]);
+
+    let values = (0..num_elements)
+        .map(|_| (rng.gen::<K>(), rng.gen::<V>()))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group(name);
+
+    group.bench_with_input(
+        BenchmarkId::new("sequential", num_elements),
+        &values,
+        |b, values| {
+            b.iter(|| {
+                let mut result = Dict::<K, V>::new();
+                for (key, value) in values {
+                    result.set(key, value).unwrap();
+                }
+                black_box(result);
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("parallel", num_elements),
+        &values,
+        |b, values| {
+            b.iter(|| {
+                let result = Dict::<K, V>::from_par_iter(values.clone()).unwrap();
+                black_box(result);
+            });
+        },
+    );
+
+    group.finish();
+}
+
+fn build_dict_par_group(c: &mut Criterion) {
+    macro_rules! decl_dict_benches {
+        ($({ $n:literal, $k:ty, $v:ident }),*$(,)?) => {
+            $({
+                let name = format!(
+                    "build_dict_par({},{})",
+                    stringify!($k), stringify!($v)
+                );
+                build_dict_par_impl::<$k, $v>(&name, $n, c);
+            });*
+        };
+    }
+
+    decl_dict_benches![
+        { 10000, u32, u64 },
+        { 100000, u32, u64 },
+        { 1000000, u32, u64 },
+        { 100000, u64, u64 },
+    ];
+}
+
+criterion_group!(build_dict_par, build_dict_par_group);
+criterion_main!(build_dict_par);
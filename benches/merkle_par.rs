@@ -0,0 +1,72 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use everscale_types::cell::*;
+use everscale_types::merkle::*;
+use rand::{Rng, SeedableRng};
+
+/// Builds a binary tree of unique cells with the specified depth.
+fn build_tree(depth: u8, next_id: &mut u32) -> Cell {
+    let mut builder = CellBuilder::new();
+    builder.store_u32(*next_id).unwrap();
+    *next_id += 1;
+
+    if depth > 0 {
+        for _ in 0..2 {
+            let child = build_tree(depth - 1, next_id);
+            builder.store_reference(child).unwrap();
+        }
+    }
+
+    builder.build().unwrap()
+}
+
+/// Includes paths to the random subset of leaves.
+fn collect_filter<R: Rng>(
+    cell: &DynCell,
+    ratio: f64,
+    rng: &mut R,
+    filter: &mut ahash::HashSet<HashBytes>,
+) -> bool {
+    let mut included = cell.reference_count() == 0 && rng.gen_bool(ratio);
+    for child in cell.references() {
+        included |= collect_filter(child, ratio, rng, filter);
+    }
+    if included {
+        filter.insert(*cell.repr_hash());
+    }
+    included
+}
+
+fn build_proof_par(c: &mut Criterion) {
+    // 2^20 - 1 cells
+    let root = build_tree(19, &mut 0);
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([0u8; 16]);
+    let mut filter = ahash::HashSet::default();
+    collect_filter(root.as_ref(), 0.1, &mut rng, &mut filter);
+
+    let mut group = c.benchmark_group("merkle_proof_1m_cells");
+    group.sample_size(10);
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            let proof = MerkleProof::create(root.as_ref(), &filter)
+                .build_raw()
+                .unwrap();
+            black_box(proof);
+        })
+    });
+
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            let proof = MerkleProof::create(root.as_ref(), &filter)
+                .build_raw_par()
+                .unwrap();
+            black_box(proof);
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(merkle_par, build_proof_par);
+criterion_main!(merkle_par);
@@ -0,0 +1,87 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use everscale_types::merkle::MerkleUpdate;
+use everscale_types::prelude::*;
+
+const CELL_COUNT: u32 = 1_000_000;
+
+fn make_cells() -> Vec<Cell> {
+    (0..CELL_COUNT)
+        .map(|i| {
+            let make_child = |value: u32| {
+                let mut builder = CellBuilder::new();
+                builder.store_u32(value).unwrap();
+                builder.build().unwrap()
+            };
+
+            let mut builder = CellBuilder::new();
+            builder.store_u32(i).unwrap();
+            builder.store_reference(make_child(i * 2)).unwrap();
+            builder.store_reference(make_child(i * 2 + 1)).unwrap();
+            builder.build().unwrap()
+        })
+        .collect()
+}
+
+fn load_refs(c: &mut Criterion) {
+    let cells = make_cells();
+
+    let mut group = c.benchmark_group("load_refs_1m_cells");
+    group.sample_size(10);
+
+    group.bench_function("separately", |b| {
+        b.iter(|| {
+            for cell in &cells {
+                let mut slice = cell.as_slice().unwrap();
+                let left = slice.get_reference_cloned(0).unwrap();
+                let right = slice.get_reference_cloned(1).unwrap();
+                assert!(slice.try_advance(32, 2));
+                black_box((left, right));
+            }
+        })
+    });
+
+    group.bench_function("at once", |b| {
+        b.iter(|| {
+            for cell in &cells {
+                let mut slice = cell.as_slice().unwrap();
+                slice.advance(32, 0).unwrap();
+                let [left, right] = slice.load_references().unwrap();
+                black_box((left, right));
+            }
+        })
+    });
+}
+
+fn load_merkle_updates(c: &mut Criterion) {
+    let updates = make_cells()
+        .into_iter()
+        .map(|cell| {
+            let old = cell.reference_cloned(0).unwrap();
+            let new = cell.reference_cloned(1).unwrap();
+            let update = MerkleUpdate {
+                old_hash: *old.repr_hash(),
+                new_hash: *new.repr_hash(),
+                old_depth: old.repr_depth(),
+                new_depth: new.repr_depth(),
+                old,
+                new,
+            };
+            CellBuilder::build_from(update).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("load_merkle_updates_1m_cells");
+    group.sample_size(10);
+
+    group.bench_function("load", |b| {
+        b.iter(|| {
+            for cell in &updates {
+                let update = cell.parse::<MerkleUpdate>().unwrap();
+                black_box(update);
+            }
+        })
+    });
+}
+
+criterion_group!(slice_refs, load_refs, load_merkle_updates);
+criterion_main!(slice_refs);
@@ -0,0 +1,300 @@
+//! An example of a custom cell backend.
+//!
+//! Cells are stored in a single flat byte buffer (which could as well
+//! be a memory-mapped file) and are exposed as [`DynCell`] without
+//! copying them into the default cell implementation.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+#[cfg(feature = "stats")]
+use everscale_types::cell::CellTreeStats;
+use everscale_types::cell::{CellDescriptor, VirtualCellWrapper};
+use everscale_types::merkle::MerkleProof;
+use everscale_types::prelude::*;
+
+/// A buffer with serialized cells.
+///
+/// Each cell is stored as:
+/// - `d1` and `d2` descriptor bytes;
+/// - bit length as `u16` (BE);
+/// - `level + 1` pairs of a hash (32 bytes) and a depth as `u16` (BE),
+///   one for each level present in the level mask;
+/// - padded cell data;
+/// - absolute offsets of child cells as `u32` (BE).
+///
+/// Children are always written before their parents.
+pub struct FlatBuffer {
+    bytes: Arc<[u8]>,
+    root_offset: usize,
+}
+
+impl FlatBuffer {
+    /// Serializes the cell tree into a flat buffer.
+    pub fn serialize(root: &DynCell) -> Self {
+        fn write_cell(
+            cell: &DynCell,
+            bytes: &mut Vec<u8>,
+            offsets: &mut HashMap<HashBytes, u32>,
+        ) -> u32 {
+            if let Some(offset) = offsets.get(cell.repr_hash()) {
+                return *offset;
+            }
+
+            let mut children = Vec::with_capacity(cell.reference_count() as usize);
+            for child in cell.references() {
+                children.push(write_cell(child, bytes, offsets));
+            }
+
+            let offset = bytes.len() as u32;
+
+            let descriptor = cell.descriptor();
+            bytes.push(descriptor.d1);
+            bytes.push(descriptor.d2);
+            bytes.extend_from_slice(&cell.bit_len().to_be_bytes());
+
+            let level_mask = descriptor.level_mask();
+            let mut hashes = [(&HashBytes::ZERO, 0u16); 4];
+            for level in 0..=3 {
+                let index = level_mask.hash_index(level) as usize;
+                hashes[index] = (cell.hash(level), cell.depth(level));
+            }
+            for (hash, depth) in &hashes[..=level_mask.level() as usize] {
+                bytes.extend_from_slice(hash.as_slice());
+                bytes.extend_from_slice(&depth.to_be_bytes());
+            }
+
+            bytes.extend_from_slice(cell.data());
+            for child in children {
+                bytes.extend_from_slice(&child.to_be_bytes());
+            }
+
+            offsets.insert(*cell.repr_hash(), offset);
+            offset
+        }
+
+        let mut bytes = Vec::new();
+        let root_offset = write_cell(root, &mut bytes, &mut HashMap::new());
+
+        Self {
+            bytes: bytes.into(),
+            root_offset: root_offset as usize,
+        }
+    }
+
+    /// Returns the root cell backed by this buffer.
+    pub fn root(&self) -> Cell {
+        FlatCell::new_cell(self.bytes.clone(), self.root_offset)
+    }
+}
+
+/// A cell which is stored in the [`FlatBuffer`].
+struct FlatCell {
+    bytes: Arc<[u8]>,
+    offset: usize,
+    children: [OnceLock<Cell>; 4],
+}
+
+impl FlatCell {
+    const HEADER_LEN: usize = 4;
+    const LEVEL_DESCR_LEN: usize = 32 + 2;
+
+    fn new_cell(bytes: Arc<[u8]>, offset: usize) -> Cell {
+        Cell::from_impl(Arc::new(Self {
+            bytes,
+            offset,
+            children: Default::default(),
+        }))
+    }
+
+    fn level_descr_offset(&self, level: u8) -> usize {
+        let index = self.descriptor().level_mask().hash_index(level) as usize;
+        self.offset + Self::HEADER_LEN + index * Self::LEVEL_DESCR_LEN
+    }
+
+    fn data_offset(&self) -> usize {
+        let hash_count = self.descriptor().level_mask().level() as usize + 1;
+        self.offset + Self::HEADER_LEN + hash_count * Self::LEVEL_DESCR_LEN
+    }
+
+    fn child(&self, index: u8) -> Option<&Cell> {
+        if index >= self.descriptor().reference_count() {
+            return None;
+        }
+
+        Some(self.children[index as usize].get_or_init(|| {
+            let offset =
+                self.data_offset() + self.descriptor().byte_len() as usize + index as usize * 4;
+            let child_offset =
+                u32::from_be_bytes(self.bytes[offset..offset + 4].try_into().unwrap());
+            FlatCell::new_cell(self.bytes.clone(), child_offset as usize)
+        }))
+    }
+}
+
+impl CellImpl for FlatCell {
+    fn descriptor(&self) -> CellDescriptor {
+        CellDescriptor::new([self.bytes[self.offset], self.bytes[self.offset + 1]])
+    }
+
+    fn data(&self) -> &[u8] {
+        let offset = self.data_offset();
+        &self.bytes[offset..offset + self.descriptor().byte_len() as usize]
+    }
+
+    fn bit_len(&self) -> u16 {
+        u16::from_be_bytes([self.bytes[self.offset + 2], self.bytes[self.offset + 3]])
+    }
+
+    fn reference(&self, index: u8) -> Option<&DynCell> {
+        Some(self.child(index)?.as_ref())
+    }
+
+    fn reference_cloned(&self, index: u8) -> Option<Cell> {
+        self.child(index).cloned()
+    }
+
+    fn virtualize(&self) -> &DynCell {
+        if self.descriptor().level_mask().is_empty() {
+            self
+        } else {
+            VirtualCellWrapper::wrap(self)
+        }
+    }
+
+    fn hash(&self, level: u8) -> &HashBytes {
+        let offset = self.level_descr_offset(level);
+        HashBytes::wrap(self.bytes[offset..offset + 32].try_into().unwrap())
+    }
+
+    fn depth(&self, level: u8) -> u16 {
+        let offset = self.level_descr_offset(level) + 32;
+        u16::from_be_bytes([self.bytes[offset], self.bytes[offset + 1]])
+    }
+
+    // Children are lazily loaded from the shared buffer,
+    // so there is no need for the deep drop.
+
+    fn take_first_child(&mut self) -> Option<Cell> {
+        None
+    }
+
+    fn replace_first_child(&mut self, parent: Cell) -> Result<Cell, Cell> {
+        Err(parent)
+    }
+
+    fn take_next_child(&mut self) -> Option<Cell> {
+        None
+    }
+
+    #[cfg(feature = "stats")]
+    fn stats(&self) -> CellTreeStats {
+        let mut stats = CellTreeStats {
+            bit_count: self.bit_len() as u64,
+            cell_count: 1,
+        };
+        for i in 0..self.descriptor().reference_count() {
+            if let Some(child) = self.reference(i) {
+                stats += child.stats();
+            }
+        }
+        stats
+    }
+}
+
+fn build_dict(len: u32) -> anyhow::Result<Dict<u32, u64>> {
+    let mut dict = Dict::new();
+    for i in 0..len {
+        dict.set(i * 3, i as u64 * 1000)?;
+    }
+    Ok(dict)
+}
+
+fn prove_key(root: &Cell, key: u32) -> anyhow::Result<(Option<u64>, MerkleProof)> {
+    let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+    let tracked = usage_tree.track(root);
+
+    let dict = Dict::<u32, u64>::from_raw(Some(tracked));
+    let value = dict.get(key)?;
+
+    let proof = MerkleProof::create(root.as_ref(), usage_tree).build()?;
+    Ok((value, proof))
+}
+
+fn main() -> anyhow::Result<()> {
+    let dict = build_dict(1000)?;
+    let original = dict.root().clone().unwrap();
+
+    let buffer = FlatBuffer::serialize(original.as_ref());
+    let root = buffer.root();
+    assert_eq!(root.repr_hash(), original.repr_hash());
+
+    let (value, proof) = prove_key(&root, 300)?;
+    println!("value: {value:?}");
+    println!("proof: {}", CellBuilder::build_from(proof)?.repr_hash());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::cell::CellType;
+
+    use super::*;
+
+    #[test]
+    fn flat_cells_are_transparent() -> anyhow::Result<()> {
+        let dict = build_dict(1000)?;
+        let original = dict.root().clone().unwrap();
+
+        let root = FlatBuffer::serialize(original.as_ref()).root();
+        assert_eq!(root.repr_hash(), original.repr_hash());
+        assert_eq!(root.repr_depth(), original.repr_depth());
+        assert_eq!(Boc::encode(&root), Boc::encode(&original));
+
+        let flat = Dict::<u32, u64>::from_raw(Some(root));
+        assert_eq!(flat.len()?, 1000);
+        for entry in flat.iter().zip(dict.iter()) {
+            let (flat, original) = entry;
+            assert_eq!(flat?, original?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn merkle_proofs_over_flat_cells() -> anyhow::Result<()> {
+        let original = build_dict(1000)?.root().clone().unwrap();
+        let root = FlatBuffer::serialize(original.as_ref()).root();
+
+        for key in [0, 1, 300, 2997, 5000] {
+            let (flat_value, flat_proof) = prove_key(&root, key)?;
+            let (value, proof) = prove_key(&original, key)?;
+            assert_eq!(flat_value, value);
+            assert_eq!(flat_proof, proof);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn flat_cells_with_levels() -> anyhow::Result<()> {
+        let original = build_dict(1000)?.root().clone().unwrap();
+        let (value, proof) = prove_key(&original, 300)?;
+        let proof = CellBuilder::build_from(proof)?;
+
+        // Merkle proof contains pruned branches and cells with non-zero levels
+        let flat_proof = FlatBuffer::serialize(proof.as_ref()).root();
+        assert_eq!(flat_proof.cell_type(), CellType::MerkleProof);
+        assert_eq!(Boc::encode(&flat_proof), Boc::encode(&proof));
+
+        let flat_proof = flat_proof.parse::<MerkleProof>()?;
+        assert_eq!(flat_proof.hash, *original.repr_hash());
+
+        let pruned = Dict::<u32, u64>::from_raw(Some(Cell::virtualize(flat_proof.cell)));
+        assert_eq!(pruned.get(300)?, value);
+        assert!(pruned.get(0).is_err());
+
+        Ok(())
+    }
+}
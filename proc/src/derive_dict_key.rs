@@ -0,0 +1,104 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::internals::{ast, attr, ctxt};
+use crate::{bound, Derive};
+
+pub fn impl_derive(input: syn::DeriveInput) -> Result<TokenStream, Vec<syn::Error>> {
+    let cx = ctxt::Ctxt::new();
+    let container = match ast::Container::from_ast(&cx, &input, Derive::Debug) {
+        Some(container) => container,
+        None => return Err(cx.check().unwrap_err()),
+    };
+
+    let fields = match &container.data {
+        ast::Data::Struct(_, fields) => fields,
+        ast::Data::Enum(_) => {
+            cx.error_spanned_by(&input, "DictKey can only be derived for structs");
+            return Err(cx.check().unwrap_err());
+        }
+    };
+    cx.check()?;
+
+    let ident = &container.ident;
+    let generics = bound::without_default(container.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let tag_bits = container
+        .attrs
+        .tlb_tag
+        .map(|tag| tag.bits)
+        .unwrap_or_default() as u16;
+    let check_tag = container.attrs.tlb_tag.and_then(check_tag_op);
+
+    let field_bits = fields.iter().map(|field| {
+        let ty = field.ty;
+        match field.attrs.dict_key_bits {
+            Some(bits) => quote!(#bits),
+            None => quote!(<#ty as ::everscale_types::dict::DictKey>::BITS),
+        }
+    });
+
+    let load_fields = fields.iter().enumerate().map(|(i, field)| {
+        let var = format_ident!("__field{}", i);
+        let ty = field.ty;
+        let ty_bits = quote!(<#ty as ::everscale_types::dict::DictKey>::BITS);
+
+        match field.attrs.dict_key_bits {
+            Some(bits) => quote! {
+                let #var = <#ty as ::everscale_types::dict::DictKey>::from_raw_data(
+                    &::everscale_types::dict::extract_raw_key_bits(
+                        __raw_data, __offset, #bits, #ty_bits,
+                    ),
+                )?;
+                __offset += #bits;
+            },
+            None => quote! {
+                let #var = <#ty as ::everscale_types::dict::DictKey>::from_raw_data(
+                    &::everscale_types::dict::shift_raw_data(__raw_data, __offset),
+                )?;
+                __offset += #ty_bits;
+            },
+        }
+    });
+
+    let members = fields.iter().enumerate().map(|(i, field)| {
+        let member = &field.member;
+        let var = format_ident!("__field{}", i);
+        quote!(#member: #var)
+    });
+
+    let result = quote! {
+        #[automatically_derived]
+        impl #impl_generics ::everscale_types::dict::DictKey for #ident #ty_generics #where_clause {
+            const BITS: u16 = #tag_bits #(+ #field_bits)*;
+
+            #[allow(unused_assignments, unused_mut, unused_variables)]
+            fn from_raw_data(__raw_data: &[u8; 128]) -> ::core::option::Option<Self> {
+                let mut __offset: u16 = #tag_bits;
+                #check_tag
+                #(#load_fields)*
+                ::core::option::Option::Some(Self { #(#members),* })
+            }
+        }
+    };
+
+    Ok(result)
+}
+
+fn check_tag_op(tag: attr::TlbTag) -> Option<TokenStream> {
+    let bits = tag.bits as u16;
+    if bits == 0 {
+        return None;
+    }
+
+    let value = tag.value;
+    Some(quote! {
+        let __tag = <u32 as ::everscale_types::dict::DictKey>::from_raw_data(
+            &::everscale_types::dict::extract_raw_key_bits(__raw_data, 0, #bits, 32),
+        )?;
+        if __tag != #value {
+            return ::core::option::Option::None;
+        }
+    })
+}
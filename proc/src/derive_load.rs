@@ -74,7 +74,10 @@ fn build_struct(
 
     let members = fields.iter().map(|field| {
         let ident = &field.member;
-        let op = load_op(lifetime_def, field.ty);
+        let op = match field.attrs.dict_key_bits {
+            Some(bits) => load_narrowed_op(field.ty, bits),
+            None => load_op(lifetime_def, field.ty),
+        };
         quote! {
             #ident: #op
         }
@@ -141,12 +144,21 @@ fn load_tag_op(tag: attr::TlbTag) -> Option<TokenStream> {
     Some(quote! {
         match #op {
             ::core::result::Result::Ok(#value) => {},
-            ::core::result::Result::Ok(_) => return ::core::result::Result::Err(::everscale_types::error::Error::InvalidTag),
+            ::core::result::Result::Ok(tag) => return ::core::result::Result::Err(
+                ::everscale_types::error::Error::InvalidTag(::core::convert::From::from(tag))
+            ),
             ::core::result::Result::Err(e) => return ::core::result::Result::Err(e),
         }
     })
 }
 
+fn load_narrowed_op(ty: &syn::Type, bits: u16) -> TokenStream {
+    quote!(match __slice.load_uint(#bits) {
+        ::core::result::Result::Ok(val) => val as #ty,
+        ::core::result::Result::Err(err) => return ::core::result::Result::Err(err),
+    })
+}
+
 fn load_op(lifetime_def: &syn::LifetimeParam, ty: &syn::Type) -> TokenStream {
     #[allow(clippy::unnecessary_operation)]
     'fallback: {
@@ -57,7 +57,10 @@ fn build_struct(
     let members = fields.iter().enumerate().map(|(i, field)| {
         let ident = &field.member;
         let field_ident = quote!(self.#ident);
-        let op = store_op(&field_ident, field.ty);
+        let op = match field.attrs.dict_key_bits {
+            Some(bits) => store_narrowed_op(&field_ident, bits),
+            None => store_op(&field_ident, field.ty),
+        };
         if i + 1 == fields_len {
             op
         } else {
@@ -154,6 +157,16 @@ fn store_op(field_ident: &TokenStream, ty: &syn::Type) -> TokenStream {
     quote! { <#ty as ::everscale_types::cell::Store>::store_into(&#field_ident, __builder, __context) }
 }
 
+fn store_narrowed_op(field_ident: &TokenStream, bits: u16) -> TokenStream {
+    quote! {{
+        let __value = #field_ident as u64;
+        if __value >> #bits != 0 {
+            return ::core::result::Result::Err(::everscale_types::error::Error::IntOverflow);
+        }
+        __builder.store_uint(__value, #bits)
+    }}
+}
+
 fn into_ok(tokens: TokenStream) -> TokenStream {
     quote!(match #tokens {
         ::core::result::Result::Ok(_) => {},
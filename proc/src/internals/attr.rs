@@ -83,14 +83,24 @@ impl Variant {
     }
 }
 
-pub struct Field;
+pub struct Field {
+    /// Narrowed bit width of an unsigned integer field
+    /// (always less than the full width of the type).
+    pub dict_key_bits: Option<u16>,
+}
 
 impl Field {
     pub fn from_ast(cx: &Ctxt, field: &syn::Field) -> Self {
+        let mut dict_key_bits = Attr::none(cx, BITS);
+
         for attr in &field.attrs {
-            if attr.path() != TLB {
+            let is_dict_key = if attr.path() == TLB {
+                false
+            } else if attr.path() == DICT_KEY {
+                true
+            } else {
                 continue;
-            }
+            };
 
             if let syn::Meta::List(meta) = &attr.meta {
                 if meta.tokens.is_empty() {
@@ -99,17 +109,64 @@ impl Field {
             }
 
             if let Err(e) = attr.parse_nested_meta(|meta| {
+                if is_dict_key && meta.path == BITS {
+                    // Parse `#[dict_key(bits = 12)]`
+                    let lit: syn::LitInt = meta.value()?.parse()?;
+                    dict_key_bits.set(&meta.path, lit.base10_parse::<u16>()?);
+                    return Ok(());
+                }
+
                 let path = meta.path.to_token_stream().to_string().replace(' ', "");
-                Err(meta.error(format_args!("unknown tl field attribute `{}`", path)))
+                Err(meta.error(if is_dict_key {
+                    format!("unknown dict key field attribute `{}`", path)
+                } else {
+                    format!("unknown tl field attribute `{}`", path)
+                }))
             }) {
                 cx.syn_error(e);
             }
         }
 
-        Self
+        let dict_key_bits = dict_key_bits.get().and_then(|bits| {
+            // Only unsigned integers can be narrowed without changing their order
+            let Some(ty_bits) = uint_bit_len(&field.ty) else {
+                cx.error_spanned_by(
+                    &field.ty,
+                    "`dict_key(bits = ..)` is only supported for unsigned integer fields",
+                );
+                return None;
+            };
+            if bits == 0 || bits > ty_bits {
+                cx.error_spanned_by(
+                    &field.ty,
+                    format!(
+                        "`dict_key(bits = {bits})` must be in range 1..={ty_bits} for this field"
+                    ),
+                );
+                return None;
+            }
+            // Full width is the same as no attribute at all
+            (bits < ty_bits).then_some(bits)
+        });
+
+        Self { dict_key_bits }
     }
 }
 
+fn uint_bit_len(ty: &syn::Type) -> Option<u16> {
+    let syn::Type::Path(syn::TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let ident = path.get_ident()?;
+    Some(match ident.to_string().as_str() {
+        "u8" => 8,
+        "u16" => 16,
+        "u32" => 32,
+        "u64" => 64,
+        _ => return None,
+    })
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct TlbTag {
     pub value: u32,
@@ -1,7 +1,9 @@
 pub const TLB: Symbol = Symbol("tlb");
+pub const DICT_KEY: Symbol = Symbol("dict_key");
 
 pub const VALIDATE_WITH: Symbol = Symbol("validate_with");
 pub const TAG: Symbol = Symbol("tag");
+pub const BITS: Symbol = Symbol("bits");
 
 #[derive(Copy, Clone)]
 pub struct Symbol(&'static str);
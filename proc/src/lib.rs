@@ -2,6 +2,7 @@ use proc_macro::TokenStream;
 use quote::quote;
 
 mod bound;
+mod derive_dict_key;
 mod derive_load;
 mod derive_store;
 mod internals;
@@ -29,6 +30,19 @@ pub fn derive_store(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Implements `DictKey` for the type.
+///
+/// Keys are stored as a concatenation of all fields in the declaration order.
+/// Use `#[dict_key(bits = N)]` to specify a custom width of an unsigned
+/// integer field. Derived `Store` and `Load` use the same width.
+#[proc_macro_derive(DictKey, attributes(dict_key))]
+pub fn derive_dict_key(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive_dict_key::impl_derive(input)
+        .unwrap_or_else(to_compile_errors)
+        .into()
+}
+
 fn to_compile_errors(errors: Vec<syn::Error>) -> proc_macro2::TokenStream {
     let compile_errors = errors.iter().map(syn::Error::to_compile_error);
     quote!(#(#compile_errors)*)
@@ -29,7 +29,10 @@ pub trait IgnoreName {
 }
 
 impl<T: IgnoreName> IgnoreName for &'_ T {
-    type Unnamed<'a> = T::Unnamed<'a> where Self: 'a;
+    type Unnamed<'a>
+        = T::Unnamed<'a>
+    where
+        Self: 'a;
 
     #[inline]
     fn ignore_name(&self) -> Self::Unnamed<'_> {
@@ -41,7 +44,10 @@ impl<T> IgnoreName for Vec<T>
 where
     [T]: IgnoreName,
 {
-    type Unnamed<'a> = <[T] as IgnoreName>::Unnamed<'a> where Self: 'a;
+    type Unnamed<'a>
+        = <[T] as IgnoreName>::Unnamed<'a>
+    where
+        Self: 'a;
 
     #[inline]
     fn ignore_name(&self) -> Self::Unnamed<'_> {
@@ -50,7 +56,10 @@ where
 }
 
 impl<T: IgnoreName> IgnoreName for Box<T> {
-    type Unnamed<'a> = T::Unnamed<'a> where Self: 'a;
+    type Unnamed<'a>
+        = T::Unnamed<'a>
+    where
+        Self: 'a;
 
     #[inline]
     fn ignore_name(&self) -> Self::Unnamed<'_> {
@@ -59,7 +68,10 @@ impl<T: IgnoreName> IgnoreName for Box<T> {
 }
 
 impl<T: IgnoreName> IgnoreName for Arc<T> {
-    type Unnamed<'a> = T::Unnamed<'a> where Self: 'a;
+    type Unnamed<'a>
+        = T::Unnamed<'a>
+    where
+        Self: 'a;
 
     #[inline]
     fn ignore_name(&self) -> Self::Unnamed<'_> {
@@ -68,7 +80,10 @@ impl<T: IgnoreName> IgnoreName for Arc<T> {
 }
 
 impl<T: IgnoreName> IgnoreName for Rc<T> {
-    type Unnamed<'a> = T::Unnamed<'a> where Self: 'a;
+    type Unnamed<'a>
+        = T::Unnamed<'a>
+    where
+        Self: 'a;
 
     #[inline]
     fn ignore_name(&self) -> Self::Unnamed<'_> {
@@ -77,7 +92,10 @@ impl<T: IgnoreName> IgnoreName for Rc<T> {
 }
 
 impl<T: IgnoreName> IgnoreName for Option<T> {
-    type Unnamed<'a> = Option<T::Unnamed<'a>> where Self: 'a;
+    type Unnamed<'a>
+        = Option<T::Unnamed<'a>>
+    where
+        Self: 'a;
 
     #[inline]
     fn ignore_name(&self) -> Self::Unnamed<'_> {
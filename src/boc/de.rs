@@ -3,7 +3,9 @@ use std::ops::Deref;
 use smallvec::SmallVec;
 
 use super::BocTag;
-use crate::cell::{Cell, CellContext, CellDescriptor, CellParts, LevelMask, MAX_REF_COUNT};
+use crate::cell::{
+    Cell, CellContext, CellDescriptor, CellFamily, CellParts, HashBytes, LevelMask, MAX_REF_COUNT,
+};
 use crate::util::{read_be_u32_fast, read_be_u64_fast, unlikely, ArrayVec};
 
 #[cfg(feature = "stats")]
@@ -16,6 +18,9 @@ pub struct Options {
     pub min_roots: Option<usize>,
     /// The maximum allowed root count.
     pub max_roots: Option<usize>,
+    /// Whether to decode absent cells into placeholders
+    /// instead of returning an [`Error::AbsentCell`].
+    pub allow_absent: bool,
 }
 
 impl Options {
@@ -24,6 +29,7 @@ impl Options {
         Self {
             min_roots: Some(number),
             max_roots: Some(number),
+            allow_absent: false,
         }
     }
 }
@@ -129,9 +135,6 @@ impl<'a> BocHeader<'a> {
         if unlikely(root_count.saturating_add(absent_count) > cell_count) {
             return Err(Error::TooManyRootCells);
         }
-        if unlikely(absent_count > 0) {
-            return Err(Error::AbsentCellsNotSupported);
-        }
         if let Some(min_roots) = options.min_roots {
             if unlikely(root_count < min_roots) {
                 return Err(Error::TooFewRootCells);
@@ -140,7 +143,7 @@ impl<'a> BocHeader<'a> {
         if unlikely(root_count > options.max_roots.unwrap_or(MAX_ROOTS)) {
             return Err(Error::TooManyRootCells);
         }
-        debug_assert!(absent_count == 0 && (1..=MAX_ROOTS).contains(&root_count));
+        debug_assert!((1..=MAX_ROOTS).contains(&root_count));
 
         // SAFETY: we have already requested at least {ref_size}*3+{offset_size}
         // and {ref_size} is in range 1..=8
@@ -203,9 +206,11 @@ impl<'a> BocHeader<'a> {
         let cells_start_offset = reader.offset;
 
         let mut cells = SmallVec::with_capacity(cell_count);
+        let mut first_absent = None;
+        let mut real_absent_count = 0;
 
         let data_ptr = data.as_ptr();
-        for _ in 0..cell_count {
+        for index in 0..cell_count {
             // SAFETY: there are manual bounds checks for bytes offset
             let start_ptr = unsafe { data_ptr.add(reader.offset) };
             let total_len = ok!(CellParts::read_raw_cell_from_ptr(
@@ -217,9 +222,26 @@ impl<'a> BocHeader<'a> {
 
             // SAFETY: We have already requested {total_len} bytes
             let cell = unsafe { std::slice::from_raw_parts(start_ptr, total_len) };
+            if unlikely(CellDescriptor::new([cell[0], cell[1]]).is_absent()) {
+                first_absent.get_or_insert(index);
+                real_absent_count += 1;
+            }
             cells.push(cell);
         }
 
+        // Check that `absent_count` is correct
+        if unlikely(real_absent_count != absent_count) {
+            return Err(Error::AbsentCountMismatch);
+        }
+        if let Some(index) = first_absent {
+            if !options.allow_absent {
+                return Err(Error::AbsentCell {
+                    index: index as u32,
+                    hash: read_absent_cell(cells[index]).0,
+                });
+            }
+        }
+
         // Check that `total_cells_size` is correct
         #[cfg(not(fuzzing))]
         if (cells_start_offset as u64).saturating_add(total_cells_size) != reader.offset as u64 {
@@ -259,17 +281,9 @@ impl<'a> BocHeader<'a> {
         }
 
         for raw_cell in self.cells().iter().rev() {
-            // SAFETY: it is safe to construct `CellParts` from a `read_raw_cell_from_ptr` output
-            let ctx = unsafe {
-                ok!(CellParts::from_raw_cell(
-                    raw_cell, &res, cell_count, ref_size
-                ))
-            };
-
-            let cell = match context.finalize_cell(ctx) {
-                Ok(cell) => cell,
-                Err(_) => return Err(Error::InvalidCell),
-            };
+            let cell = ok!(finalize_raw_cell(
+                raw_cell, &res, cell_count, ref_size, context
+            ));
             res.push(cell);
         }
 
@@ -292,6 +306,120 @@ impl<'a> BocHeader<'a> {
     }
 }
 
+fn finalize_raw_cell(
+    raw_cell: &[u8],
+    cells: &[Cell],
+    cell_count: u32,
+    ref_size: usize,
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    if unlikely(CellDescriptor::new([raw_cell[0], raw_cell[1]]).is_absent()) {
+        let (hash, depth) = read_absent_cell(raw_cell);
+        return Ok(Cell::new_absent(hash, depth));
+    }
+
+    // SAFETY: it is safe to construct `CellParts` from a `read_raw_cell_from_ptr` output
+    let ctx = unsafe {
+        ok!(CellParts::from_raw_cell(
+            raw_cell, cells, cell_count, ref_size
+        ))
+    };
+
+    match context.finalize_cell(ctx) {
+        Ok(cell) => Ok(cell),
+        Err(_) => Err(Error::InvalidCell),
+    }
+}
+
+/// Resumable BOC decoder.
+///
+/// Assembles the cell tree in steps of the limited size, so that
+/// decoding of large BOCs can be interleaved with other work.
+/// The result is the same as for [`Boc::decode_indexed`].
+///
+/// [`Boc::decode_indexed`]: super::Boc::decode_indexed
+pub struct BocDecoder<'a> {
+    header: BocHeader<'a>,
+    cells: Vec<Cell>,
+}
+
+impl<'a> BocDecoder<'a> {
+    /// Decodes the BOC header and prepares the decoder.
+    pub fn new(data: &'a [u8], options: &Options) -> Result<Self, Error> {
+        let header = ok!(BocHeader::decode(data, options));
+
+        let mut cells = Vec::new();
+        if cells.try_reserve_exact(header.cells.len()).is_err() {
+            return Err(Error::InvalidTotalSize);
+        }
+
+        Ok(Self { header, cells })
+    }
+
+    /// Assembles at most `max_cells` cells using an empty cell context.
+    pub fn step(&mut self, max_cells: usize) -> Result<DecodeProgress, Error> {
+        self.step_ext(max_cells, &mut Cell::empty_context())
+    }
+
+    /// Assembles at most `max_cells` cells using the specified cell context.
+    ///
+    /// Returns the root cells once all cells are assembled.
+    pub fn step_ext(
+        &mut self,
+        max_cells: usize,
+        context: &mut dyn CellContext,
+    ) -> Result<DecodeProgress, Error> {
+        let ref_size = self.header.ref_size;
+        let cells_total = self.header.cells.len();
+        let cell_count = cells_total as u32;
+
+        // NOTE: cells are assembled starting from the last one
+        let remaining = cells_total - self.cells.len();
+        let step_start = remaining - std::cmp::min(max_cells, remaining);
+        for raw_cell in self.header.cells[step_start..remaining].iter().rev() {
+            let cell = ok!(finalize_raw_cell(
+                raw_cell,
+                &self.cells,
+                cell_count,
+                ref_size,
+                context
+            ));
+            self.cells.push(cell);
+        }
+
+        let cells_done = self.cells.len();
+        if cells_done < cells_total {
+            return Ok(DecodeProgress::InProgress {
+                cells_done,
+                cells_total,
+            });
+        }
+
+        let mut roots = Vec::with_capacity(self.header.roots.len());
+        for &index in &self.header.roots {
+            match self.cells.get(cells_total.wrapping_sub(index as usize + 1)) {
+                Some(cell) => roots.push(cell.clone()),
+                None => return Err(Error::RootCellNotFound),
+            }
+        }
+        Ok(DecodeProgress::Done(roots))
+    }
+}
+
+/// Progress of the [`BocDecoder`].
+#[derive(Debug, Clone)]
+pub enum DecodeProgress {
+    /// Some cells are still not assembled.
+    InProgress {
+        /// The number of assembled cells.
+        cells_done: usize,
+        /// The total number of cells in the BOC.
+        cells_total: usize,
+    },
+    /// All cells are assembled. Contains the root cells.
+    Done(Vec<Cell>),
+}
+
 /// Array of processed cells.
 pub struct ProcessedCells(SmallVec<[Cell; CELLS_ON_STACK]>);
 
@@ -300,6 +428,18 @@ impl ProcessedCells {
     pub fn get(&self, index: u32) -> Option<Cell> {
         self.0.get(self.0.len() - index as usize - 1).cloned()
     }
+
+    /// Returns processed cells in the order they are stored in the BOC.
+    pub(crate) fn into_ordered(self) -> Vec<Cell> {
+        let mut cells = self.0.into_vec();
+        cells.reverse();
+        cells
+    }
+
+    #[cfg(feature = "tracing")]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &crate::cell::DynCell> {
+        self.0.iter().map(AsRef::as_ref)
+    }
 }
 
 /// Wrapper around indexed bytes slice access
@@ -487,7 +627,18 @@ impl<'a> CellParts<'a> {
         let descriptor = CellDescriptor::new(unsafe { *(bytes_ptr as *const [u8; 2]) });
 
         if unlikely(descriptor.is_absent()) {
-            return Err(Error::AbsentCellsNotSupported);
+            // 2 bytes - descriptor
+            // 32 bytes - representation hash
+            // 2 bytes - representation depth
+            const ABSENT_CELL_LEN: usize = 2 + 32 + 2;
+
+            if unlikely(descriptor.d2 != CellDescriptor::compute_d2(8 * (32 + 2))) {
+                return Err(Error::InvalidCell);
+            }
+            if unlikely(bytes_len < ABSENT_CELL_LEN) {
+                return Err(Error::UnexpectedEof);
+            }
+            return Ok(ABSENT_CELL_LEN);
         }
 
         // 0b11111111 -> 0b01111111 + 1 = 0b10000000 = byte len 128, max bit len = 1023
@@ -525,6 +676,17 @@ impl<'a> CellParts<'a> {
     }
 }
 
+/// Reads the representation hash and depth of the absent cell.
+///
+/// NOTE: `raw_cell` must be an output of `read_raw_cell_from_ptr` for an absent cell.
+fn read_absent_cell(raw_cell: &[u8]) -> (HashBytes, u16) {
+    debug_assert_eq!(raw_cell.len(), 2 + 32 + 2);
+    let mut hash = HashBytes::ZERO;
+    hash.0.copy_from_slice(&raw_cell[2..34]);
+    let depth = u16::from_be_bytes([raw_cell[34], raw_cell[35]]);
+    (hash, depth)
+}
+
 const CELLS_ON_STACK: usize = 16;
 const ROOTS_ON_STACK: usize = 2;
 
@@ -557,9 +719,17 @@ pub enum Error {
     /// The number of roots in BOC is greater than expected.
     #[error("too many root cells")]
     TooManyRootCells,
-    /// Absent cells are legacy therefore not supported.
-    #[error("absent cells are not supported")]
-    AbsentCellsNotSupported,
+    /// BOC contains an absent cell, but they are not allowed by the options.
+    #[error("absent cell #{index} with hash {hash}")]
+    AbsentCell {
+        /// Index of the first absent cell in the BOC.
+        index: u32,
+        /// Representation hash of the absent cell.
+        hash: HashBytes,
+    },
+    /// The number of absent cells differs from the header.
+    #[error("absent cell count mismatch")]
+    AbsentCountMismatch,
     /// The number of roots in BOC is less than expected.
     #[error("too few root cells")]
     TooFewRootCells,
@@ -584,4 +754,10 @@ pub enum Error {
     /// Crc mismatch.
     #[error("invalid checksum")]
     InvalidChecksum,
+    /// Failed to decompress the data.
+    #[error("invalid compressed data")]
+    InvalidCompressedData,
+    /// Decompressed data is bigger than allowed.
+    #[error("decompressed size limit exceeded")]
+    DecompressedSizeLimit,
 }
@@ -79,6 +79,44 @@ impl OptionBoc {
     }
 }
 
+/// Zstd frame magic number.
+#[cfg(feature = "zstd")]
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Decompresses zstd frames into a buffer of at most `max_size` bytes.
+#[cfg(feature = "zstd")]
+fn decompress_zstd(data: &[u8], max_size: usize) -> Result<Vec<u8>, de::Error> {
+    use std::io::Read;
+
+    // Reject frames with a too large declared size without allocating anything
+    let capacity = match zstd::zstd_safe::get_frame_content_size(data) {
+        Ok(Some(size)) if size > max_size as u64 => return Err(de::Error::DecompressedSizeLimit),
+        Ok(Some(size)) => size as usize,
+        Ok(None) => 0,
+        Err(_) => return Err(de::Error::InvalidCompressedData),
+    };
+
+    let Ok(decoder) = zstd::stream::read::Decoder::with_buffer(data) else {
+        return Err(de::Error::InvalidCompressedData);
+    };
+
+    // NOTE: The declared size can't be trusted, so read at most one byte
+    // more than the limit to detect the overflow.
+    let mut result = Vec::with_capacity(capacity);
+    if decoder
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut result)
+        .is_err()
+    {
+        return Err(de::Error::InvalidCompressedData);
+    }
+
+    if result.len() > max_size {
+        return Err(de::Error::DecompressedSizeLimit);
+    }
+    Ok(result)
+}
+
 /// BOC (Bag Of Cells) helper.
 pub struct Boc;
 
@@ -114,6 +152,46 @@ impl Boc {
         encode_impl(cell.as_ref())
     }
 
+    /// Encodes the specified cell tree as BOC and
+    /// appends it to the `target` buffer.
+    pub fn encode_to<T>(cell: T, target: &mut Vec<u8>)
+    where
+        T: AsRef<DynCell>,
+    {
+        fn encode_to_impl(cell: &DynCell, target: &mut Vec<u8>) {
+            ser::BocHeader::<ahash::RandomState>::new(cell).encode(target);
+        }
+        encode_to_impl(cell.as_ref(), target)
+    }
+
+    /// Encodes the specified cell tree as BOC into the writer.
+    ///
+    /// The BOC is written cell by cell without building the whole byte
+    /// vector, so consider using a buffered writer.
+    /// Use [`BocHeader::encode_to_writer`] to also include a CRC.
+    ///
+    /// [`BocHeader::encode_to_writer`]: ser::BocHeader::encode_to_writer
+    pub fn encode_to_writer<T, W>(cell: T, writer: W) -> std::io::Result<()>
+    where
+        T: AsRef<DynCell>,
+        W: std::io::Write,
+    {
+        ser::BocHeader::<ahash::RandomState>::new(cell.as_ref()).encode_to_writer(writer)
+    }
+
+    /// Computes the size of the specified cell tree encoded as BOC.
+    ///
+    /// NOTE: requires a pass through the whole tree.
+    pub fn encoded_size_hint<T>(cell: T) -> usize
+    where
+        T: AsRef<DynCell>,
+    {
+        fn encoded_size_hint_impl(cell: &DynCell) -> usize {
+            ser::BocHeader::<ahash::RandomState>::new(cell).encoded_size() as usize
+        }
+        encoded_size_hint_impl(cell.as_ref())
+    }
+
     /// Encodes a pair of cell trees as BOC.
     pub fn encode_pair<T1, T2>((cell1, cell2): (T1, T2)) -> Vec<u8>
     where
@@ -172,16 +250,22 @@ impl Boc {
     pub fn decode_ext(data: &[u8], context: &mut dyn CellContext) -> Result<Cell, de::Error> {
         use self::de::*;
 
+        #[cfg(feature = "tracing")]
+        let span = Self::decode_span(data);
+
         let header = ok!(de::BocHeader::decode(
             data,
             &Options {
                 max_roots: Some(1),
                 min_roots: Some(1),
+                allow_absent: false,
             },
         ));
 
         if let Some(&root) = header.roots().first() {
             let cells = ok!(header.finalize(context));
+            #[cfg(feature = "tracing")]
+            crate::util::record_cells(&span, cells.iter());
             if let Some(root) = cells.get(root) {
                 return Ok(root);
             }
@@ -197,17 +281,23 @@ impl Boc {
     ) -> Result<(Cell, Cell), de::Error> {
         use self::de::*;
 
+        #[cfg(feature = "tracing")]
+        let span = Self::decode_span(data);
+
         let header = ok!(de::BocHeader::decode(
             data,
             &Options {
                 max_roots: Some(2),
                 min_roots: Some(2),
+                allow_absent: false,
             },
         ));
 
         let mut roots = header.roots().iter();
         if let (Some(&root1), Some(&root2)) = (roots.next(), roots.next()) {
             let cells = ok!(header.finalize(context));
+            #[cfg(feature = "tracing")]
+            crate::util::record_cells(&span, cells.iter());
             if let (Some(root1), Some(root2)) = (cells.get(root1), cells.get(root2)) {
                 return Ok((root1, root2));
             }
@@ -216,6 +306,105 @@ impl Boc {
         Err(de::Error::RootCellNotFound)
     }
 
+    /// Decodes a BOC with any number of roots using an empty cell context,
+    /// keeping the order of cells.
+    #[inline]
+    pub fn decode_indexed<T>(data: T) -> Result<IndexedBoc, de::Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        fn decode_indexed_impl(data: &[u8]) -> Result<IndexedBoc, de::Error> {
+            Boc::decode_indexed_ext(data, &mut Cell::empty_context())
+        }
+        decode_indexed_impl(data.as_ref())
+    }
+
+    /// Decodes a BOC with any number of roots using the specified cell context,
+    /// keeping the order of cells.
+    pub fn decode_indexed_ext(
+        data: &[u8],
+        context: &mut dyn CellContext,
+    ) -> Result<IndexedBoc, de::Error> {
+        #[cfg(feature = "tracing")]
+        let span = Self::decode_span(data);
+
+        let header = ok!(de::BocHeader::decode(data, &de::Options::default()));
+
+        let cells = ok!(header.finalize(context));
+        #[cfg(feature = "tracing")]
+        crate::util::record_cells(&span, cells.iter());
+
+        let cells = cells.into_ordered();
+        let roots = header.roots().to_vec();
+        if roots.iter().any(|&index| index as usize >= cells.len()) {
+            return Err(de::Error::RootCellNotFound);
+        }
+
+        Ok(IndexedBoc { cells, roots })
+    }
+
+    /// Encodes the specified cell tree as BOC and compresses it with zstd.
+    #[cfg(feature = "zstd")]
+    pub fn encode_zstd<T>(cell: T, level: i32) -> std::io::Result<Vec<u8>>
+    where
+        T: AsRef<DynCell>,
+    {
+        fn encode_zstd_impl(cell: &DynCell, level: i32) -> std::io::Result<Vec<u8>> {
+            zstd::bulk::compress(&Boc::encode(cell), level)
+        }
+        encode_zstd_impl(cell.as_ref(), level)
+    }
+
+    /// Decodes a zstd compressed BOC using an empty cell context.
+    ///
+    /// Decompressed BOC must not exceed `max_decompressed` bytes, otherwise
+    /// [`de::Error::DecompressedSizeLimit`] is returned. The size declared in
+    /// the frame header is checked before decompression, and the output is
+    /// never allowed to grow beyond the limit.
+    ///
+    /// NOTE: all other BOC limits are applied to the decompressed data as usual,
+    /// so the limit only bounds the memory required for the BOC bytes.
+    #[cfg(feature = "zstd")]
+    pub fn decode_zstd<T>(data: T, max_decompressed: usize) -> Result<Cell, de::Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        fn decode_zstd_impl(data: &[u8], max_decompressed: usize) -> Result<Cell, de::Error> {
+            let data = ok!(decompress_zstd(data, max_decompressed));
+            Boc::decode_ext(&data, &mut Cell::empty_context())
+        }
+        decode_zstd_impl(data.as_ref(), max_decompressed)
+    }
+
+    /// Decodes either a plain or a zstd compressed BOC (detected by the magic bytes)
+    /// using an empty cell context.
+    ///
+    /// See [`Boc::decode_zstd`] for the meaning of `max_decompressed`.
+    #[cfg(feature = "zstd")]
+    pub fn decode_auto<T>(data: T, max_decompressed: usize) -> Result<Cell, de::Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        fn decode_auto_impl(data: &[u8], max_decompressed: usize) -> Result<Cell, de::Error> {
+            if data.starts_with(&ZSTD_MAGIC) {
+                Boc::decode_zstd(data, max_decompressed)
+            } else {
+                Boc::decode(data)
+            }
+        }
+        decode_auto_impl(data.as_ref(), max_decompressed)
+    }
+
+    #[cfg(feature = "tracing")]
+    fn decode_span(data: &[u8]) -> tracing::span::EnteredSpan {
+        debug_span!(
+            "boc_decode",
+            bytes = data.len(),
+            cells = tracing::field::Empty,
+            bits = tracing::field::Empty,
+        )
+    }
+
     /// Serializes cell into an encoded BOC (as base64 for human readable serializers).
     #[cfg(feature = "serde")]
     pub fn serialize<S, T>(cell: &T, serializer: S) -> Result<S::Ok, S::Error>
@@ -396,6 +585,91 @@ impl BocRepr {
     }
 }
 
+/// Decoded BOC which keeps the cells in the order they are stored in it.
+///
+/// See [`Boc::decode_indexed`].
+#[derive(Clone)]
+pub struct IndexedBoc {
+    cells: Vec<Cell>,
+    roots: Vec<u32>,
+}
+
+impl IndexedBoc {
+    /// Returns an iterator over the root cells.
+    pub fn roots(&self) -> IndexedBocRoots<'_> {
+        IndexedBocRoots {
+            cells: &self.cells,
+            indices: self.roots.iter(),
+        }
+    }
+
+    /// Returns indices of the root cells.
+    pub fn root_indices(&self) -> &[u32] {
+        &self.roots
+    }
+
+    /// Returns a cell by its index in the BOC.
+    pub fn cell_by_index(&self, index: usize) -> Option<&Cell> {
+        self.cells.get(index)
+    }
+
+    /// Returns an index of the cell with the specified representation hash.
+    ///
+    /// NOTE: performs a linear search through all cells.
+    pub fn index_of(&self, repr_hash: &HashBytes) -> Option<usize> {
+        self.cells
+            .iter()
+            .position(|cell| cell.repr_hash() == repr_hash)
+    }
+
+    /// Returns the number of unique cells in the BOC.
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns all cells in the order they are stored in the BOC.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+}
+
+impl std::fmt::Debug for IndexedBoc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexedBoc")
+            .field("cell_count", &self.cells.len())
+            .field("roots", &self.roots)
+            .finish()
+    }
+}
+
+/// An iterator over the root cells of an [`IndexedBoc`].
+///
+/// This struct is created by the [`roots`] method on [`IndexedBoc`].
+///
+/// [`roots`]: IndexedBoc::roots
+#[derive(Clone)]
+pub struct IndexedBocRoots<'a> {
+    cells: &'a [Cell],
+    indices: std::slice::Iter<'a, u32>,
+}
+
+impl<'a> Iterator for IndexedBocRoots<'a> {
+    type Item = &'a Cell;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = *self.indices.next()?;
+        self.cells.get(index as usize)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for IndexedBocRoots<'_> {}
+
 /// Error type for BOC repr decoding related errors.
 #[derive(Debug, thiserror::Error)]
 pub enum BocReprError {
@@ -474,6 +748,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cell::CellType;
     use crate::util::decode_base64;
 
     #[test]
@@ -500,6 +775,254 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn absent_cells() -> anyhow::Result<()> {
+        let absent_hash = HashBytes([0x11; 32]);
+        let absent_depth = 5u16;
+
+        let mut boc = vec![
+            0xb5, 0xee, 0x9c, 0x72, // tag
+            0x01, 0x01, // flags, offset size
+            0x02, 0x01, 0x01, // cell count, root count, absent count
+            0x28, // total cells size
+            0x00, // root index
+            0x01, 0x02, 0xab, 0x01, // root cell with a reference to the absent cell
+            0x0f, 0x44, // absent cell descriptor
+        ];
+        boc.extend_from_slice(absent_hash.as_slice());
+        boc.extend_from_slice(&absent_depth.to_be_bytes());
+
+        // Absent cells are not allowed by default
+        assert!(matches!(
+            Boc::decode(&boc),
+            Err(de::Error::AbsentCell { index: 1, hash }) if hash == absent_hash
+        ));
+
+        // Decode with placeholders
+        let options = de::Options {
+            allow_absent: true,
+            ..Default::default()
+        };
+        let header = de::BocHeader::decode(&boc, &options)?;
+        let cells = header.finalize(&mut Cell::empty_context())?;
+        let root = cells.get(header.roots()[0]).unwrap();
+
+        let child = root.reference(0).unwrap();
+        assert_eq!(child.cell_type(), CellType::Absent);
+        assert_eq!(child.reference_count(), 0);
+        assert_eq!(child.repr_hash(), &absent_hash);
+        assert_eq!(child.repr_depth(), absent_depth);
+
+        let expected = {
+            let mut builder = CellBuilder::new();
+            builder.store_u8(0xab)?;
+            builder.store_reference(Cell::new_absent(absent_hash, absent_depth))?;
+            builder.build()?
+        };
+        assert_eq!(root.repr_hash(), expected.repr_hash());
+        assert_eq!(root.repr_depth(), absent_depth + 1);
+
+        // Encode back
+        assert_eq!(Boc::encode(root.as_ref()), boc);
+        assert_eq!(Boc::encode(expected.as_ref()), boc);
+
+        // Absent count mismatch
+        drop(header);
+        boc[8] = 0x00;
+        assert!(matches!(
+            de::BocHeader::decode(&boc, &options),
+            Err(de::Error::AbsentCountMismatch)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_indexed() {
+        let shared = {
+            let mut builder = CellBuilder::new();
+            builder.store_u32(0xdeadbeaf).unwrap();
+            builder.build().unwrap()
+        };
+        let make_root = |value: u8| {
+            let mut builder = CellBuilder::new();
+            builder.store_u8(value).unwrap();
+            builder.store_reference(shared.clone()).unwrap();
+            builder.store_reference(Cell::empty_cell()).unwrap();
+            builder.build().unwrap()
+        };
+        let root1 = make_root(1);
+        let root2 = make_root(2);
+
+        // Single root
+        let encoded = Boc::encode(root1.as_ref());
+        let header = de::BocHeader::decode(&encoded, &de::Options::exact(1)).unwrap();
+
+        let boc = Boc::decode_indexed(&encoded).unwrap();
+        assert_eq!(boc.cell_count(), 3);
+        assert_eq!(boc.root_indices(), header.roots());
+        assert_eq!(
+            boc.index_of(root1.repr_hash()),
+            Some(header.roots()[0] as usize)
+        );
+        assert_eq!(boc.roots().collect::<Vec<_>>(), [&root1]);
+
+        for (index, cell) in boc.cells().iter().enumerate() {
+            assert_eq!(boc.cell_by_index(index), Some(cell));
+            assert_eq!(boc.index_of(cell.repr_hash()), Some(index));
+        }
+        assert!(boc.cell_by_index(boc.cell_count()).is_none());
+        assert!(boc.index_of(root2.repr_hash()).is_none());
+
+        // Multiple roots with shared cells
+        let mut encoded = Vec::new();
+        let mut header = ser::BocHeader::<ahash::RandomState>::new(root1.as_ref());
+        header.add_root(root2.as_ref());
+        header.encode(&mut encoded);
+
+        let boc = Boc::decode_indexed(&encoded).unwrap();
+        assert_eq!(boc.cell_count(), 4);
+        assert_eq!(boc.roots().collect::<Vec<_>>(), [&root1, &root2]);
+
+        let unique = [&root1, &root2, &shared, &Cell::empty_cell()].map(|cell| *cell.repr_hash());
+        assert_eq!(unique.len(), boc.cell_count());
+        for hash in &unique {
+            let index = boc.index_of(hash).unwrap();
+            assert_eq!(boc.cell_by_index(index).unwrap().repr_hash(), hash);
+        }
+
+        for (index, root) in boc.root_indices().iter().zip([&root1, &root2]) {
+            assert_eq!(boc.index_of(root.repr_hash()), Some(*index as usize));
+        }
+    }
+
+    #[test]
+    fn decode_in_steps() -> anyhow::Result<()> {
+        fn decode_all(data: &[u8], max_cells: usize) -> anyhow::Result<(Vec<Cell>, usize)> {
+            let mut decoder = de::BocDecoder::new(data, &de::Options::default())?;
+            let mut steps = 0;
+            let mut last_done = 0;
+            loop {
+                steps += 1;
+                match decoder.step(max_cells)? {
+                    de::DecodeProgress::InProgress {
+                        cells_done,
+                        cells_total,
+                    } => {
+                        assert_eq!(cells_done, last_done + max_cells);
+                        assert!(cells_done < cells_total);
+                        last_done = cells_done;
+                    }
+                    de::DecodeProgress::Done(roots) => return Ok((roots, steps)),
+                }
+            }
+        }
+
+        let mut dict = crate::dict::Dict::<u32, u32>::new();
+        for i in 0..100 {
+            dict.set(i, i * 10)?;
+        }
+        let root1 = CellBuilder::build_from(&dict)?;
+        let root2 = CellBuilder::build_from((123u32, root1.clone()))?;
+
+        let mut encoded = Vec::new();
+        let mut header = ser::BocHeader::<ahash::RandomState>::new(root1.as_ref());
+        header.add_root(root2.as_ref());
+        header.encode(&mut encoded);
+
+        let expected = Boc::decode_indexed(&encoded)?;
+        let cell_count = expected.cell_count();
+        for max_cells in [1, 2, 7, cell_count, cell_count + 1] {
+            let (roots, steps) = decode_all(&encoded, max_cells)?;
+            assert_eq!(steps, cell_count.div_ceil(max_cells));
+            assert_eq!(roots.iter().collect::<Vec<_>>(), [&root1, &root2]);
+            assert_eq!(
+                roots.iter().collect::<Vec<_>>(),
+                expected.roots().collect::<Vec<_>>()
+            );
+        }
+
+        // Zero budget makes no progress
+        let mut decoder = de::BocDecoder::new(&encoded, &de::Options::default())?;
+        assert!(matches!(
+            decoder.step(0)?,
+            de::DecodeProgress::InProgress { cells_done: 0, .. }
+        ));
+
+        // Header errors are reported immediately
+        assert!(de::BocDecoder::new(&encoded, &de::Options::exact(1)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_to_writer() {
+        fn check_encoders(cell: &DynCell) {
+            let encoded = Boc::encode(cell);
+            assert_eq!(Boc::encoded_size_hint(cell), encoded.len());
+
+            let mut target = vec![0xaa; 3];
+            Boc::encode_to(cell, &mut target);
+            assert_eq!(&target[..3], &[0xaa; 3]);
+            assert_eq!(&target[3..], encoded);
+
+            let mut written = Vec::new();
+            Boc::encode_to_writer(cell, &mut written).unwrap();
+            assert_eq!(written, encoded);
+
+            for without_hashes in [false, true] {
+                let header = || {
+                    ser::BocHeader::<ahash::RandomState>::new(cell)
+                        .with_crc(true)
+                        .without_hashes(without_hashes)
+                };
+
+                let mut encoded = Vec::new();
+                header().encode(&mut encoded);
+                assert_eq!(header().encoded_size(), encoded.len() as u64);
+
+                let mut written = Vec::new();
+                header().encode_to_writer(&mut written).unwrap();
+                assert_eq!(written, encoded);
+            }
+        }
+
+        let boc =
+            "te6ccgEBCAEAMAABAcABAgPPQAUCAgEgBAMACQAAADqgAAkAAABQYAIBIAcGAAkAAAAe4AAJAAAAbCA=";
+        check_encoders(Boc::decode_base64(boc).unwrap().as_ref());
+
+        let boc_merkle_proof = "te6ccgECBQEAARwACUYDcijLZ4hNbjcLQiThSx8fvxTaVufKbXsXRYbyiUZApXoADQEiccAJ2Y4sgpswmr6/odN0WmKosRtoIzobXRBE9uCeOA1nuXKSo06DG3E/cAAAdbacX3gRQHLHOx0TQAQCAdURYfZ8pYDdK5k1lnsEEJ4OmIYB/AiU4UX3zVZTToFyVwAAAYRmS/s2iLD7PlLAbpXMmss9gghPB0xDAP4ESnCi++arKadAuSuAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAACAsAMARaACLD7PlLAbpXMmss9gghPB0xDAP4ESnCi++arKadAuSuAQKEgBAYDWxHxKJVQ8mzl7cXFvP64eLF0kcXTFLiwZvYlkQrEFAAw=";
+        check_encoders(Boc::decode_base64(boc_merkle_proof).unwrap().as_ref());
+
+        check_encoders(Cell::empty_cell_ref());
+
+        // Synthetic tree with 100k cells
+        let dict =
+            crate::dict::Dict::<u32, u32>::from_sorted_pairs((0..50_000).map(|i| (i, i))).unwrap();
+        let root = dict.root().as_ref().unwrap();
+        check_encoders(root.as_ref());
+
+        // Writer errors are propagated
+        struct FailingWriter(usize);
+
+        impl std::io::Write for FailingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                if self.0 < buf.len() {
+                    return Err(std::io::ErrorKind::WriteZero.into());
+                }
+                self.0 -= buf.len();
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let err = Boc::encode_to_writer(root, FailingWriter(1000)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+    }
+
     #[cfg(feature = "serde")]
     #[allow(unused)]
     #[derive(::serde::Serialize)]
@@ -531,6 +1054,69 @@ mod tests {
         merkle_update: crate::merkle::MerkleUpdate,
     }
 
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd_boc() {
+        let mut dict = crate::dict::Dict::<u32, u32>::new();
+        for i in 0..1000 {
+            dict.set(i, i).unwrap();
+        }
+        let cell = crate::cell::CellBuilder::build_from(&dict).unwrap();
+        let plain = Boc::encode(&cell);
+
+        let compressed = Boc::encode_zstd(&cell, 3).unwrap();
+        assert!(compressed.starts_with(&ZSTD_MAGIC));
+        assert!(compressed.len() < plain.len());
+
+        let decoded = Boc::decode_zstd(&compressed, plain.len()).unwrap();
+        assert_eq!(decoded.as_ref(), cell.as_ref());
+
+        // Magic sniffing
+        let decoded = Boc::decode_auto(&compressed, plain.len()).unwrap();
+        assert_eq!(decoded.as_ref(), cell.as_ref());
+        let decoded = Boc::decode_auto(&plain, 0).unwrap();
+        assert_eq!(decoded.as_ref(), cell.as_ref());
+
+        // Limit is checked for the declared size
+        assert!(matches!(
+            Boc::decode_zstd(&compressed, plain.len() - 1),
+            Err(de::Error::DecompressedSizeLimit)
+        ));
+
+        // Limit is checked for the frame without a declared size
+        let mut streamed = Vec::new();
+        let mut encoder = zstd::stream::write::Encoder::new(&mut streamed, 3).unwrap();
+        encoder.include_contentsize(false).unwrap();
+        std::io::Write::write_all(&mut encoder, &plain).unwrap();
+        encoder.finish().unwrap();
+        assert!(matches!(
+            zstd::zstd_safe::get_frame_content_size(&streamed),
+            Ok(None)
+        ));
+
+        let decoded = Boc::decode_zstd(&streamed, plain.len()).unwrap();
+        assert_eq!(decoded.as_ref(), cell.as_ref());
+        assert!(matches!(
+            Boc::decode_zstd(&streamed, plain.len() - 1),
+            Err(de::Error::DecompressedSizeLimit)
+        ));
+
+        // Huge declared size is rejected before decompression
+        let mut header = ZSTD_MAGIC.to_vec();
+        header.push(0b1110_0000); // 8-byte content size, single segment
+        header.extend_from_slice(&(1u64 << 40).to_le_bytes());
+        assert!(matches!(
+            Boc::decode_zstd(&header, 1 << 20),
+            Err(de::Error::DecompressedSizeLimit)
+        ));
+
+        // Garbage
+        assert!(matches!(
+            Boc::decode_zstd(ZSTD_MAGIC, 1 << 20),
+            Err(de::Error::InvalidCompressedData)
+        ));
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn hex_bytes() {
@@ -556,6 +1142,32 @@ mod tests {
         assert_eq!(some_cell.as_ref(), original.as_ref());
     }
 
+    #[test]
+    fn encode_cyclic_cell() -> anyhow::Result<()> {
+        use crate::cell::LoopCell;
+
+        let mut builder = CellBuilder::new();
+        builder.store_u32(123)?;
+        builder.store_reference(Cell::empty_cell())?;
+        let looped = LoopCell(builder.build()?);
+
+        assert!(matches!(
+            ser::BocHeader::<ahash::RandomState>::try_new(&looped),
+            Err(crate::error::Error::InvalidData)
+        ));
+
+        // Failed roots must not affect the header
+        let valid = CellBuilder::build_from(0xdeafbeafu32)?;
+        let mut header = ser::BocHeader::<ahash::RandomState>::try_new(valid.as_ref())?;
+        assert!(header.try_add_root(&looped).is_err());
+
+        let mut encoded = Vec::new();
+        header.encode(&mut encoded);
+        assert_eq!(encoded, Boc::encode(valid.as_ref()));
+
+        Ok(())
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn struct_with_repr() {
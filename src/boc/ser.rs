@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 use std::hash::BuildHasher;
+use std::io::Write;
 
 use super::BocTag;
 use crate::cell::{CellDescriptor, DynCell, HashBytes};
+use crate::error::Error;
+use crate::util::unlikely;
 
 /// Intermediate BOC serializer state.
 pub struct BocHeader<'a, S = ahash::RandomState> {
@@ -12,6 +15,7 @@ pub struct BocHeader<'a, S = ahash::RandomState> {
     total_data_size: u64,
     reference_count: u64,
     cell_count: u32,
+    absent_count: u32,
     without_hashes: bool,
     include_crc: bool,
 }
@@ -21,7 +25,25 @@ where
     S: BuildHasher + Default,
 {
     /// Creates an intermediate BOC serializer state with a single root.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell tree is malformed. See [`try_new`].
+    ///
+    /// [`try_new`]: Self::try_new
     pub fn new(root: &'a DynCell) -> Self {
+        match Self::try_new(root) {
+            Ok(res) => res,
+            Err(e) => panic!("failed to encode cell tree: {e}"),
+        }
+    }
+
+    /// Creates an intermediate BOC serializer state with a single root.
+    ///
+    /// Returns an error if the cell tree is malformed. See [`try_add_root`].
+    ///
+    /// [`try_add_root`]: Self::try_add_root
+    pub fn try_new(root: &'a DynCell) -> Result<Self, Error> {
         let mut res = Self {
             root_rev_indices: Default::default(),
             rev_indices: Default::default(),
@@ -29,11 +51,12 @@ where
             total_data_size: 0,
             reference_count: 0,
             cell_count: 0,
+            absent_count: 0,
             without_hashes: false,
             include_crc: false,
         };
-        res.add_root(root);
-        res
+        ok!(res.try_add_root(root));
+        Ok(res)
     }
 }
 
@@ -42,9 +65,51 @@ where
     S: BuildHasher,
 {
     /// Adds an additional root to the state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell tree is malformed. See [`try_add_root`].
+    ///
+    /// [`try_add_root`]: Self::try_add_root
     pub fn add_root(&mut self, root: &'a DynCell) {
-        let root_rev_index = self.fill(root);
-        self.root_rev_indices.push(root_rev_index);
+        if let Err(e) = self.try_add_root(root) {
+            panic!("failed to encode cell tree: {e}");
+        }
+    }
+
+    /// Adds an additional root to the state.
+    ///
+    /// Returns [`Error::InvalidData`] if some child cell is not shallower
+    /// than its parent (e.g. a cell which references itself), so that
+    /// the cells can't be ordered. The state remains unchanged in that case.
+    pub fn try_add_root(&mut self, root: &'a DynCell) -> Result<(), Error> {
+        let cells_before = self.rev_cells.len();
+        let counters_before = (
+            self.total_data_size,
+            self.reference_count,
+            self.cell_count,
+            self.absent_count,
+        );
+
+        match self.fill(root) {
+            Ok(root_rev_index) => {
+                self.root_rev_indices.push(root_rev_index);
+                Ok(())
+            }
+            Err(e) => {
+                // Revert all cells added during this call
+                for cell in self.rev_cells.drain(cells_before..) {
+                    self.rev_indices.remove(cell.repr_hash());
+                }
+                (
+                    self.total_data_size,
+                    self.reference_count,
+                    self.cell_count,
+                    self.absent_count,
+                ) = counters_before;
+                Err(e)
+            }
+        }
     }
 
     /// Includes CRC bytes in the encoded BOC.
@@ -65,6 +130,74 @@ where
 
     /// Encodes cell trees into bytes.
     pub fn encode(self, target: &mut Vec<u8>) {
+        let layout = self.compute_layout();
+        #[cfg(feature = "tracing")]
+        let _span = self.encode_span(&layout);
+        target.reserve(layout.total_size as usize);
+
+        let target_len_before = target.len();
+        self.write_cells(&layout, target);
+
+        if self.include_crc {
+            let target_len_after = target.len();
+            debug_assert!(target_len_before < target_len_after);
+
+            let crc = crc32c::crc32c(&target[target_len_before..target_len_after]);
+            target.extend_from_slice(&crc.to_le_bytes());
+        }
+
+        debug_assert_eq!(
+            target.len() as u64,
+            target_len_before as u64 + layout.total_size
+        );
+    }
+
+    /// Encodes cell trees into the specified writer.
+    ///
+    /// Cells are written one by one without building the whole BOC in memory,
+    /// so it is better to use a buffered writer. CRC (if enabled) is computed
+    /// along the way.
+    pub fn encode_to_writer<W: Write>(self, writer: W) -> std::io::Result<()> {
+        let layout = self.compute_layout();
+        #[cfg(feature = "tracing")]
+        let _span = self.encode_span(&layout);
+
+        let mut writer = CrcWriter {
+            inner: writer,
+            compute_crc: self.include_crc,
+            crc: 0,
+            error: None,
+        };
+        self.write_cells(&layout, &mut writer);
+        if let Some(e) = writer.error {
+            return Err(e);
+        }
+
+        if self.include_crc {
+            let crc = writer.crc;
+            writer.inner.write_all(&crc.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Returns the size of the encoded BOC in bytes.
+    pub fn encoded_size(&self) -> u64 {
+        self.compute_layout().total_size
+    }
+
+    #[cfg(feature = "tracing")]
+    fn encode_span(&self, layout: &BocLayout) -> tracing::span::EnteredSpan {
+        let span = debug_span!(
+            "boc_encode",
+            bytes = layout.total_size,
+            cells = tracing::field::Empty,
+            bits = tracing::field::Empty,
+        );
+        crate::util::record_cells(&span, self.rev_cells.iter().copied());
+        span
+    }
+
+    fn compute_layout(&self) -> BocLayout {
         let root_count = self.root_rev_indices.len();
 
         let ref_size = number_of_bytes_to_fit(self.cell_count as u64);
@@ -81,8 +214,6 @@ where
         // is at least 1, and `total_cells_size` is `u64`
         debug_assert!((1..=8).contains(&offset_size));
 
-        let flags = (ref_size as u8) | (u8::from(self.include_crc) * 0b0100_0000);
-
         // 4 bytes - BOC tag
         // 1 byte - flags
         // 1 byte - offset size
@@ -99,23 +230,40 @@ where
             + (offset_size as u64)
             + total_cells_size
             + u64::from(self.include_crc) * 4;
-        target.reserve(total_size as usize);
 
-        let target_len_before = target.len();
+        BocLayout {
+            ref_size,
+            offset_size,
+            total_cells_size,
+            total_size,
+        }
+    }
+
+    /// Writes everything except the CRC.
+    fn write_cells<T: BocSink>(&self, layout: &BocLayout, target: &mut T) {
+        let BocLayout {
+            ref_size,
+            offset_size,
+            total_cells_size,
+            ..
+        } = *layout;
+        let root_count = self.root_rev_indices.len();
+
+        let flags = (ref_size as u8) | (u8::from(self.include_crc) * 0b0100_0000);
 
         target.extend_from_slice(&BocTag::GENERIC);
         target.extend_from_slice(&[flags, offset_size as u8]);
         target.extend_from_slice(&self.cell_count.to_be_bytes()[4 - ref_size..]);
         target.extend_from_slice(&(root_count as u32).to_be_bytes()[4 - ref_size..]);
-        target.extend_from_slice(&[0; 4][4 - ref_size..]);
+        target.extend_from_slice(&self.absent_count.to_be_bytes()[4 - ref_size..]);
         target.extend_from_slice(&total_cells_size.to_be_bytes()[8 - offset_size..]);
 
-        for rev_index in self.root_rev_indices {
+        for rev_index in &self.root_rev_indices {
             let root_index = self.cell_count - rev_index - 1;
             target.extend_from_slice(&root_index.to_be_bytes()[4 - ref_size..]);
         }
 
-        for cell in self.rev_cells.into_iter().rev() {
+        for cell in self.rev_cells.iter().rev() {
             let mut descriptor = cell.descriptor();
             descriptor.d1 &= !(u8::from(self.without_hashes) * CellDescriptor::STORE_HASHES_MASK);
             target.extend_from_slice(&[descriptor.d1, descriptor.d2]);
@@ -138,40 +286,36 @@ where
                 }
             }
         }
-
-        if self.include_crc {
-            let target_len_after = target.len();
-            debug_assert!(target_len_before < target_len_after);
-
-            let crc = crc32c::crc32c(&target[target_len_before..target_len_after]);
-            target.extend_from_slice(&crc.to_le_bytes());
-        }
-
-        debug_assert_eq!(target.len() as u64, target_len_before as u64 + total_size);
     }
 
-    fn fill(&mut self, root: &'a DynCell) -> u32 {
+    fn fill(&mut self, root: &'a DynCell) -> Result<u32, Error> {
         const SAFE_DEPTH: u16 = 128;
 
         if let Some(index) = self.rev_indices.get(root.repr_hash()) {
-            return *index;
+            return Ok(*index);
         }
 
         let repr_depth = root.repr_depth();
         if repr_depth <= SAFE_DEPTH {
-            self.fill_recursive(root);
+            ok!(self.fill_recursive(root));
         } else {
-            self.fill_deep(root, repr_depth);
+            ok!(self.fill_deep(root, repr_depth));
         }
 
         debug_assert!(self.cell_count > 0);
-        self.cell_count - 1
+        Ok(self.cell_count - 1)
     }
 
-    fn fill_recursive(&mut self, cell: &'a DynCell) {
+    fn fill_recursive(&mut self, cell: &'a DynCell) -> Result<(), Error> {
+        let repr_depth = cell.repr_depth();
         for child in cell.references() {
+            // NOTE: Depth strictly decreases along the references,
+            // so this check also guarantees that there are no cycles.
+            if child.repr_depth() >= repr_depth {
+                return Err(Error::InvalidData);
+            }
             if !self.rev_indices.contains_key(child.repr_hash()) {
-                self.fill_recursive(child);
+                ok!(self.fill_recursive(child));
             }
         }
 
@@ -180,12 +324,17 @@ where
 
         let descriptor = cell.descriptor();
         self.total_data_size += descriptor.byte_len_full(self.without_hashes);
-        self.reference_count += descriptor.reference_count() as u64;
+        if unlikely(descriptor.is_absent()) {
+            self.absent_count += 1;
+        } else {
+            self.reference_count += descriptor.reference_count() as u64;
+        }
         self.cell_count += 1;
+        Ok(())
     }
 
     #[cold]
-    fn fill_deep(&mut self, root: &'a DynCell, repr_depth: u16) {
+    fn fill_deep(&mut self, root: &'a DynCell, repr_depth: u16) -> Result<(), Error> {
         const MAX_DEFAULT_CAPACITY: u16 = 256;
 
         let mut stack = Vec::with_capacity(repr_depth.min(MAX_DEFAULT_CAPACITY) as usize);
@@ -193,6 +342,10 @@ where
 
         while let Some(children) = stack.last_mut() {
             if let Some(cell) = children.next() {
+                // NOTE: See `fill_recursive` for details.
+                if cell.repr_depth() >= children.cell().repr_depth() {
+                    return Err(Error::InvalidData);
+                }
                 if !self.rev_indices.contains_key(cell.repr_hash()) {
                     stack.push(cell.references());
                 }
@@ -204,12 +357,60 @@ where
 
                 let descriptor = cell.descriptor();
                 self.total_data_size += descriptor.byte_len_full(self.without_hashes);
-                self.reference_count += descriptor.reference_count() as u64;
+                if unlikely(descriptor.is_absent()) {
+                    self.absent_count += 1;
+                } else {
+                    self.reference_count += descriptor.reference_count() as u64;
+                }
                 self.cell_count += 1;
 
                 stack.pop();
             }
         }
+
+        Ok(())
+    }
+}
+
+struct BocLayout {
+    ref_size: usize,
+    offset_size: usize,
+    total_cells_size: u64,
+    total_size: u64,
+}
+
+/// Output of the BOC encoder.
+trait BocSink {
+    fn extend_from_slice(&mut self, bytes: &[u8]);
+}
+
+impl BocSink for Vec<u8> {
+    #[inline]
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        Vec::extend_from_slice(self, bytes);
+    }
+}
+
+/// Writer wrapper which optionally computes CRC of the written bytes.
+///
+/// Stops writing after the first error.
+struct CrcWriter<W> {
+    inner: W,
+    compute_crc: bool,
+    crc: u32,
+    error: Option<std::io::Error>,
+}
+
+impl<W: Write> BocSink for CrcWriter<W> {
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        match self.inner.write_all(bytes) {
+            Ok(()) if self.compute_crc => self.crc = crc32c::crc32c_append(self.crc, bytes),
+            Ok(()) => {}
+            Err(e) => self.error = Some(e),
+        }
     }
 }
 
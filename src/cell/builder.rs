@@ -4,9 +4,10 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::cell::cell_context::{CellContext, CellParts};
+use crate::cell::slice::{STACK_SLICE_BITS, STACK_SLICE_TAG};
 use crate::cell::{
-    Cell, CellDescriptor, CellImpl, CellSlice, CellType, DynCell, HashBytes, LevelMask,
-    MAX_BIT_LEN, MAX_REF_COUNT,
+    Cell, CellDescriptor, CellImpl, CellSlice, CellSliceParts, CellType, DynCell, HashBytes,
+    LevelMask, MAX_BIT_LEN, MAX_REF_COUNT,
 };
 use crate::error::Error;
 use crate::util::{ArrayVec, Bitstring};
@@ -170,6 +171,18 @@ impl_primitive_store! {
     u128 => |b, v| b.store_u128(*v),
     i128 => |b, v| b.store_u128(*v as u128),
     HashBytes => |b, v| b.store_u256(v),
+    std::net::Ipv4Addr => |b, v| b.store_u32(u32::from(*v)),
+    std::net::Ipv6Addr => |b, v| b.store_u128(u128::from(*v)),
+}
+
+impl<const N: usize> Store for [u8; N] {
+    #[inline]
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        if N > 128 {
+            return Err(Error::CellOverflow);
+        }
+        builder.store_raw(self, (N * 8) as u16)
+    }
 }
 
 /// Builder for constructing cells with densely packed data.
@@ -200,15 +213,32 @@ impl Clone for CellBuilder {
 
 impl Eq for CellBuilder {}
 
+/// Builders are equal when they have the same data bits, the same exotic flag
+/// and the same references (compared by their representation hashes).
+///
+/// NOTE: all bits after `bit_len` are always zero, so the whole data array
+/// can be compared.
 impl PartialEq for CellBuilder {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.bit_len == other.bit_len
+            && self.is_exotic == other.is_exotic
             && self.data == other.data
             && self.references.as_ref() == other.references.as_ref()
     }
 }
 
+impl std::hash::Hash for CellBuilder {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.bit_len.hash(state);
+        self.is_exotic.hash(state);
+        self.data[..self.bit_len.div_ceil(8) as usize].hash(state);
+        for cell in self.references.as_ref() {
+            cell.repr_hash().hash(state);
+        }
+    }
+}
+
 impl Ord for CellBuilder {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self.bit_len, self.references.len()).cmp(&(other.bit_len, other.references.len())) {
@@ -229,7 +259,7 @@ impl Ord for CellBuilder {
             }
         }
 
-        Ordering::Equal
+        self.is_exotic.cmp(&other.is_exotic)
     }
 }
 
@@ -700,6 +730,19 @@ impl CellBuilder {
 
     /// Tries to store all data bits of the specified cell in the current cell,
     /// returning `false` if there is not enough remaining capacity.
+    ///
+    /// Only `bit_len` bits are copied, so the completion tag of the last
+    /// non-aligned byte is never included. Copying the data of a cell into
+    /// an empty builder produces exactly the same data as the source cell.
+    ///
+    /// References are not copied. Data of exotic cells can be stored
+    /// as well, but the exotic flag of the builder is left unchanged
+    /// (see [`set_exotic`]).
+    ///
+    /// Use [`store_slice_data`] to copy only the remaining data of a slice.
+    ///
+    /// [`set_exotic`]: CellBuilder::set_exotic
+    /// [`store_slice_data`]: CellBuilder::store_slice_data
     #[inline]
     pub fn store_cell_data<T>(&mut self, value: T) -> Result<(), Error>
     where
@@ -718,6 +761,11 @@ impl CellBuilder {
 
     /// Tries to store the remaining slice data in the cell,
     /// returning `false` if there is not enough remaining capacity.
+    ///
+    /// Only the bits of the slice data window are copied, with the same
+    /// guarantees as [`store_cell_data`].
+    ///
+    /// [`store_cell_data`]: CellBuilder::store_cell_data
     #[inline]
     pub fn store_slice_data<'a, T>(&mut self, value: T) -> Result<(), Error>
     where
@@ -920,11 +968,39 @@ impl CellBuilder {
         store_slice_impl(self, value.as_ref())
     }
 
+    /// Tries to store a cell slice as a TVM stack value.
+    ///
+    /// The slice cell is stored as a reference along with the slice range.
+    /// See [`CellSlice::load_stack_slice`] for the TLB scheme.
+    pub fn store_slice_as_stack_value(&mut self, slice: &CellSliceParts) -> Result<(), Error> {
+        let (cell, range) = slice;
+        if !range.is_within(cell.as_ref()) {
+            return Err(Error::InvalidData);
+        }
+        if !self.has_capacity(STACK_SLICE_BITS, 1) {
+            return Err(Error::CellOverflow);
+        }
+
+        ok!(self.store_reference(cell.clone()));
+        ok!(self.store_u8(STACK_SLICE_TAG));
+        ok!(self.store_uint(range.bits_offset() as u64, 10));
+        ok!(self.store_uint((range.bits_offset() + range.remaining_bits()) as u64, 10));
+        ok!(self.store_small_uint(range.refs_offset(), 3));
+        self.store_small_uint(range.refs_offset() + range.remaining_refs(), 3)
+    }
+
     /// Tries to build a new cell using the specified cell context.
     pub fn build_ext(mut self, context: &mut dyn CellContext) -> Result<Cell, Error> {
         debug_assert!(self.bit_len <= MAX_BIT_LEN);
         debug_assert!(self.references.len() <= MAX_REF_COUNT);
 
+        if !context
+            .limits()
+            .contains(self.bit_len, self.references.len() as u8)
+        {
+            return Err(Error::CellOverflow);
+        }
+
         #[cfg(feature = "stats")]
         let mut stats = CellTreeStats {
             bit_count: self.bit_len as u64,
@@ -1214,6 +1290,7 @@ impl CellImpl for IntermediateFullCell {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cell::{CellSizeLimits, LoadMode};
 
     #[test]
     fn clone_builder() {
@@ -1261,6 +1338,60 @@ mod tests {
         assert_ne!(a, b);
     }
 
+    #[test]
+    fn builder_eq_hash() -> anyhow::Result<()> {
+        use std::hash::{BuildHasher, RandomState};
+
+        let hasher = RandomState::new();
+
+        let mut a = CellBuilder::new();
+        a.store_u8(0b1010_1100)?;
+        a.store_reference(Cell::empty_cell())?;
+
+        let mut b = CellBuilder::new();
+        for bit in [true, false, true, false, true, true, false, false] {
+            b.store_bit(bit)?;
+        }
+        b.store_reference(Cell::empty_cell())?;
+
+        assert_eq!(a, b);
+        assert_eq!(a.clone(), b);
+        assert_eq!(hasher.hash_one(&a), hasher.hash_one(&b));
+
+        // Trailing bit
+        a.store_bit_zero()?;
+        b.store_bit_one()?;
+        assert_ne!(a, b);
+
+        a.rewind(1)?;
+        b.rewind(1)?;
+        assert_eq!(a, b);
+        assert_eq!(hasher.hash_one(&a), hasher.hash_one(&b));
+
+        // Prefix comparison without building
+        b.store_bit_one()?;
+        let prefix = a.as_data_slice();
+        assert!(b.as_data_slice().strip_data_prefix(&prefix).is_some());
+        b.rewind(1)?;
+
+        // Exotic flag
+        b.set_exotic(true);
+        assert_ne!(a, b);
+
+        // References are compared by hash
+        let mut c = CellBuilder::new();
+        c.store_u8(0b1010_1100)?;
+        c.store_reference(CellBuilder::new().build()?)?;
+        assert_eq!(a, c);
+
+        let mut c = CellBuilder::new();
+        c.store_u8(0b1010_1100)?;
+        c.store_reference(CellBuilder::build_from(123u32)?)?;
+        assert_ne!(a, c);
+
+        Ok(())
+    }
+
     #[test]
     fn rewind_builder() {
         let mut builder = CellBuilder::new();
@@ -1323,6 +1454,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn store_cell_data() -> anyhow::Result<()> {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand_xorshift::XorShiftRng::from_seed([5u8; 16]);
+        let mut bytes = [0u8; 128];
+
+        for bits in 0..=MAX_BIT_LEN {
+            rng.fill(&mut bytes[..]);
+            let cell = {
+                let mut builder = CellBuilder::new();
+                builder.store_raw(&bytes, bits)?;
+                builder.build()?
+            };
+            // Make sure that the completion tag is ignored
+            let cell = crate::boc::Boc::decode(crate::boc::Boc::encode(&cell))?;
+
+            let mut expected = CellBuilder::new();
+            expected.store_raw(cell.data(), cell.bit_len())?;
+
+            let mut copied = CellBuilder::new();
+            copied.store_cell_data(cell.as_ref())?;
+            assert_eq!(copied, expected);
+            assert_eq!(copied.build()?.data(), cell.data());
+
+            // Same for the full slice
+            let mut copied = CellBuilder::new();
+            copied.store_slice_data(cell.as_slice()?)?;
+            assert_eq!(copied, expected);
+
+            // Copy the remaining window with an unaligned offset
+            let offset = rng.gen_range(0..=bits);
+            let mut slice = cell.as_slice()?;
+            slice.advance(offset, 0)?;
+            let mut copied = CellBuilder::new();
+            copied.store_slice_data(slice)?;
+            assert_eq!(copied.bit_len(), bits - offset);
+            assert_eq!(
+                copied
+                    .as_data_slice()
+                    .longest_common_data_prefix(&slice)
+                    .remaining_bits(),
+                bits - offset
+            );
+        }
+
+        // Not enough capacity
+        let cell = CellBuilder::build_from(u32::MAX)?;
+        let mut builder = CellBuilder::new();
+        builder.store_zeros(MAX_BIT_LEN - 31)?;
+        assert!(matches!(
+            builder.store_cell_data(cell.as_ref()),
+            Err(Error::CellOverflow)
+        ));
+
+        // Exotic data can be copied into an ordinary builder
+        let pruned =
+            crate::merkle::make_pruned_branch(cell.as_ref(), 0, &mut Cell::empty_context())?;
+        let mut builder = CellBuilder::new();
+        builder.store_cell_data(pruned.as_ref())?;
+        assert!(!builder.is_exotic());
+        assert_eq!(builder.bit_len(), pruned.bit_len());
+
+        Ok(())
+    }
+
     #[test]
     fn prepend_raw() {
         let mut builder = CellBuilder::new();
@@ -1356,4 +1553,85 @@ mod tests {
 
         Ok(())
     }
+
+    struct LimitedContext(CellSizeLimits);
+
+    impl CellContext for LimitedContext {
+        fn finalize_cell(&mut self, cell: CellParts<'_>) -> Result<Cell, Error> {
+            Cell::empty_context().finalize_cell(cell)
+        }
+
+        fn load_cell(&mut self, cell: Cell, mode: LoadMode) -> Result<Cell, Error> {
+            Cell::empty_context().load_cell(cell, mode)
+        }
+
+        fn load_dyn_cell<'a>(
+            &mut self,
+            cell: &'a DynCell,
+            mode: LoadMode,
+        ) -> Result<&'a DynCell, Error> {
+            Cell::empty_context().load_dyn_cell(cell, mode)
+        }
+
+        fn limits(&self) -> CellSizeLimits {
+            self.0
+        }
+    }
+
+    #[test]
+    fn build_with_limits() -> anyhow::Result<()> {
+        assert_eq!(Cell::empty_context().limits(), CellSizeLimits::STANDARD);
+
+        let mut builder = CellBuilder::new();
+        builder.store_u32(123)?;
+        for _ in 0..3 {
+            builder.store_reference(Cell::empty_cell())?;
+        }
+
+        // Narrowed limits
+        let restricted = &mut LimitedContext(CellSizeLimits {
+            max_bit_len: 16,
+            max_ref_count: 2,
+        });
+        assert!(matches!(
+            builder.clone().build_ext(restricted),
+            Err(Error::CellOverflow)
+        ));
+
+        restricted.0.max_bit_len = 32;
+        restricted.0.max_ref_count = 3;
+        let cell = builder.clone().build_ext(restricted)?;
+        assert_eq!(cell, builder.clone().build()?);
+
+        // Limits can't exceed the cell representation
+        let permissive = CellSizeLimits {
+            max_bit_len: 2048,
+            max_ref_count: 8,
+        };
+        assert!(permissive.contains(MAX_BIT_LEN, MAX_REF_COUNT as u8));
+        assert!(!permissive.contains(MAX_BIT_LEN + 1, 0));
+        assert!(!permissive.contains(0, MAX_REF_COUNT as u8 + 1));
+
+        builder.store_reference(Cell::empty_cell())?;
+        assert!(builder.store_reference(Cell::empty_cell()).is_err());
+        builder.build_ext(&mut LimitedContext(permissive))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn too_deep_reference() -> anyhow::Result<()> {
+        let deepest = Cell::new_absent(HashBytes::ZERO, u16::MAX);
+
+        let mut builder = CellBuilder::new();
+        builder.store_reference(deepest)?;
+        assert!(matches!(builder.build(), Err(Error::DepthOverflow)));
+
+        let almost_deepest = Cell::new_absent(HashBytes::ZERO, u16::MAX - 1);
+        let mut builder = CellBuilder::new();
+        builder.store_reference(almost_deepest)?;
+        assert_eq!(builder.build()?.repr_depth(), u16::MAX);
+
+        Ok(())
+    }
 }
@@ -1,6 +1,8 @@
 use sha2::digest::Digest;
 
-use crate::cell::{Cell, CellDescriptor, CellType, DynCell, HashBytes, LevelMask, MAX_REF_COUNT};
+use crate::cell::{
+    Cell, CellDescriptor, CellType, DynCell, HashBytes, LevelMask, MAX_BIT_LEN, MAX_REF_COUNT,
+};
 use crate::error::Error;
 use crate::util::{unlikely, ArrayVec};
 
@@ -21,6 +23,50 @@ pub trait CellContext {
         cell: &'a DynCell,
         mode: LoadMode,
     ) -> Result<&'a DynCell, Error>;
+
+    /// Returns the size limits for the cells built with this context.
+    ///
+    /// Uses [`CellSizeLimits::STANDARD`] by default.
+    #[inline]
+    fn limits(&self) -> CellSizeLimits {
+        CellSizeLimits::STANDARD
+    }
+}
+
+/// Size limits for the cells built with a [`CellContext`].
+///
+/// NOTE: limits can only be narrowed. Cell descriptors and cell storage
+/// are designed for at most [`MAX_BIT_LEN`] bits and [`MAX_REF_COUNT`]
+/// references, so larger values are never allowed regardless of the limits.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CellSizeLimits {
+    /// Max cell data length in bits.
+    pub max_bit_len: u16,
+    /// Max number of child cells.
+    pub max_ref_count: u8,
+}
+
+impl Default for CellSizeLimits {
+    #[inline]
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+impl CellSizeLimits {
+    /// Standard cell size limits.
+    pub const STANDARD: Self = Self {
+        max_bit_len: MAX_BIT_LEN,
+        max_ref_count: MAX_REF_COUNT as u8,
+    };
+
+    /// Returns `true` if a cell with the specified size fits into the limits.
+    pub const fn contains(&self, bit_len: u16, ref_count: u8) -> bool {
+        bit_len <= self.max_bit_len
+            && bit_len <= MAX_BIT_LEN
+            && ref_count <= self.max_ref_count
+            && ref_count as usize <= MAX_REF_COUNT
+    }
 }
 
 /// Dictionary insertion mode.
@@ -82,16 +128,13 @@ impl<'a> CellParts<'a> {
         const HASH_BITS: usize = 256;
         const DEPTH_BITS: usize = 16;
 
-        let mut descriptor = self.descriptor;
+        let descriptor = self.descriptor;
         let bit_len = self.bit_len as usize;
         let level_mask = descriptor.level_mask();
         let level = level_mask.level() as usize;
 
         let references = self.references.as_ref();
 
-        // `hashes_len` is guaranteed to be in range 1..4
-        let mut hashes_len = level + 1;
-
         let (cell_type, computed_level_mask) = if unlikely(descriptor.is_exotic()) {
             let Some(&first_byte) = self.data.first() else {
                 return Err(Error::InvalidCell);
@@ -114,7 +157,6 @@ impl<'a> CellParts<'a> {
                         return Err(Error::InvalidCell);
                     }
 
-                    hashes_len = 1;
                     (CellType::PrunedBranch, level_mask)
                 }
                 // 8 bits type, hash, depth
@@ -154,58 +196,99 @@ impl<'a> CellParts<'a> {
             return Err(Error::InvalidCell);
         }
 
-        let level_offset = cell_type.is_merkle() as u8;
-        let is_pruned = cell_type.is_pruned_branch();
+        compute_repr_hashes(
+            descriptor,
+            cell_type,
+            self.data,
+            references.iter().map(AsRef::as_ref),
+            |_, _| {},
+        )
+    }
+}
 
-        let mut hashes = Vec::<(HashBytes, u16)>::with_capacity(hashes_len);
-        for level in 0..4 {
-            // Skip non-zero levels for pruned branches and insignificant hashes for other cells
-            if level != 0 && (is_pruned || !level_mask.contains(level)) {
-                continue;
-            }
+/// Computes hashes and depths for all significant levels of the cell.
+///
+/// The representation of each level is streamed into the hasher. `on_repr`
+/// is called with the index of the hash being computed and each chunk
+/// of its representation bytes (the bytes which are hashed).
+///
+/// Returns [`Error::InvalidCell`] if the cell is too large to be represented
+/// and [`Error::DepthOverflow`] if some depth doesn't fit into `u16`.
+pub(crate) fn compute_repr_hashes<'r, I, F>(
+    mut descriptor: CellDescriptor,
+    cell_type: CellType,
+    data: &[u8],
+    references: I,
+    mut on_repr: F,
+) -> Result<Vec<(HashBytes, u16)>, Error>
+where
+    I: Iterator<Item = &'r DynCell> + Clone,
+    F: FnMut(usize, &[u8]),
+{
+    if unlikely(data.len() > 128 || references.clone().count() > MAX_REF_COUNT) {
+        return Err(Error::InvalidCell);
+    }
 
-            let mut hasher = sha2::Sha256::new();
+    let level_mask = descriptor.level_mask();
+    let level_offset = cell_type.is_merkle() as u8;
+    let is_pruned = cell_type.is_pruned_branch();
 
-            let level_mask = if is_pruned {
-                level_mask
-            } else {
-                LevelMask::from_level(level)
-            };
+    let mut depth_overflow = false;
+    let mut hashes = Vec::<(HashBytes, u16)>::with_capacity(level_mask.level() as usize + 1);
+    for level in 0..4 {
+        // Skip non-zero levels for pruned branches and insignificant hashes for other cells
+        if level != 0 && (is_pruned || !level_mask.contains(level)) {
+            continue;
+        }
 
-            descriptor.d1 &= !(CellDescriptor::LEVEL_MASK | CellDescriptor::STORE_HASHES_MASK);
-            descriptor.d1 |= u8::from(level_mask) << 5;
-            hasher.update([descriptor.d1, descriptor.d2]);
-
-            if level == 0 {
-                hasher.update(self.data);
-            } else {
-                // SAFETY: new hash is added on each iteration, so there will
-                // definitely be a hash, when level>0
-                let prev_hash = unsafe { hashes.last().unwrap_unchecked() };
-                hasher.update(prev_hash.0.as_slice());
-            }
+        let index = hashes.len();
+        let mut hasher = sha2::Sha256::new();
+        let mut update = |bytes: &[u8]| {
+            hasher.update(bytes);
+            on_repr(index, bytes);
+        };
 
-            let mut depth = 0;
-            for child in references {
-                let child_depth = child.as_ref().depth(level + level_offset);
-                let next_depth = match child_depth.checked_add(1) {
-                    Some(next_depth) => next_depth,
-                    None => return Err(Error::DepthOverflow),
-                };
-                depth = std::cmp::max(depth, next_depth);
+        let level_mask = if is_pruned {
+            level_mask
+        } else {
+            LevelMask::from_level(level)
+        };
 
-                hasher.update(child_depth.to_be_bytes());
-            }
+        descriptor.d1 &= !(CellDescriptor::LEVEL_MASK | CellDescriptor::STORE_HASHES_MASK);
+        descriptor.d1 |= u8::from(level_mask) << 5;
+        update(&[descriptor.d1, descriptor.d2]);
 
-            for child in references {
-                let child_hash = child.as_ref().hash(level + level_offset);
-                hasher.update(child_hash.as_slice());
-            }
+        match hashes.last() {
+            Some((prev_hash, _)) => update(prev_hash.as_slice()),
+            None => update(data),
+        }
+
+        let mut depth = 0;
+        for child in references.clone() {
+            let child_depth = child.depth(level + level_offset);
+            let next_depth = match child_depth.checked_add(1) {
+                Some(next_depth) => next_depth,
+                None => {
+                    depth_overflow = true;
+                    u16::MAX
+                }
+            };
+            depth = std::cmp::max(depth, next_depth);
 
-            let hash = hasher.finalize().into();
-            hashes.push((hash, depth));
+            update(&child_depth.to_be_bytes());
         }
 
-        Ok(hashes)
+        for child in references.clone() {
+            let child_hash = child.hash(level + level_offset);
+            update(child_hash.as_slice());
+        }
+
+        let hash = hasher.finalize().into();
+        hashes.push((hash, depth));
+    }
+
+    if unlikely(depth_overflow) {
+        return Err(Error::DepthOverflow);
     }
+    Ok(hashes)
 }
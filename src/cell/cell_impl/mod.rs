@@ -4,7 +4,8 @@ use std::mem::MaybeUninit;
 #[cfg(feature = "stats")]
 use super::CellTreeStats;
 use super::{
-    Cell, CellDescriptor, CellFamily, CellImpl, DynCell, HashBytes, EMPTY_CELL_HASH, MAX_REF_COUNT,
+    Cell, CellDescriptor, CellFamily, CellImpl, CellParts, DynCell, HashBytes, LevelMask,
+    EMPTY_CELL_HASH, MAX_REF_COUNT,
 };
 use crate::util::TryAsMut;
 
@@ -514,6 +515,147 @@ impl CellImpl for LibraryReference {
     }
 }
 
+/// Placeholder for a cell which is not included into the BOC.
+///
+/// Data consists of the representation hash and depth of the original cell.
+struct AbsentCell {
+    repr_hash: HashBytes,
+    repr_depth: u16,
+    data: [u8; 34],
+}
+
+impl AbsentCell {
+    const BIT_LEN: u16 = 8 * 34;
+    const DESCRIPTOR: CellDescriptor = CellDescriptor {
+        d1: CellDescriptor::REF_COUNT_MASK | CellDescriptor::IS_EXOTIC_MASK,
+        d2: CellDescriptor::compute_d2(Self::BIT_LEN),
+    };
+
+    fn new(repr_hash: HashBytes, repr_depth: u16) -> Self {
+        let mut data = [0; 34];
+        data[..32].copy_from_slice(repr_hash.as_slice());
+        data[32..].copy_from_slice(&repr_depth.to_be_bytes());
+        Self {
+            repr_hash,
+            repr_depth,
+            data,
+        }
+    }
+}
+
+impl CellImpl for AbsentCell {
+    fn descriptor(&self) -> CellDescriptor {
+        Self::DESCRIPTOR
+    }
+
+    fn data(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    fn bit_len(&self) -> u16 {
+        AbsentCell::BIT_LEN
+    }
+
+    fn reference(&self, _: u8) -> Option<&DynCell> {
+        None
+    }
+
+    fn reference_cloned(&self, _: u8) -> Option<Cell> {
+        None
+    }
+
+    fn virtualize(&self) -> &DynCell {
+        self
+    }
+
+    fn hash(&self, _: u8) -> &HashBytes {
+        &self.repr_hash
+    }
+
+    fn depth(&self, _: u8) -> u16 {
+        self.repr_depth
+    }
+
+    fn take_first_child(&mut self) -> Option<Cell> {
+        None
+    }
+
+    fn replace_first_child(&mut self, parent: Cell) -> ReplacedChild {
+        Err(parent)
+    }
+
+    fn take_next_child(&mut self) -> Option<Cell> {
+        None
+    }
+
+    #[cfg(feature = "stats")]
+    fn stats(&self) -> CellTreeStats {
+        CellTreeStats {
+            bit_count: AbsentCell::BIT_LEN as u64,
+            cell_count: 1,
+        }
+    }
+}
+
+/// Malicious cell which references itself.
+///
+/// NOTE: cycles are impossible for cells with computed hashes,
+/// so this cell is only used to test cycle detection.
+#[cfg(test)]
+pub(crate) struct LoopCell(pub Cell);
+
+#[cfg(test)]
+impl CellImpl for LoopCell {
+    fn descriptor(&self) -> CellDescriptor {
+        self.0.descriptor()
+    }
+
+    fn data(&self) -> &[u8] {
+        self.0.data()
+    }
+
+    fn bit_len(&self) -> u16 {
+        self.0.bit_len()
+    }
+
+    fn reference(&self, _: u8) -> Option<&DynCell> {
+        Some(self)
+    }
+
+    fn reference_cloned(&self, _: u8) -> Option<Cell> {
+        None
+    }
+
+    fn virtualize(&self) -> &DynCell {
+        self
+    }
+
+    fn hash(&self, level: u8) -> &HashBytes {
+        self.0.hash(level)
+    }
+
+    fn depth(&self, level: u8) -> u16 {
+        self.0.depth(level)
+    }
+
+    fn take_first_child(&mut self) -> Option<Cell> {
+        None
+    }
+
+    fn replace_first_child(&mut self, parent: Cell) -> ReplacedChild {
+        Err(parent)
+    }
+
+    fn take_next_child(&mut self) -> Option<Cell> {
+        None
+    }
+
+    #[cfg(feature = "stats")]
+    fn stats(&self) -> CellTreeStats {
+        self.0.stats()
+    }
+}
+
 type PrunedBranch<const N: usize> = HeaderWithData<PrunedBranchHeader, N>;
 
 struct PrunedBranchHeader {
@@ -797,6 +939,60 @@ fn hash_index(descriptor: CellDescriptor, level: u8) -> u8 {
     descriptor.level_mask().hash_index(level)
 }
 
+/// Number of ordinary cells without references and with at most 8 data bits.
+const SMALL_CELL_COUNT: usize = (1 << 9) - 1;
+
+/// Returns an index of the shared instance for the specified cell parts.
+///
+/// Only ordinary cells without references and with at most 8 data bits are shared.
+#[inline]
+fn small_cell_index(parts: &CellParts<'_>) -> Option<usize> {
+    // NOTE: `d1 == 0` means an ordinary cell without references and with zero level
+    if parts.descriptor.d1 != 0 || parts.bit_len > 8 {
+        return None;
+    }
+
+    let bit_len = parts.bit_len as usize;
+    let value = match parts.data.first() {
+        Some(byte) if bit_len > 0 => (*byte >> (8 - bit_len)) as usize,
+        _ => 0,
+    };
+    Some((1 << bit_len) - 1 + value)
+}
+
+/// Builds all cells which can be shared, ordered by their index.
+fn make_small_cells<F>(mut finalize: F) -> Box<[Cell]>
+where
+    F: FnMut(CellParts<'_>) -> Cell,
+{
+    let mut cells = Vec::with_capacity(SMALL_CELL_COUNT);
+    for bit_len in 0..=8u16 {
+        let descriptor = CellDescriptor::new([0, CellDescriptor::compute_d2(bit_len)]);
+        let tag = if bit_len < 8 { 0x80 >> bit_len } else { 0 };
+
+        for value in 0..(1u16 << bit_len) {
+            let data = [((value << (8 - bit_len)) as u8) | tag];
+            let parts = CellParts {
+                #[cfg(feature = "stats")]
+                stats: CellTreeStats {
+                    bit_count: bit_len as u64,
+                    cell_count: 1,
+                },
+                bit_len,
+                descriptor,
+                children_mask: LevelMask::EMPTY,
+                references: Default::default(),
+                data: &data[..descriptor.byte_len() as usize],
+            };
+
+            debug_assert_eq!(small_cell_index(&parts), Some(cells.len()));
+            cells.push(finalize(parts));
+        }
+    }
+    debug_assert_eq!(cells.len(), SMALL_CELL_COUNT);
+    cells.into_boxed_slice()
+}
+
 #[cfg(feature = "stats")]
 fn aligned_leaf_stats(descriptor: CellDescriptor) -> CellTreeStats {
     CellTreeStats {
@@ -807,8 +1003,15 @@ fn aligned_leaf_stats(descriptor: CellDescriptor) -> CellTreeStats {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(not(feature = "sync"))]
+    use super::rc::make_cell;
+    #[cfg(feature = "sync")]
+    use super::sync::make_cell;
+    use super::*;
     use crate::boc::Boc;
-    use crate::cell::{Cell, CellBuilder, CellFamily};
+    use crate::cell::{CellBuilder, CellContext, LoadMode, WeakCell};
+    use crate::dict::Dict;
+    use crate::error::Error;
 
     #[test]
     fn static_cells() {
@@ -827,4 +1030,141 @@ mod tests {
         assert_eq!(cell.as_ref().data(), all_ones.data());
         assert_eq!(Boc::encode(cell.as_ref()), Boc::encode(all_ones));
     }
+
+    #[test]
+    fn shared_small_cells() -> anyhow::Result<()> {
+        #[derive(Default)]
+        struct CountingContext {
+            small_cells: Vec<Cell>,
+        }
+
+        impl CellContext for CountingContext {
+            fn finalize_cell(&mut self, cell: CellParts<'_>) -> Result<Cell, Error> {
+                let is_small = small_cell_index(&cell).is_some();
+                let cell = ok!(Cell::empty_context().finalize_cell(cell));
+                if is_small {
+                    // NOTE: keep cells alive to prevent address reuse
+                    self.small_cells.push(cell.clone());
+                }
+                Ok(cell)
+            }
+
+            fn load_cell(&mut self, cell: Cell, _: LoadMode) -> Result<Cell, Error> {
+                Ok(cell)
+            }
+
+            fn load_dyn_cell<'a>(
+                &mut self,
+                cell: &'a DynCell,
+                _: LoadMode,
+            ) -> Result<&'a DynCell, Error> {
+                Ok(cell)
+            }
+        }
+
+        let context = &mut CountingContext::default();
+        let mut dict = Dict::<u32, ()>::new();
+        for i in 0..10000u32 {
+            dict.set_ext(i, (), context)?;
+        }
+        assert_eq!(dict.keys().count(), 10000);
+
+        let allocated = context
+            .small_cells
+            .iter()
+            .map(|cell| cell.as_ref() as *const DynCell as *const ())
+            .collect::<ahash::HashSet<_>>();
+        assert!(context.small_cells.len() >= 10000);
+        assert!(allocated.len() <= SMALL_CELL_COUNT);
+
+        // Shared cells must be the same as the cells built without sharing
+        struct UnsharedContext;
+
+        impl CellContext for UnsharedContext {
+            fn finalize_cell(&mut self, cell: CellParts<'_>) -> Result<Cell, Error> {
+                let hashes = ok!(cell.compute_hashes());
+                // SAFETY: cell parts are always well-formed in this test
+                Ok(unsafe { make_cell(cell, hashes) })
+            }
+
+            fn load_cell(&mut self, cell: Cell, _: LoadMode) -> Result<Cell, Error> {
+                Ok(cell)
+            }
+
+            fn load_dyn_cell<'a>(
+                &mut self,
+                cell: &'a DynCell,
+                _: LoadMode,
+            ) -> Result<&'a DynCell, Error> {
+                Ok(cell)
+            }
+        }
+
+        let mut checked = 0;
+        for bit_len in 0..=8u16 {
+            for value in 0..(1u16 << bit_len) {
+                let mut builder = CellBuilder::new();
+                builder.store_uint(value as u64, bit_len)?;
+                let shared = builder.clone().build()?;
+                let unshared = builder.build_ext(&mut UnsharedContext)?;
+
+                if bit_len > 0 {
+                    assert!(!std::ptr::addr_eq(
+                        shared.as_ref() as *const DynCell,
+                        unshared.as_ref() as *const DynCell
+                    ));
+                }
+                assert_eq!(shared.bit_len(), unshared.bit_len());
+                assert_eq!(shared.data(), unshared.data());
+                assert_eq!(shared.repr_hash(), unshared.repr_hash());
+                checked += 1;
+            }
+        }
+        assert_eq!(checked, SMALL_CELL_COUNT);
+
+        let first = CellBuilder::build_from(true)?;
+        let second = CellBuilder::build_from(true)?;
+        assert!(std::ptr::addr_eq(
+            first.as_ref() as *const DynCell,
+            second.as_ref() as *const DynCell
+        ));
+        assert_eq!(
+            Cell::empty_cell().repr_hash(),
+            CellBuilder::new().build()?.repr_hash()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn weak_cells_cache() -> anyhow::Result<()> {
+        let mut cache = ahash::HashMap::<HashBytes, WeakCell>::default();
+
+        let mut cells = Vec::new();
+        for i in 0..10u32 {
+            let mut builder = CellBuilder::new();
+            builder.store_u32(i)?;
+            let cell = builder.build()?;
+            assert_eq!(cell.strong_count(), 1);
+
+            cache.insert(*cell.repr_hash(), cell.downgrade());
+            assert_eq!(cell.weak_count(), 1);
+            cells.push(cell);
+        }
+
+        // Cache doesn't hold cells
+        for cell in &cells {
+            let cached = cache[cell.repr_hash()].upgrade().unwrap();
+            assert_eq!(cached.as_ref().repr_hash(), cell.repr_hash());
+            assert_eq!(cell.strong_count(), 2);
+        }
+
+        // Drop all strong owners
+        let cloned = cache.values().next().cloned().unwrap();
+        drop(cells);
+        assert!(cache.values().all(|cell| cell.upgrade().is_none()));
+        assert!(cloned.upgrade().is_none());
+
+        Ok(())
+    }
 }
@@ -3,9 +3,12 @@ use std::borrow::Borrow;
 use std::sync::atomic::AtomicUsize;
 use std::sync::{Arc, OnceLock};
 
+#[cfg(feature = "models")]
+use super::StaticCell;
 use super::{
-    EmptyOrdinaryCell, HeaderWithData, LibraryReference, OrdinaryCell, OrdinaryCellHeader,
-    PrunedBranch, PrunedBranchHeader, VirtualCell, ALL_ONES_CELL, ALL_ZEROS_CELL,
+    make_small_cells, small_cell_index, AbsentCell, EmptyOrdinaryCell, HeaderWithData,
+    LibraryReference, OrdinaryCell, OrdinaryCellHeader, PrunedBranch, PrunedBranchHeader,
+    VirtualCell, ALL_ONES_CELL, ALL_ZEROS_CELL,
 };
 use crate::cell::cell_context::{CellContext, CellParts, LoadMode};
 use crate::cell::{CellFamily, CellImpl, CellType, DynCell, HashBytes};
@@ -74,6 +77,75 @@ impl From<Arc<DynCell>> for Cell {
     }
 }
 
+impl Cell {
+    /// Wraps a custom cell implementation.
+    ///
+    /// See [`CellImpl`] for the invariants which the implementation must uphold.
+    #[inline]
+    pub fn from_impl(cell: Arc<DynCell>) -> Self {
+        Self(cell)
+    }
+
+    /// Creates a placeholder for a cell which is not included into the BOC.
+    ///
+    /// Such cells are encoded as absent cell records, so the encoded BOC
+    /// contains only the hash and depth of the original cell.
+    pub fn new_absent(repr_hash: HashBytes, repr_depth: u16) -> Self {
+        Self(Arc::new(AbsentCell::new(repr_hash, repr_depth)))
+    }
+
+    /// Wraps a static cell without building it.
+    #[cfg(feature = "models")]
+    pub(crate) fn from_static(cell: StaticCell) -> Self {
+        Self(Arc::new(cell))
+    }
+
+    /// Creates a new weak reference to this cell.
+    #[inline]
+    pub fn downgrade(&self) -> WeakCell {
+        WeakCell(Arc::downgrade(&self.0))
+    }
+
+    /// Returns the number of strong references to this cell.
+    ///
+    /// NOTE: Shared cells (like the empty cell or small cells
+    /// from the default context) are always referenced by a static.
+    #[inline]
+    pub fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+
+    /// Returns the number of weak references to this cell.
+    #[inline]
+    pub fn weak_count(&self) -> usize {
+        Arc::weak_count(&self.0)
+    }
+}
+
+/// A weak reference to a thread-safe cell.
+///
+/// Doesn't keep the cell alive, so it can be used for caches
+/// which must not prevent cells from being dropped.
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct WeakCell(std::sync::Weak<DynCell>);
+
+impl WeakCell {
+    /// Tries to get a strong reference to the cell.
+    ///
+    /// Returns `None` if the cell was already dropped.
+    #[inline]
+    pub fn upgrade(&self) -> Option<Cell> {
+        self.0.upgrade().map(Cell)
+    }
+}
+
+impl std::fmt::Debug for WeakCell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(WeakCell)")
+    }
+}
+
 impl CellFamily for Cell {
     type EmptyCellContext = EmptyCellContext;
 
@@ -134,6 +206,13 @@ pub struct EmptyCellContext;
 
 impl CellContext for EmptyCellContext {
     fn finalize_cell(&mut self, ctx: CellParts) -> Result<Cell, Error> {
+        if let Some(index) = small_cell_index(&ctx) {
+            static SMALL_CELLS: OnceLock<Box<[Cell]>> = OnceLock::new();
+            let cells = SMALL_CELLS.get_or_init(|| make_small_cells(finalize_small_cell));
+            // SAFETY: index is always in the range of all small cells
+            return Ok(unsafe { cells.get_unchecked(index) }.clone());
+        }
+
         let hashes = ok!(ctx.compute_hashes());
         // SAFETY: ctx now represents a well-formed cell
         Ok(unsafe { make_cell(ctx, hashes) })
@@ -150,7 +229,15 @@ impl CellContext for EmptyCellContext {
     }
 }
 
-unsafe fn make_cell(ctx: CellParts, hashes: Vec<(HashBytes, u16)>) -> Cell {
+fn finalize_small_cell(ctx: CellParts) -> Cell {
+    let hashes = ctx
+        .compute_hashes()
+        .expect("small ordinary cells are always valid");
+    // SAFETY: ctx now represents a well-formed cell
+    unsafe { make_cell(ctx, hashes) }
+}
+
+pub(super) unsafe fn make_cell(ctx: CellParts, hashes: Vec<(HashBytes, u16)>) -> Cell {
     match ctx.descriptor.cell_type() {
         CellType::PrunedBranch => {
             debug_assert!(hashes.len() == 1);
@@ -179,7 +266,7 @@ unsafe fn make_cell(ctx: CellParts, hashes: Vec<(HashBytes, u16)>) -> Cell {
             }))
         }
         CellType::Ordinary if ctx.descriptor.d1 == 0 && ctx.descriptor.d2 == 0 => {
-            Cell(Arc::new(EmptyOrdinaryCell))
+            Cell::empty_cell()
         }
         _ => make_ordinary_cell(
             OrdinaryCellHeader {
@@ -3,20 +3,27 @@
 use std::ops::{BitOr, BitOrAssign};
 use std::str::FromStr;
 
+use self::cell_context::compute_repr_hashes;
 use crate::error::{Error, ParseHashBytesError};
-use crate::util::Bitstring;
+use crate::util::{unlikely, Bitstring};
 
 pub use self::builder::{CellBuilder, CellRefsBuilder, Store};
-pub use self::cell_context::{CellContext, CellParts, LoadMode};
+pub use self::cell_context::{CellContext, CellParts, CellSizeLimits, LoadMode};
 pub use self::cell_impl::{StaticCell, VirtualCellWrapper};
-pub use self::slice::{CellSlice, CellSliceParts, CellSliceRange, CellSliceSize, ExactSize, Load};
+
+#[cfg(test)]
+pub(crate) use self::cell_impl::LoopCell;
+pub use self::slice::{
+    CellSlice, CellSliceParts, CellSliceRange, CellSliceSize, ExactSize, Load, TagDispatch,
+    TagLoader,
+};
 pub use self::usage_tree::{UsageTree, UsageTreeMode, UsageTreeWithSubtrees};
 
 #[cfg(not(feature = "sync"))]
-pub use self::cell_impl::rc::Cell;
+pub use self::cell_impl::rc::{Cell, WeakCell};
 
 #[cfg(feature = "sync")]
-pub use self::cell_impl::sync::Cell;
+pub use self::cell_impl::sync::{Cell, WeakCell};
 
 pub use everscale_types_proc::{Load, Store};
 
@@ -40,6 +47,7 @@ mod __checks {
     use super::*;
 
     assert_impl_all!(Cell: Send);
+    assert_impl_all!(WeakCell: Send, Sync);
     assert_impl_all!(CellSlice: Send);
     assert_impl_all!(CellBuilder: Send);
 }
@@ -102,6 +110,41 @@ impl AsMut<DynCell> for DynCell {
 ///
 /// Since all basic operations are implements via dynamic dispatch,
 /// all high-level helper methods are implemented for `dyn Cell`.
+///
+/// # Custom implementations
+///
+/// This trait can be implemented outside of this crate to provide
+/// a different cell storage (e.g. cells backed by a memory-mapped file).
+/// Such cells can be wrapped into a [`Cell`] with `Cell::from_impl`.
+///
+/// The rest of the crate trusts the implementation, so the following
+/// must be true for every cell:
+/// - [`descriptor`] must be consistent with the content: the reference count
+///   must match the number of children, the data length must match
+///   [`bit_len`] and the level mask must be computed from the children
+///   (or from the data for pruned branches).
+/// - [`data`] must be padded as described there.
+/// - [`hash`] and [`depth`] must return precomputed values for every level
+///   in `0..=3`. Levels which are not present in the level mask must
+///   return the values of the nearest lower present level (the same as
+///   [`LevelMask::hash_index`]).
+/// - [`reference`] and [`reference_cloned`] must return the same cells
+///   for the same index for the whole lifetime of the cell.
+///
+/// Deep drop methods ([`take_first_child`], [`replace_first_child`] and
+/// [`take_next_child`]) can just return `None` or `Err(parent)`
+/// if the implementation doesn't own its children.
+///
+/// [`descriptor`]: CellImpl::descriptor
+/// [`bit_len`]: CellImpl::bit_len
+/// [`data`]: CellImpl::data
+/// [`hash`]: CellImpl::hash
+/// [`depth`]: CellImpl::depth
+/// [`reference`]: CellImpl::reference
+/// [`reference_cloned`]: CellImpl::reference_cloned
+/// [`take_first_child`]: CellImpl::take_first_child
+/// [`replace_first_child`]: CellImpl::replace_first_child
+/// [`take_next_child`]: CellImpl::take_next_child
 pub trait CellImpl {
     /// Returns cell descriptor.
     ///
@@ -118,6 +161,11 @@ pub trait CellImpl {
     fn descriptor(&self) -> CellDescriptor;
 
     /// Returns the raw data of this cell.
+    ///
+    /// Data is padded to the whole number of bytes (`(bit_len + 7) / 8`).
+    /// If the bit length is not a multiple of 8, the last byte contains
+    /// the completion tag: a single `1` bit right after the data bits
+    /// followed by zeros (e.g. `xxxx1000` for 4 bits).
     fn data(&self) -> &[u8];
 
     /// Returns the data size of this cell in bits.
@@ -184,7 +232,12 @@ impl DynCell {
     /// Computes the number of child cells from descriptor bytes.
     #[inline]
     pub fn reference_count(&self) -> u8 {
-        self.descriptor().reference_count()
+        let descriptor = self.descriptor();
+        if unlikely(descriptor.is_absent()) {
+            0
+        } else {
+            descriptor.reference_count()
+        }
     }
 
     /// Tries to load the specified child cell as slice.
@@ -221,6 +274,77 @@ impl DynCell {
         self.hash(LevelMask::MAX_LEVEL) == EMPTY_CELL_HASH
     }
 
+    /// Returns the standard representation bytes of the cell,
+    /// which are hashed to produce [`hash(level)`].
+    ///
+    /// NOTE: Lower hashes of pruned branches are stored in the cell
+    /// data, so the bytes for the representation hash are returned
+    /// for all levels.
+    ///
+    /// [`hash(level)`]: CellImpl::hash
+    pub fn repr_bytes(&self, level: u8) -> Vec<u8> {
+        let hash_index = if self.cell_type().is_pruned_branch() {
+            0
+        } else {
+            self.level_mask().hash_index(level) as usize
+        };
+
+        let mut result = Vec::new();
+        let descriptor = self.descriptor();
+        // NOTE: Cells which are too large to be represented have no bytes
+        _ = compute_repr_hashes(
+            descriptor,
+            descriptor.cell_type(),
+            self.data(),
+            self.references(),
+            |index, bytes| {
+                if index == hash_index {
+                    result.extend_from_slice(bytes);
+                }
+            },
+        );
+        result
+    }
+
+    /// Recomputes all hashes and depths of the cell from its data and
+    /// children and compares them with the stored values.
+    ///
+    /// Can be used to validate cells from untrusted [`CellImpl`]s.
+    pub fn verify_hashes(&self) -> bool {
+        let descriptor = self.descriptor();
+        let Ok(hashes) = compute_repr_hashes(
+            descriptor,
+            descriptor.cell_type(),
+            self.data(),
+            self.references(),
+            |_, _| {},
+        ) else {
+            return false;
+        };
+        let computed = |index: usize| hashes[index];
+
+        if self.cell_type().is_pruned_branch() {
+            let (hash, depth) = computed(0);
+            return self.repr_hash() == &hash && self.repr_depth() == depth;
+        }
+
+        let level_mask = self.level_mask();
+        (0..=LevelMask::MAX_LEVEL).all(|level| {
+            let (hash, depth) = computed(level_mask.hash_index(level) as usize);
+            self.hash(level) == &hash && self.depth(level) == depth
+        })
+    }
+
+    /// Returns the descriptor, raw data and child nodes of this cell.
+    ///
+    /// See [`data`] for the padding of the last data byte.
+    ///
+    /// [`data`]: CellImpl::data
+    #[inline]
+    pub fn raw_parts(&self) -> (CellDescriptor, &[u8], RefsIter<'_>) {
+        (self.descriptor(), self.data(), self.references())
+    }
+
     /// Creates an iterator through child nodes.
     #[inline]
     pub fn references(&self) -> RefsIter<'_> {
@@ -319,6 +443,58 @@ impl DynCell {
         DisplayData(self)
     }
 
+    /// Rebuilds the whole cell tree using the specified cell context.
+    ///
+    /// Cells are deduplicated by their representation hash, so the shared
+    /// subtrees are copied only once. Exotic cells are copied as is with
+    /// their level masks intact.
+    pub fn copy_into_context(&self, context: &mut dyn CellContext) -> Result<Cell, Error> {
+        struct Node<'a> {
+            references: RefsIter<'a>,
+            children: CellRefsBuilder,
+        }
+
+        let mut cells = ahash::HashMap::<&HashBytes, Cell>::default();
+
+        let mut stack = Vec::with_capacity(self.repr_depth() as usize + 1);
+        stack.push(Node {
+            references: self.references(),
+            children: CellRefsBuilder::default(),
+        });
+
+        while let Some(last) = stack.last_mut() {
+            if let Some(child) = last.references.next() {
+                // Reuse already copied cells or process a new child
+                match cells.get(child.repr_hash()) {
+                    Some(cell) => _ = last.children.store_reference(cell.clone()),
+                    None => stack.push(Node {
+                        references: child.references(),
+                        children: CellRefsBuilder::default(),
+                    }),
+                }
+            } else if let Some(last) = stack.pop() {
+                // Build a new cell if there are no child nodes left to process
+                let cell = last.references.cell();
+
+                let mut builder = CellBuilder::new();
+                builder.set_exotic(cell.is_exotic());
+                ok!(builder.store_cell_data(cell));
+                builder.set_references(last.children);
+                let copied = ok!(builder.build_ext(context));
+
+                cells.insert(cell.repr_hash(), copied.clone());
+
+                match stack.last_mut() {
+                    Some(last) => _ = last.children.store_reference(copied),
+                    None => return Ok(copied),
+                }
+            }
+        }
+
+        // Something is wrong if we are here
+        Err(Error::InvalidCell)
+    }
+
     /// Converts this cell into a slice and tries to load the specified type from it.
     ///
     /// NOTE: parsing `Cell` will load the first reference!
@@ -600,6 +776,24 @@ impl HashBytes {
     pub const fn as_ptr(&self) -> *const u8 {
         &self.0 as *const [u8] as *const u8
     }
+
+    /// Returns the hash which must be signed.
+    ///
+    /// Without a prefix returns the hash itself. Otherwise returns
+    /// `sha256(prefix || hash)`, e.g. for TON Connect style domain separation.
+    pub fn signable_hash(&self, prefix: Option<&[u8]>) -> Self {
+        use sha2::Digest;
+
+        match prefix {
+            None => *self,
+            Some(prefix) => {
+                let mut hasher = sha2::Sha256::new();
+                hasher.update(prefix);
+                hasher.update(self.0);
+                hasher.finalize().into()
+            }
+        }
+    }
 }
 
 impl Default for HashBytes {
@@ -877,6 +1071,9 @@ pub enum CellType {
     MerkleProof,
     /// Exotic cell with two hashes and two references.
     MerkleUpdate,
+    /// Placeholder for a cell which is not included into the BOC.
+    /// Stores only the representation hash and depth of the original cell.
+    Absent,
 }
 
 impl CellType {
@@ -907,6 +1104,8 @@ impl CellType {
             CellType::LibraryReference => 2,
             CellType::MerkleProof => 3,
             CellType::MerkleUpdate => 4,
+            // NOTE: absent cells are not stored with a type byte
+            CellType::Absent => 0,
         }
     }
 
@@ -999,12 +1198,16 @@ impl CellDescriptor {
                     }
                 }
                 1 => CellType::MerkleProof,
+                Self::REF_COUNT_MASK if self.is_absent() => CellType::Absent,
                 _ => CellType::MerkleUpdate,
             }
         }
     }
 
     /// Computes child cell count.
+    ///
+    /// NOTE: Absent cells have all reference count bits set,
+    /// so they must be handled separately.
     #[inline(always)]
     pub const fn reference_count(self) -> u8 {
         self.d1 & Self::REF_COUNT_MASK
@@ -1509,7 +1712,66 @@ pub const MAX_REF_COUNT: usize = 4;
 
 #[cfg(test)]
 mod tests {
+    use sha2::Digest;
+
     use super::*;
+    use crate::boc::Boc;
+    use crate::merkle::{MerkleProof, MerkleUpdate};
+
+    #[test]
+    fn repr_bytes_and_hashes() -> anyhow::Result<()> {
+        fn check_tree(root: &DynCell) {
+            let mut stack = vec![root];
+            while let Some(cell) = stack.pop() {
+                assert!(cell.verify_hashes());
+
+                let levels = if cell.cell_type().is_pruned_branch() {
+                    cell.level()..=cell.level()
+                } else {
+                    0..=LevelMask::MAX_LEVEL
+                };
+                for level in levels {
+                    let hash = sha2::Sha256::digest(cell.repr_bytes(level));
+                    assert_eq!(cell.hash(level).as_slice(), hash.as_slice());
+                }
+
+                stack.extend(cell.references());
+            }
+        }
+
+        // Ordinary cells
+        let old = Boc::decode(include_bytes!("../merkle/tests/simple_proof.boc"))?;
+        check_tree(old.as_ref());
+
+        // Merkle proof with pruned branches
+        let child_hash = old.as_ref().reference(1).unwrap().repr_hash();
+        let proof = MerkleProof::create_for_cell(old.as_ref(), child_hash).build()?;
+        let proof = CellBuilder::build_from(proof)?;
+        assert_eq!(proof.cell_type(), CellType::MerkleProof);
+        check_tree(proof.as_ref());
+
+        // Merkle update
+        let mut builder = CellBuilder::new();
+        builder.store_u32(123)?;
+        builder.store_reference(old.as_ref().reference_cloned(1).unwrap())?;
+        let new = builder.build()?;
+
+        let update = MerkleUpdate::create(
+            old.as_ref(),
+            new.as_ref(),
+            ahash::HashSet::<HashBytes>::default(),
+        )
+        .build()?;
+        let update = CellBuilder::build_from(update)?;
+        assert_eq!(update.cell_type(), CellType::MerkleUpdate);
+        check_tree(update.as_ref());
+
+        // Cell with an invalid hash
+        static INVALID: StaticCell = unsafe { StaticCell::new(&[0x80], 0, &[0; 32]) };
+        assert!(!(&INVALID as &DynCell).verify_hashes());
+
+        Ok(())
+    }
 
     #[test]
     fn correct_level() {
@@ -1544,6 +1806,90 @@ mod tests {
         }
     }
 
+    #[test]
+    fn copy_into_context() {
+        let proof =
+            crate::boc::Boc::decode(include_bytes!("../models/block/tests/mc_block_proof.boc"))
+                .unwrap();
+        let merkle_proof = proof.reference(0).unwrap();
+        assert_eq!(merkle_proof.cell_type(), CellType::MerkleProof);
+
+        let copied = proof.copy_into_context(&mut Cell::empty_context()).unwrap();
+        assert_eq!(copied.as_ref(), proof.as_ref());
+        assert_eq!(
+            copied.reference(0).unwrap().cell_type(),
+            CellType::MerkleProof
+        );
+        assert_eq!(
+            copied.compute_unique_stats(usize::MAX),
+            proof.compute_unique_stats(usize::MAX)
+        );
+
+        // Shared subtrees must be copied only once
+        let shared = {
+            let mut builder = CellBuilder::new();
+            builder.store_u32(123).unwrap();
+            builder.build().unwrap()
+        };
+        let root = {
+            let mut builder = CellBuilder::new();
+            builder.store_reference(shared.clone()).unwrap();
+            builder.store_reference(shared).unwrap();
+            builder.build().unwrap()
+        };
+        let copied = root.copy_into_context(&mut Cell::empty_context()).unwrap();
+        assert_eq!(copied.repr_hash(), root.repr_hash());
+
+        let left = copied.reference(0).unwrap() as *const DynCell as *const ();
+        let right = copied.reference(1).unwrap() as *const DynCell as *const ();
+        assert_eq!(left, right);
+    }
+
+    #[test]
+    fn raw_parts_round_trip() {
+        fn rebuild(cell: &DynCell) -> Cell {
+            let (descriptor, data, refs) = cell.raw_parts();
+            let mut builder = CellBuilder::new();
+            builder.set_exotic(descriptor.is_exotic());
+            builder.store_raw(data, cell.bit_len()).unwrap();
+            for child in refs.cloned() {
+                builder.store_reference(child).unwrap();
+            }
+            builder.build().unwrap()
+        }
+
+        for bits in [0, 1, 7, 8, 9, 255, 1023] {
+            let mut builder = CellBuilder::new();
+            builder.store_ones(bits).unwrap();
+            let cell = builder.build().unwrap();
+
+            let data = cell.data();
+            assert_eq!(data.len(), (bits as usize).div_ceil(8));
+            if bits % 8 != 0 {
+                // Completion tag
+                assert_eq!(
+                    *data.last().unwrap(),
+                    0xffu8 << (8 - bits % 8) | 1 << (7 - bits % 8)
+                );
+            }
+
+            assert_eq!(rebuild(cell.as_ref()).as_ref(), cell.as_ref());
+        }
+
+        // Exotic cells
+        let pruned = crate::merkle::make_pruned_branch(
+            Cell::empty_cell_ref(),
+            0,
+            &mut Cell::empty_context(),
+        )
+        .unwrap();
+        let rebuilt = rebuild(pruned.as_ref());
+        assert_eq!(rebuilt.cell_type(), CellType::PrunedBranch);
+        assert_eq!(rebuilt.repr_hash(), pruned.repr_hash());
+        assert_eq!(rebuilt.hash(0), pruned.hash(0));
+        assert_eq!(rebuilt.depth(0), pruned.depth(0));
+    }
+
     #[test]
     fn ultra_virtual_cell_by_ref() {
         let cell = Cell::empty_cell();
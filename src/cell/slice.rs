@@ -2,6 +2,8 @@ use std::num::{NonZeroU16, NonZeroU32, NonZeroU8};
 use std::rc::Rc;
 use std::sync::Arc;
 
+use smallvec::SmallVec;
+
 use crate::cell::{
     Cell, CellTreeStats, CellType, DynCell, HashBytes, LevelMask, RefsIter, StorageStat,
     MAX_BIT_LEN, MAX_REF_COUNT,
@@ -158,6 +160,20 @@ impl_primitive_loads! {
         Err(e) => Err(e),
     },
     HashBytes => |s| s.load_u256(),
+    std::net::Ipv4Addr => |s| s.load_u32().map(std::net::Ipv4Addr::from),
+    std::net::Ipv6Addr => |s| s.load_u128().map(std::net::Ipv6Addr::from),
+}
+
+impl<const N: usize> Load<'_> for [u8; N] {
+    #[inline]
+    fn load_from(slice: &mut CellSlice) -> Result<Self, Error> {
+        if N > 128 {
+            return Err(Error::CellUnderflow);
+        }
+        let mut result = [0; N];
+        ok!(slice.load_raw(&mut result, (N * 8) as u16));
+        Ok(result)
+    }
 }
 
 impl<'a> Load<'a> for &'a DynCell {
@@ -186,6 +202,12 @@ impl ExactSize for Cell {
     }
 }
 
+/// Tag of a cell slice serialized as a TVM stack value.
+pub(crate) const STACK_SLICE_TAG: u8 = 0x04;
+
+/// Number of bits of a cell slice serialized as a TVM stack value.
+pub(crate) const STACK_SLICE_BITS: u16 = 8 + 10 + 10 + 3 + 3;
+
 /// Owned cell slice parts alias.
 pub type CellSliceParts = (Cell, CellSliceRange);
 
@@ -358,6 +380,44 @@ impl CellSliceRange {
         }
     }
 
+    /// Returns a slice range containing exactly the first `bits` of data
+    /// and `refs` of children.
+    pub fn only_first(&self, bits: u16, refs: u8) -> Result<Self, Error> {
+        if self.has_remaining(bits, refs) {
+            Ok(Self {
+                bits_start: self.bits_start,
+                bits_end: self.bits_start + bits,
+                refs_start: self.refs_start,
+                refs_end: self.refs_start + refs,
+            })
+        } else {
+            Err(Error::CellUnderflow)
+        }
+    }
+
+    /// Returns a slice range containing exactly the last `bits` of data
+    /// and `refs` of children.
+    pub fn only_last(&self, bits: u16, refs: u8) -> Result<Self, Error> {
+        if self.has_remaining(bits, refs) {
+            Ok(Self {
+                bits_start: self.bits_end - bits,
+                bits_end: self.bits_end,
+                refs_start: self.refs_end - refs,
+                refs_end: self.refs_end,
+            })
+        } else {
+            Err(Error::CellUnderflow)
+        }
+    }
+
+    /// Returns whether the range is within the cell bounds.
+    pub fn is_within(&self, cell: &DynCell) -> bool {
+        self.bits_start <= self.bits_end
+            && self.refs_start <= self.refs_end
+            && self.bits_end <= cell.bit_len()
+            && self.refs_end <= cell.reference_count()
+    }
+
     /// Returns whether this range has the same size as the cell.
     #[inline]
     pub fn is_full(&self, cell: &DynCell) -> bool {
@@ -684,6 +744,28 @@ impl<'a> CellSlice<'a> {
         }
     }
 
+    /// Returns a slice containing exactly the first `bits` of data
+    /// and `refs` of children.
+    ///
+    /// Fails with [`Error::CellUnderflow`] if there are not enough bits or refs.
+    pub fn only_first(&self, bits: u16, refs: u8) -> Result<Self, Error> {
+        Ok(Self {
+            cell: self.cell,
+            range: ok!(self.range.only_first(bits, refs)),
+        })
+    }
+
+    /// Returns a slice containing exactly the last `bits` of data
+    /// and `refs` of children.
+    ///
+    /// Fails with [`Error::CellUnderflow`] if there are not enough bits or refs.
+    pub fn only_last(&self, bits: u16, refs: u8) -> Result<Self, Error> {
+        Ok(Self {
+            cell: self.cell,
+            range: ok!(self.range.only_last(bits, refs)),
+        })
+    }
+
     /// Shrinks the slice down to a prefix of the specified length.
     pub fn shrink(&mut self, bits: Option<u16>, refs: Option<u8>) -> Result<(), Error> {
         let bits = bits.unwrap_or_else(|| self.remaining_bits());
@@ -1346,6 +1428,26 @@ impl<'a> CellSlice<'a> {
         res
     }
 
+    /// Reads a constructor tag of the specified number of `bits` (0..=64)
+    /// without advancing the bits window start.
+    #[inline]
+    pub fn peek_tag(&self, bits: u16) -> Result<u64, Error> {
+        self.get_uint(0, bits)
+    }
+
+    /// Loads a constructor tag of the specified number of `bits` (0..=64)
+    /// and checks that it is equal to the expected `tag`.
+    ///
+    /// The slice is left unchanged if the tag doesn't match.
+    pub fn load_prefix_tag(&mut self, tag: u64, bits: u16) -> Result<(), Error> {
+        let found = ok!(self.peek_tag(bits));
+        if found != tag {
+            return Err(Error::InvalidTag(found));
+        }
+        self.range.bits_start += bits;
+        Ok(())
+    }
+
     /// Reads the specified number of bits to the target starting from the `offset`.
     pub fn get_raw<'b>(
         &'_ self,
@@ -1517,6 +1619,87 @@ impl<'a> CellSlice<'a> {
         }
     }
 
+    /// Loads a cell slice serialized as a TVM stack value.
+    ///
+    /// Returns the referenced cell along with the slice range.
+    ///
+    /// # TLB scheme
+    ///
+    /// ```text
+    /// _ cell:^Cell st_bits:(## 10) end_bits:(## 10) { st_bits <= end_bits }
+    ///   st_ref:(#<= 4) end_ref:(#<= 4) { st_ref <= end_ref } = VmCellSlice;
+    ///
+    /// vm_stk_slice#04 _:VmCellSlice = VmStackValue;
+    /// ```
+    pub fn load_stack_slice(&mut self) -> Result<CellSliceParts, Error> {
+        if !self.has_remaining(STACK_SLICE_BITS, 1) {
+            return Err(Error::CellUnderflow);
+        }
+
+        let mut slice = *self;
+        let tag = ok!(slice.load_u8());
+        if tag != STACK_SLICE_TAG {
+            return Err(Error::InvalidTag(tag.into()));
+        }
+
+        let cell = ok!(slice.load_reference_cloned());
+        let range = CellSliceRange {
+            bits_start: ok!(slice.load_uint(10)) as u16,
+            bits_end: ok!(slice.load_uint(10)) as u16,
+            refs_start: ok!(slice.load_small_uint(3)),
+            refs_end: ok!(slice.load_small_uint(3)),
+        };
+        if !range.is_within(cell.as_ref()) {
+            return Err(Error::InvalidData);
+        }
+
+        *self = slice;
+        Ok((cell, range))
+    }
+
+    /// Returns the next `N` child cells (relative to this slice's refs window),
+    /// incrementing the refs window start.
+    ///
+    /// NOTE: The slice remains unchanged if there are not enough references.
+    pub fn load_references<const N: usize>(&mut self) -> Result<[Cell; N], Error> {
+        let start = self.range.refs_start;
+        if unlikely(
+            N > (self.range.refs_end - start) as usize
+                || start as usize + N > self.cell.reference_count() as usize,
+        ) {
+            return Err(Error::CellUnderflow);
+        }
+
+        let mut underflow = false;
+        let refs = std::array::from_fn(|i| match self.cell.reference_cloned(start + i as u8) {
+            Some(cell) => cell,
+            None => {
+                underflow = true;
+                Cell::empty_cell()
+            }
+        });
+        if unlikely(underflow) {
+            return Err(Error::CellUnderflow);
+        }
+
+        self.range.refs_start += N as u8;
+        Ok(refs)
+    }
+
+    /// Returns all remaining child cells (relative to this slice's refs window),
+    /// moving the refs window start to the end.
+    pub fn load_remaining_refs(&mut self) -> SmallVec<[Cell; MAX_REF_COUNT]> {
+        let mut refs = SmallVec::new();
+        while self.range.refs_start < self.range.refs_end {
+            match self.cell.reference_cloned(self.range.refs_start) {
+                Some(cell) => refs.push(cell),
+                None => break,
+            }
+            self.range.refs_start += 1;
+        }
+        refs
+    }
+
     /// Tries to load the next child cell as slice.
     /// Returns an error if the loaded cell is absent or is pruned.
     ///
@@ -1569,6 +1752,62 @@ impl<'a> CellSlice<'a> {
     }
 }
 
+/// A loader function used in [`TagDispatch`].
+pub type TagLoader<T> = for<'a> fn(&mut CellSlice<'a>) -> Result<T, Error>;
+
+/// A table of loaders selected by the constructor tag.
+///
+/// # Example
+///
+/// ```
+/// # use everscale_types::cell::{CellBuilder, TagDispatch};
+/// # use everscale_types::error::Error;
+/// #[derive(Debug, PartialEq)]
+/// enum Value {
+///     Small(u8),
+///     Large(u64),
+/// }
+///
+/// const DISPATCH: TagDispatch<Value> = TagDispatch::new(4, &[
+///     (0x1, |slice| Ok(Value::Small(slice.load_u8()?))),
+///     (0x2, |slice| Ok(Value::Large(slice.load_u64()?))),
+/// ]);
+///
+/// let mut builder = CellBuilder::new();
+/// builder.store_small_uint(0x1, 4)?;
+/// builder.store_u8(123)?;
+/// let cell = builder.build()?;
+///
+/// assert_eq!(DISPATCH.load(&mut cell.as_slice()?)?, Value::Small(123));
+/// # Ok::<_, Error>(())
+/// ```
+pub struct TagDispatch<T: 'static> {
+    bits: u16,
+    loaders: &'static [(u64, TagLoader<T>)],
+}
+
+impl<T> TagDispatch<T> {
+    /// Creates a new dispatch table for tags of the specified number of `bits` (0..=64).
+    pub const fn new(bits: u16, loaders: &'static [(u64, TagLoader<T>)]) -> Self {
+        Self { bits, loaders }
+    }
+
+    /// Loads the constructor tag and calls the corresponding loader.
+    ///
+    /// Returns [`Error::InvalidTag`] if there is no loader for the tag.
+    /// The slice is left unchanged in that case.
+    pub fn load(&self, slice: &mut CellSlice<'_>) -> Result<T, Error> {
+        let tag = ok!(slice.peek_tag(self.bits));
+        for (expected, loader) in self.loaders {
+            if *expected == tag {
+                slice.range.bits_start += self.bits;
+                return loader(slice);
+            }
+        }
+        Err(Error::InvalidTag(tag))
+    }
+}
+
 impl ExactSize for CellSlice<'_> {
     #[inline]
     fn exact_size(&self) -> CellSliceSize {
@@ -1743,6 +1982,7 @@ impl std::ops::SubAssign for CellSliceSize {
 
 #[cfg(test)]
 mod tests {
+    use crate::cell::TagDispatch;
     use crate::error::Error;
     use crate::prelude::*;
 
@@ -1915,6 +2155,83 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn load_tags() -> anyhow::Result<()> {
+        let cell = build_cell(|b| {
+            b.store_u32(0xdeadbeef)?;
+            b.store_small_uint(0b101, 3)
+        });
+
+        let mut slice = cell.as_slice()?;
+        assert_eq!(slice.peek_tag(32), Ok(0xdeadbeef));
+        assert_eq!(slice.remaining_bits(), 35);
+
+        // Incorrect tag
+        assert_eq!(
+            slice.load_prefix_tag(0xdeadbeee, 32),
+            Err(Error::InvalidTag(0xdeadbeef))
+        );
+        assert_eq!(slice.remaining_bits(), 35);
+
+        // Correct tags
+        slice.load_prefix_tag(0xdeadbeef, 32)?;
+        assert_eq!(slice.remaining_bits(), 3);
+        slice.load_prefix_tag(0b1, 1)?;
+        assert_eq!(slice.peek_tag(2), Ok(0b01));
+
+        // Truncated tag
+        assert_eq!(slice.peek_tag(4), Err(Error::CellUnderflow));
+        assert_eq!(slice.load_prefix_tag(0b010, 3), Err(Error::CellUnderflow));
+        assert_eq!(slice.remaining_bits(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tag_dispatch() -> anyhow::Result<()> {
+        const DISPATCH: TagDispatch<u16> = TagDispatch::new(
+            4,
+            &[
+                (0x1, |slice| Ok(slice.load_u8()? as u16)),
+                (0x2, |slice| slice.load_u16()),
+            ],
+        );
+
+        let cell = build_cell(|b| {
+            b.store_small_uint(0x2, 4)?;
+            b.store_u16(0xabcd)
+        });
+        assert_eq!(DISPATCH.load(&mut cell.as_slice()?), Ok(0xabcd));
+
+        let cell = build_cell(|b| {
+            b.store_small_uint(0x1, 4)?;
+            b.store_u8(0xab)
+        });
+        assert_eq!(DISPATCH.load(&mut cell.as_slice()?), Ok(0xab));
+
+        // Unknown tag
+        let cell = build_cell(|b| b.store_small_uint(0x3, 4));
+        let mut slice = cell.as_slice()?;
+        assert_eq!(DISPATCH.load(&mut slice), Err(Error::InvalidTag(0x3)));
+        assert_eq!(slice.remaining_bits(), 4);
+
+        // Truncated tag
+        let cell = build_cell(|b| b.store_small_uint(0x1, 3));
+        assert_eq!(
+            DISPATCH.load(&mut cell.as_slice()?),
+            Err(Error::CellUnderflow)
+        );
+
+        // Truncated body
+        let cell = build_cell(|b| b.store_small_uint(0x2, 4));
+        assert_eq!(
+            DISPATCH.load(&mut cell.as_slice()?),
+            Err(Error::CellUnderflow)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_uniform() -> anyhow::Result<()> {
         let cell = build_cell(|b| b.store_zeros(10));
@@ -1998,4 +2315,132 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn load_references() -> anyhow::Result<()> {
+        let children = (0..3u8)
+            .map(|i| build_cell(|b| b.store_u8(i)))
+            .collect::<Vec<_>>();
+        let cell = build_cell(|b| {
+            for child in &children {
+                ok!(b.store_reference(child.clone()));
+            }
+            Ok(())
+        });
+
+        let mut slice = cell.as_slice()?;
+        let [first, second] = slice.load_references()?;
+        assert_eq!(first, children[0]);
+        assert_eq!(second, children[1]);
+        assert_eq!(slice.remaining_refs(), 1);
+
+        // Not enough references
+        assert!(matches!(
+            slice.load_references::<2>(),
+            Err(Error::CellUnderflow)
+        ));
+        assert_eq!(slice.remaining_refs(), 1);
+
+        let [] = slice.load_references()?;
+        assert_eq!(slice.load_remaining_refs().as_slice(), &children[2..]);
+        assert_eq!(slice.remaining_refs(), 0);
+        assert!(slice.load_remaining_refs().is_empty());
+
+        let mut slice = cell.as_slice()?;
+        slice.advance(0, 1)?;
+        assert_eq!(slice.load_remaining_refs().as_slice(), &children[1..]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn exact_windows() -> anyhow::Result<()> {
+        let child = Cell::empty_cell();
+        let cell = build_cell(|b| {
+            ok!(b.store_u32(0xdeadbeef));
+            ok!(b.store_reference(child.clone()));
+            b.store_reference(child.clone())
+        });
+        let mut slice = cell.as_slice()?;
+        slice.advance(4, 0)?;
+
+        let first = slice.only_first(8, 1)?;
+        assert_eq!(first.bits_offset(), 4);
+        assert_eq!((first.remaining_bits(), first.remaining_refs()), (8, 1));
+        assert_eq!(first.get_u8(0)?, 0xea);
+
+        let last = slice.only_last(8, 1)?;
+        assert_eq!(last.bits_offset(), 24);
+        assert_eq!(last.refs_offset(), 1);
+        assert_eq!(last.get_u8(0)?, 0xef);
+
+        assert_eq!(slice.only_first(28, 2)?, slice);
+        assert_eq!(slice.only_last(28, 2)?, slice);
+        assert!(slice.only_first(0, 0)?.is_data_empty());
+
+        // Windows are exact
+        assert!(matches!(slice.only_first(29, 0), Err(Error::CellUnderflow)));
+        assert!(matches!(slice.only_last(0, 3), Err(Error::CellUnderflow)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn stack_slice_value() -> anyhow::Result<()> {
+        let child = build_cell(|b| b.store_u8(0xaa));
+        let cell = build_cell(|b| {
+            ok!(b.store_u32(0xdeadbeef));
+            b.store_reference(child.clone())
+        });
+
+        let range = cell.as_slice()?.range();
+        let mut range = range.only_last(24, 1)?;
+
+        // vm_stk_slice#04 st_bits:8 end_bits:32 st_ref:0 end_ref:1
+        let expected = {
+            let mut b = CellBuilder::from_raw_data(&[0x04, 0x02, 0x02, 0x00, 0x40], 34)?;
+            b.store_reference(cell.clone())?;
+            b.build()?
+        };
+
+        let mut builder = CellBuilder::new();
+        builder.store_slice_as_stack_value(&(cell.clone(), range))?;
+        let stored = builder.build()?;
+        assert_eq!(stored, expected);
+
+        let mut slice = stored.as_slice()?;
+        let (parsed_cell, parsed_range) = slice.load_stack_slice()?;
+        assert!(slice.is_data_empty() && slice.is_refs_empty());
+        assert_eq!(parsed_cell, cell);
+        assert_eq!(parsed_range, range);
+
+        let parsed = parsed_range.apply(&parsed_cell)?;
+        assert_eq!((parsed.remaining_bits(), parsed.remaining_refs()), (24, 1));
+        assert_eq!(parsed.get_u16(0)?, 0xadbe);
+
+        // Invalid tag
+        let mut b = CellBuilder::from_raw_data(&[0x05, 0x02, 0x02, 0x00, 0x40], 34)?;
+        b.store_reference(cell.clone())?;
+        let invalid = b.build()?;
+        assert!(matches!(
+            invalid.as_slice()?.load_stack_slice(),
+            Err(Error::InvalidTag(0x05))
+        ));
+
+        // Range out of cell bounds (end_bits:40)
+        let mut b = CellBuilder::from_raw_data(&[0x04, 0x02, 0x02, 0x80, 0x40], 34)?;
+        b.store_reference(cell.clone())?;
+        let invalid = b.build()?;
+        let mut slice = invalid.as_slice()?;
+        assert!(matches!(slice.load_stack_slice(), Err(Error::InvalidData)));
+        assert_eq!((slice.remaining_bits(), slice.remaining_refs()), (34, 1));
+
+        range = CellSliceRange::full(child.as_ref());
+        assert!(matches!(
+            CellBuilder::new().store_slice_as_stack_value(&(Cell::empty_cell(), range)),
+            Err(Error::InvalidData)
+        ));
+
+        Ok(())
+    }
 }
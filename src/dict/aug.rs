@@ -1,14 +1,14 @@
 use std::borrow::Borrow;
 use std::marker::PhantomData;
 
-use super::{aug_dict_insert, aug_dict_remove_owned, SetMode};
+use super::{aug_dict_insert, aug_dict_merge, aug_dict_remove_owned, aug_dict_split_at, SetMode};
 use crate::cell::*;
 use crate::error::*;
 use crate::util::*;
 
 use super::raw::*;
 use super::typed::*;
-use super::{read_label, DictKey};
+use super::{read_label, DictCursor, DictKey};
 
 /// A trait for values that can be used as augmented values in an augmented dictionary.
 pub trait AugDictExtra: Default {
@@ -151,6 +151,17 @@ where
     K: DictKey,
     for<'a> A: Default + Load<'a>,
 {
+    fn from_dict(dict: Dict<K, (A, V)>) -> Result<Self, Error> {
+        let mut result = Self {
+            dict,
+            extra: A::default(),
+            _key: PhantomData,
+            _value: PhantomData,
+        };
+        ok!(result.update_root_extra());
+        Ok(result)
+    }
+
     fn update_root_extra(&mut self) -> Result<(), Error> {
         self.extra = match &self.dict.root {
             Some(root) => {
@@ -167,6 +178,116 @@ where
     }
 }
 
+/// Combines the extra values of all entries of the subtree within the bounds.
+///
+/// Bounds contain the remaining key bits and a flag whether the key itself
+/// is included. `None` means that the subtree is not limited from that side.
+fn aggregate_subtree<A>(
+    node: &DynCell,
+    key_bit_len: u16,
+    mut lower: Option<(CellSlice<'_>, bool)>,
+    mut upper: Option<(CellSlice<'_>, bool)>,
+    result: &mut Option<A>,
+    context: &mut dyn CellContext,
+) -> Result<(), Error>
+where
+    for<'a> A: AugDictExtra + Store + Load<'a>,
+{
+    use std::cmp::Ordering;
+
+    fn cmp_label(label: &CellSlice<'_>, key: &mut CellSlice<'_>) -> Result<Ordering, Error> {
+        let lcp_len = label.longest_common_data_prefix(key).remaining_bits();
+        if lcp_len == label.remaining_bits() {
+            key.try_advance(lcp_len, 0);
+            Ok(Ordering::Equal)
+        } else if ok!(label.get_bit(lcp_len)) {
+            Ok(Ordering::Greater)
+        } else {
+            Ok(Ordering::Less)
+        }
+    }
+
+    // TODO: change mode to `LoadMode::UseGas` if copy-on-write for libraries is not ok
+    let node = ok!(context.load_dyn_cell(node, LoadMode::Full));
+    let mut data = ok!(node.as_slice());
+    let label = ok!(read_label(&mut data, key_bit_len));
+
+    // Skip subtrees outside of the range, and stop tracking
+    // the bounds which no longer affect the subtree
+    if let Some((key, _)) = &mut lower {
+        match ok!(cmp_label(&label, key)) {
+            Ordering::Less => return Ok(()),
+            Ordering::Greater => lower = None,
+            Ordering::Equal => {}
+        }
+    }
+    if let Some((key, _)) = &mut upper {
+        match ok!(cmp_label(&label, key)) {
+            Ordering::Greater => return Ok(()),
+            Ordering::Less => upper = None,
+            Ordering::Equal => {}
+        }
+    }
+
+    if label.remaining_bits() == key_bit_len {
+        // Leaf with the bound key itself
+        if matches!(lower, Some((_, false))) || matches!(upper, Some((_, false))) {
+            return Ok(());
+        }
+    } else if lower.is_some() || upper.is_some() {
+        // Fork which is only partially covered by the range
+        let child_key_bit_len = key_bit_len - label.remaining_bits() - 1;
+        for bit in [false, true] {
+            let mut child_lower = lower;
+            if let Some((key, _)) = &mut child_lower {
+                match ok!(key.load_bit()).cmp(&bit) {
+                    Ordering::Less => child_lower = None,
+                    Ordering::Greater => continue,
+                    Ordering::Equal => {}
+                }
+            }
+            let mut child_upper = upper;
+            if let Some((key, _)) = &mut child_upper {
+                match ok!(key.load_bit()).cmp(&bit) {
+                    Ordering::Greater => child_upper = None,
+                    Ordering::Less => continue,
+                    Ordering::Equal => {}
+                }
+            }
+
+            ok!(aggregate_subtree(
+                ok!(data.get_reference(bit as u8)),
+                child_key_bit_len,
+                child_lower,
+                child_upper,
+                result,
+                context,
+            ));
+        }
+        return Ok(());
+    } else {
+        // Fork which is fully covered by the range
+        ok!(data.advance(0, 2));
+    }
+
+    *result = Some(match result.take() {
+        None => ok!(A::load_from(&mut data)),
+        Some(prev) => {
+            let mut left = CellBuilder::new();
+            ok!(prev.store_into(&mut left, context));
+            let mut builder = CellBuilder::new();
+            ok!(A::comp_add(
+                &mut left.as_full_slice(),
+                &mut data,
+                &mut builder,
+                context
+            ));
+            ok!(A::load_from(&mut builder.as_full_slice()))
+        }
+    });
+    Ok(())
+}
+
 fn load_from_root<'a, A, V>(
     slice: &mut CellSlice<'a>,
     key_bit_len: u16,
@@ -245,6 +366,110 @@ where
     {
         self.dict.get(key)
     }
+
+    /// Returns only the augmented value corresponding to the key.
+    ///
+    /// The value itself is not parsed.
+    pub fn get_aug<'a: 'b, 'b, Q>(&'a self, key: Q) -> Result<Option<A>, Error>
+    where
+        Q: Borrow<K> + 'b,
+        A: Load<'a>,
+    {
+        match ok!(self.dict.get_raw(key)) {
+            Some(mut value) => match A::load_from(&mut value) {
+                Ok(aug) => Ok(Some(aug)),
+                Err(e) => Err(e),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+impl<K, A, V> AugDict<K, A, V>
+where
+    K: DictKey,
+{
+    /// Finds the entry by following the child with the minimal
+    /// augmented value at each fork. Takes `O(depth)` steps.
+    ///
+    /// Returns the entry with the minimal augmented value if the
+    /// augmented value of each fork is the minimum of its children.
+    pub fn find_min_aug<'a>(&'a self) -> Result<Option<(K, A, V)>, Error>
+    where
+        A: Ord + Load<'a>,
+        V: Load<'a>,
+    {
+        self.find_aug_impl(false)
+    }
+
+    /// Finds the entry by following the child with the maximal
+    /// augmented value at each fork. Takes `O(depth)` steps.
+    ///
+    /// Returns the entry with the maximal augmented value if the
+    /// augmented value of each fork is the maximum of its children.
+    pub fn find_max_aug<'a>(&'a self) -> Result<Option<(K, A, V)>, Error>
+    where
+        A: Ord + Load<'a>,
+        V: Load<'a>,
+    {
+        self.find_aug_impl(true)
+    }
+
+    fn find_aug_impl<'a>(&'a self, max: bool) -> Result<Option<(K, A, V)>, Error>
+    where
+        A: Ord + Load<'a>,
+        V: Load<'a>,
+    {
+        let Some(root) = self.dict.root() else {
+            return Ok(None);
+        };
+
+        let mut key = CellBuilder::new();
+        let mut data = ok!(root.as_slice());
+        let mut key_bit_len = K::BITS;
+        loop {
+            let label = ok!(read_label(&mut data, key_bit_len));
+            ok!(key.store_slice_data(label));
+
+            let label_len = label.remaining_bits();
+            if label_len == key_bit_len {
+                break;
+            }
+            key_bit_len -= label_len + 1;
+
+            // Pick the child with the best extra (the left one on ties)
+            let left = ok!(data.get_reference_as_slice(0));
+            let right = ok!(data.get_reference_as_slice(1));
+            let left_extra = ok!(load_node_extra::<A>(left, key_bit_len));
+            let right_extra = ok!(load_node_extra::<A>(right, key_bit_len));
+
+            let bit = if max {
+                right_extra > left_extra
+            } else {
+                right_extra < left_extra
+            };
+            ok!(key.store_bit(bit));
+            data = if bit { right } else { left };
+        }
+
+        let extra = ok!(A::load_from(&mut data));
+        let value = ok!(V::load_from(&mut data));
+        match K::from_raw_data(key.raw_data()) {
+            Some(key) => Ok(Some((key, extra, value))),
+            None => Err(Error::CellUnderflow),
+        }
+    }
+}
+
+fn load_node_extra<'a, A: Load<'a>>(
+    mut slice: CellSlice<'a>,
+    key_bit_len: u16,
+) -> Result<A, Error> {
+    let label = ok!(read_label(&mut slice, key_bit_len));
+    if label.remaining_bits() != key_bit_len && !slice.try_advance(0, 2) {
+        return Err(Error::CellUnderflow);
+    }
+    A::load_from(&mut slice)
 }
 
 impl<K, A, V> AugDict<K, A, V>
@@ -327,6 +552,66 @@ where
         )
     }
 
+    /// Recomputes the augmented value of the entry with the specified key
+    /// using `f`, keeping the value itself unchanged.
+    /// Only nodes on the path to the entry are rebuilt.
+    ///
+    /// Returns `false` if there was no entry with the specified key.
+    ///
+    /// Use [`update_aug_ext`] if you need to use a custom cell context.
+    ///
+    /// [`update_aug_ext`]: AugDict::update_aug_ext
+    pub fn update_aug<Q, F>(&mut self, key: Q, f: F) -> Result<bool, Error>
+    where
+        Q: Borrow<K>,
+        F: FnOnce(&V) -> A,
+        for<'a> V: Load<'a>,
+    {
+        self.update_aug_ext(key, f, &mut Cell::empty_context())
+    }
+
+    /// Recomputes the augmented value of the entry with the specified key
+    /// using `f`, keeping the value itself unchanged.
+    ///
+    /// The entry is found in a single traversal, and the value
+    /// bits are reused as is.
+    pub fn update_aug_ext<Q, F>(
+        &mut self,
+        key: Q,
+        f: F,
+        context: &mut dyn CellContext,
+    ) -> Result<bool, Error>
+    where
+        Q: Borrow<K>,
+        F: FnOnce(&V) -> A,
+        for<'a> V: Load<'a>,
+    {
+        let mut key_builder = CellBuilder::new();
+        ok!(key
+            .borrow()
+            .store_into(&mut key_builder, &mut Cell::empty_context()));
+        let cursor = ok!(DictCursor::new(
+            self.dict.root.as_ref(),
+            key_builder,
+            K::BITS,
+            context
+        ));
+
+        let Some((cell, range)) = ok!(cursor.value_owned()) else {
+            return Ok(false);
+        };
+        let mut value = ok!(range.apply(&cell));
+
+        // Skip the previous augmented value
+        ok!(A::load_from(&mut value));
+        let aug = f(&ok!(V::load_from(&mut value.clone())));
+
+        let root = ok!(cursor.set_aug(&aug, &value, A::comp_add, context));
+        self.dict.root = Some(root);
+        ok!(self.update_root_extra());
+        Ok(true)
+    }
+
     /// Sets the value associated with key in aug dictionary,
     /// but only if it is not already present.
     ///
@@ -489,6 +774,130 @@ where
 
         Ok((left, right))
     }
+
+    /// Split dictionary into 2 dictionaries at the key boundary.
+    ///
+    /// The left dictionary contains all keys less than `key`,
+    /// the right dictionary contains all keys greater or equal to `key`.
+    pub fn split_at<Q>(&self, key: Q) -> Result<(Self, Self), Error>
+    where
+        Q: Borrow<K>,
+    {
+        self.split_at_ext(key, false, &mut Cell::empty_context())
+    }
+
+    /// Split dictionary into 2 dictionaries at the key boundary.
+    ///
+    /// The `key` goes to the left dictionary if `inclusive` is set.
+    pub fn split_at_ext<Q>(
+        &self,
+        key: Q,
+        inclusive: bool,
+        context: &mut dyn CellContext,
+    ) -> Result<(Self, Self), Error>
+    where
+        Q: Borrow<K>,
+    {
+        let mut key_builder = CellBuilder::new();
+        ok!(key
+            .borrow()
+            .store_into(&mut key_builder, &mut Cell::empty_context()));
+
+        let (left, right) = ok!(aug_dict_split_at(
+            self.dict.root.as_ref(),
+            &key_builder.as_data_slice(),
+            K::BITS,
+            inclusive,
+            A::comp_add,
+            context,
+        ));
+
+        let left = ok!(Self::from_dict(Dict::from_raw(left)));
+        let right = ok!(Self::from_dict(Dict::from_raw(right)));
+        Ok((left, right))
+    }
+
+    /// Computes the combined augmented value of all entries in the key range.
+    ///
+    /// Only nodes on the paths to the range boundaries are visited,
+    /// all other subtrees contribute their pre-computed extra values.
+    ///
+    /// NOTE: keys are compared by their raw bits, so the range follows
+    /// the unsigned order (e.g. negative signed keys are greater than
+    /// all non-negative ones).
+    pub fn aggregate_range<R>(&self, range: R) -> Result<A, Error>
+    where
+        R: std::ops::RangeBounds<K>,
+    {
+        self.aggregate_range_ext(range, &mut Cell::empty_context())
+    }
+
+    /// Computes the combined augmented value of all entries in the key range.
+    pub fn aggregate_range_ext<R>(
+        &self,
+        range: R,
+        context: &mut dyn CellContext,
+    ) -> Result<A, Error>
+    where
+        R: std::ops::RangeBounds<K>,
+    {
+        use std::ops::Bound;
+
+        fn make_bound<K: Store>(bound: Bound<&K>) -> Result<Option<(CellBuilder, bool)>, Error> {
+            let (key, inclusive) = match bound {
+                Bound::Included(key) => (key, true),
+                Bound::Excluded(key) => (key, false),
+                Bound::Unbounded => return Ok(None),
+            };
+            let mut builder = CellBuilder::new();
+            ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
+            Ok(Some((builder, inclusive)))
+        }
+
+        let Some(root) = self.dict.root() else {
+            return Ok(A::default());
+        };
+
+        let lower = ok!(make_bound(range.start_bound()));
+        let upper = ok!(make_bound(range.end_bound()));
+
+        let mut result = None;
+        ok!(aggregate_subtree(
+            root.as_ref(),
+            K::BITS,
+            lower
+                .as_ref()
+                .map(|(key, inclusive)| (key.as_data_slice(), *inclusive)),
+            upper
+                .as_ref()
+                .map(|(key, inclusive)| (key.as_data_slice(), *inclusive)),
+            &mut result,
+            context,
+        ));
+        Ok(result.unwrap_or_default())
+    }
+
+    /// Merges two dictionaries into a new one,
+    /// keeping entries from `other` for duplicate keys.
+    pub fn merge(&self, other: &Self) -> Result<Self, Error> {
+        self.merge_ext(other, &mut Cell::empty_context())
+    }
+
+    /// Merges two dictionaries into a new one,
+    /// keeping entries from `other` for duplicate keys.
+    ///
+    /// Both tries are traversed in parallel, so subtrees which are present
+    /// only in one of the dictionaries are reused with their extra values.
+    pub fn merge_ext(&self, other: &Self, context: &mut dyn CellContext) -> Result<Self, Error> {
+        let root = ok!(aug_dict_merge(
+            self.dict.root.as_ref(),
+            other.dict.root.as_ref(),
+            K::BITS,
+            A::comp_add,
+            context,
+        ));
+        Self::from_dict(Dict::from_raw(root))
+    }
 }
 
 impl<K, A, V> AugDict<K, A, V>
@@ -758,6 +1167,38 @@ mod tests {
         }
     }
 
+    #[derive(Debug, Default, Load, Store, Eq, PartialEq, Ord, PartialOrd)]
+    struct MinValue(u32);
+
+    impl AugDictExtra for MinValue {
+        fn comp_add(
+            left: &mut CellSlice,
+            right: &mut CellSlice,
+            b: &mut CellBuilder,
+            _: &mut dyn CellContext,
+        ) -> Result<(), Error> {
+            let left = left.load_u32()?;
+            let right = right.load_u32()?;
+            b.store_u32(left.min(right))
+        }
+    }
+
+    #[derive(Debug, Default, Load, Store, Eq, PartialEq, Ord, PartialOrd)]
+    struct MaxValue(u32);
+
+    impl AugDictExtra for MaxValue {
+        fn comp_add(
+            left: &mut CellSlice,
+            right: &mut CellSlice,
+            b: &mut CellBuilder,
+            _: &mut dyn CellContext,
+        ) -> Result<(), Error> {
+            let left = left.load_u32()?;
+            let right = right.load_u32()?;
+            b.store_u32(left.max(right))
+        }
+    }
+
     #[test]
     fn dict_set() {
         let mut dict = AugDict::<u32, OrCmp, u16>::new();
@@ -875,6 +1316,250 @@ mod tests {
         }
     }
 
+    #[test]
+    fn dict_get_aug() -> anyhow::Result<()> {
+        let mut dict = AugDict::<u32, SomeValue, u32>::new();
+        for i in 0..10 {
+            dict.set(i, SomeValue(i * 10), i)?;
+        }
+
+        for i in 0..10 {
+            assert_eq!(dict.get_aug(i)?, Some(SomeValue(i * 10)));
+            assert_eq!(dict.get(i)?, Some((SomeValue(i * 10), i)));
+        }
+        assert_eq!(dict.get_aug(10)?, None);
+        assert_eq!(AugDict::<u32, SomeValue, u32>::new().get_aug(0)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_find_min_max_aug() -> anyhow::Result<()> {
+        assert_eq!(AugDict::<u32, MinValue, u32>::new().find_min_aug()?, None);
+        assert_eq!(AugDict::<u32, MaxValue, u32>::new().find_max_aug()?, None);
+
+        // Pseudo-random permutation of extras
+        let extra = |i: u32| (i * 37 + 11) % 101;
+
+        let mut min_dict = AugDict::<u32, MinValue, u32>::new();
+        let mut max_dict = AugDict::<u32, MaxValue, u32>::new();
+        for i in 0..101 {
+            min_dict.set(i * 1000, MinValue(extra(i)), i)?;
+            max_dict.set(i * 1000, MaxValue(extra(i)), i)?;
+        }
+
+        let min = (0..101).min_by_key(|i| extra(*i)).unwrap();
+        assert_eq!(
+            min_dict.find_min_aug()?,
+            Some((min * 1000, MinValue(0), min))
+        );
+
+        let max = (0..101).max_by_key(|i| extra(*i)).unwrap();
+        assert_eq!(
+            max_dict.find_max_aug()?,
+            Some((max * 1000, MaxValue(100), max))
+        );
+
+        min_dict.remove(min * 1000)?;
+        let (_, next_min, _) = min_dict.find_min_aug()?.unwrap();
+        assert_eq!(next_min, MinValue(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_root_extra() -> anyhow::Result<()> {
+        let mut dict = AugDict::<u32, SomeValue, u32>::new();
+        assert_eq!(dict.root_extra(), &SomeValue::default());
+
+        for i in 0..100 {
+            dict.set(i * 3, SomeValue(i * 5 + 1), i)?;
+        }
+
+        let mut total = 0;
+        for entry in dict.iter() {
+            let (key, SomeValue(aug), value) = entry?;
+            assert_eq!(key, value * 3);
+            total += aug;
+        }
+        assert_eq!(dict.root_extra(), &SomeValue(total));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_update_aug() -> anyhow::Result<()> {
+        let mut dict = AugDict::<u32, SomeValue, u32>::new();
+        for i in 0..20 {
+            dict.set(i, SomeValue(i), i * 10)?;
+        }
+        assert_eq!(*dict.root_extra(), SomeValue(190));
+
+        assert!(dict.update_aug(5, |value| SomeValue(*value))?);
+        assert_eq!(dict.get(5)?, Some((SomeValue(50), 50)));
+        assert_eq!(*dict.root_extra(), SomeValue(190 - 5 + 50));
+
+        assert!(!dict.update_aug(100, |_| SomeValue(1))?);
+        assert_eq!(*dict.root_extra(), SomeValue(190 - 5 + 50));
+
+        // Same as building the dictionary from scratch
+        let mut expected = AugDict::<u32, SomeValue, u32>::new();
+        for i in 0..20 {
+            let aug = if i == 5 { 50 } else { i };
+            expected.set(i, SomeValue(aug), i * 10)?;
+        }
+        assert_eq!(dict, expected);
+
+        // Each node on the path is loaded once through the context and rebuilt once
+        #[derive(Default)]
+        struct CountingContext {
+            finalized: usize,
+            loaded: usize,
+        }
+
+        impl CellContext for CountingContext {
+            fn finalize_cell(&mut self, cell: CellParts<'_>) -> Result<Cell, Error> {
+                self.finalized += 1;
+                Cell::empty_context().finalize_cell(cell)
+            }
+
+            fn load_cell(&mut self, cell: Cell, _: LoadMode) -> Result<Cell, Error> {
+                self.loaded += 1;
+                Ok(cell)
+            }
+
+            fn load_dyn_cell<'a>(
+                &mut self,
+                cell: &'a DynCell,
+                _: LoadMode,
+            ) -> Result<&'a DynCell, Error> {
+                self.loaded += 1;
+                Ok(cell)
+            }
+        }
+
+        let context = &mut CountingContext::default();
+        assert!(dict.update_aug_ext(7, |value| SomeValue(*value), context)?);
+        assert!(context.loaded > 1);
+        assert_eq!(context.loaded, context.finalized);
+        assert_eq!(dict.get(7)?, Some((SomeValue(70), 70)));
+
+        let context = &mut CountingContext::default();
+        assert!(!dict.update_aug_ext(100, |_| SomeValue(1), context)?);
+        assert_eq!(context.finalized, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_aggregate_range() -> anyhow::Result<()> {
+        let mut dict = AugDict::<u32, SomeValue, u32>::new();
+        assert_eq!(dict.aggregate_range(..)?, SomeValue(0));
+
+        for i in 0..200 {
+            dict.set(i * 3, SomeValue(i + 1), i)?;
+        }
+
+        let naive = |range: &dyn Fn(u32) -> bool| -> anyhow::Result<SomeValue> {
+            let mut total = 0;
+            for entry in dict.iter() {
+                let (key, SomeValue(aug), _) = entry?;
+                if range(key) {
+                    total += aug;
+                }
+            }
+            Ok(SomeValue(total))
+        };
+
+        assert_eq!(dict.aggregate_range(..)?, *dict.root_extra());
+        assert_eq!(
+            dict.aggregate_range(30..90)?,
+            naive(&|k| (30..90).contains(&k))?
+        );
+        assert_eq!(
+            dict.aggregate_range(30..=90)?,
+            naive(&|k| (30..=90).contains(&k))?
+        );
+        assert_eq!(dict.aggregate_range(31..)?, naive(&|k| k >= 31)?);
+        assert_eq!(dict.aggregate_range(..=1)?, SomeValue(1));
+        assert_eq!(dict.aggregate_range(1000..)?, SomeValue(0));
+        assert_eq!(dict.aggregate_range(4..6)?, SomeValue(0));
+
+        for start in (0..600).step_by(29) {
+            for end in (start..610).step_by(37) {
+                assert_eq!(
+                    dict.aggregate_range(start..end)?,
+                    naive(&|k| (start..end).contains(&k))?
+                );
+                assert_eq!(
+                    dict.aggregate_range(start..=end)?,
+                    naive(&|k| (start..=end).contains(&k))?
+                );
+            }
+        }
+
+        // Ranges follow the raw key bits order
+        let mut dict = AugDict::<i32, SomeValue, u32>::new();
+        dict.set(-1, SomeValue(1), 0)?;
+        dict.set(1, SomeValue(10), 0)?;
+        assert_eq!(dict.aggregate_range(0..)?, SomeValue(11));
+        assert_eq!(dict.aggregate_range(-1..=1)?, SomeValue(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_split_at_and_merge() -> anyhow::Result<()> {
+        let build = |range: std::ops::Range<u32>| -> anyhow::Result<_> {
+            let mut dict = AugDict::<u32, SomeValue, u32>::new();
+            for i in range {
+                dict.set(i * 7, SomeValue(i), i)?;
+            }
+            Ok(dict)
+        };
+
+        let dict = build(0..50)?;
+        assert_eq!(*dict.root_extra(), SomeValue((0..50).sum()));
+
+        for at in [0, 1, 7, 100, 101, 343, 1000] {
+            let (left, right) = dict.split_at(at)?;
+            let split = at.div_ceil(7).min(50);
+            assert_eq!(left, build(0..split)?);
+            assert_eq!(right, build(split..50)?);
+            assert_eq!(*left.root_extra(), SomeValue((0..split).sum()));
+            assert_eq!(*right.root_extra(), SomeValue((split..50).sum()));
+
+            assert_eq!(left.merge(&right)?, dict);
+            assert_eq!(right.merge(&left)?, dict);
+        }
+
+        let (left, right) = dict.split_at_ext(7, true, &mut Cell::empty_context())?;
+        assert_eq!(left, build(0..2)?);
+        assert_eq!(right, build(2..50)?);
+
+        // Interleaved keys rebuild every fork
+        let mut even = AugDict::<u32, SomeValue, u32>::new();
+        let mut odd = AugDict::<u32, SomeValue, u32>::new();
+        for i in 0..50 {
+            let target = if i % 2 == 0 { &mut even } else { &mut odd };
+            target.set(i * 7, SomeValue(i), i)?;
+        }
+        assert_eq!(even.merge(&odd)?, dict);
+        assert_eq!(dict.merge(&dict)?, dict);
+        assert_eq!(dict.merge(&AugDict::new())?, dict);
+
+        let mut other = AugDict::<u32, SomeValue, u32>::new();
+        other.set(7, SomeValue(100), 100)?;
+        let merged = dict.merge(&other)?;
+        assert_eq!(merged.get(7)?, Some((SomeValue(100), 100)));
+        assert_eq!(
+            *merged.root_extra(),
+            SomeValue((0..50).sum::<u32>() - 1 + 100)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn aug_test() {
         let boc = Boc::decode(include_bytes!("./tests/account_blocks_aug_dict.boc")).unwrap();
@@ -1,13 +1,19 @@
 //! Dictionary implementation.
 
+pub use everscale_types_proc::DictKey;
+
 pub use aug::*;
+pub use patch::*;
+pub use pfx::*;
 pub use raw::*;
 pub use typed::*;
 
 use crate::cell::*;
-use crate::error::Error;
+use crate::error::{DictError, Error};
 
 mod aug;
+mod patch;
+mod pfx;
 mod raw;
 mod typed;
 
@@ -45,13 +51,96 @@ impl_dict_key! {
     i64 => 64 => |d| i64::from_be_bytes(d[..8].try_into().unwrap()),
     u128 => 128 => |d| u128::from_be_bytes(d[..16].try_into().unwrap()),
     i128 => 128 => |d| i128::from_be_bytes(d[..16].try_into().unwrap()),
-    [u8; 16] => 128 => |d| d[..16].try_into().unwrap(),
-    [u8; 20] => 160 => |d| d[..20].try_into().unwrap(),
-    [u8; 32] => 256 => |d| d[..32].try_into().unwrap(),
     HashBytes => 256 => |d| HashBytes(d[..32].try_into().unwrap()),
-     (u64, u32) => 96 => |d| {
-        (u64::from_be_bytes(d[..8].try_into().unwrap()), u32::from_be_bytes(d[8..12].try_into().unwrap()))
-    },
+    std::net::Ipv4Addr => 32 => |d| std::net::Ipv4Addr::new(d[0], d[1], d[2], d[3]),
+    std::net::Ipv6Addr => 128 => |d| <[u8; 16]>::try_from(&d[..16]).unwrap().into(),
+}
+
+/// Byte arrays are stored as is (in big-endian bit order).
+impl<const N: usize> DictKey for [u8; N] {
+    const BITS: u16 = {
+        assert!(N < 128, "dictionary key must not exceed 1023 bits");
+        N as u16 * 8
+    };
+
+    #[inline]
+    fn from_raw_data(raw_data: &[u8; 128]) -> Option<Self> {
+        let mut result = [0; N];
+        result.copy_from_slice(raw_data.get(..N)?);
+        Some(result)
+    }
+}
+
+/// Tuples of keys are stored as a concatenation of their components.
+///
+/// Keys are ordered component by component. NOTE: signed iteration only
+/// affects the highest bit of the first component.
+macro_rules! impl_dict_key_for_tuples {
+    ($( ($($field:ident: $t:ident),+) ),*$(,)?) => {$(
+        impl<$($t: DictKey),+> DictKey for ($($t),*) {
+            const BITS: u16 = 0 $(+ $t::BITS)+;
+
+            #[allow(unused_assignments)]
+            fn from_raw_data(raw_data: &[u8; 128]) -> Option<Self> {
+                let mut offset = 0;
+                $(
+                    let $field = $t::from_raw_data(&shift_raw_data(raw_data, offset))?;
+                    offset += $t::BITS;
+                )+
+                Some(($($field),+))
+            }
+        }
+    )*};
+}
+
+impl_dict_key_for_tuples! {
+    (t1: T1, t2: T2),
+    (t1: T1, t2: T2, t3: T3),
+}
+
+/// Shifts raw key data to the left by the specified number of bits.
+#[doc(hidden)]
+pub fn shift_raw_data(raw_data: &[u8; 128], bits: u16) -> [u8; 128] {
+    let (bytes, bits) = ((bits / 8) as usize, bits % 8);
+
+    let mut result = [0; 128];
+    if bytes >= raw_data.len() {
+        return result;
+    }
+
+    let src = &raw_data[bytes..];
+    if bits == 0 {
+        result[..src.len()].copy_from_slice(src);
+    } else {
+        for (i, byte) in result[..src.len()].iter_mut().enumerate() {
+            let next = src.get(i + 1).copied().unwrap_or_default();
+            *byte = (src[i] << bits) | (next >> (8 - bits));
+        }
+    }
+    result
+}
+
+/// Extracts `bits` of raw key data starting at `offset`
+/// and aligns them to the end of the first `target_bits`.
+#[doc(hidden)]
+pub fn extract_raw_key_bits(
+    raw_data: &[u8; 128],
+    offset: u16,
+    bits: u16,
+    target_bits: u16,
+) -> [u8; 128] {
+    let mut result = [0; 128];
+    let shift = target_bits.saturating_sub(bits);
+    for i in 0..bits {
+        let (src, dst) = ((offset + i) as usize, (shift + i) as usize);
+        if src >= 1024 || dst >= 1024 {
+            break;
+        }
+        if raw_data[src / 8] & (0x80 >> (src % 8)) != 0 {
+            result[dst / 8] |= 0x80 >> (dst % 8);
+        }
+    }
+    result
 }
 
 /// Dictionary insertion mode.
@@ -604,6 +693,234 @@ pub fn dict_insert_owned(
     Ok((true, value))
 }
 
+/// A path from the dictionary root to the node where the key is
+/// (or would be) located.
+///
+/// Allows to inspect the value and then to modify the dictionary
+/// without traversing it again. Only the nodes on the path are rebuilt.
+pub(crate) struct DictCursor {
+    key: CellBuilder,
+    key_bit_len: u16,
+    /// Forks on the path with the branch taken and their key length.
+    forks: Vec<(Cell, Branch, u16)>,
+    /// The node where the search stopped, `None` for an empty dictionary.
+    node: Option<Cell>,
+    /// The number of key bits remaining at the node.
+    node_key_bit_len: u16,
+    /// Whether the node is a leaf with the specified key.
+    found: bool,
+}
+
+impl DictCursor {
+    /// Finds the position of the key in the dictionary.
+    pub fn new(
+        dict: Option<&Cell>,
+        key: CellBuilder,
+        key_bit_len: u16,
+        context: &mut dyn CellContext,
+    ) -> Result<Self, Error> {
+        if key.bit_len() != key_bit_len {
+            return Err(Error::CellUnderflow);
+        }
+
+        let mut forks = Vec::new();
+        let mut node = match dict {
+            // TODO: change mode to `LoadMode::UseGas` if copy-on-write for libraries is not ok.
+            Some(root) => ok!(context.load_cell(root.clone(), LoadMode::Full)),
+            None => {
+                return Ok(Self {
+                    key,
+                    key_bit_len,
+                    forks,
+                    node: None,
+                    node_key_bit_len: key_bit_len,
+                    found: false,
+                })
+            }
+        };
+
+        let mut remaining_key = key.as_data_slice();
+        let found = loop {
+            let mut remaining_data = ok!(node.as_slice());
+
+            // Read the next part of the key from the current data
+            let prefix = ok!(read_label(
+                &mut remaining_data,
+                remaining_key.remaining_bits()
+            ));
+
+            // Match the prefix with the key
+            let lcp = remaining_key.longest_common_data_prefix(&prefix);
+            match lcp.remaining_bits().cmp(&remaining_key.remaining_bits()) {
+                // If all bits match, an existing value was found
+                std::cmp::Ordering::Equal => break true,
+                // LCP is less than prefix, an edge to slice was found
+                std::cmp::Ordering::Less if lcp.remaining_bits() < prefix.remaining_bits() => {
+                    break false
+                }
+                // The key contains the entire prefix, but there are still some bits left
+                std::cmp::Ordering::Less => {
+                    // Fail fast if there are not enough references in the fork
+                    if node.reference_count() != 2 {
+                        return Err(Error::CellUnderflow);
+                    }
+
+                    // Remove the LCP from the key
+                    let fork_key_bit_len = remaining_key.remaining_bits();
+                    remaining_key.try_advance(lcp.remaining_bits(), 0);
+
+                    // Load the next branch
+                    let next_branch = Branch::from(ok!(remaining_key.load_bit()));
+
+                    let child = match node.reference_cloned(next_branch as u8) {
+                        // TODO: change mode to `LoadMode::UseGas` if copy-on-write for libraries is not ok
+                        Some(child) => ok!(context.load_cell(child, LoadMode::Full)),
+                        None => return Err(Error::CellUnderflow),
+                    };
+
+                    // Push an intermediate edge to the path
+                    let fork = std::mem::replace(&mut node, child);
+                    forks.push((fork, next_branch, fork_key_bit_len));
+                }
+                std::cmp::Ordering::Greater => {
+                    debug_assert!(false, "LCP of prefix and key can't be greater than key");
+                    unsafe { std::hint::unreachable_unchecked() };
+                }
+            }
+        };
+        let node_key_bit_len = remaining_key.remaining_bits();
+
+        Ok(Self {
+            key,
+            key_bit_len,
+            forks,
+            node: Some(node),
+            node_key_bit_len,
+            found,
+        })
+    }
+
+    /// Returns the value slice if the key is present in the dictionary.
+    pub fn value(&self) -> Result<Option<CellSlice<'_>>, Error> {
+        match &self.node {
+            Some(node) if self.found => {
+                let mut value = ok!(node.as_slice());
+                ok!(read_label(&mut value, self.node_key_bit_len));
+                Ok(Some(value))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the owned value slice parts if the key is present in the dictionary.
+    pub fn value_owned(&self) -> Result<Option<CellSliceParts>, Error> {
+        match &self.node {
+            Some(node) if self.found => {
+                let value = ok!(self.value()).map(|value| value.range());
+                Ok(value.map(|range| (node.clone(), range)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Sets the value for the key and returns the new dictionary root.
+    pub fn set(self, value: &dyn Store, context: &mut dyn CellContext) -> Result<Cell, Error> {
+        let mut key = self.remaining_key();
+        let leaf = match &self.node {
+            Some(node) if !self.found => {
+                let mut remaining_data = ok!(node.as_slice());
+                let prefix = &mut ok!(read_label(&mut remaining_data, key.remaining_bits()));
+                let lcp = key.longest_common_data_prefix(prefix);
+                ok!(split_edge(
+                    &remaining_data,
+                    prefix,
+                    &lcp,
+                    &mut key,
+                    value,
+                    context
+                ))
+            }
+            _ => ok!(make_leaf(&key, key.remaining_bits(), value, context)),
+        };
+        rebuild_dict_from_stack(self.segments(), leaf, context)
+    }
+
+    /// Sets the augmented value for the key and returns the new dictionary root.
+    ///
+    /// Extra values of all forks on the path are recomputed with `comparator`.
+    pub fn set_aug(
+        self,
+        extra: &dyn Store,
+        value: &dyn Store,
+        comparator: AugDictFn,
+        context: &mut dyn CellContext,
+    ) -> Result<Cell, Error> {
+        let mut key = self.remaining_key();
+        let leaf = match &self.node {
+            Some(node) if !self.found => {
+                let mut remaining_data = ok!(node.as_slice());
+                let prefix = &mut ok!(read_label(&mut remaining_data, key.remaining_bits()));
+                let lcp = key.longest_common_data_prefix(prefix);
+                ok!(split_aug_edge(
+                    &mut remaining_data,
+                    prefix,
+                    &lcp,
+                    &mut key,
+                    extra,
+                    value,
+                    comparator,
+                    context
+                ))
+            }
+            _ => ok!(make_leaf_with_extra(
+                &key,
+                key.remaining_bits(),
+                extra,
+                value,
+                context
+            )),
+        };
+        rebuild_aug_dict_from_stack(self.segments(), leaf, comparator, context)
+    }
+
+    /// Removes the key and returns the new dictionary root
+    /// (`None` if the dictionary became empty).
+    ///
+    /// Returns an error if the key is not present in the dictionary.
+    pub fn remove(self, context: &mut dyn CellContext) -> Result<Option<Cell>, Error> {
+        if !self.found {
+            return Err(Error::CellUnderflow);
+        }
+
+        let key = self.remaining_key();
+        let mut segments = self.segments();
+        let Some(last) = segments.pop() else {
+            return Ok(None);
+        };
+
+        let prev_key_bit_len = last.key_bit_len;
+        let (leaf, _) = ok!(last.rebuild_as_removed(&key, prev_key_bit_len, context));
+        rebuild_dict_from_stack(segments, leaf, context).map(Some)
+    }
+
+    fn remaining_key(&self) -> CellSlice<'_> {
+        let mut key = self.key.as_data_slice();
+        key.try_advance(self.key_bit_len - self.node_key_bit_len, 0);
+        key
+    }
+
+    fn segments(&self) -> Vec<Segment<'_>> {
+        self.forks
+            .iter()
+            .map(|(data, next_branch, key_bit_len)| Segment {
+                data: data.as_ref(),
+                next_branch: *next_branch,
+                key_bit_len: *key_bit_len,
+            })
+            .collect()
+    }
+}
+
 /// Returns a `CellSlice` of the value corresponding to the key.
 pub fn dict_get<'a: 'b, 'b>(
     dict: Option<&'a Cell>,
@@ -1007,6 +1324,136 @@ pub fn dict_find_owned(
     Ok(Some((result_key, (cell, value_range))))
 }
 
+/// Finds the key with the smallest absolute distance to the specified key.
+///
+/// Keys are compared as unsigned big-endian integers. If the lower and the upper
+/// keys are equidistant, the lower one is returned.
+///
+/// The trie is traversed once along the key path, remembering the closest
+/// subtrees below and above the key. Only their bounds are visited afterwards.
+pub fn dict_find_nearest(
+    dict: Option<&Cell>,
+    key: CellSlice<'_>,
+    key_bit_len: u16,
+) -> Result<Option<CellBuilder>, Error> {
+    type Subtree<'a> = (&'a DynCell, CellBuilder, u16);
+
+    let Some(root) = dict else {
+        return Ok(None);
+    };
+    if key.remaining_bits() != key_bit_len {
+        return Err(Error::CellUnderflow);
+    }
+
+    let mut lower = None::<Subtree<'_>>;
+    let mut upper = None::<Subtree<'_>>;
+
+    let mut node = root.as_ref();
+    let mut prefix = CellBuilder::new();
+    let mut remaining_key = key;
+    let mut remaining_bits = key_bit_len;
+    loop {
+        let mut data = ok!(node.as_slice());
+        let label = ok!(read_label(&mut data, remaining_bits));
+        let label_len = label.remaining_bits();
+
+        let lcp_len = remaining_key
+            .longest_common_data_prefix(&label)
+            .remaining_bits();
+        if lcp_len < label_len {
+            // The whole subtree is either below or above the key
+            let subtree = Some((node, prefix, remaining_bits));
+            if ok!(remaining_key.get_bit(lcp_len)) {
+                lower = subtree;
+            } else {
+                upper = subtree;
+            }
+            break;
+        }
+
+        ok!(prefix.store_slice_data(label));
+        ok!(remaining_key.advance(label_len, 0));
+        remaining_bits = match remaining_bits.checked_sub(label_len) {
+            Some(0) => return Ok(Some(prefix)),
+            Some(remaining) => remaining - 1,
+            None => return Err(Error::CellUnderflow),
+        };
+
+        // Remember the sibling branch as the closest subtree on its side
+        let bit = ok!(remaining_key.load_bit());
+        let mut sibling_prefix = prefix.clone();
+        ok!(sibling_prefix.store_bit(!bit));
+        let sibling = Some((
+            ok!(data.get_reference(!bit as u8)),
+            sibling_prefix,
+            remaining_bits,
+        ));
+        if bit {
+            lower = sibling;
+        } else {
+            upper = sibling;
+        }
+
+        ok!(prefix.store_bit(bit));
+        node = ok!(data.get_reference(bit as u8));
+    }
+
+    // Descends to the maximal (or minimal) key of the subtree
+    let find_bound = |(mut node, mut prefix, mut remaining_bits): Subtree<'_>, max: bool| loop {
+        let mut data = ok!(node.as_slice());
+        let label = ok!(read_label(&mut data, remaining_bits));
+        ok!(prefix.store_slice_data(label));
+        remaining_bits = match remaining_bits.checked_sub(label.remaining_bits()) {
+            Some(0) => return Ok::<_, Error>(prefix),
+            Some(remaining) => remaining - 1,
+            None => return Err(Error::CellUnderflow),
+        };
+        ok!(prefix.store_bit(max));
+        node = ok!(data.get_reference(max as u8));
+    };
+
+    let lower = match lower {
+        Some(subtree) => Some(ok!(find_bound(subtree, true))),
+        None => None,
+    };
+    let upper = match upper {
+        Some(subtree) => Some(ok!(find_bound(subtree, false))),
+        None => None,
+    };
+
+    Ok(match (lower, upper) {
+        (Some(lower), Some(upper)) => {
+            // NOTE: `key - lower <= upper - key` <=> `2 * key <= lower + upper`.
+            // All keys have the same zero padding, so it is enough
+            // to compare the padded bytes.
+            let len = key_bit_len.div_ceil(8) as usize;
+            let mut sum = vec![0u8; len + 1];
+            let mut twice = vec![0u8; len + 1];
+
+            let (mut sum_carry, mut twice_carry) = (0u16, 0u8);
+            let mut key_data = [0u8; 128];
+            let key_data = ok!(key.get_raw(0, &mut key_data, key_bit_len));
+            for i in (0..len).rev() {
+                let s = lower.raw_data()[i] as u16 + upper.raw_data()[i] as u16 + sum_carry;
+                sum[i + 1] = s as u8;
+                sum_carry = s >> 8;
+
+                twice[i + 1] = key_data[i] << 1 | twice_carry;
+                twice_carry = key_data[i] >> 7;
+            }
+            sum[0] = sum_carry as u8;
+            twice[0] = twice_carry;
+
+            if twice <= sum {
+                Some(lower)
+            } else {
+                Some(upper)
+            }
+        }
+        (lower, upper) => lower.or(upper),
+    })
+}
+
 /// Finds the specified dict bound and returns a key and a value corresponding to the key.
 pub fn dict_find_bound<'a: 'b, 'b>(
     dict: Option<&'a Cell>,
@@ -1305,6 +1752,137 @@ pub fn dict_split_by_prefix(
     Ok((Some(left_branch), Some(right_branch)))
 }
 
+/// Splits the dictionary into two parts by the key boundary.
+///
+/// Returns `(left, right)`, where `left` contains all keys less than `key`
+/// (or equal if `inclusive` is set) and `right` contains the remaining keys.
+/// Keys are compared as unsigned bit strings.
+///
+/// Only nodes on the path to the boundary key are rebuilt,
+/// all other subtrees are reused as is.
+pub fn dict_split_at(
+    dict: Option<&Cell>,
+    key: &CellSlice,
+    key_bit_len: u16,
+    inclusive: bool,
+    context: &mut dyn CellContext,
+) -> Result<(Option<Cell>, Option<Cell>), Error> {
+    if key.remaining_bits() != key_bit_len {
+        return Err(Error::CellUnderflow);
+    }
+
+    match dict {
+        Some(root) => split_subtree_at(root.clone(), *key, key_bit_len, inclusive, None, context),
+        None => Ok((None, None)),
+    }
+}
+
+/// Splits the augmented dictionary into two parts by the key boundary.
+///
+/// Same as [`dict_split_at`], but the extra values of the rebuilt
+/// forks are recomputed using the `comparator`.
+pub fn aug_dict_split_at(
+    dict: Option<&Cell>,
+    key: &CellSlice,
+    key_bit_len: u16,
+    inclusive: bool,
+    comparator: AugDictFn,
+    context: &mut dyn CellContext,
+) -> Result<(Option<Cell>, Option<Cell>), Error> {
+    if key.remaining_bits() != key_bit_len {
+        return Err(Error::CellUnderflow);
+    }
+
+    match dict {
+        Some(root) => split_subtree_at(
+            root.clone(),
+            *key,
+            key_bit_len,
+            inclusive,
+            Some(comparator),
+            context,
+        ),
+        None => Ok((None, None)),
+    }
+}
+
+fn split_subtree_at(
+    node: Cell,
+    mut key: CellSlice<'_>,
+    key_bit_len: u16,
+    inclusive: bool,
+    comparator: Option<AugDictFn>,
+    context: &mut dyn CellContext,
+) -> Result<(Option<Cell>, Option<Cell>), Error> {
+    let loaded = ok!(context.load_cell(node.clone(), LoadMode::Full));
+    let mut data = ok!(loaded.as_slice());
+
+    let label = ok!(read_label(&mut data, key_bit_len));
+    let lcp_len = label.longest_common_data_prefix(&key).remaining_bits();
+
+    if lcp_len < label.remaining_bits() {
+        // The whole subtree is either less or greater than the key
+        return Ok(if ok!(label.get_bit(lcp_len)) {
+            (None, Some(node))
+        } else {
+            (Some(node), None)
+        });
+    }
+
+    if lcp_len == key_bit_len {
+        // Found the leaf with the same key
+        return Ok(if inclusive {
+            (Some(node), None)
+        } else {
+            (None, Some(node))
+        });
+    }
+
+    // Split the branch which contains the key
+    key.try_advance(lcp_len, 0);
+    let bit = ok!(key.load_bit());
+    let (left, right) = ok!(split_subtree_at(
+        ok!(data.get_reference_cloned(bit as u8)),
+        key,
+        key_bit_len - lcp_len - 1,
+        inclusive,
+        comparator,
+        context
+    ));
+
+    let (left_children, right_children) = if bit {
+        let other = ok!(data.get_reference_cloned(0));
+        ([Some(other), left], [None, right])
+    } else {
+        let other = ok!(data.get_reference_cloned(1));
+        ([left, None], [right, Some(other)])
+    };
+
+    let (left, right) = match comparator {
+        Some(comparator) => (
+            ok!(make_aug_fork(
+                &label,
+                key_bit_len,
+                left_children,
+                comparator,
+                context
+            )),
+            ok!(make_aug_fork(
+                &label,
+                key_bit_len,
+                right_children,
+                comparator,
+                context
+            )),
+        ),
+        None => (
+            ok!(make_fork(&label, key_bit_len, left_children, context)),
+            ok!(make_fork(&label, key_bit_len, right_children, context)),
+        ),
+    };
+    Ok((left, right))
+}
+
 /// Merges two dictionaries into one (left)
 pub fn dict_merge(
     left: &mut Option<Cell>,
@@ -1342,54 +1920,829 @@ pub fn dict_merge(
     Ok(())
 }
 
-/// Creates a leaf node
-fn make_leaf(
-    key: &CellSlice,
+/// Merges two dictionaries into a new one, traversing both tries in parallel.
+///
+/// Subtrees which are present only in one of the dictionaries are reused as is.
+/// For keys present in both dictionaries, `on_conflict` is called with the key,
+/// the left value and the right value. It must return the resulting value.
+pub fn dict_union<F>(
+    left: Option<&Cell>,
+    right: Option<&Cell>,
     key_bit_len: u16,
-    value: &dyn Store,
+    mut on_conflict: F,
     context: &mut dyn CellContext,
-) -> Result<Cell, Error> {
-    let mut builder = CellBuilder::new();
-    ok!(write_label(key, key_bit_len, &mut builder));
-    ok!(value.store_into(&mut builder, context));
-    builder.build_ext(context)
+) -> Result<Option<Cell>, Error>
+where
+    F: FnMut(&CellBuilder, CellSlice<'_>, CellSlice<'_>) -> Result<CellBuilder, Error>,
+{
+    dict_merge_subtrees(
+        left,
+        right,
+        key_bit_len,
+        &mut DictMergeOps {
+            keep_left: true,
+            keep_right: true,
+            on_same: SameSubtree::Traverse,
+            on_both: &mut |key, left, right| on_conflict(key, left, right).map(Some),
+            comparator: None,
+        },
+        context,
+    )
 }
 
-/// Creates a leaf node with extra value
-fn make_leaf_with_extra(
-    key: &CellSlice,
+/// Merges two augmented dictionaries into a new one, traversing both tries
+/// in parallel and keeping entries from the right dictionary for duplicate keys.
+///
+/// Same as [`dict_union`], but the extra values of the rebuilt forks
+/// are recomputed using the `comparator`. Subtrees which are present
+/// only in one of the dictionaries are reused with their extra values.
+pub fn aug_dict_merge(
+    left: Option<&Cell>,
+    right: Option<&Cell>,
     key_bit_len: u16,
-    extra: &dyn Store,
-    value: &dyn Store,
+    comparator: AugDictFn,
     context: &mut dyn CellContext,
-) -> Result<Cell, Error> {
-    let mut builder = CellBuilder::new();
-    ok!(write_label(key, key_bit_len, &mut builder));
-    ok!(extra.store_into(&mut builder, context));
-    ok!(value.store_into(&mut builder, context));
-    builder.build_ext(context)
+) -> Result<Option<Cell>, Error> {
+    dict_merge_subtrees(
+        left,
+        right,
+        key_bit_len,
+        &mut DictMergeOps {
+            keep_left: true,
+            keep_right: true,
+            on_same: SameSubtree::Keep,
+            on_both: &mut |_, _, right| {
+                // NOTE: `right` contains both the extra and the value
+                let mut builder = CellBuilder::new();
+                ok!(builder.store_slice(right));
+                Ok(Some(builder))
+            },
+            comparator: Some(comparator),
+        },
+        context,
+    )
 }
 
-/// Splits an edge or leaf
-fn split_edge(
-    data: &CellSlice,
-    prefix: &mut CellSlice,
-    lcp: &CellSlice,
-    key: &mut CellSlice,
-    value: &dyn Store,
+/// Builds a dictionary from the keys present in both dictionaries,
+/// traversing both tries in parallel.
+///
+/// Subtrees which don't overlap are skipped without visiting them.
+/// For each common key, `f` is called with the key, the left value and
+/// the right value. It must return the resulting value.
+pub fn dict_intersection<F>(
+    left: Option<&Cell>,
+    right: Option<&Cell>,
+    key_bit_len: u16,
+    mut f: F,
     context: &mut dyn CellContext,
-) -> Result<Cell, Error> {
-    // Advance the key
-    let prev_key_bit_len = key.remaining_bits();
-    if !key.try_advance(lcp.remaining_bits() + 1, 0) {
-        return Err(Error::CellUnderflow);
-    }
-
-    // Read the next bit from the data
-    prefix.try_advance(lcp.remaining_bits(), 0);
-    let old_to_right = ok!(prefix.load_bit());
+) -> Result<Option<Cell>, Error>
+where
+    F: FnMut(&CellBuilder, CellSlice<'_>, CellSlice<'_>) -> Result<CellBuilder, Error>,
+{
+    dict_merge_subtrees(
+        left,
+        right,
+        key_bit_len,
+        &mut DictMergeOps {
+            keep_left: false,
+            keep_right: false,
+            on_same: SameSubtree::Traverse,
+            on_both: &mut |key, left, right| f(key, left, right).map(Some),
+            comparator: None,
+        },
+        context,
+    )
+}
 
-    // Create a leaf for the old value
+/// Builds a dictionary from the entries of the left dictionary
+/// whose keys are not present in the right dictionary.
+///
+/// Both tries are traversed in parallel, so subtrees which don't overlap
+/// are reused as is, and identical subtrees are removed without visiting them.
+pub fn dict_difference(
+    left: Option<&Cell>,
+    right: Option<&Cell>,
+    key_bit_len: u16,
+    context: &mut dyn CellContext,
+) -> Result<Option<Cell>, Error> {
+    dict_merge_subtrees(
+        left,
+        right,
+        key_bit_len,
+        &mut DictMergeOps {
+            keep_left: true,
+            keep_right: false,
+            on_same: SameSubtree::Remove,
+            on_both: &mut |_, _, _| Ok(None),
+            comparator: None,
+        },
+        context,
+    )
+}
+
+/// Builds a new dictionary with the same keys and values transformed by `f`.
+///
+/// `f` is called with the key and the value of each entry. Entries for which
+/// `f` returns the same data are not rebuilt, so unchanged subtrees reuse
+/// the original cells.
+pub fn dict_map_values<F>(
+    dict: Option<&Cell>,
+    key_bit_len: u16,
+    mut f: F,
+    context: &mut dyn CellContext,
+) -> Result<Option<Cell>, Error>
+where
+    F: FnMut(&CellBuilder, CellSlice<'_>) -> Result<CellBuilder, Error>,
+{
+    match dict {
+        None => Ok(None),
+        Some(root) => {
+            map_subtree(root, &CellBuilder::new(), key_bit_len, &mut f, context).map(Some)
+        }
+    }
+}
+
+/// Transforms the values of the subtree with the key `prefix`.
+fn map_subtree(
+    node: &Cell,
+    prefix: &CellBuilder,
+    key_bit_len: u16,
+    f: &mut dyn FnMut(&CellBuilder, CellSlice<'_>) -> Result<CellBuilder, Error>,
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    let loaded = ok!(context.load_cell(node.clone(), LoadMode::Full));
+    let full = ok!(loaded.as_slice());
+    let mut data = full;
+
+    let prev_key_bit_len = key_bit_len - prefix.bit_len();
+    let label = ok!(read_label(&mut data, prev_key_bit_len));
+
+    let mut key = prefix.clone();
+    ok!(key.store_slice_data(label));
+
+    // NOTE: Keep the original label encoding
+    let mut builder = CellBuilder::new();
+    ok!(builder.store_slice_data(full.get_prefix(full.remaining_bits() - data.remaining_bits(), 0)));
+
+    if label.remaining_bits() == prev_key_bit_len {
+        // Leaf
+        let value = ok!(f(&key, data));
+        if is_same_value(&value, &data) {
+            return Ok(node.clone());
+        }
+        ok!(builder.store_builder(&value));
+    } else {
+        // Fork
+        let mut changed = false;
+        for bit in [false, true] {
+            let child = ok!(data.get_reference_cloned(bit as u8));
+
+            let mut child_prefix = key.clone();
+            ok!(child_prefix.store_bit(bit));
+            let new_child = ok!(map_subtree(&child, &child_prefix, key_bit_len, f, context));
+
+            changed |= new_child.repr_hash() != child.repr_hash();
+            ok!(builder.store_reference(new_child));
+        }
+        if !changed {
+            return Ok(node.clone());
+        }
+    }
+
+    builder.build_ext(context)
+}
+
+pub(crate) fn is_same_value(value: &CellBuilder, data: &CellSlice<'_>) -> bool {
+    let bits = value.bit_len();
+    let refs = value.references();
+
+    bits == data.remaining_bits()
+        && value
+            .as_data_slice()
+            .longest_common_data_prefix(data)
+            .remaining_bits()
+            == bits
+        && refs.len() == data.remaining_refs() as usize
+        && refs.iter().enumerate().all(|(i, cell)| {
+            matches!(data.get_reference(i as u8), Ok(old) if old.repr_hash() == cell.repr_hash())
+        })
+}
+
+/// Behaviour of the parallel dictionary traversal.
+struct DictMergeOps<'a> {
+    /// Whether to keep entries which are present only in the left dictionary.
+    keep_left: bool,
+    /// Whether to keep entries which are present only in the right dictionary.
+    keep_right: bool,
+    /// What to do with identical subtrees.
+    on_same: SameSubtree,
+    /// Resolves entries which are present in both dictionaries.
+    /// Returning `None` removes the entry.
+    #[allow(clippy::type_complexity)]
+    on_both: &'a mut dyn FnMut(
+        &CellBuilder,
+        CellSlice<'_>,
+        CellSlice<'_>,
+    ) -> Result<Option<CellBuilder>, Error>,
+    /// Recomputes the extra values of rebuilt forks of augmented dictionaries.
+    comparator: Option<AugDictFn>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum SameSubtree {
+    /// Resolve each entry using `on_both`.
+    Traverse,
+    /// Keep the subtree as is.
+    Keep,
+    /// Remove the subtree.
+    Remove,
+}
+
+fn dict_merge_subtrees(
+    left: Option<&Cell>,
+    right: Option<&Cell>,
+    key_bit_len: u16,
+    ops: &mut DictMergeOps<'_>,
+    context: &mut dyn CellContext,
+) -> Result<Option<Cell>, Error> {
+    match (left, right) {
+        (None, None) => Ok(None),
+        (Some(left), None) => Ok(ops.keep_left.then(|| left.clone())),
+        (None, Some(right)) => Ok(ops.keep_right.then(|| right.clone())),
+        (Some(left), Some(right)) => merge_subtrees(
+            left.clone(),
+            right.clone(),
+            &CellBuilder::new(),
+            key_bit_len,
+            ops,
+            context,
+        ),
+    }
+}
+
+/// Merges two subtrees with the same key `prefix`.
+fn merge_subtrees(
+    left: Cell,
+    right: Cell,
+    prefix: &CellBuilder,
+    key_bit_len: u16,
+    ops: &mut DictMergeOps<'_>,
+    context: &mut dyn CellContext,
+) -> Result<Option<Cell>, Error> {
+    if left.repr_hash() == right.repr_hash() {
+        match ops.on_same {
+            SameSubtree::Traverse => {}
+            SameSubtree::Keep => return Ok(Some(left)),
+            SameSubtree::Remove => return Ok(None),
+        }
+    }
+
+    let left = ok!(context.load_cell(left, LoadMode::Full));
+    let right = ok!(context.load_cell(right, LoadMode::Full));
+    let mut left_data = ok!(left.as_slice());
+    let mut right_data = ok!(right.as_slice());
+
+    let prev_key_bit_len = key_bit_len - prefix.bit_len();
+    let left_label = ok!(read_label(&mut left_data, prev_key_bit_len));
+    let right_label = ok!(read_label(&mut right_data, prev_key_bit_len));
+
+    let lcp_len = left_label
+        .longest_common_data_prefix(&right_label)
+        .remaining_bits();
+    let child_key_bit_len = prev_key_bit_len.saturating_sub(lcp_len + 1);
+
+    let child_prefix = |bit: bool| {
+        let mut prefix = prefix.clone();
+        ok!(prefix.store_slice_data(left_label.get_prefix(lcp_len, 0)));
+        ok!(prefix.store_bit(bit));
+        Ok::<_, Error>(prefix)
+    };
+
+    // Splits the node label at the common prefix
+    let split_node = |label: &CellSlice<'_>, data: CellSlice<'_>, context: &mut dyn CellContext| {
+        let mut label_rem = *label;
+        label_rem.try_advance(lcp_len, 0);
+        let bit = ok!(label_rem.load_bit());
+        let node = ok!(relabel_node(&label_rem, child_key_bit_len, data, context));
+        Ok::<_, Error>((bit, node))
+    };
+
+    let left_is_prefix = lcp_len == left_label.remaining_bits();
+    let right_is_prefix = lcp_len == right_label.remaining_bits();
+
+    let children = match (left_is_prefix, right_is_prefix) {
+        // Both labels are equal
+        (true, true) => {
+            if lcp_len == prev_key_bit_len {
+                // Both nodes are leaves with the same key
+                let mut key = prefix.clone();
+                ok!(key.store_slice_data(left_label));
+                let Some(value) = ok!((ops.on_both)(&key, left_data, right_data)) else {
+                    return Ok(None);
+                };
+
+                let mut builder = CellBuilder::new();
+                ok!(write_label(&left_label, prev_key_bit_len, &mut builder));
+                ok!(builder.store_builder(&value));
+                return builder.build_ext(context).map(Some);
+            }
+
+            // Both nodes are forks
+            let mut children = [None, None];
+            for (bit, child) in children.iter_mut().enumerate() {
+                *child = ok!(merge_subtrees(
+                    ok!(left_data.get_reference_cloned(bit as u8)),
+                    ok!(right_data.get_reference_cloned(bit as u8)),
+                    &ok!(child_prefix(bit != 0)),
+                    key_bit_len,
+                    ops,
+                    context,
+                ));
+            }
+            children
+        }
+        // Right node is in the subtree of the left fork
+        (true, false) => {
+            let (bit, right) = ok!(split_node(&right_label, right_data, context));
+            let mut children = [None, None];
+            if ops.keep_left {
+                children[!bit as usize] = Some(ok!(left_data.get_reference_cloned(!bit as u8)));
+            }
+            children[bit as usize] = ok!(merge_subtrees(
+                ok!(left_data.get_reference_cloned(bit as u8)),
+                right,
+                &ok!(child_prefix(bit)),
+                key_bit_len,
+                ops,
+                context,
+            ));
+            children
+        }
+        // Left node is in the subtree of the right fork
+        (false, true) => {
+            let (bit, left) = ok!(split_node(&left_label, left_data, context));
+            let mut children = [None, None];
+            if ops.keep_right {
+                children[!bit as usize] = Some(ok!(right_data.get_reference_cloned(!bit as u8)));
+            }
+            children[bit as usize] = ok!(merge_subtrees(
+                left,
+                ok!(right_data.get_reference_cloned(bit as u8)),
+                &ok!(child_prefix(bit)),
+                key_bit_len,
+                ops,
+                context,
+            ));
+            children
+        }
+        // Labels diverge, so subtrees don't overlap
+        (false, false) => {
+            let mut children = [None, None];
+            if ops.keep_left {
+                let (bit, left) = ok!(split_node(&left_label, left_data, context));
+                children[bit as usize] = Some(left);
+            }
+            if ops.keep_right {
+                let (bit, right) = ok!(split_node(&right_label, right_data, context));
+                children[bit as usize] = Some(right);
+            }
+            children
+        }
+    };
+
+    let label = left_label.get_prefix(lcp_len, 0);
+    match ops.comparator {
+        None => make_fork(&label, prev_key_bit_len, children, context),
+        Some(comparator) => make_aug_fork(&label, prev_key_bit_len, children, comparator, context),
+    }
+}
+
+/// Creates a fork node from the optional children.
+///
+/// If only one child is present, it is merged with the fork label.
+fn make_fork(
+    label: &CellSlice<'_>,
+    key_bit_len: u16,
+    children: [Option<Cell>; 2],
+    context: &mut dyn CellContext,
+) -> Result<Option<Cell>, Error> {
+    let (bit, child) = match children {
+        [Some(left), Some(right)] => {
+            let mut builder = CellBuilder::new();
+            ok!(write_label(label, key_bit_len, &mut builder));
+            ok!(builder.store_reference(left));
+            ok!(builder.store_reference(right));
+            return builder.build_ext(context).map(Some);
+        }
+        [Some(child), None] => (false, child),
+        [None, Some(child)] => (true, child),
+        [None, None] => return Ok(None),
+    };
+
+    let child = ok!(context.load_cell(child, LoadMode::Full));
+    let mut child_data = ok!(child.as_slice());
+    let child_label = ok!(read_label(
+        &mut child_data,
+        key_bit_len - label.remaining_bits() - 1
+    ));
+
+    let mut merged_label = CellBuilder::new();
+    ok!(merged_label.store_slice_data(label));
+    ok!(merged_label.store_bit(bit));
+    ok!(merged_label.store_slice_data(child_label));
+
+    relabel_node(
+        &merged_label.as_data_slice(),
+        key_bit_len,
+        child_data,
+        context,
+    )
+    .map(Some)
+}
+
+/// Creates a fork node of an augmented dictionary from the optional children.
+///
+/// Same as [`make_fork`], but the extra value of the fork is computed
+/// from the extra values of both children.
+fn make_aug_fork(
+    label: &CellSlice<'_>,
+    key_bit_len: u16,
+    children: [Option<Cell>; 2],
+    comparator: AugDictFn,
+    context: &mut dyn CellContext,
+) -> Result<Option<Cell>, Error> {
+    let [Some(left), Some(right)] = children else {
+        // NOTE: a single child is merged with the fork and keeps its extra
+        return make_fork(label, key_bit_len, children, context);
+    };
+
+    let child_key_bit_len = key_bit_len - label.remaining_bits() - 1;
+
+    let left_slice = &mut ok!(left.as_slice());
+    let right_slice = &mut ok!(right.as_slice());
+    ok!(read_label(left_slice, child_key_bit_len));
+    ok!(read_label(right_slice, child_key_bit_len));
+
+    let mut builder = CellBuilder::new();
+    ok!(write_label(label, key_bit_len, &mut builder));
+    ok!(builder.store_reference(left.clone()));
+    ok!(builder.store_reference(right.clone()));
+    ok!(comparator(left_slice, right_slice, &mut builder, context));
+    builder.build_ext(context).map(Some)
+}
+
+/// Creates a node with the new label and the same content.
+fn relabel_node(
+    label: &CellSlice<'_>,
+    key_bit_len: u16,
+    data: CellSlice<'_>,
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    let mut builder = CellBuilder::new();
+    ok!(write_label(label, key_bit_len, &mut builder));
+    ok!(builder.store_slice(data));
+    builder.build_ext(context)
+}
+
+/// Dictionary entry difference.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DiffEntry<V> {
+    /// The entry is present only in the right dictionary.
+    Added(V),
+    /// The entry is present only in the left dictionary.
+    Removed(V),
+    /// The entry is present in both dictionaries with different values.
+    Changed {
+        /// Value from the left dictionary.
+        old: V,
+        /// Value from the right dictionary.
+        new: V,
+    },
+}
+
+/// Walks both dictionaries in parallel and calls `f` for each entry
+/// which differs (in ascending order of the key bits).
+///
+/// Identical subtrees are compared by their representation hashes
+/// and skipped without visiting them, so the traversal only touches
+/// the parts which are different. See [`DiffRawIter`] for the lazy version.
+pub fn dict_diff<F>(
+    left: Option<&Cell>,
+    right: Option<&Cell>,
+    key_bit_len: u16,
+    mut f: F,
+    context: &mut dyn CellContext,
+) -> Result<(), Error>
+where
+    F: FnMut(&CellBuilder, DiffEntry<CellSlice<'_>>) -> Result<(), Error>,
+{
+    let left = left.map(AsRef::as_ref);
+    let right = right.map(AsRef::as_ref);
+    for entry in DiffRawIter::new_ext(left, right, key_bit_len, context) {
+        let (key, entry) = ok!(entry);
+        ok!(f(&key, entry));
+    }
+    Ok(())
+}
+
+/// Builds a dictionary from the entries sorted by key.
+///
+/// Keys must be unique and sorted in ascending order of their bits
+/// (i.e. in the same order as unsigned dictionary iterators yield them),
+/// otherwise [`Error::InvalidData`] is returned before any cell is created.
+///
+/// Unlike sequential insertion, exactly `2n - 1` cells are created for `n` entries.
+pub fn build_dict_from_sorted_iter<I, V>(
+    entries: I,
+    key_bit_len: u16,
+    context: &mut dyn CellContext,
+) -> Result<Option<Cell>, Error>
+where
+    I: IntoIterator<Item = (CellBuilder, V)>,
+    V: Store,
+{
+    let entries = entries.into_iter();
+    let mut sorted = Vec::<(CellBuilder, V)>::with_capacity(entries.size_hint().0);
+
+    for (key, value) in entries {
+        ok!(check_next_sorted_key(sorted.last(), &key, key_bit_len));
+        sorted.push((key, value));
+    }
+
+    if sorted.is_empty() {
+        Ok(None)
+    } else {
+        build_sorted_subtree(&sorted, 0, key_bit_len, context).map(Some)
+    }
+}
+
+/// Inserts the entries sorted by key into the dictionary,
+/// replacing the existing values.
+///
+/// Keys must be unique and sorted in ascending order of their bits
+/// (see [`build_dict_from_sorted_iter`]), otherwise [`Error::InvalidData`]
+/// is returned before the dictionary is modified.
+///
+/// Unlike sequential insertion, each affected path is rebuilt only once.
+pub fn dict_insert_sorted_iter<I, V>(
+    dict: &mut Option<Cell>,
+    entries: I,
+    key_bit_len: u16,
+    context: &mut dyn CellContext,
+) -> Result<(), Error>
+where
+    I: IntoIterator<Item = (CellBuilder, V)>,
+    V: Store,
+{
+    let Some(root) = dict.clone() else {
+        *dict = ok!(build_dict_from_sorted_iter(entries, key_bit_len, context));
+        return Ok(());
+    };
+
+    let entries = entries.into_iter();
+    let mut sorted = Vec::<(CellBuilder, V)>::with_capacity(entries.size_hint().0);
+    for (key, value) in entries {
+        ok!(check_next_sorted_key(sorted.last(), &key, key_bit_len));
+        sorted.push((key, value));
+    }
+
+    if !sorted.is_empty() {
+        *dict = Some(ok!(merge_sorted_subtree(
+            root,
+            &sorted,
+            0,
+            key_bit_len,
+            context
+        )));
+    }
+    Ok(())
+}
+
+fn check_next_sorted_key<V>(
+    prev: Option<&(CellBuilder, V)>,
+    key: &CellBuilder,
+    key_bit_len: u16,
+) -> Result<(), Error> {
+    if key.bit_len() != key_bit_len {
+        return Err(Error::CellUnderflow);
+    }
+
+    if let Some((prev_key, _)) = prev {
+        let prev_key = prev_key.as_data_slice();
+        let lcp = prev_key.longest_common_data_prefix(&key.as_data_slice());
+
+        // NOTE: the previous key must have zero bit at the first difference
+        let lcp_len = lcp.remaining_bits();
+        if lcp_len >= key_bit_len || ok!(prev_key.get_bit(lcp_len)) {
+            return Err(Error::InvalidData);
+        }
+    }
+
+    Ok(())
+}
+
+/// Merges the non-empty sorted entries into the existing subtree.
+/// All entries must have the same first `offset` bits of the key as the subtree.
+fn merge_sorted_subtree<V: Store>(
+    node: Cell,
+    entries: &[(CellBuilder, V)],
+    offset: u16,
+    key_bit_len: u16,
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    let node = ok!(context.load_cell(node, LoadMode::Full));
+    let mut data = ok!(node.as_slice());
+
+    let prev_key_bit_len = key_bit_len - offset;
+    let label = ok!(read_label(&mut data, prev_key_bit_len));
+
+    // Sorted keys have the smallest common prefix with the label at the ends
+    let lcp_len = {
+        let key_suffix = |key: &CellBuilder| {
+            let mut key = key.as_data_slice();
+            key.try_advance(offset, 0);
+            label.longest_common_data_prefix(&key).remaining_bits()
+        };
+        let first = key_suffix(&entries[0].0);
+        let last = key_suffix(&entries[entries.len() - 1].0);
+        std::cmp::min(first, last)
+    };
+    let fork_offset = offset + lcp_len;
+
+    // Find the first key which goes to the right branch
+    let split = entries
+        .partition_point(|(key, _)| matches!(key.as_data_slice().get_bit(fork_offset), Ok(false)));
+    let (left_entries, right_entries) = entries.split_at(split);
+
+    let (left, right) = if lcp_len == label.remaining_bits() {
+        if fork_offset == key_bit_len {
+            // Replace the existing value (keys are unique so there is only one entry)
+            return make_leaf(&label, prev_key_bit_len, &entries[0].1, context);
+        }
+
+        // Merge entries into both branches of the existing fork
+        let mut merge_branch = |index: u8, entries: &[(CellBuilder, V)]| {
+            let child = ok!(data.get_reference_cloned(index));
+            if entries.is_empty() {
+                Ok(child)
+            } else {
+                merge_sorted_subtree(child, entries, fork_offset + 1, key_bit_len, context)
+            }
+        };
+        let left = ok!(merge_branch(0, left_entries));
+        let right = ok!(merge_branch(1, right_entries));
+        (left, right)
+    } else {
+        // Split the existing edge at the first difference
+        let mut label_rem = label;
+        label_rem.try_advance(lcp_len, 0);
+        let old_to_right = ok!(label_rem.load_bit());
+
+        let rem_key_bit_len = key_bit_len - fork_offset - 1;
+        let existing = ok!(relabel_node(&label_rem, rem_key_bit_len, data, context));
+
+        let (old_entries, new_entries) = if old_to_right {
+            (right_entries, left_entries)
+        } else {
+            (left_entries, right_entries)
+        };
+
+        let old = if old_entries.is_empty() {
+            existing
+        } else {
+            ok!(merge_sorted_subtree(
+                existing,
+                old_entries,
+                fork_offset + 1,
+                key_bit_len,
+                context
+            ))
+        };
+        let new = ok!(build_sorted_subtree(
+            new_entries,
+            fork_offset + 1,
+            key_bit_len,
+            context
+        ));
+
+        if old_to_right {
+            (new, old)
+        } else {
+            (old, new)
+        }
+    };
+
+    // Create fork
+    let mut builder = CellBuilder::new();
+    ok!(write_label(
+        &label.get_prefix(lcp_len, 0),
+        prev_key_bit_len,
+        &mut builder
+    ));
+    ok!(builder.store_reference(left));
+    ok!(builder.store_reference(right));
+    builder.build_ext(context)
+}
+
+/// Builds a subtree for the non-empty sorted entries
+/// which have the same first `offset` bits of the key.
+fn build_sorted_subtree<V: Store>(
+    entries: &[(CellBuilder, V)],
+    offset: u16,
+    key_bit_len: u16,
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    fn key_suffix(key: &CellBuilder, offset: u16) -> CellSlice<'_> {
+        let mut key = key.as_data_slice();
+        key.try_advance(offset, 0);
+        key
+    }
+
+    let (first_key, first_value) = &entries[0];
+    let first_key = key_suffix(first_key, offset);
+
+    let last_key = match entries.last() {
+        Some((key, _)) if entries.len() > 1 => key_suffix(key, offset),
+        _ => return make_leaf(&first_key, key_bit_len - offset, first_value, context),
+    };
+
+    // Sorted keys have the same prefix as the first and the last key
+    let lcp = first_key.longest_common_data_prefix(&last_key);
+    let fork_offset = offset + lcp.remaining_bits();
+
+    // Find the first key which goes to the right branch
+    let split = entries
+        .partition_point(|(key, _)| matches!(key.as_data_slice().get_bit(fork_offset), Ok(false)));
+
+    let left = ok!(build_sorted_subtree(
+        &entries[..split],
+        fork_offset + 1,
+        key_bit_len,
+        context
+    ));
+    let right = ok!(build_sorted_subtree(
+        &entries[split..],
+        fork_offset + 1,
+        key_bit_len,
+        context
+    ));
+
+    // Create fork
+    let mut builder = CellBuilder::new();
+    ok!(write_label(&lcp, key_bit_len - offset, &mut builder));
+    ok!(builder.store_reference(left));
+    ok!(builder.store_reference(right));
+    builder.build_ext(context)
+}
+
+/// Creates a leaf node
+fn make_leaf(
+    key: &CellSlice,
+    key_bit_len: u16,
+    value: &dyn Store,
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    let mut builder = CellBuilder::new();
+    ok!(write_label(key, key_bit_len, &mut builder));
+    ok!(value.store_into(&mut builder, context));
+    builder.build_ext(context)
+}
+
+/// Creates a leaf node with extra value
+fn make_leaf_with_extra(
+    key: &CellSlice,
+    key_bit_len: u16,
+    extra: &dyn Store,
+    value: &dyn Store,
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    let mut builder = CellBuilder::new();
+    ok!(write_label(key, key_bit_len, &mut builder));
+    ok!(extra.store_into(&mut builder, context));
+    ok!(value.store_into(&mut builder, context));
+    builder.build_ext(context)
+}
+
+/// Splits an edge or leaf
+fn split_edge(
+    data: &CellSlice,
+    prefix: &mut CellSlice,
+    lcp: &CellSlice,
+    key: &mut CellSlice,
+    value: &dyn Store,
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    // Advance the key
+    let prev_key_bit_len = key.remaining_bits();
+    if !key.try_advance(lcp.remaining_bits() + 1, 0) {
+        return Err(Error::CellUnderflow);
+    }
+
+    // Read the next bit from the data
+    prefix.try_advance(lcp.remaining_bits(), 0);
+    let old_to_right = ok!(prefix.load_bit());
+
+    // Create a leaf for the old value
     let mut left = ok!(make_leaf(prefix, key.remaining_bits(), data, context));
     // Create a leaf for the right value
     let mut right = ok!(make_leaf(key, key.remaining_bits(), value, context));
@@ -1457,6 +2810,131 @@ fn split_aug_edge(
     builder.build_ext(context)
 }
 
+/// Walks the whole dictionary and checks its structure.
+///
+/// Verifies that every edge label is well-formed and fits into the key,
+/// that all forks have both branches, and that all leaves are at exactly
+/// `key_bit_len` bits. Calls `f` for each leaf value.
+///
+/// NOTE: each fork consumes at least one key bit, so the traversal depth
+/// is bounded by `key_bit_len` even for malicious dictionaries.
+pub fn dict_validate<'a, F>(
+    dict: Option<&'a Cell>,
+    key_bit_len: u16,
+    mut f: F,
+) -> Result<DictStats, Error>
+where
+    F: FnMut(CellSlice<'a>) -> Result<(), Error>,
+{
+    let mut stats = DictStats::default();
+
+    let Some(root) = dict else {
+        return Ok(stats);
+    };
+
+    let mut stack = Vec::<(&'a DynCell, u16, u16)>::new();
+    stack.push((root.as_ref(), key_bit_len, 0));
+
+    while let Some((cell, key_bit_len, depth)) = stack.pop() {
+        let mut data = ok!(cell.as_slice());
+
+        // Read the key part written in the current edge
+        let label = ok!(read_label(&mut data, key_bit_len));
+        match key_bit_len.checked_sub(label.remaining_bits()) {
+            // All key parts were collected <=> leaf found
+            Some(0) => {
+                stats.entry_count += 1;
+                stats.max_depth = std::cmp::max(stats.max_depth, depth);
+                ok!(f(data));
+            }
+            // Fork must contain only two branches
+            Some(remaining_bits) => {
+                if !data.is_data_empty() || data.remaining_refs() != 2 {
+                    return Err(Error::InvalidData);
+                }
+                // Push right branch first to visit leaves in key order
+                for index in [1, 0] {
+                    let child = ok!(data.get_reference(index));
+                    stack.push((child, remaining_bits - 1, depth + 1));
+                }
+            }
+            // Label is longer than the remaining key
+            None => return Err(Error::InvalidData),
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Walks the whole dictionary and checks all trie invariants.
+///
+/// In addition to the checks of [`dict_validate`], recomputes the hash
+/// of each cell. This is much slower, so use it only to diagnose corrupted
+/// dictionaries.
+///
+/// NOTE: keys are always unique and sorted, since each fork appends
+/// a different bit to the keys of its branches.
+pub fn dict_verify_consistency(dict: Option<&Cell>, key_bit_len: u16) -> Result<(), DictError> {
+    let Some(root) = dict else {
+        return Ok(());
+    };
+
+    let mut stack = Vec::<(&DynCell, u16)>::new();
+    stack.push((root.as_ref(), key_bit_len));
+
+    while let Some((cell, key_bit_len)) = stack.pop() {
+        // Recompute the cell hash from its data and children
+        let mut builder = CellBuilder::new();
+        builder.set_exotic(cell.is_exotic());
+        ok!(builder
+            .store_raw(cell.data(), cell.bit_len())
+            .map_err(DictError::Cell));
+        for child in cell.references().cloned() {
+            ok!(builder.store_reference(child).map_err(DictError::Cell));
+        }
+        let rebuilt = ok!(builder.build().map_err(DictError::Cell));
+        if rebuilt.repr_hash() != cell.repr_hash() {
+            return Err(DictError::HashMismatch);
+        }
+
+        let mut data = ok!(cell.as_slice().map_err(DictError::Cell));
+
+        // Read the key part written in the current edge
+        let Ok(label) = read_label(&mut data, key_bit_len) else {
+            return Err(DictError::InvalidLabel);
+        };
+        let remaining_bits = match key_bit_len.checked_sub(label.remaining_bits()) {
+            // All key parts were collected <=> leaf found
+            Some(0) => continue,
+            Some(remaining_bits) => remaining_bits,
+            // Label is longer than the remaining key
+            None => return Err(DictError::InvalidLabel),
+        };
+
+        if data.remaining_refs() < 2 {
+            return Err(DictError::EmptyBranch);
+        } else if !data.is_data_empty() || data.remaining_refs() != 2 {
+            return Err(DictError::InvalidFork);
+        }
+
+        for index in [1, 0] {
+            let child = ok!(data.get_reference(index).map_err(DictError::Cell));
+            stack.push((child, remaining_bits - 1));
+        }
+    }
+
+    Ok(())
+}
+
+/// Dictionary structure stats.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct DictStats {
+    /// The number of entries in the dictionary.
+    pub entry_count: usize,
+    /// The max number of forks on the path from the root to any leaf.
+    pub max_depth: u16,
+}
+
 /// Type alias for a pair of key and value as cell slice parts.
 pub type DictOwnedEntry = (CellBuilder, CellSliceParts);
 
@@ -1727,7 +3205,10 @@ fn write_label_parts(
     label.store_slice_data(rem)
 }
 
-fn read_label<'a>(label: &mut CellSlice<'a>, key_bit_len: u16) -> Result<CellSlice<'a>, Error> {
+pub(crate) fn read_label<'a>(
+    label: &mut CellSlice<'a>,
+    key_bit_len: u16,
+) -> Result<CellSlice<'a>, Error> {
     let bits_for_len = (16 - key_bit_len.leading_zeros()) as u16;
 
     if bits_for_len == 0 && label.is_data_empty() {
@@ -1892,4 +3373,142 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn byte_array_and_ip_keys() -> anyhow::Result<()> {
+        use std::net::{Ipv4Addr, Ipv6Addr};
+
+        use crate::dict::Dict;
+
+        assert_eq!(<[u8; 4]>::BITS, 32);
+        assert_eq!(<[u8; 33]>::BITS, 264);
+
+        let mut dict = Dict::<[u8; 33], u32>::new();
+        let keys = [[0x00; 33], [0xaa; 33], [0xff; 33]];
+        for (i, key) in keys.iter().enumerate() {
+            dict.set(key, i as u32)?;
+        }
+        assert_eq!(dict.keys().collect::<Result<Vec<_>, _>>()?, keys);
+        assert_eq!(dict.get([0xaa; 33])?, Some(1));
+
+        let mut dict = Dict::<Ipv4Addr, u32>::new();
+        dict.set(Ipv4Addr::new(192, 168, 0, 1), 1)?;
+        dict.set(Ipv4Addr::LOCALHOST, 2)?;
+        assert_eq!(
+            dict.keys().collect::<Result<Vec<_>, _>>()?,
+            [Ipv4Addr::LOCALHOST, Ipv4Addr::new(192, 168, 0, 1)]
+        );
+
+        let mut dict = Dict::<Ipv6Addr, u32>::new();
+        let addr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+        dict.set(addr, 1)?;
+        dict.set(Ipv6Addr::LOCALHOST, 2)?;
+        assert_eq!(
+            dict.keys().collect::<Result<Vec<_>, _>>()?,
+            [Ipv6Addr::LOCALHOST, addr]
+        );
+
+        let cell = CellBuilder::build_from(addr)?;
+        assert_eq!(cell.parse::<Ipv6Addr>()?, addr);
+
+        Ok(())
+    }
+
+    #[test]
+    fn derived_dict_key() -> anyhow::Result<()> {
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Store, Load, DictKey)]
+        struct ShardKey {
+            workchain: i32,
+            prefix: u64,
+        }
+
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Store, Load, DictKey)]
+        #[tlb(tag = "#a")]
+        struct TaggedKey(u8, bool);
+
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Store, Load, DictKey)]
+        struct PackedKey {
+            #[dict_key(bits = 12)]
+            id: u16,
+            flags: u8,
+        }
+
+        fn check<K>(keys: &[K]) -> anyhow::Result<()>
+        where
+            K: Store + DictKey + Copy + Eq + std::fmt::Debug,
+        {
+            let mut dict = crate::dict::Dict::<K, u32>::new();
+            for (i, key) in keys.iter().enumerate() {
+                dict.set(key, i as u32)?;
+            }
+            for (i, key) in keys.iter().enumerate() {
+                assert_eq!(dict.get(key)?, Some(i as u32));
+            }
+
+            let mut loaded = dict.keys().collect::<Result<Vec<_>, _>>()?;
+            loaded.sort_by_key(|key| keys.iter().position(|k| k == key));
+            assert_eq!(loaded, keys);
+            Ok(())
+        }
+
+        assert_eq!(ShardKey::BITS, 96);
+        check(&[
+            ShardKey {
+                workchain: 0,
+                prefix: 0x8000_0000_0000_0000,
+            },
+            ShardKey {
+                workchain: -1,
+                prefix: 0x8000_0000_0000_0000,
+            },
+            ShardKey {
+                workchain: 0,
+                prefix: 0x4000_0000_0000_0000,
+            },
+        ])?;
+
+        assert_eq!(TaggedKey::BITS, 4 + 8 + 1);
+        check(&[
+            TaggedKey(0, false),
+            TaggedKey(123, true),
+            TaggedKey(255, false),
+        ])?;
+        let mut raw_data = [0; 128];
+        raw_data[0] = 0xb0;
+        assert_eq!(TaggedKey::from_raw_data(&raw_data), None);
+
+        assert_eq!(PackedKey::BITS, 12 + 8);
+        check(&[
+            PackedKey { id: 0, flags: 1 },
+            PackedKey {
+                id: 0xfff,
+                flags: 0xff,
+            },
+            PackedKey {
+                id: 0x123,
+                flags: 0x45,
+            },
+        ])?;
+
+        // Store and Load use the same narrowed layout
+        let key = PackedKey {
+            id: 0xabc,
+            flags: 0xde,
+        };
+        let cell = CellBuilder::build_from(key)?;
+        assert_eq!(cell.bit_len(), PackedKey::BITS);
+        assert_eq!(cell.as_slice()?.load_uint(20)?, 0xabcde);
+        assert_eq!(cell.parse::<PackedKey>()?, key);
+
+        let overflow = PackedKey {
+            id: 0x1000,
+            flags: 0,
+        };
+        assert!(matches!(
+            CellBuilder::build_from(overflow),
+            Err(Error::IntOverflow)
+        ));
+
+        Ok(())
+    }
 }
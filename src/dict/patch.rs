@@ -0,0 +1,421 @@
+use crate::cell::*;
+use crate::error::Error;
+
+use super::{
+    build_dict_from_sorted_iter, check_next_sorted_key, dict_diff, dict_difference, dict_insert,
+    dict_insert_sorted_iter, dict_remove_owned, Dict, DictKey, SetMode, Values,
+};
+
+/// A single dictionary operation.
+///
+/// ```text
+/// dict_patch_insert$0 {K:Type} {V:Type} key:K value:^V = DictPatch K V;
+/// dict_patch_remove$1 {K:Type} {V:Type} key:K = DictPatch K V;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DictPatch<K, V> {
+    /// Sets the value for the key.
+    Insert {
+        /// Entry key.
+        key: K,
+        /// New value.
+        value: V,
+    },
+    /// Removes the entry for the key.
+    Remove {
+        /// Entry key.
+        key: K,
+    },
+}
+
+impl<K, V> DictPatch<K, V> {
+    /// Returns the key of the operation.
+    pub fn key(&self) -> &K {
+        match self {
+            Self::Insert { key, .. } | Self::Remove { key } => key,
+        }
+    }
+}
+
+impl<K: Store, V: Store> Store for DictPatch<K, V> {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Insert { key, value } => {
+                ok!(builder.store_bit_zero());
+                ok!(key.store_into(builder, context));
+                let value = ok!(CellBuilder::build_from_ext(value, context));
+                builder.store_reference(value)
+            }
+            Self::Remove { key } => {
+                ok!(builder.store_bit_one());
+                key.store_into(builder, context)
+            }
+        }
+    }
+}
+
+impl<'a, K: Load<'a>, V: Load<'a>> Load<'a> for DictPatch<K, V> {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        if ok!(slice.load_bit()) {
+            Ok(Self::Remove {
+                key: ok!(K::load_from(slice)),
+            })
+        } else {
+            let key = ok!(K::load_from(slice));
+            let value = ok!(V::load_from(&mut ok!(slice.load_reference_as_slice())));
+            Ok(Self::Insert { key, value })
+        }
+    }
+}
+
+/// A sequence of dictionary operations which can be stored in cells
+/// (e.g. to replay or audit state transitions).
+///
+/// ```text
+/// dict_patch_boc$_ {K:Type} {V:Type} ops:(HashmapE 32 (DictPatch K V)) = DictPatchBoc K V;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DictPatchBoc<K, V> {
+    ops: Dict<u32, DictPatch<K, V>>,
+}
+
+impl<K, V> Default for DictPatchBoc<K, V> {
+    #[inline]
+    fn default() -> Self {
+        Self { ops: Dict::new() }
+    }
+}
+
+impl<K, V> DictPatchBoc<K, V> {
+    /// Returns `true` if there are no operations.
+    pub const fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Gets an iterator over the operations in the order of application.
+    pub fn iter<'a>(&'a self) -> Values<'a, DictPatch<K, V>>
+    where
+        K: Load<'a>,
+        V: Load<'a>,
+    {
+        self.ops.values()
+    }
+}
+
+impl<K: Store, V: Store> DictPatchBoc<K, V> {
+    /// Collects the operations into cells.
+    pub fn from_patch<I>(patch: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = DictPatch<K, V>>,
+    {
+        let mut ops = Vec::new();
+        for (i, op) in patch.into_iter().enumerate() {
+            let Ok(i) = u32::try_from(i) else {
+                return Err(Error::IntOverflow);
+            };
+            ops.push((i, op));
+        }
+
+        Ok(Self {
+            ops: ok!(Dict::from_sorted_pairs(ops)),
+        })
+    }
+}
+
+impl<K, V> Store for DictPatchBoc<K, V> {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        self.ops.store_into(builder, context)
+    }
+}
+
+impl<'a, K, V> Load<'a> for DictPatchBoc<K, V> {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        Ok(Self {
+            ops: ok!(Dict::load_from(slice)),
+        })
+    }
+}
+
+impl<K, V> Dict<K, V>
+where
+    K: Store + DictKey,
+    V: Store,
+{
+    /// Applies all operations in order.
+    ///
+    /// If the keys of the patch are unique and sorted, all affected paths
+    /// are rebuilt in a single pass. Otherwise, operations are applied
+    /// one by one.
+    ///
+    /// Returns the number of entries which were added, removed or changed.
+    /// The dictionary is left untouched on error.
+    pub fn apply_patch<I>(&mut self, patch: I) -> Result<usize, Error>
+    where
+        I: IntoIterator<Item = DictPatch<K, V>>,
+    {
+        let cx = &mut Cell::empty_context();
+
+        let patch = patch.into_iter();
+        let mut ops = Vec::<(CellBuilder, Option<V>)>::with_capacity(patch.size_hint().0);
+        let mut sorted = true;
+        for op in patch {
+            let mut key = CellBuilder::new();
+            ok!(op.key().store_into(&mut key, cx));
+            let value = match op {
+                DictPatch::Insert { value, .. } => Some(value),
+                DictPatch::Remove { .. } => None,
+            };
+
+            if sorted {
+                match check_next_sorted_key(ops.last(), &key, K::BITS) {
+                    Ok(()) => {}
+                    Err(Error::InvalidData) => sorted = false,
+                    Err(e) => return Err(e),
+                }
+            }
+            ops.push((key, value));
+        }
+
+        let mut root = self.root.clone();
+        if sorted {
+            // NOTE: keys are unique, so the order of removals and insertions doesn't matter
+            let (inserts, removes) = ops
+                .into_iter()
+                .partition::<Vec<_>, _>(|(_, value)| value.is_some());
+
+            if !removes.is_empty() {
+                let removes = ok!(build_dict_from_sorted_iter(
+                    removes.into_iter().map(|(key, _)| (key, ())),
+                    K::BITS,
+                    cx
+                ));
+                root = ok!(dict_difference(
+                    root.as_ref(),
+                    removes.as_ref(),
+                    K::BITS,
+                    cx
+                ));
+            }
+
+            ok!(dict_insert_sorted_iter(
+                &mut root,
+                inserts
+                    .into_iter()
+                    .filter_map(|(key, value)| Some((key, value?))),
+                K::BITS,
+                cx
+            ));
+        } else {
+            for (key, value) in ops {
+                let key = &mut key.as_data_slice();
+                match value {
+                    Some(value) => {
+                        ok!(dict_insert(
+                            &mut root,
+                            key,
+                            K::BITS,
+                            &value,
+                            SetMode::Set,
+                            cx
+                        ));
+                    }
+                    None => {
+                        ok!(dict_remove_owned(&mut root, key, K::BITS, false, cx));
+                    }
+                }
+            }
+        }
+
+        let mut changed = 0;
+        ok!(dict_diff(
+            self.root.as_ref(),
+            root.as_ref(),
+            K::BITS,
+            |_, _| {
+                changed += 1;
+                Ok(())
+            },
+            cx
+        ));
+
+        self.root = root;
+        Ok(changed)
+    }
+
+    /// Sets the values for all keys.
+    ///
+    /// Sorted pairs with unique keys are inserted in a single pass.
+    /// See [`apply_patch`] for details.
+    ///
+    /// Returns the number of entries which were added or changed.
+    ///
+    /// [`apply_patch`]: Dict::apply_patch
+    pub fn set_many<I>(&mut self, pairs: I) -> Result<u64, Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let patch = pairs
+            .into_iter()
+            .map(|(key, value)| DictPatch::Insert { key, value });
+        self.apply_patch(patch).map(|changed| changed as u64)
+    }
+
+    /// Removes all keys.
+    ///
+    /// Sorted unique keys are removed in a single pass.
+    /// See [`apply_patch`] for details.
+    ///
+    /// Returns the number of entries which were removed.
+    ///
+    /// [`apply_patch`]: Dict::apply_patch
+    pub fn delete_many<I>(&mut self, keys: I) -> Result<u64, Error>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let patch = keys.into_iter().map(|key| DictPatch::Remove { key });
+        self.apply_patch(patch).map(|changed| changed as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::boc::BocRepr;
+
+    fn check_patch(
+        dict: &Dict<u32, u64>,
+        patch: &[DictPatch<u32, u64>],
+        expected_changed: usize,
+    ) -> anyhow::Result<Dict<u32, u64>> {
+        let mut map = BTreeMap::new();
+        for entry in dict.iter() {
+            let (key, value) = entry?;
+            map.insert(key, value);
+        }
+        for op in patch {
+            match op {
+                DictPatch::Insert { key, value } => map.insert(*key, *value),
+                DictPatch::Remove { key } => map.remove(key),
+            };
+        }
+
+        let mut patched = dict.clone();
+        let changed = patched.apply_patch(patch.iter().cloned())?;
+        assert_eq!(changed, expected_changed);
+
+        let expected = Dict::<u32, u64>::from_sorted_pairs(map)?;
+        assert_eq!(patched, expected);
+        Ok(patched)
+    }
+
+    #[test]
+    fn apply_patch() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u64>::new();
+        for i in 0..100 {
+            dict.set(i * 10, i as u64)?;
+        }
+
+        // Empty patch
+        check_patch(&dict, &[], 0)?;
+
+        // Sorted patch
+        let sorted = [
+            DictPatch::Insert { key: 0, value: 0 }, // same value
+            DictPatch::Remove { key: 5 },           // absent key
+            DictPatch::Remove { key: 10 },
+            DictPatch::Insert { key: 15, value: 1 },
+            DictPatch::Insert {
+                key: 20,
+                value: 123,
+            },
+            DictPatch::Remove { key: 990 },
+            DictPatch::Insert {
+                key: u32::MAX,
+                value: 1,
+            },
+        ];
+        check_patch(&dict, &sorted, 5)?;
+
+        // Unsorted patch with repeated keys
+        let unsorted = [
+            DictPatch::Insert { key: 15, value: 1 },
+            DictPatch::Remove { key: 10 },
+            DictPatch::Insert { key: 10, value: 1 },
+            DictPatch::Remove { key: 15 },
+            DictPatch::Insert { key: 10, value: 7 },
+            DictPatch::Remove { key: 0 },
+        ];
+        check_patch(&dict, &unsorted, 2)?;
+
+        // Patch an empty dict
+        let patched = check_patch(&Dict::new(), &sorted, 4)?;
+        let removes = patched
+            .keys()
+            .map(|key| Ok(DictPatch::Remove { key: key? }))
+            .collect::<Result<Vec<_>, Error>>()?;
+        assert!(check_patch(&patched, &removes, 4)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_delete_many() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u64>::new();
+        assert_eq!(dict.set_many((0..100).map(|i| (i * 10, i as u64)))?, 100);
+
+        // Sorted, with already-correct entries
+        assert_eq!(dict.set_many([(0, 0), (5, 1), (10, 2), (20, 2)])?, 2);
+        assert_eq!(dict.get(5)?, Some(1));
+        assert_eq!(dict.get(10)?, Some(2));
+
+        // Unsorted, with repeated keys
+        assert_eq!(dict.set_many([(30, 1), (15, 1), (30, 3)])?, 1);
+        assert_eq!(dict.get(30)?, Some(3));
+
+        assert_eq!(dict.delete_many([5, 6, 15])?, 2);
+        assert_eq!(dict.delete_many([990, 0, 990])?, 2);
+        assert_eq!(dict.len()?, 98);
+
+        let keys = dict.keys().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(dict.delete_many(keys)?, 98);
+        assert!(dict.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_patch_boc() -> anyhow::Result<()> {
+        let patch = vec![
+            DictPatch::Insert {
+                key: 20,
+                value: 123u64,
+            },
+            DictPatch::Remove { key: 10u32 },
+            DictPatch::Insert { key: 10, value: 1 },
+        ];
+
+        let boc = BocRepr::encode(DictPatchBoc::from_patch(patch.clone())?)?;
+        let decoded = BocRepr::decode::<DictPatchBoc<u32, u64>, _>(boc)?;
+        let ops = decoded.iter().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(ops, patch);
+
+        let mut dict = Dict::<u32, u64>::new();
+        assert_eq!(dict.apply_patch(ops)?, 2);
+        assert_eq!(dict.get(10)?, Some(1));
+        assert_eq!(dict.get(20)?, Some(123));
+
+        assert!(DictPatchBoc::<u32, u64>::default().is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,617 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+use crate::cell::*;
+use crate::error::Error;
+
+use super::{read_label, write_label};
+
+/// Prefix dictionary with variable length keys
+/// (where `N` is a maximum number of bits in each key).
+///
+/// No key in the dictionary can be a prefix of another key.
+///
+/// # TLB scheme
+///
+/// ```text
+/// phm_edge#_ {n:#} {X:Type} {l:#} {m:#} label:(HmLabel ~l n)
+///            {n = (~m) + l} node:(PfxHashmapNode m X) = PfxHashmap n X;
+///
+/// phmn_leaf$0 {n:#} {X:Type} value:X = PfxHashmapNode n X;
+/// phmn_fork$1 {n:#} {X:Type} left:^(PfxHashmap n X)
+///             right:^(PfxHashmap n X) = PfxHashmapNode (n + 1) X;
+///
+/// phme_empty$0 {n:#} {X:Type} = PfxHashmapE n X;
+/// phme_root$1 {n:#} {X:Type} root:^(PfxHashmap n X) = PfxHashmapE n X;
+/// ```
+#[repr(transparent)]
+pub struct PfxDict<const N: u16, V> {
+    root: Option<Cell>,
+    _value: PhantomData<V>,
+}
+
+impl<const N: u16, V> ExactSize for PfxDict<N, V> {
+    #[inline]
+    fn exact_size(&self) -> CellSliceSize {
+        CellSliceSize {
+            bits: 1,
+            refs: self.root.is_some() as u8,
+        }
+    }
+}
+
+impl<'a, const N: u16, V> Load<'a> for PfxDict<N, V> {
+    #[inline]
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        Ok(Self::from_raw(ok!(<_>::load_from(slice))))
+    }
+}
+
+impl<const N: u16, V> Store for PfxDict<N, V> {
+    #[inline]
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        self.root.store_into(builder, context)
+    }
+}
+
+impl<const N: u16, V> Default for PfxDict<N, V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: u16, V> Clone for PfxDict<N, V> {
+    fn clone(&self) -> Self {
+        Self::from_raw(self.root.clone())
+    }
+}
+
+impl<const N: u16, V> Eq for PfxDict<N, V> {}
+impl<const N: u16, V> PartialEq for PfxDict<N, V> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(this), Some(other)) => this.as_ref() == other.as_ref(),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<const N: u16, V> std::fmt::Debug for PfxDict<N, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PfxDict")
+            .field("max_key_bit_len", &N)
+            .field("root", &self.root)
+            .finish()
+    }
+}
+
+impl<const N: u16, V> PfxDict<N, V> {
+    /// Creates an empty dictionary.
+    pub const fn new() -> Self {
+        Self {
+            root: None,
+            _value: PhantomData,
+        }
+    }
+
+    /// Creates a dictionary from a raw cell.
+    pub const fn from_raw(root: Option<Cell>) -> Self {
+        Self {
+            root,
+            _value: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the dictionary contains no elements.
+    pub const fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns the underlying root cell of the dictionary.
+    #[inline]
+    pub const fn root(&self) -> &Option<Cell> {
+        &self.root
+    }
+
+    /// Returns the underlying root cell of the dictionary.
+    #[inline]
+    pub fn into_root(self) -> Option<Cell> {
+        self.root
+    }
+
+    /// Returns the value for the key which is exactly equal to the specified one.
+    pub fn get_exact<'a>(&'a self, key: CellSlice<'_>) -> Result<Option<V>, Error>
+    where
+        V: Load<'a>,
+    {
+        let Some((key_len, mut value)) = ok!(pfx_dict_find(self.root.as_deref(), key, N)) else {
+            return Ok(None);
+        };
+        if key_len != key.remaining_bits() {
+            return Ok(None);
+        }
+        V::load_from(&mut value).map(Some)
+    }
+
+    /// Returns the value for the longest key which is a prefix of the specified one
+    /// along with the length of that key in bits.
+    ///
+    /// Since no key in the dictionary can be a prefix of another key,
+    /// there is at most one such entry.
+    pub fn get_longest_prefix<'a>(&'a self, key: CellSlice<'_>) -> Result<Option<(u16, V)>, Error>
+    where
+        V: Load<'a>,
+    {
+        match ok!(pfx_dict_find(self.root.as_deref(), key, N)) {
+            Some((key_len, mut value)) => Ok(Some((key_len, ok!(V::load_from(&mut value))))),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets an iterator over the entries of the dictionary,
+    /// in lexicographic order of the keys.
+    /// The iterator element type is `Result<(CellBuilder, V)>`.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn iter<'a>(&'a self) -> PfxDictIter<'a, V>
+    where
+        V: Load<'a>,
+    {
+        PfxDictIter::new(&self.root, N)
+    }
+}
+
+impl<const N: u16, V: Store> PfxDict<N, V> {
+    /// Sets the value associated with the key in the dictionary.
+    /// Returns `true` if the key was not present before.
+    ///
+    /// Fails with [`Error::PrefixConflict`] if the key is a prefix of
+    /// an existing key or an existing key is a prefix of it.
+    ///
+    /// Use [`set_ext`] if you need to use a custom cell context.
+    ///
+    /// [`set_ext`]: PfxDict::set_ext
+    pub fn set<T>(&mut self, key: CellSlice<'_>, value: T) -> Result<bool, Error>
+    where
+        T: Borrow<V>,
+    {
+        self.set_ext(key, value, &mut Cell::empty_context())
+    }
+
+    /// Sets the value associated with the key in the dictionary.
+    /// Returns `true` if the key was not present before.
+    ///
+    /// Fails with [`Error::PrefixConflict`] if the key is a prefix of
+    /// an existing key or an existing key is a prefix of it.
+    pub fn set_ext<T>(
+        &mut self,
+        key: CellSlice<'_>,
+        value: T,
+        context: &mut dyn CellContext,
+    ) -> Result<bool, Error>
+    where
+        T: Borrow<V>,
+    {
+        if key.remaining_bits() > N {
+            return Err(Error::CellOverflow);
+        }
+
+        let value = value.borrow() as &dyn Store;
+        let (root, added) = match &self.root {
+            None => (ok!(make_pfx_leaf(&key, N, value, context)), true),
+            Some(root) => ok!(pfx_dict_insert(root.as_ref(), key, N, value, context)),
+        };
+        self.root = Some(root);
+        Ok(added)
+    }
+}
+
+/// Returns the length of the stored key which is a prefix of the specified key
+/// and its value.
+fn pfx_dict_find<'a>(
+    root: Option<&'a DynCell>,
+    mut key: CellSlice<'_>,
+    max_key_bit_len: u16,
+) -> Result<Option<(u16, CellSlice<'a>)>, Error> {
+    let Some(mut node) = root else {
+        return Ok(None);
+    };
+
+    let full_key_len = key.remaining_bits();
+    let mut key_bit_len = max_key_bit_len;
+    loop {
+        let mut data = ok!(node.as_slice());
+        let label = ok!(read_pfx_label(&mut data, key_bit_len));
+
+        key = match key.strip_data_prefix(&label) {
+            Some(rest) => rest,
+            None => return Ok(None),
+        };
+
+        if !ok!(data.load_bit()) {
+            // Leaf
+            return Ok(Some((full_key_len - key.remaining_bits(), data)));
+        }
+
+        // Fork
+        let child_key_bit_len = match key_bit_len.checked_sub(label.remaining_bits() + 1) {
+            Some(len) => len,
+            None => return Err(Error::InvalidData),
+        };
+        if key.is_data_empty() {
+            return Ok(None);
+        }
+
+        let bit = ok!(key.load_bit());
+        node = ok!(data.get_reference(bit as u8));
+        key_bit_len = child_key_bit_len;
+    }
+}
+
+fn pfx_dict_insert(
+    node: &DynCell,
+    mut key: CellSlice<'_>,
+    key_bit_len: u16,
+    value: &dyn Store,
+    context: &mut dyn CellContext,
+) -> Result<(Cell, bool), Error> {
+    let mut data = ok!(node.as_slice());
+    let label = ok!(read_pfx_label(&mut data, key_bit_len));
+    let label_len = label.remaining_bits();
+    let lcp_len = key.longest_common_data_prefix(&label).remaining_bits();
+
+    if lcp_len < label_len {
+        if lcp_len == key.remaining_bits() {
+            // New key is a prefix of the existing keys
+            return Err(Error::PrefixConflict);
+        }
+
+        // Split the edge at the first different bit
+        let child_key_bit_len = key_bit_len - lcp_len - 1;
+
+        let mut old_label = label;
+        ok!(old_label.advance(lcp_len + 1, 0));
+        let mut old = CellBuilder::new();
+        ok!(write_label(&old_label, child_key_bit_len, &mut old));
+        ok!(old.store_slice(data));
+        let old = ok!(old.build_ext(context));
+
+        let prefix = key.get_prefix(lcp_len, 0);
+        ok!(key.advance(lcp_len, 0));
+        let bit = ok!(key.load_bit());
+        let new = ok!(make_pfx_leaf(&key, child_key_bit_len, value, context));
+
+        let children = if bit { [old, new] } else { [new, old] };
+        let fork = ok!(make_pfx_fork(&prefix, key_bit_len, children, context));
+        return Ok((fork, true));
+    }
+
+    ok!(key.advance(label_len, 0));
+
+    if !ok!(data.load_bit()) {
+        // Leaf
+        return if key.is_data_empty() {
+            let leaf = ok!(make_pfx_leaf(&label, key_bit_len, value, context));
+            Ok((leaf, false))
+        } else {
+            // Existing key is a prefix of the new key
+            Err(Error::PrefixConflict)
+        };
+    }
+
+    // Fork
+    let child_key_bit_len = match key_bit_len.checked_sub(label_len + 1) {
+        Some(len) => len,
+        None => return Err(Error::InvalidData),
+    };
+    if key.is_data_empty() {
+        // New key is a prefix of the existing keys
+        return Err(Error::PrefixConflict);
+    }
+
+    let bit = ok!(key.load_bit());
+    let mut children = [
+        ok!(data.get_reference_cloned(0)),
+        ok!(data.get_reference_cloned(1)),
+    ];
+
+    let child = &mut children[bit as usize];
+    let (new_child, added) = ok!(pfx_dict_insert(
+        child.as_ref(),
+        key,
+        child_key_bit_len,
+        value,
+        context
+    ));
+    *child = new_child;
+
+    let fork = ok!(make_pfx_fork(&label, key_bit_len, children, context));
+    Ok((fork, added))
+}
+
+fn read_pfx_label<'a>(data: &mut CellSlice<'a>, key_bit_len: u16) -> Result<CellSlice<'a>, Error> {
+    let label = ok!(read_label(data, key_bit_len));
+    if label.remaining_bits() > key_bit_len {
+        return Err(Error::InvalidData);
+    }
+    Ok(label)
+}
+
+fn make_pfx_leaf(
+    label: &CellSlice<'_>,
+    key_bit_len: u16,
+    value: &dyn Store,
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    let mut builder = CellBuilder::new();
+    ok!(write_label(label, key_bit_len, &mut builder));
+    ok!(builder.store_bit_zero());
+    ok!(value.store_into(&mut builder, context));
+    builder.build_ext(context)
+}
+
+fn make_pfx_fork(
+    label: &CellSlice<'_>,
+    key_bit_len: u16,
+    [left, right]: [Cell; 2],
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    let mut builder = CellBuilder::new();
+    ok!(write_label(label, key_bit_len, &mut builder));
+    ok!(builder.store_bit_one());
+    ok!(builder.store_reference(left));
+    ok!(builder.store_reference(right));
+    builder.build_ext(context)
+}
+
+/// An iterator over the entries of a [`PfxDict`].
+///
+/// This struct is created by the [`iter`] method on [`PfxDict`].
+/// See its documentation for more.
+///
+/// [`iter`]: PfxDict::iter
+pub struct PfxDictIter<'a, V> {
+    segments: Vec<PfxIterSegment<'a>>,
+    _value: PhantomData<V>,
+}
+
+struct PfxIterSegment<'a> {
+    data: &'a DynCell,
+    prefix: CellBuilder,
+    key_bit_len: u16,
+}
+
+impl<'a, V> Clone for PfxDictIter<'a, V> {
+    fn clone(&self) -> Self {
+        Self {
+            segments: self
+                .segments
+                .iter()
+                .map(|segment| PfxIterSegment {
+                    data: segment.data,
+                    prefix: segment.prefix.clone(),
+                    key_bit_len: segment.key_bit_len,
+                })
+                .collect(),
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<'a, V> PfxDictIter<'a, V> {
+    /// Creates an iterator over the entries of a dictionary.
+    pub fn new(root: &'a Option<Cell>, max_key_bit_len: u16) -> Self {
+        let mut segments = Vec::new();
+        if let Some(root) = root {
+            segments.push(PfxIterSegment {
+                data: root.as_ref(),
+                prefix: CellBuilder::new(),
+                key_bit_len: max_key_bit_len,
+            });
+        }
+        Self {
+            segments,
+            _value: PhantomData,
+        }
+    }
+
+    fn visit(&mut self) -> Result<Option<(CellBuilder, CellSlice<'a>)>, Error> {
+        while let Some(PfxIterSegment {
+            data,
+            mut prefix,
+            key_bit_len,
+        }) = self.segments.pop()
+        {
+            let mut data = ok!(data.as_slice());
+            let label = ok!(read_pfx_label(&mut data, key_bit_len));
+            ok!(prefix.store_slice_data(label));
+
+            if !ok!(data.load_bit()) {
+                return Ok(Some((prefix, data)));
+            }
+
+            let Some(child_key_bit_len) = key_bit_len.checked_sub(label.remaining_bits() + 1)
+            else {
+                return Err(Error::InvalidData);
+            };
+
+            // NOTE: Push the right branch first to visit the left one first
+            for bit in [true, false] {
+                let child = ok!(data.get_reference(bit as u8));
+                let mut prefix = prefix.clone();
+                ok!(prefix.store_bit(bit));
+                self.segments.push(PfxIterSegment {
+                    data: child,
+                    prefix,
+                    key_bit_len: child_key_bit_len,
+                });
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'a, V> Iterator for PfxDictIter<'a, V>
+where
+    V: Load<'a>,
+{
+    type Item = Result<(CellBuilder, V), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = match self.visit() {
+            Ok(Some((key, mut value))) => match V::load_from(&mut value) {
+                Ok(value) => Ok((key, value)),
+                Err(e) => Err(e),
+            },
+            Ok(None) => return None,
+            Err(e) => Err(e),
+        };
+        if res.is_err() {
+            self.segments.clear();
+        }
+        Some(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_key(bits: &str) -> anyhow::Result<Cell> {
+        let mut builder = CellBuilder::new();
+        for bit in bits.chars() {
+            builder.store_bit(bit == '1')?;
+        }
+        Ok(builder.build()?)
+    }
+
+    #[test]
+    fn pfx_dict_set_get() -> anyhow::Result<()> {
+        let mut dict = PfxDict::<16, u32>::new();
+        assert!(dict.is_empty());
+
+        let keys = ["0", "10", "110", "1110", "11110000", "11111111"];
+        for (i, key) in keys.iter().enumerate() {
+            assert!(dict.set(build_key(key)?.as_slice()?, i as u32)?);
+        }
+
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(dict.get_exact(build_key(key)?.as_slice()?)?, Some(i as u32));
+        }
+        assert_eq!(dict.get_exact(build_key("")?.as_slice()?)?, None);
+        assert_eq!(dict.get_exact(build_key("1")?.as_slice()?)?, None);
+        assert_eq!(dict.get_exact(build_key("01")?.as_slice()?)?, None);
+        assert_eq!(dict.get_exact(build_key("1111")?.as_slice()?)?, None);
+
+        // Replace the existing value
+        assert!(!dict.set(build_key("110")?.as_slice()?, 100)?);
+        assert_eq!(dict.get_exact(build_key("110")?.as_slice()?)?, Some(100));
+
+        // Too long key
+        assert_eq!(
+            dict.set(build_key("11111111000000001")?.as_slice()?, 0),
+            Err(Error::CellOverflow)
+        );
+
+        // Roundtrip
+        let cell = CellBuilder::build_from(&dict)?;
+        let parsed = cell.parse::<PfxDict<16, u32>>()?;
+        assert_eq!(parsed, dict);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pfx_dict_overlapping_insert() -> anyhow::Result<()> {
+        let mut dict = PfxDict::<8, u32>::new();
+        dict.set(build_key("1010")?.as_slice()?, 1)?;
+        dict.set(build_key("0")?.as_slice()?, 2)?;
+        let before = dict.clone();
+
+        for key in ["", "1", "10", "101", "10100", "10101111", "00", "01101"] {
+            assert_eq!(
+                dict.set(build_key(key)?.as_slice()?, 3),
+                Err(Error::PrefixConflict),
+                "key: {key}"
+            );
+        }
+        assert_eq!(dict, before);
+
+        // Empty key occupies the whole dictionary
+        let mut dict = PfxDict::<8, u32>::new();
+        dict.set(build_key("")?.as_slice()?, 1)?;
+        assert_eq!(dict.get_exact(build_key("")?.as_slice()?)?, Some(1));
+        assert_eq!(
+            dict.set(build_key("0")?.as_slice()?, 2),
+            Err(Error::PrefixConflict)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn pfx_dict_longest_prefix() -> anyhow::Result<()> {
+        // Routing table
+        let mut routes = PfxDict::<32, u8>::new();
+        routes.set(build_key("0")?.as_slice()?, 0)?;
+        routes.set(build_key("100")?.as_slice()?, 1)?;
+        routes.set(build_key("1010")?.as_slice()?, 2)?;
+        routes.set(build_key("1011")?.as_slice()?, 3)?;
+        routes.set(build_key("11")?.as_slice()?, 4)?;
+
+        for (addr, expected) in [
+            ("0", Some((1, 0))),
+            ("01111111", Some((1, 0))),
+            ("100", Some((3, 1))),
+            ("10011010", Some((3, 1))),
+            ("10100000", Some((4, 2))),
+            ("1011", Some((4, 3))),
+            ("11000000", Some((2, 4))),
+            ("", None),
+            ("1", None),
+            ("10", None),
+            ("101", None),
+        ] {
+            let addr = build_key(addr)?;
+            assert_eq!(routes.get_longest_prefix(addr.as_slice()?)?, expected);
+        }
+
+        assert_eq!(
+            PfxDict::<32, u8>::new().get_longest_prefix(build_key("1")?.as_slice()?)?,
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn pfx_dict_iter() -> anyhow::Result<()> {
+        let mut keys = vec!["1111", "0", "1101", "10", "1110", "11001"];
+
+        let mut dict = PfxDict::<8, u32>::new();
+        for key in &keys {
+            dict.set(build_key(key)?.as_slice()?, key.len() as u32)?;
+        }
+
+        keys.sort_unstable();
+        let mut count = 0;
+        for (entry, expected) in dict.iter().zip(&keys) {
+            let (key, value) = entry?;
+            assert_eq!(key.bit_len() as u32, value);
+            assert_eq!(key.build()?.as_ref(), build_key(expected)?.as_ref());
+            count += 1;
+        }
+        assert_eq!(count, keys.len());
+
+        assert_eq!(PfxDict::<8, u32>::new().iter().count(), 0);
+
+        Ok(())
+    }
+}
@@ -5,7 +5,8 @@ use crate::util::{unlikely, IterStatus};
 use super::{
     dict_find_bound, dict_find_bound_owned, dict_find_owned, dict_get, dict_get_owned,
     dict_get_subdict, dict_insert, dict_load_from_root, dict_remove_bound_owned, dict_remove_owned,
-    dict_split_by_prefix, read_label, DictBound, DictOwnedEntry, SetMode,
+    dict_split_by_prefix, dict_union, dict_validate, read_label, Dict, DictBound, DictKey,
+    DictOwnedEntry, DictStats, DiffEntry, SetMode,
 };
 
 /// Dictionary with fixed length keys (where `N` is a number of bits in each key).
@@ -98,6 +99,30 @@ impl<const N: u16> From<Option<Cell>> for RawDict<N> {
     }
 }
 
+impl<K: DictKey, V, const N: u16> TryFrom<Dict<K, V>> for RawDict<N> {
+    type Error = Error;
+
+    /// Fails with [`Error::InvalidData`] if `K::BITS` differs from `N`.
+    #[inline]
+    fn try_from(value: Dict<K, V>) -> Result<Self, Self::Error> {
+        Self::from_parts(value.into_root(), K::BITS)
+    }
+}
+
+impl<K: DictKey, V, const N: u16> TryFrom<RawDict<N>> for Dict<K, V> {
+    type Error = Error;
+
+    /// Fails with [`Error::InvalidData`] if `K::BITS` differs from `N`.
+    #[inline]
+    fn try_from(value: RawDict<N>) -> Result<Self, Self::Error> {
+        if K::BITS == N {
+            Ok(Dict::from_raw(value.0))
+        } else {
+            Err(Error::InvalidData)
+        }
+    }
+}
+
 impl<const N: u16> std::fmt::Debug for RawDict<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RawDict")
@@ -120,13 +145,75 @@ impl<const N: u16> RawDict<N> {
         self.0.is_none()
     }
 
+    /// Creates a dictionary from a raw root cell.
+    ///
+    /// NOTE: The tree is not validated, use [`validate`] to check it.
+    ///
+    /// [`validate`]: RawDict::validate
+    #[inline]
+    pub const fn from_raw(root: Option<Cell>) -> Self {
+        Self(root)
+    }
+
+    /// Creates a dictionary from a raw root cell and the expected key length.
+    ///
+    /// Returns [`Error::InvalidData`] if `key_bit_len` differs from `N`.
+    ///
+    /// NOTE: The tree is not validated, use [`validate`] to check it.
+    ///
+    /// [`validate`]: RawDict::validate
+    pub fn from_parts(root: Option<Cell>, key_bit_len: u16) -> Result<Self, Error> {
+        if key_bit_len == N {
+            Ok(Self(root))
+        } else {
+            Err(Error::InvalidData)
+        }
+    }
+
     /// Returns the underlying root cell of the dictionary.
     #[inline]
     pub const fn root(&self) -> &Option<Cell> {
         &self.0
     }
 
-    /// Loads a non-empty dictionary from a root cell.
+    /// Converts the dictionary into its root cell.
+    #[inline]
+    pub fn into_root(self) -> Option<Cell> {
+        self.0
+    }
+
+    /// Walks the whole dictionary and checks its structure.
+    ///
+    /// Use it to verify dictionaries from untrusted sources, as some
+    /// malformed dictionaries can still be partially iterated.
+    pub fn validate(&self) -> Result<DictStats, Error> {
+        dict_validate(self.0.as_ref(), N, |_| Ok(()))
+    }
+
+    /// Counts the number of entries in the dictionary.
+    ///
+    /// Walks the whole dictionary, but doesn't parse values.
+    pub fn len(&self) -> Result<u64, Error> {
+        let stats = ok!(self.validate());
+        Ok(stats.entry_count as u64)
+    }
+
+    /// Loads a non-empty dictionary, treating the slice itself as the root node.
+    ///
+    /// Use it for the `Hashmap` layout. Use [`Load`] for the `HashmapE` layout
+    /// (a maybe-bit followed by an optional root reference).
+    ///
+    /// NOTE: Uses the default cell context.
+    #[inline]
+    pub fn load_from_root(slice: &mut CellSlice<'_>) -> Result<Self, Error> {
+        Self::load_from_root_ext(slice, &mut Cell::empty_context())
+    }
+
+    /// Loads a non-empty dictionary, treating the slice itself as the root node.
+    ///
+    /// See [`load_from_root`] for the difference with [`Load`].
+    ///
+    /// [`load_from_root`]: RawDict::load_from_root
     #[inline]
     pub fn load_from_root_ext(
         slice: &mut CellSlice<'_>,
@@ -235,6 +322,35 @@ impl<const N: u16> RawDict<N> {
         )
     }
 
+    /// Returns the minimal key in dictionary that is lexicographically greater than `key`
+    /// without loading the associated value.
+    pub fn raw_next_key(&self, key: CellSlice<'_>) -> Result<Option<CellBuilder>, Error> {
+        self.find_key(key, DictBound::Max)
+    }
+
+    /// Returns the maximal key in dictionary that is lexicographically smaller than `key`
+    /// without loading the associated value.
+    pub fn raw_prev_key(&self, key: CellSlice<'_>) -> Result<Option<CellBuilder>, Error> {
+        self.find_key(key, DictBound::Min)
+    }
+
+    fn find_key(
+        &self,
+        key: CellSlice<'_>,
+        towards: DictBound,
+    ) -> Result<Option<CellBuilder>, Error> {
+        let found = ok!(dict_find_owned(
+            self.0.as_ref(),
+            N,
+            key,
+            towards,
+            false,
+            false,
+            &mut Cell::empty_context(),
+        ));
+        Ok(found.map(|(key, _)| key))
+    }
+
     /// Returns cell slice parts of the value corresponding to the key.
     ///
     /// NOTE: Uses the default cell context.
@@ -445,6 +561,65 @@ impl<const N: u16> RawDict<N> {
         Ok((Self(left), Self(right)))
     }
 
+    /// Merges two dictionaries into a new one.
+    ///
+    /// When both dictionaries contain the same key, `on_conflict` is called
+    /// with the values from `self` and `other` respectively. Its result
+    /// cell content is used as the merged value.
+    ///
+    /// Both tries are traversed in parallel, so subtrees which are present
+    /// only in one of the dictionaries are reused without visiting them.
+    ///
+    /// See also: [`merge_take_left`], [`merge_take_right`].
+    ///
+    /// [`merge_take_left`]: RawDict::merge_take_left
+    /// [`merge_take_right`]: RawDict::merge_take_right
+    pub fn merge<F>(&self, other: &Self, mut on_conflict: F) -> Result<Self, Error>
+    where
+        F: FnMut(&mut CellSlice<'_>, &mut CellSlice<'_>) -> Result<Cell, Error>,
+    {
+        self.merge_with(other, |mut left, mut right| {
+            let merged = ok!(on_conflict(&mut left, &mut right));
+            let mut builder = CellBuilder::new();
+            ok!(builder.store_slice(ok!(merged.as_slice())));
+            Ok(builder)
+        })
+    }
+
+    /// Merges two dictionaries into a new one,
+    /// keeping values from `self` for duplicate keys.
+    pub fn merge_take_left(&self, other: &Self) -> Result<Self, Error> {
+        self.merge_with(other, |left, _| {
+            let mut builder = CellBuilder::new();
+            ok!(builder.store_slice(left));
+            Ok(builder)
+        })
+    }
+
+    /// Merges two dictionaries into a new one,
+    /// keeping values from `other` for duplicate keys.
+    pub fn merge_take_right(&self, other: &Self) -> Result<Self, Error> {
+        self.merge_with(other, |_, right| {
+            let mut builder = CellBuilder::new();
+            ok!(builder.store_slice(right));
+            Ok(builder)
+        })
+    }
+
+    fn merge_with<F>(&self, other: &Self, mut on_conflict: F) -> Result<Self, Error>
+    where
+        F: FnMut(CellSlice<'_>, CellSlice<'_>) -> Result<CellBuilder, Error>,
+    {
+        let root = ok!(dict_union(
+            self.0.as_ref(),
+            other.0.as_ref(),
+            N,
+            |_, left, right| on_conflict(left, right),
+            &mut Cell::empty_context(),
+        ));
+        Ok(Self(root))
+    }
+
     /// Gets an iterator over the entries of the dictionary, sorted by key.
     /// The iterator element type is `Result<(CellBuilder, CellSlice)>`.
     ///
@@ -528,6 +703,67 @@ impl<const N: u16> RawDict<N> {
         RawOwnedValues::new(&self.0, N)
     }
 
+    /// Gets an iterator over the leaves of the dictionary, sorted by key.
+    /// The iterator element type is `Result<(Vec<bool>, CellSlice)>`.
+    ///
+    /// The path consists of all label and branch bits from the root to the leaf.
+    /// For a well-formed dictionary it has exactly `N` bits and matches
+    /// the key of the leaf, so no path is a prefix of another.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn raw_key_iter(
+        &'_ self,
+    ) -> impl Iterator<Item = Result<(Vec<bool>, CellSlice<'_>), Error>> + '_ {
+        self.iter().map(|entry| match entry {
+            Ok((key, value)) => Ok((key_path(&key), value)),
+            Err(e) => Err(e),
+        })
+    }
+
+    /// Gets an iterator over the typed entries of the dictionary, sorted by key.
+    /// The iterator element type is `Result<(K, V)>`.
+    ///
+    /// Keys are parsed as [`DictKey`], while values are parsed
+    /// using the provided closure. Useful for values which don't
+    /// implement [`Load`] or must be partially decoded.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error. Fails with [`Error::InvalidData`] if `K::BITS` differs from `N`.
+    pub fn typed_iter<'a, K, V, F>(
+        &'a self,
+        mut f: F,
+    ) -> impl Iterator<Item = Result<(K, V), Error>> + 'a
+    where
+        K: DictKey,
+        F: FnMut(&mut CellSlice<'a>) -> Result<V, Error> + 'a,
+    {
+        let mut inner = self.iter();
+        let mut invalid_key = K::BITS != N;
+        std::iter::from_fn(move || {
+            if unlikely(invalid_key) {
+                // NOTE: the inner iterator will yield nothing after this
+                invalid_key = false;
+                return Some(Err(inner.finish(Error::InvalidData)));
+            }
+
+            Some(match inner.next()? {
+                Ok((key, mut value)) => {
+                    let err = if let Some(key) = K::from_raw_data(key.raw_data()) {
+                        match f(&mut value) {
+                            Ok(value) => return Some(Ok((key, value))),
+                            Err(e) => e,
+                        }
+                    } else {
+                        Error::CellUnderflow
+                    };
+                    Err(inner.finish(err))
+                }
+                Err(e) => Err(e),
+            })
+        })
+    }
+
     /// Sets the value associated with the key in the dictionary.
     ///
     /// Use [`set_ext`] if you need to use a custom cell context.
@@ -602,6 +838,14 @@ impl<const N: u16> RawDict<N> {
     }
 }
 
+/// Expands key bits into a trie path.
+pub(crate) fn key_path(key: &CellBuilder) -> Vec<bool> {
+    let data = key.raw_data();
+    (0..key.bit_len() as usize)
+        .map(|i| data[i / 8] & (0x80 >> (i % 8)) != 0)
+        .collect()
+}
+
 /// An iterator over the owned entries of a [`RawDict`].
 ///
 /// This struct is created by the [`iter_owned`] method on [`RawDict`].
@@ -1117,6 +1361,282 @@ impl<'a> Iterator for UnionRawIter<'a> {
     }
 }
 
+/// An iterator over the entries which differ between two dictionaries,
+/// in ascending order of the key bits.
+///
+/// Both tries are traversed in parallel and identical subtrees are skipped
+/// by comparing their representation hashes, so only the nodes on the paths
+/// to the changed entries are visited.
+///
+/// This struct is created by the [`raw_diff`] method on [`Dict`].
+/// See its documentation for more.
+///
+/// [`Dict`]: crate::dict::Dict
+/// [`raw_diff`]: crate::dict::Dict::raw_diff
+pub struct DiffRawIter<'a> {
+    segments: Vec<DiffSegment<'a>>,
+    key_bit_len: u16,
+    context: Option<&'a mut dyn CellContext>,
+}
+
+/// A pair of subtrees with the same key prefix.
+/// A missing side means that the other subtree is entirely added or removed.
+struct DiffSegment<'a> {
+    left: Option<DiffNode<'a>>,
+    right: Option<DiffNode<'a>>,
+    prefix: CellBuilder,
+}
+
+/// Dictionary node with the first `skip` bits of its label
+/// already included into the key prefix.
+#[derive(Clone, Copy)]
+struct DiffNode<'a> {
+    cell: &'a DynCell,
+    skip: u16,
+}
+
+impl<'a> DiffNode<'a> {
+    fn new(cell: &'a DynCell) -> Self {
+        Self { cell, skip: 0 }
+    }
+
+    fn child(data: &CellSlice<'a>, bit: bool) -> Result<Self, Error> {
+        Ok(Self::new(ok!(data.get_reference(bit as u8))))
+    }
+
+    fn skipped(self, bits: u16) -> Self {
+        Self {
+            cell: self.cell,
+            skip: self.skip + bits,
+        }
+    }
+}
+
+impl<'a> DiffRawIter<'a> {
+    /// Creates an iterator over the entries which differ between two dictionaries.
+    pub fn new(
+        left_root: Option<&'a DynCell>,
+        right_root: Option<&'a DynCell>,
+        bit_len: u16,
+    ) -> Self {
+        Self::with_context(left_root, right_root, bit_len, None)
+    }
+
+    /// Creates an iterator over the entries which differ between two dictionaries,
+    /// resolving all visited cells with the specified context.
+    pub fn new_ext(
+        left_root: Option<&'a DynCell>,
+        right_root: Option<&'a DynCell>,
+        bit_len: u16,
+        context: &'a mut dyn CellContext,
+    ) -> Self {
+        Self::with_context(left_root, right_root, bit_len, Some(context))
+    }
+
+    fn with_context(
+        left_root: Option<&'a DynCell>,
+        right_root: Option<&'a DynCell>,
+        key_bit_len: u16,
+        context: Option<&'a mut dyn CellContext>,
+    ) -> Self {
+        let mut segments = Vec::new();
+        if left_root.is_some() || right_root.is_some() {
+            segments.push(DiffSegment {
+                left: left_root.map(DiffNode::new),
+                right: right_root.map(DiffNode::new),
+                prefix: CellBuilder::new(),
+            });
+        }
+
+        Self {
+            segments,
+            key_bit_len,
+            context,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn finish(&mut self, err: Error) -> Error {
+        self.segments.clear();
+        err
+    }
+
+    /// Loads the remaining part of the node label and the node data.
+    fn load(
+        &mut self,
+        node: DiffNode<'a>,
+        key_bit_len: u16,
+    ) -> Result<(CellSlice<'a>, CellSlice<'a>), Error> {
+        let cell = match &mut self.context {
+            Some(context) => ok!(context.load_dyn_cell(node.cell, LoadMode::Full)),
+            None => node.cell,
+        };
+        let mut data = ok!(cell.as_slice());
+        let mut label = ok!(read_label(&mut data, key_bit_len + node.skip));
+        ok!(label.advance(node.skip, 0));
+        Ok((label, data))
+    }
+
+    fn visit(
+        &mut self,
+        segment: DiffSegment<'a>,
+    ) -> Result<Option<(CellBuilder, DiffEntry<CellSlice<'a>>)>, Error> {
+        let prefix = segment.prefix;
+        let rem_key_bit_len = self.key_bit_len - prefix.bit_len();
+
+        let (left, right) = match (segment.left, segment.right) {
+            (Some(left), Some(right)) => (left, right),
+            (None, None) => return Ok(None),
+            // Report all entries of the subtree as added or removed
+            (left, right) => {
+                let added = right.is_some();
+                let node = left.or(right).unwrap();
+
+                let (label, data) = ok!(self.load(node, rem_key_bit_len));
+                let mut key = prefix;
+                ok!(key.store_slice_data(label));
+
+                if label.remaining_bits() == rem_key_bit_len {
+                    return Ok(Some(if added {
+                        (key, DiffEntry::Added(data))
+                    } else {
+                        (key, DiffEntry::Removed(data))
+                    }));
+                }
+
+                // Push the right branch first to visit the left one first
+                for bit in [true, false] {
+                    let mut prefix = key.clone();
+                    ok!(prefix.store_bit(bit));
+                    let child = Some(ok!(DiffNode::child(&data, bit)));
+                    self.segments.push(DiffSegment {
+                        left: if added { None } else { child },
+                        right: if added { child } else { None },
+                        prefix,
+                    });
+                }
+                return Ok(None);
+            }
+        };
+
+        if left.skip == right.skip && left.cell.repr_hash() == right.cell.repr_hash() {
+            return Ok(None);
+        }
+
+        let (left_label, left_data) = ok!(self.load(left, rem_key_bit_len));
+        let (right_label, right_data) = ok!(self.load(right, rem_key_bit_len));
+
+        let lcp_len = left_label
+            .longest_common_data_prefix(&right_label)
+            .remaining_bits();
+
+        let mut common = prefix;
+        ok!(common.store_slice_data(left_label.get_prefix(lcp_len, 0)));
+
+        let left_is_prefix = lcp_len == left_label.remaining_bits();
+        let right_is_prefix = lcp_len == right_label.remaining_bits();
+
+        match (left_is_prefix, right_is_prefix) {
+            // Both labels are equal
+            (true, true) => {
+                if lcp_len == rem_key_bit_len {
+                    // Both nodes are leaves with the same key
+                    if ok!(left_data.cmp_by_content(&right_data)).is_eq() {
+                        return Ok(None);
+                    }
+                    return Ok(Some((
+                        common,
+                        DiffEntry::Changed {
+                            old: left_data,
+                            new: right_data,
+                        },
+                    )));
+                }
+
+                // Both nodes are forks
+                for bit in [true, false] {
+                    let mut prefix = common.clone();
+                    ok!(prefix.store_bit(bit));
+                    self.segments.push(DiffSegment {
+                        left: Some(ok!(DiffNode::child(&left_data, bit))),
+                        right: Some(ok!(DiffNode::child(&right_data, bit))),
+                        prefix,
+                    });
+                }
+            }
+            // One node is in the subtree of the other fork
+            (true, false) | (false, true) => {
+                let (fork_data, node, node_bit) = if left_is_prefix {
+                    (&left_data, right, ok!(right_label.get_bit(lcp_len)))
+                } else {
+                    (&right_data, left, ok!(left_label.get_bit(lcp_len)))
+                };
+                let node = node.skipped(lcp_len + 1);
+
+                for bit in [true, false] {
+                    let mut prefix = common.clone();
+                    ok!(prefix.store_bit(bit));
+
+                    let fork_child = Some(ok!(DiffNode::child(fork_data, bit)));
+                    // Entries of the other branch are present only in the fork
+                    let node = (bit == node_bit).then_some(node);
+
+                    self.segments.push(if left_is_prefix {
+                        DiffSegment {
+                            left: fork_child,
+                            right: node,
+                            prefix,
+                        }
+                    } else {
+                        DiffSegment {
+                            left: node,
+                            right: fork_child,
+                            prefix,
+                        }
+                    });
+                }
+            }
+            // Labels diverge, so subtrees have no common keys
+            (false, false) => {
+                let left = DiffSegment {
+                    left: Some(left.skipped(lcp_len)),
+                    right: None,
+                    prefix: common.clone(),
+                };
+                let right = DiffSegment {
+                    left: None,
+                    right: Some(right.skipped(lcp_len)),
+                    prefix: common,
+                };
+
+                // Push the subtree with the greater key first
+                let mut subtrees = [left, right];
+                if !ok!(left_label.get_bit(lcp_len)) {
+                    subtrees.reverse();
+                }
+                self.segments.extend(subtrees);
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl<'a> Iterator for DiffRawIter<'a> {
+    type Item = Result<(CellBuilder, DiffEntry<CellSlice<'a>>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let segment = self.segments.pop()?;
+            match self.visit(segment) {
+                Ok(Some(entry)) => return Some(Ok(entry)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(self.finish(e))),
+            }
+        }
+    }
+}
+
 /// An iterator over the keys of a [`RawDict`] or a [`Dict`].
 ///
 /// This struct is created by the [`keys`] method on [`RawDict`] or the [`raw_keys`] method on [`Dict`].
@@ -1459,6 +1979,34 @@ mod tests {
         builder.build().unwrap()
     }
 
+    #[test]
+    fn dict_raw_parts() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u16>::new();
+        for i in 0..10 {
+            dict.set(i, i as u16 * 10)?;
+        }
+
+        // Round-trip through raw parts
+        let root = dict.clone().into_root();
+        let raw = RawDict::<32>::from_parts(root.clone(), 32)?;
+        assert_eq!(raw.root(), &root);
+        assert_eq!(raw.len()?, 10);
+
+        let typed = Dict::<u32, u16>::try_from(raw.clone())?;
+        assert_eq!(typed, dict);
+        assert_eq!(typed.get(5)?, Some(50));
+
+        let raw = RawDict::<32>::try_from(dict.clone())?;
+        assert_eq!(raw.into_root(), root);
+
+        // Mismatched key length
+        assert!(RawDict::<64>::from_parts(root.clone(), 32).is_err());
+        assert!(RawDict::<64>::try_from(dict).is_err());
+        assert!(Dict::<u64, u16>::try_from(RawDict::<32>::from_raw(root)).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn dict_set() -> anyhow::Result<()> {
         let mut dict = RawDict::<32>::new();
@@ -1509,6 +2057,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn dict_validate() -> anyhow::Result<()> {
+        let mut dict = RawDict::<32>::new();
+        assert_eq!(dict.validate()?, DictStats::default());
+
+        for i in 0..100 {
+            dict.set(build_cell(|b| b.store_u32(i)).as_slice()?, i)?;
+        }
+        let stats = dict.validate()?;
+        assert_eq!(stats.entry_count, 100);
+        assert!(stats.max_depth >= 7);
+        assert_eq!(dict.len()?, 100);
+
+        // hml_long$10 with 32-bit length but only 8 bits of the key
+        let truncated_label = build_cell(|b| {
+            b.store_small_uint(0b10, 2)?;
+            b.store_small_uint(32, 6)?;
+            b.store_u8(0xaa)
+        });
+        let dict = RawDict::<32>::from(Some(truncated_label));
+        assert_eq!(dict.validate().unwrap_err(), Error::CellUnderflow);
+
+        // hml_long$10 with a label longer than the key
+        let long_label = build_cell(|b| {
+            b.store_small_uint(0b10, 2)?;
+            b.store_small_uint(10, 4)?;
+            b.store_uint(0, 10)?;
+            b.store_u32(123)
+        });
+        let dict = RawDict::<8>::from(Some(long_label));
+        assert_eq!(dict.validate().unwrap_err(), Error::InvalidData);
+
+        // Fork without branches
+        let empty_fork = build_cell(|b| b.store_zeros(2));
+        let dict = RawDict::<32>::from(Some(empty_fork));
+        assert_eq!(dict.validate().unwrap_err(), Error::InvalidData);
+
+        Ok(())
+    }
+
     #[test]
     fn dict_replace() -> anyhow::Result<()> {
         let mut dict = RawDict::<32>::new();
@@ -1756,6 +2344,116 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn dict_typed_iter() -> anyhow::Result<()> {
+        let mut dict = RawDict::<32>::new();
+        for i in 0..10u32 {
+            let key = CellBuilder::build_from(i)?;
+            dict.set(key.as_slice()?, (i as u16, i * 2))?;
+        }
+
+        // Parse only the second field of the value
+        for (i, entry) in dict
+            .typed_iter::<u32, _, _>(|slice| {
+                ok!(slice.advance(16, 0));
+                slice.load_u32()
+            })
+            .enumerate()
+        {
+            let (key, value) = entry?;
+            assert_eq!(key, i as u32);
+            assert_eq!(value, i as u32 * 2);
+        }
+
+        // Errors from the closure stop the iterator
+        let mut iter = dict.typed_iter::<u32, (), _>(|_| Err(Error::InvalidTag(0)));
+        assert!(matches!(iter.next(), Some(Err(Error::InvalidTag(0)))));
+        assert!(iter.next().is_none());
+
+        // Key length must match
+        let mut iter = dict.typed_iter::<u16, _, _>(|slice| slice.load_u16());
+        assert!(matches!(iter.next(), Some(Err(Error::InvalidData))));
+        assert!(iter.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_merge() -> anyhow::Result<()> {
+        fn build_dict(entries: &[(u32, u32)]) -> anyhow::Result<RawDict<32>> {
+            let mut dict = RawDict::<32>::new();
+            for (key, value) in entries {
+                let key = CellBuilder::build_from(key)?;
+                dict.set(key.as_slice()?, value)?;
+            }
+            Ok(dict)
+        }
+
+        let left = build_dict(&[(1, 10), (2, 20), (3, 30)])?;
+        let right = build_dict(&[(3, 300), (4, 400)])?;
+
+        let merged = left.merge(&right, |left, right| {
+            let sum = ok!(left.load_u32()) + ok!(right.load_u32());
+            CellBuilder::build_from(sum)
+        })?;
+        assert_eq!(merged, build_dict(&[(1, 10), (2, 20), (3, 330), (4, 400)])?);
+
+        assert_eq!(
+            left.merge_take_left(&right)?,
+            build_dict(&[(1, 10), (2, 20), (3, 30), (4, 400)])?
+        );
+        assert_eq!(
+            left.merge_take_right(&right)?,
+            build_dict(&[(1, 10), (2, 20), (3, 300), (4, 400)])?
+        );
+
+        let empty = RawDict::<32>::new();
+        assert_eq!(empty.merge(&left, |_, _| unreachable!())?, left);
+        assert_eq!(left.merge(&empty, |_, _| unreachable!())?, left);
+
+        // Errors from the closure are propagated
+        assert!(matches!(
+            left.merge(&right, |_, _| Err(Error::InvalidData)),
+            Err(Error::InvalidData)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_raw_key_iter() -> anyhow::Result<()> {
+        let mut dict = RawDict::<16>::new();
+        for key in [0x0000u16, 0x0001, 0x8000, 0xffff] {
+            let key = CellBuilder::build_from(key)?;
+            dict.set(key.as_slice()?, ())?;
+        }
+
+        let paths = dict
+            .raw_key_iter()
+            .map(|entry| entry.map(|(path, _)| path))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(paths.len(), 4);
+
+        for (path, key) in paths.iter().zip(dict.keys()) {
+            let key = key?;
+            assert_eq!(path.len(), 16);
+            for (i, bit) in path.iter().enumerate() {
+                assert_eq!(*bit, key.as_data_slice().get_bit(i as u16)?);
+            }
+        }
+
+        // Trie invariant: no path is a prefix of another
+        for (i, a) in paths.iter().enumerate() {
+            for b in &paths[i + 1..] {
+                assert!(!b.starts_with(a) && !a.starts_with(b));
+            }
+        }
+
+        assert_eq!(RawDict::<16>::new().raw_key_iter().count(), 0);
+
+        Ok(())
+    }
+
     #[test]
     fn dict_iter_union() -> anyhow::Result<()> {
         let mut left = RawDict::<32>::new();
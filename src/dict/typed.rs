@@ -3,12 +3,17 @@ use std::marker::PhantomData;
 
 use crate::cell::*;
 use crate::dict::dict_remove_owned;
-use crate::error::Error;
+use crate::error::{DictError, Error};
+use crate::merkle::MerkleProof;
 use crate::util::*;
 
 use super::{
-    dict_find_bound, dict_find_owned, dict_get, dict_insert, dict_load_from_root,
-    dict_split_by_prefix, DictBound, DictKey, SetMode,
+    build_dict_from_sorted_iter, dict_difference, dict_find_bound, dict_find_nearest,
+    dict_find_owned, dict_get, dict_insert, dict_insert_owned, dict_insert_sorted_iter,
+    dict_intersection, dict_load_from_root, dict_map_values, dict_merge_subtrees, dict_split_at,
+    dict_split_by_prefix, dict_union, dict_validate, dict_verify_consistency, is_same_value,
+    read_label, DictBound, DictCursor, DictKey, DictMergeOps, DictStats, DiffEntry, SameSubtree,
+    SetMode,
 };
 use super::{dict_remove_bound_owned, raw::*};
 
@@ -30,6 +35,10 @@ impl<K, V> ExactSize for Dict<K, V> {
     }
 }
 
+/// Loads a dictionary with the `HashmapE` layout (a maybe-bit followed
+/// by an optional root reference).
+///
+/// Use [`Dict::load_from_root`] for the `Hashmap` layout.
 impl<'a, K, V> Load<'a> for Dict<K, V> {
     #[inline]
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
@@ -88,6 +97,34 @@ impl<K, V> From<Option<Cell>> for Dict<K, V> {
     }
 }
 
+impl<'a, K, V> IntoIterator for &'a Dict<K, V>
+where
+    K: DictKey,
+    V: Load<'a>,
+{
+    type Item = Result<(K, V), Error>;
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(&self.root)
+    }
+}
+
+impl<K, V> IntoIterator for Dict<K, V>
+where
+    K: DictKey,
+    for<'a> V: Load<'a>,
+{
+    type Item = Result<(K, V), Error>;
+    type IntoIter = IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.root)
+    }
+}
+
 impl<K, V> std::fmt::Debug for Dict<K, V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         debug_struct_field1_finish(f, "Dict", "root", &self.root)
@@ -105,6 +142,10 @@ impl<K, V> Dict<K, V> {
     }
 
     /// Creates a dictionary from a raw cell.
+    ///
+    /// NOTE: The tree is not validated, use [`validate`] to check it.
+    ///
+    /// [`validate`]: Dict::validate
     pub const fn from_raw(dict: Option<Cell>) -> Self {
         Self {
             root: dict,
@@ -124,6 +165,12 @@ impl<K, V> Dict<K, V> {
         &self.root
     }
 
+    /// Converts the dictionary into its root cell.
+    #[inline]
+    pub fn into_root(self) -> Option<Cell> {
+        self.root
+    }
+
     /// Converts into a dictionary with an equivalent value type.
     #[inline]
     pub fn cast_into<Q, T>(self) -> Dict<Q, T>
@@ -150,7 +197,23 @@ impl<K, V> Dict<K, V> {
 }
 
 impl<K: DictKey, V> Dict<K, V> {
-    /// Loads a non-empty dictionary from a root cell.
+    /// Loads a non-empty dictionary, treating the slice itself as the root node.
+    ///
+    /// Use it for the `Hashmap` layout, e.g. when a dictionary is stored
+    /// directly in a config param cell. Use [`Load`] for the `HashmapE` layout
+    /// (a maybe-bit followed by an optional root reference).
+    ///
+    /// NOTE: Uses the default cell context.
+    #[inline]
+    pub fn load_from_root(slice: &mut CellSlice<'_>) -> Result<Self, Error> {
+        Self::load_from_root_ext(slice, &mut Cell::empty_context())
+    }
+
+    /// Loads a non-empty dictionary, treating the slice itself as the root node.
+    ///
+    /// See [`load_from_root`] for the difference with [`Load`].
+    ///
+    /// [`load_from_root`]: Dict::load_from_root
     pub fn load_from_root_ext(
         slice: &mut CellSlice<'_>,
         context: &mut dyn CellContext,
@@ -166,6 +229,80 @@ impl<K: DictKey, V> Dict<K, V> {
     }
 }
 
+impl<K: DictKey, V> Dict<K, V> {
+    /// Walks the whole dictionary and checks all trie invariants,
+    /// including the hashes of all cells.
+    ///
+    /// Use it to diagnose corrupted dictionaries. See [`has_valid_structure`]
+    /// for a faster check.
+    ///
+    /// [`has_valid_structure`]: Dict::has_valid_structure
+    pub fn verify_consistency(&self) -> Result<(), DictError> {
+        dict_verify_consistency(self.root.as_ref(), K::BITS)
+    }
+
+    /// Returns `true` if all edge labels and forks are well-formed.
+    ///
+    /// Unlike [`verify_consistency`], doesn't recompute cell hashes.
+    ///
+    /// [`verify_consistency`]: Dict::verify_consistency
+    pub fn has_valid_structure(&self) -> bool {
+        dict_validate(self.root.as_ref(), K::BITS, |_| Ok(())).is_ok()
+    }
+
+    /// Counts the number of entries in the dictionary.
+    ///
+    /// Walks the whole dictionary, but doesn't parse values.
+    /// Use [`is_empty`] to check for emptiness in constant time.
+    ///
+    /// [`is_empty`]: Dict::is_empty
+    pub fn len(&self) -> Result<u64, Error> {
+        let stats = ok!(dict_validate(self.root.as_ref(), K::BITS, |_| Ok(())));
+        Ok(stats.entry_count as u64)
+    }
+
+    /// Returns the max number of forks on the path from the root to any leaf
+    /// (i.e. the worst-case number of cells visited during the lookup minus one).
+    ///
+    /// Walks the whole dictionary, but doesn't parse values.
+    pub fn depth(&self) -> Result<u16, Error> {
+        let stats = ok!(dict_validate(self.root.as_ref(), K::BITS, |_| Ok(())));
+        Ok(stats.max_depth)
+    }
+
+    /// Returns the number of unique cells in the dictionary,
+    /// including the cells referenced by values.
+    ///
+    /// Identical subtrees are counted only once, the same way
+    /// as for storage fees.
+    pub fn estimated_size_in_cells(&self) -> usize {
+        match &self.root {
+            Some(root) => StorageStat::compute_for_cell(root.as_ref(), usize::MAX)
+                .map(|stats| stats.cell_count as usize)
+                .unwrap_or_default(),
+            None => 0,
+        }
+    }
+
+    /// Walks the whole dictionary and checks its structure.
+    /// Additionally checks that every value can be parsed as `V`.
+    ///
+    /// See [`RawDict::validate`] for more details.
+    pub fn validate<'a>(&'a self) -> Result<DictStats, Error>
+    where
+        V: Load<'a>,
+    {
+        dict_validate(
+            self.root.as_ref(),
+            K::BITS,
+            |mut value| match V::load_from(&mut value) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(e),
+            },
+        )
+    }
+}
+
 impl<K, V> Dict<K, V>
 where
     K: Store + DictKey,
@@ -233,6 +370,101 @@ where
         get_impl(&self.root, key.borrow())
     }
 
+    /// Gets an iterator over the entries of the dictionary, sorted by key,
+    /// along with a Merkle proof for each entry.
+    ///
+    /// Each proof contains only the path from the root to the leaf.
+    /// Paths are tracked during a single traversal of the dictionary,
+    /// so cells are not visited again for adjacent keys.
+    pub fn iter_with_proofs(&self) -> ProofIter<'_, K, V> {
+        ProofIter::new(&self.root)
+    }
+
+    /// Returns the value corresponding to the key along with a Merkle proof
+    /// of the path from the dictionary root to the leaf.
+    ///
+    /// The proof contains only the cells visited during the lookup,
+    /// all other subtrees are pruned. Use [`contains_key_with_proof`]
+    /// to prove that the key is absent.
+    ///
+    /// [`contains_key_with_proof`]: Dict::contains_key_with_proof
+    pub fn get_with_proof<Q>(&self, key: Q) -> Result<Option<(V, MerkleProof)>, Error>
+    where
+        Q: Borrow<K>,
+        for<'a> V: Load<'a>,
+    {
+        let Some(root) = &self.root else {
+            return Ok(None);
+        };
+
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let tracked = Dict::<K, V>::from_raw(Some(usage_tree.track(root)));
+        let Some(value) = ok!(tracked.get(key)) else {
+            return Ok(None);
+        };
+
+        let proof = ok!(MerkleProof::create(root.as_ref(), usage_tree).build());
+        Ok(Some((value, proof)))
+    }
+
+    /// Returns `true` if the dictionary contains a value for the specified key
+    /// along with a Merkle proof of the lookup path.
+    ///
+    /// For an absent key the proof contains the path to the node where
+    /// the key diverges from the existing keys, so it is enough to verify
+    /// the absence.
+    ///
+    /// Returns [`Error::EmptyProof`] for an empty dictionary since
+    /// there are no cells to prove.
+    pub fn contains_key_with_proof<Q>(&self, key: Q) -> Result<(bool, MerkleProof), Error>
+    where
+        Q: Borrow<K>,
+    {
+        let Some(root) = &self.root else {
+            return Err(Error::EmptyProof);
+        };
+
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let tracked = Dict::<K, V>::from_raw(Some(usage_tree.track(root)));
+        let contains = ok!(tracked.contains_key(key));
+
+        let proof = ok!(MerkleProof::create(root.as_ref(), usage_tree).build());
+        Ok((contains, proof))
+    }
+
+    /// Returns values for all `keys` along with a single Merkle proof
+    /// which covers all lookup paths.
+    ///
+    /// Absent keys are returned with `None`, and the proof contains
+    /// enough cells to verify their absence.
+    ///
+    /// Returns [`Error::EmptyProof`] for an empty dictionary since
+    /// there are no cells to prove.
+    #[allow(clippy::type_complexity)]
+    pub fn batch_get_with_proof(
+        &self,
+        keys: &[K],
+    ) -> Result<(Vec<(K, Option<V>)>, MerkleProof), Error>
+    where
+        K: Clone,
+        for<'a> V: Load<'a>,
+    {
+        let Some(root) = &self.root else {
+            return Err(Error::EmptyProof);
+        };
+
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let tracked = Dict::<K, V>::from_raw(Some(usage_tree.track(root)));
+
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push((key.clone(), ok!(tracked.get(key))));
+        }
+
+        let proof = ok!(MerkleProof::create(root.as_ref(), usage_tree).build());
+        Ok((values, proof))
+    }
+
     /// Returns the raw value corresponding to the key.
     pub fn get_raw<'a: 'b, 'b, Q>(&'a self, key: Q) -> Result<Option<CellSlice<'a>>, Error>
     where
@@ -258,6 +490,45 @@ where
         get_raw_impl(&self.root, key.borrow())
     }
 
+    /// Returns a nested dictionary stored as the value corresponding to the key.
+    ///
+    /// The value must contain a `HashmapE` with `K2::BITS` keys. Nodes on the path
+    /// to the first entry of the nested dictionary are checked to match the key
+    /// length, otherwise [`Error::InvalidData`] is returned.
+    pub fn get_subdict<Q, K2, V2>(&self, key: Q) -> Result<Option<Dict<K2, V2>>, Error>
+    where
+        Q: Borrow<K>,
+        K2: DictKey,
+    {
+        let Some(mut value) = ok!(self.get_raw(key.borrow())) else {
+            return Ok(None);
+        };
+        let dict = ok!(Dict::<K2, V2>::load_from(&mut value));
+
+        if let Some(root) = &dict.root {
+            let mut node = root.as_ref();
+            let mut key_bit_len = K2::BITS;
+            loop {
+                let mut data = ok!(node.as_slice());
+                let Ok(label) = read_label(&mut data, key_bit_len) else {
+                    return Err(Error::InvalidData);
+                };
+                let remaining_bits = match key_bit_len.checked_sub(label.remaining_bits()) {
+                    Some(0) => break,
+                    Some(remaining_bits) => remaining_bits,
+                    None => return Err(Error::InvalidData),
+                };
+                if !data.is_data_empty() || data.remaining_refs() != 2 {
+                    return Err(Error::InvalidData);
+                }
+                node = ok!(data.get_reference(0));
+                key_bit_len = remaining_bits - 1;
+            }
+        }
+
+        Ok(Some(dict))
+    }
+
     /// Removes the value associated with key in dictionary.
     /// Returns an optional removed value.
     ///
@@ -267,13 +538,7 @@ where
         Q: Borrow<K>,
         for<'a> V: Load<'a> + 'static,
     {
-        match ok!(self.remove_raw_ext(key, &mut Cell::empty_context())) {
-            Some((cell, range)) => {
-                let mut slice = ok!(range.apply(&cell));
-                Ok(Some(ok!(V::load_from(&mut slice))))
-            }
-            None => Ok(None),
-        }
+        self.remove_ext(key, &mut Cell::empty_context())
     }
 
     /// Removes the value associated with key in dictionary.
@@ -350,6 +615,92 @@ where
         ));
         Ok((Self::from_raw(left), Self::from_raw(right)))
     }
+
+    /// Split dictionary into 2 dictionaries at the key boundary.
+    ///
+    /// The left dictionary contains all keys less than `key`,
+    /// the right dictionary contains all keys greater or equal to `key`.
+    /// Keys are compared in the same order as [`iter`] yields them.
+    ///
+    /// [`iter`]: Dict::iter
+    pub fn split_at<Q>(&self, key: Q) -> Result<(Self, Self), Error>
+    where
+        Q: Borrow<K>,
+    {
+        self.split_at_ext(key, false, &mut Cell::empty_context())
+    }
+
+    /// Split dictionary into 2 dictionaries at the key boundary.
+    ///
+    /// The left dictionary contains all keys less or equal to `key`,
+    /// the right dictionary contains all keys greater than `key`.
+    pub fn split_at_key_inclusive<Q>(&self, key: Q) -> Result<(Self, Self), Error>
+    where
+        Q: Borrow<K>,
+    {
+        self.split_at_ext(key, true, &mut Cell::empty_context())
+    }
+
+    /// Split dictionary into 2 dictionaries at the key boundary.
+    ///
+    /// The `key` goes to the left dictionary if `inclusive` is set.
+    pub fn split_at_ext<Q>(
+        &self,
+        key: Q,
+        inclusive: bool,
+        context: &mut dyn CellContext,
+    ) -> Result<(Self, Self), Error>
+    where
+        Q: Borrow<K>,
+    {
+        let mut key_builder = CellBuilder::new();
+        ok!(key
+            .borrow()
+            .store_into(&mut key_builder, &mut Cell::empty_context()));
+
+        let (left, right) = ok!(dict_split_at(
+            self.root.as_ref(),
+            &key_builder.as_data_slice(),
+            K::BITS,
+            inclusive,
+            context
+        ));
+        Ok((Self::from_raw(left), Self::from_raw(right)))
+    }
+
+    /// Returns a new dictionary with all entries with keys in the range.
+    ///
+    /// Keys are compared in the same order as [`iter`] yields them.
+    /// Subtrees which are entirely within the range are reused as is.
+    ///
+    /// [`iter`]: Dict::iter
+    pub fn get_range<R>(&self, range: R) -> Result<Self, Error>
+    where
+        R: std::ops::RangeBounds<K>,
+    {
+        self.get_range_ext(range, &mut Cell::empty_context())
+    }
+
+    /// Returns a new dictionary with all entries with keys in the range.
+    pub fn get_range_ext<R>(&self, range: R, context: &mut dyn CellContext) -> Result<Self, Error>
+    where
+        R: std::ops::RangeBounds<K>,
+    {
+        use std::ops::Bound;
+
+        let mut result = self.clone();
+        match range.start_bound() {
+            Bound::Included(start) => result = ok!(result.split_at_ext(start, false, context)).1,
+            Bound::Excluded(start) => result = ok!(result.split_at_ext(start, true, context)).1,
+            Bound::Unbounded => {}
+        }
+        match range.end_bound() {
+            Bound::Included(end) => result = ok!(result.split_at_ext(end, true, context)).0,
+            Bound::Excluded(end) => result = ok!(result.split_at_ext(end, false, context)).0,
+            Bound::Unbounded => {}
+        }
+        Ok(result)
+    }
 }
 
 impl<K, V> Dict<K, V>
@@ -423,6 +774,37 @@ where
         Iter::new(&self.root)
     }
 
+    /// Gets an iterator over the entries of the dictionary along with
+    /// their trie paths, sorted by key.
+    /// The iterator element type is `Result<(Vec<bool>, K, V)>`.
+    ///
+    /// Intended for diagnostics. See [`RawDict::raw_key_iter`] for details.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn trie_iter<'a>(&'a self) -> impl Iterator<Item = Result<(Vec<bool>, K, V), Error>> + 'a
+    where
+        V: Load<'a>,
+    {
+        let mut inner = RawIter::new(&self.root, K::BITS);
+        std::iter::from_fn(move || {
+            Some(match inner.next()? {
+                Ok((key, mut value)) => {
+                    let err = if let Some(parsed) = K::from_raw_data(key.raw_data()) {
+                        match V::load_from(&mut value) {
+                            Ok(value) => return Some(Ok((key_path(&key), parsed, value))),
+                            Err(e) => e,
+                        }
+                    } else {
+                        Error::CellUnderflow
+                    };
+                    Err(inner.finish(err))
+                }
+                Err(e) => Err(e),
+            })
+        })
+    }
+
     /// Gets an iterator over the entries of two dictionaries, sorted by key.
     /// The iterator element type is `Result<(K, Option<V>, Option<V>)>`.
     ///
@@ -456,6 +838,55 @@ where
         Keys::new(&self.root)
     }
 
+    /// Folds all entries of the dictionary into an accumulator, in sorted order.
+    ///
+    /// Returns an error on the first invalid element.
+    pub fn fold<'a, A, F>(&'a self, init: A, mut f: F) -> Result<A, Error>
+    where
+        V: Load<'a>,
+        F: FnMut(A, K, V) -> A,
+    {
+        let mut acc = init;
+        for entry in self.iter() {
+            let (key, value) = ok!(entry);
+            acc = f(acc, key, value);
+        }
+        Ok(acc)
+    }
+
+    /// Calls a closure on each entry of the dictionary, in sorted order.
+    ///
+    /// Stops on the first invalid element or the first error returned
+    /// by the closure.
+    pub fn for_each<'a, F>(&'a self, mut f: F) -> Result<(), Error>
+    where
+        V: Load<'a>,
+        F: FnMut(K, V) -> Result<(), Error>,
+    {
+        for entry in self.iter() {
+            let (key, value) = ok!(entry);
+            ok!(f(key, value));
+        }
+        Ok(())
+    }
+
+    /// Searches for the first entry (in sorted order) that satisfies a predicate.
+    ///
+    /// Returns an error on the first invalid element.
+    pub fn find<'a, F>(&'a self, predicate: F) -> Result<Option<(K, V)>, Error>
+    where
+        V: Load<'a>,
+        F: Fn(&K, &V) -> bool,
+    {
+        for entry in self.iter() {
+            let (key, value) = ok!(entry);
+            if predicate(&key, &value) {
+                return Ok(Some((key, value)));
+            }
+        }
+        Ok(None)
+    }
+
     /// Computes the minimal key in dictionary that is lexicographically greater than `key`,
     /// and returns it along with associated value as cell slice parts.
     #[inline]
@@ -500,6 +931,84 @@ where
         self.find_ext(key, DictBound::Min, true, signed)
     }
 
+    /// Returns the minimal key in dictionary that is greater than `key`
+    /// without loading the associated value.
+    ///
+    /// Keys are compared in the same order as they are returned by [`keys`].
+    ///
+    /// [`keys`]: Dict::keys
+    pub fn next_key<Q>(&self, key: Q) -> Result<Option<K>, Error>
+    where
+        Q: Borrow<K>,
+    {
+        self.find_key_ext(key.borrow(), DictBound::Max)
+    }
+
+    /// Returns the maximal key in dictionary that is less than `key`
+    /// without loading the associated value.
+    ///
+    /// Keys are compared in the same order as they are returned by [`keys`].
+    ///
+    /// [`keys`]: Dict::keys
+    pub fn prev_key<Q>(&self, key: Q) -> Result<Option<K>, Error>
+    where
+        Q: Borrow<K>,
+    {
+        self.find_key_ext(key.borrow(), DictBound::Min)
+    }
+
+    /// Returns the key in dictionary with the smallest absolute distance to `key`.
+    /// If two keys are equally close, the lower one is returned.
+    ///
+    /// Keys are compared as unsigned integers in the same order as they
+    /// are returned by [`keys`].
+    ///
+    /// [`keys`]: Dict::keys
+    pub fn nearest_key<Q>(&self, key: Q) -> Result<Option<K>, Error>
+    where
+        Q: Borrow<K>,
+    {
+        let mut builder = CellBuilder::new();
+        ok!(key
+            .borrow()
+            .store_into(&mut builder, &mut Cell::empty_context()));
+
+        match ok!(dict_find_nearest(
+            self.root.as_ref(),
+            builder.as_data_slice(),
+            K::BITS
+        )) {
+            Some(key) => match K::from_raw_data(key.raw_data()) {
+                Some(key) => Ok(Some(key)),
+                None => Err(Error::CellUnderflow),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn find_key_ext(&self, key: &K, towards: DictBound) -> Result<Option<K>, Error> {
+        let context = &mut Cell::empty_context();
+        let mut builder = CellBuilder::new();
+        ok!(key.store_into(&mut builder, context));
+
+        let Some((key, _)) = ok!(dict_find_owned(
+            self.root.as_ref(),
+            K::BITS,
+            builder.as_data_slice(),
+            towards,
+            false,
+            false,
+            context,
+        )) else {
+            return Ok(None);
+        };
+
+        match K::from_raw_data(key.raw_data()) {
+            Some(key) => Ok(Some(key)),
+            None => Err(Error::CellUnderflow),
+        }
+    }
+
     #[inline]
     fn find_ext<Q>(
         &self,
@@ -643,31 +1152,68 @@ where
     K: Store + DictKey,
 {
     /// Removes the value associated with key in dictionary.
-    /// Returns an optional removed value as cell slice parts.
+    /// Returns an optional removed value.
     ///
     /// Dict is rebuild using the provided cell context.
-    pub fn remove_raw_ext<Q>(
+    pub fn remove_ext<Q>(
         &mut self,
         key: Q,
         context: &mut dyn CellContext,
-    ) -> Result<Option<CellSliceParts>, Error>
+    ) -> Result<Option<V>, Error>
     where
         Q: Borrow<K>,
+        for<'a> V: Load<'a> + 'static,
     {
-        fn remove_raw_ext_impl<K>(
-            root: &mut Option<Cell>,
-            key: &K,
-            context: &mut dyn CellContext,
-        ) -> Result<Option<CellSliceParts>, Error>
-        where
-            K: Store + DictKey,
-        {
+        match ok!(self.remove_raw_ext(key, context)) {
+            Some((cell, range)) => {
+                let mut slice = ok!(range.apply(&cell));
+                Ok(Some(ok!(V::load_from(&mut slice))))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Removes the value associated with key in dictionary.
+    /// Returns an optional removed value as cell slice parts.
+    ///
+    /// Dict is rebuild using the provided cell context.
+    pub fn remove_raw_ext<Q>(
+        &mut self,
+        key: Q,
+        context: &mut dyn CellContext,
+    ) -> Result<Option<CellSliceParts>, Error>
+    where
+        Q: Borrow<K>,
+    {
+        fn remove_raw_ext_impl<K>(
+            root: &mut Option<Cell>,
+            key: &K,
+            context: &mut dyn CellContext,
+        ) -> Result<Option<CellSliceParts>, Error>
+        where
+            K: Store + DictKey,
+        {
             let mut builder = CellBuilder::new();
             ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
             dict_remove_owned(root, &mut builder.as_data_slice(), K::BITS, false, context)
         }
 
-        remove_raw_ext_impl(&mut self.root, key.borrow(), context)
+        #[cfg(feature = "tracing")]
+        let span = debug_span!(
+            "dict_remove",
+            key_bits = K::BITS,
+            cells = tracing::field::Empty,
+            bits = tracing::field::Empty,
+        );
+        #[cfg(feature = "tracing")]
+        let context = &mut crate::util::TracedContext::new(context);
+
+        let result = remove_raw_ext_impl(&mut self.root, key.borrow(), context);
+
+        #[cfg(feature = "tracing")]
+        context.record(&span);
+
+        result
     }
 
     /// Gets an iterator over the raw entries of the dictionary, sorted by key.
@@ -726,6 +1272,10 @@ where
     /// Gets an iterator over the raw values of the dictionary, in order by key.
     /// The iterator element type is `Result<CellSlice>`.
     ///
+    /// Keys are not reconstructed and value slices are borrowed
+    /// from the dictionary cells, so this is the cheapest way to visit
+    /// all values (especially for large keys).
+    ///
     /// If the dictionary is invalid, finishes after the first invalid element,
     /// returning an error.
     pub fn raw_values(&'_ self) -> RawValues<'_> {
@@ -749,7 +1299,22 @@ where
         Q: Borrow<K>,
         T: Borrow<V>,
     {
-        self.insert_impl(key.borrow(), value.borrow(), SetMode::Set, context)
+        #[cfg(feature = "tracing")]
+        let span = debug_span!(
+            "dict_set",
+            key_bits = K::BITS,
+            cells = tracing::field::Empty,
+            bits = tracing::field::Empty,
+        );
+        #[cfg(feature = "tracing")]
+        let context = &mut crate::util::TracedContext::new(context);
+
+        let result = self.insert_impl(key.borrow(), value.borrow(), SetMode::Set, context);
+
+        #[cfg(feature = "tracing")]
+        context.record(&span);
+
+        result
     }
 
     /// Sets the value associated with the key in the dictionary
@@ -782,6 +1347,123 @@ where
         self.insert_impl(key.borrow(), value.borrow(), SetMode::Add, context)
     }
 
+    /// Builds a dictionary from the key-value pairs sorted by key.
+    ///
+    /// The dictionary is constructed bottom-up, creating each cell only once.
+    /// Keys must be unique and sorted in the same order as [`iter`] yields them
+    /// (ascending order of the serialized key bits), otherwise [`Error::InvalidData`]
+    /// is returned.
+    ///
+    /// [`iter`]: Dict::iter
+    pub fn from_sorted_pairs<I>(pairs: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let pairs = pairs.into_iter().map(|(key, value)| {
+            let mut builder = CellBuilder::new();
+            ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
+            Ok((builder, value))
+        });
+        let pairs = ok!(pairs.collect::<Result<Vec<_>, Error>>());
+
+        let root = ok!(build_dict_from_sorted_iter(
+            pairs,
+            K::BITS,
+            &mut Cell::empty_context()
+        ));
+        Ok(Self::from_raw(root))
+    }
+
+    /// Inserts all key-value pairs from the iterator.
+    /// Returns the number of newly inserted entries
+    /// (the remaining entries have overwritten existing ones).
+    ///
+    /// If the dictionary is empty and the keys are unique and sorted
+    /// (in the same order as [`iter`] yields them), the dictionary
+    /// is built bottom-up instead of inserting entries one by one.
+    ///
+    /// [`iter`]: Dict::iter
+    pub fn bulk_insert<I>(&mut self, entries: I) -> Result<u64, Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let cx = &mut Cell::empty_context();
+
+        let mut entries = ok!(entries
+            .into_iter()
+            .map(|(key, value)| {
+                let mut builder = CellBuilder::new();
+                ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
+                Ok((builder, value))
+            })
+            .collect::<Result<Vec<_>, Error>>());
+
+        if self.root.is_none() {
+            let sorted = entries.iter().map(|(key, value)| (key.clone(), value));
+            match build_dict_from_sorted_iter(sorted, K::BITS, cx) {
+                Ok(root) => {
+                    self.root = root;
+                    return Ok(entries.len() as u64);
+                }
+                // Fallback to the sequential insertion for unsorted keys
+                Err(Error::InvalidData) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut added = 0;
+        for (key, value) in entries.drain(..) {
+            let (_, prev) = ok!(dict_insert_owned(
+                &mut self.root,
+                &mut key.as_data_slice(),
+                K::BITS,
+                &value,
+                SetMode::Set,
+                cx
+            ));
+            added += prev.is_none() as u64;
+        }
+        Ok(added)
+    }
+
+    /// Sets all key-value pairs from the iterator.
+    ///
+    /// Entries are sorted by key and merged with the existing tree,
+    /// so each affected path is rebuilt only once. Later duplicates
+    /// in the iterator replace earlier ones.
+    ///
+    /// The result is the same as calling [`set`] for each entry.
+    ///
+    /// [`set`]: Dict::set
+    pub fn extend<I>(&mut self, entries: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let entries = entries.into_iter();
+        let mut sorted = Vec::<(CellBuilder, V)>::with_capacity(entries.size_hint().0);
+        for (key, value) in entries {
+            let mut builder = CellBuilder::new();
+            ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
+            sorted.push((builder, value));
+        }
+
+        // NOTE: stable sort keeps the original order of duplicates
+        sorted.sort_by(|(a, _), (b, _)| a.raw_data().cmp(b.raw_data()));
+
+        // Keep only the last value for each key
+        let mut unique = Vec::<(CellBuilder, V)>::with_capacity(sorted.len());
+        for (key, value) in sorted {
+            match unique.last_mut() {
+                Some((last_key, last_value)) if last_key.raw_data() == key.raw_data() => {
+                    *last_value = value;
+                }
+                _ => unique.push((key, value)),
+            }
+        }
+
+        dict_insert_sorted_iter(&mut self.root, unique, K::BITS, &mut Cell::empty_context())
+    }
+
     fn insert_impl(
         &mut self,
         key: &K,
@@ -806,155 +1488,668 @@ where
     }
 }
 
-#[cfg(feature = "serde")]
-impl<K, V> serde::Serialize for Dict<K, V>
+impl<K, V> Dict<K, V>
 where
-    K: serde::Serialize + Store + DictKey,
-    for<'a> V: serde::Serialize + Load<'a>,
+    K: Store + DictKey,
+    for<'a> V: Store + Load<'a> + 'static,
 {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    /// Gets the given key's corresponding entry in the dictionary
+    /// for in-place manipulation.
+    ///
+    /// The dictionary is traversed only once. The path to the entry is kept,
+    /// so the entry rebuilds it without searching for the key again.
+    /// Changes are written when the entry is dropped, but the update is
+    /// silently lost if writing fails then. Use [`DictEntry::commit`]
+    /// to handle the error.
+    pub fn entry(&mut self, key: K) -> Result<DictEntry<'_, K, V>, Error> {
+        let mut key_builder = CellBuilder::new();
+        ok!(key.store_into(&mut key_builder, &mut Cell::empty_context()));
+        let cursor = ok!(DictCursor::new(
+            self.root.as_ref(),
+            key_builder,
+            K::BITS,
+            &mut Cell::empty_context()
+        ));
+
+        let value = match ok!(cursor.value()) {
+            Some(mut value) => Some(ok!(V::load_from(&mut value))),
+            None => None,
+        };
+
+        Ok(DictEntry {
+            dict: self,
+            key,
+            cursor: Some(cursor),
+            value,
+            dirty: false,
+        })
+    }
+
+    /// Returns the value corresponding to the key, or inserts
+    /// the provided default value if the key is not present.
+    pub fn get_or_insert<Q>(&mut self, key: Q, default: V) -> Result<V, Error>
     where
-        S: serde::Serializer,
+        Q: Borrow<K>,
     {
-        use serde::ser::{Error, SerializeMap};
+        self.get_or_try_insert_with(key, || Ok(default))
+    }
 
-        if serializer.is_human_readable() {
-            let mut ser = serializer.serialize_map(None)?;
-            for ref entry in self.iter() {
-                let (key, value) = match entry {
-                    Ok(entry) => entry,
-                    Err(e) => return Err(Error::custom(e)),
-                };
-                ok!(ser.serialize_entry(key, value));
-            }
-            ser.end()
-        } else {
-            crate::boc::BocRepr::serialize(self, serializer)
-        }
+    /// Returns the value corresponding to the key, or inserts
+    /// the result of the default function if the key is not present.
+    pub fn get_or_insert_with<Q, F>(&mut self, key: Q, f: F) -> Result<V, Error>
+    where
+        Q: Borrow<K>,
+        F: FnOnce() -> V,
+    {
+        self.get_or_try_insert_with(key, || Ok(f()))
     }
-}
 
-#[cfg(feature = "serde")]
-impl<'de, K, V> serde::Deserialize<'de> for Dict<K, V>
-where
-    K: serde::Deserialize<'de> + std::hash::Hash + Eq + Store + DictKey,
-    V: serde::Deserialize<'de> + Store,
-{
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    /// Returns the value corresponding to the key, or inserts
+    /// the result of the fallible default function if the key is not present.
+    ///
+    /// The dictionary is traversed only once, and is left unchanged
+    /// if the function fails.
+    pub fn get_or_try_insert_with<Q, F>(&mut self, key: Q, f: F) -> Result<V, Error>
     where
-        D: serde::Deserializer<'de>,
+        Q: Borrow<K>,
+        F: FnOnce() -> Result<V, Error>,
     {
-        if deserializer.is_human_readable() {
-            let map = ok!(ahash::HashMap::<K, V>::deserialize(deserializer));
+        let context = &mut Cell::empty_context();
 
-            // TODO: Build from sorted collection
-            let cx = &mut Cell::empty_context();
-            let mut dict = Dict::new();
-            for (key, value) in map {
-                if let Err(e) = dict.set_ext(key, value, cx) {
-                    return Err(serde::de::Error::custom(e));
-                }
-            }
-            Ok(dict)
-        } else {
-            crate::boc::BocRepr::deserialize(deserializer)
+        let mut key_builder = CellBuilder::new();
+        ok!(key.borrow().store_into(&mut key_builder, context));
+        let cursor = ok!(DictCursor::new(
+            self.root.as_ref(),
+            key_builder,
+            K::BITS,
+            context
+        ));
+
+        if let Some(mut value) = ok!(cursor.value()) {
+            return V::load_from(&mut value);
         }
+
+        let value = ok!(f());
+        self.root = Some(ok!(cursor.set(&value, context)));
+        Ok(value)
     }
-}
 
-/// An iterator over the entries of a [`Dict`].
-///
-/// This struct is created by the [`iter`] method on [`Dict`]. See its documentation for more.
-///
-/// [`iter`]: Dict::iter
-pub struct Iter<'a, K, V> {
-    inner: RawIter<'a>,
-    _key: PhantomData<K>,
-    _value: PhantomData<V>,
-}
+    /// Updates the value associated with the key using the provided function.
+    ///
+    /// The function receives the current value (or `None` if the key is absent)
+    /// and returns the new value. Returning `None` removes the entry.
+    ///
+    /// Returns whether the dictionary was changed.
+    pub fn update_with<Q, F>(&mut self, key: Q, f: F) -> Result<bool, Error>
+    where
+        Q: Borrow<K>,
+        F: FnOnce(Option<V>) -> Option<V>,
+    {
+        self.update_with_ext(key, f, &mut Cell::empty_context())
+    }
 
-impl<K, V> Clone for Iter<'_, K, V> {
-    fn clone(&self) -> Self {
-        Self {
-            inner: self.inner.clone(),
-            _key: PhantomData,
-            _value: PhantomData,
+    /// Updates the value associated with the key using the provided function.
+    ///
+    /// The dictionary is traversed only once, and both the lookup
+    /// and the rebuilt path go through the provided cell context.
+    ///
+    /// See [`update_with`] for details.
+    ///
+    /// [`update_with`]: Dict::update_with
+    pub fn update_with_ext<Q, F>(
+        &mut self,
+        key: Q,
+        f: F,
+        context: &mut dyn CellContext,
+    ) -> Result<bool, Error>
+    where
+        Q: Borrow<K>,
+        F: FnOnce(Option<V>) -> Option<V>,
+    {
+        let mut key_builder = CellBuilder::new();
+        ok!(key
+            .borrow()
+            .store_into(&mut key_builder, &mut Cell::empty_context()));
+        let cursor = ok!(DictCursor::new(
+            self.root.as_ref(),
+            key_builder,
+            K::BITS,
+            context
+        ));
+
+        let prev = ok!(cursor.value());
+        let value = match prev {
+            Some(mut prev) => Some(ok!(V::load_from(&mut prev))),
+            None => None,
+        };
+
+        match (f(value), prev) {
+            (Some(value), prev) => {
+                let mut builder = CellBuilder::new();
+                ok!(value.store_into(&mut builder, context));
+                if matches!(prev, Some(prev) if is_same_value(&builder, &prev)) {
+                    return Ok(false);
+                }
+                self.root = Some(ok!(cursor.set(&builder.as_full_slice(), context)));
+            }
+            (None, Some(_)) => self.root = ok!(cursor.remove(context)),
+            (None, None) => return Ok(false),
         }
+        Ok(true)
     }
 }
 
-impl<'a, K, V> Iter<'a, K, V>
+impl<K, V> Dict<K, V>
 where
     K: DictKey,
+    for<'a> V: Store + Load<'a>,
 {
-    /// Creates an iterator over the entries of a dictionary.
-    pub fn new(root: &'a Option<Cell>) -> Self {
-        Self {
-            inner: RawIter::new(root, K::BITS),
-            _key: PhantomData,
-            _value: PhantomData,
-        }
+    /// Merges two dictionaries into a new one.
+    ///
+    /// Calls `on_conflict` with the key, the value from `self` and the value
+    /// from `other` for each key present in both dictionaries.
+    ///
+    /// Both tries are traversed in parallel, so subtrees which are present
+    /// only in one of the dictionaries are reused without visiting them.
+    pub fn union<F>(&self, other: &Self, on_conflict: F) -> Result<Self, Error>
+    where
+        F: Fn(&K, V, V) -> V,
+    {
+        let root = ok!(dict_union(
+            self.root.as_ref(),
+            other.root.as_ref(),
+            K::BITS,
+            |key, mut left, mut right| {
+                let Some(key) = K::from_raw_data(key.raw_data()) else {
+                    return Err(Error::CellUnderflow);
+                };
+                let left = ok!(V::load_from(&mut left));
+                let right = ok!(V::load_from(&mut right));
+
+                let mut builder = CellBuilder::new();
+                ok!(on_conflict(&key, left, right)
+                    .store_into(&mut builder, &mut Cell::empty_context()));
+                Ok(builder)
+            },
+            &mut Cell::empty_context(),
+        ));
+        Ok(Self::from_raw(root))
     }
 
-    /// Changes the direction of the iterator to descending.
-    #[inline]
-    pub fn reversed(mut self) -> Self {
-        self.inner = self.inner.reversed();
-        self
+    /// Merges another dictionary into this one.
+    ///
+    /// See [`union`] for details.
+    ///
+    /// [`union`]: Dict::union
+    pub fn merge_with<F>(&mut self, other: Dict<K, V>, on_conflict: F) -> Result<(), Error>
+    where
+        F: Fn(&K, V, V) -> V,
+    {
+        self.union_with(&other, on_conflict)
     }
 
-    /// Changes the behavior of the iterator to reverse the high bit.
-    #[inline]
-    pub fn signed(mut self) -> Self {
-        self.inner = self.inner.signed();
-        self
+    /// Merges another dictionary into this one.
+    ///
+    /// Calls `merge_fn` with the key, the value from `self` and the value
+    /// from `other` for each key present in both dictionaries.
+    /// Same as [`merge_with`], but doesn't take ownership of `other`.
+    ///
+    /// [`merge_with`]: Dict::merge_with
+    pub fn union_with<F>(&mut self, other: &Self, merge_fn: F) -> Result<(), Error>
+    where
+        F: Fn(&K, V, V) -> V,
+    {
+        *self = ok!(self.union(other, merge_fn));
+        Ok(())
+    }
+
+    /// Builds a new dictionary from the keys present in both dictionaries.
+    ///
+    /// Calls `f` with the key, the value from `self` and the value
+    /// from `other` for each common key.
+    ///
+    /// Both tries are traversed in parallel, so subtrees which don't
+    /// overlap are skipped without visiting them.
+    pub fn intersection<W, X, F>(&self, other: &Dict<K, W>, f: F) -> Result<Dict<K, X>, Error>
+    where
+        for<'a> W: Load<'a>,
+        X: Store,
+        F: Fn(&K, V, W) -> X,
+    {
+        let root = ok!(dict_intersection(
+            self.root.as_ref(),
+            other.root.as_ref(),
+            K::BITS,
+            |key, mut left, mut right| {
+                let Some(key) = K::from_raw_data(key.raw_data()) else {
+                    return Err(Error::CellUnderflow);
+                };
+                let left = ok!(V::load_from(&mut left));
+                let right = ok!(W::load_from(&mut right));
+
+                let mut builder = CellBuilder::new();
+                ok!(f(&key, left, right).store_into(&mut builder, &mut Cell::empty_context()));
+                Ok(builder)
+            },
+            &mut Cell::empty_context(),
+        ));
+        Ok(Dict::from_raw(root))
     }
 }
 
-impl<'a, K, V> Iterator for Iter<'a, K, V>
+impl<K, V> Dict<K, V>
 where
     K: DictKey,
-    V: Load<'a>,
+    for<'a> V: Load<'a>,
 {
-    type Item = Result<(K, V), Error>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        Some(match self.inner.next()? {
-            Ok((key, mut value)) => {
-                let err = if let Some(key) = K::from_raw_data(key.raw_data()) {
-                    match V::load_from(&mut value) {
-                        Ok(value) => return Some(Ok((key, value))),
-                        Err(e) => e,
-                    }
-                } else {
-                    Error::CellUnderflow
+    /// Builds a new dictionary with the same keys and values transformed by `f`.
+    ///
+    /// The whole trie is traversed, but entries for which `f` returns a value
+    /// with the same representation are not rebuilt, so unchanged subtrees
+    /// reuse the original cells.
+    pub fn map_values<W, F>(&self, mut f: F) -> Result<Dict<K, W>, Error>
+    where
+        W: Store,
+        F: FnMut(&K, V) -> Result<W, Error>,
+    {
+        let root = ok!(dict_map_values(
+            self.root.as_ref(),
+            K::BITS,
+            |key, mut value| {
+                let Some(key) = K::from_raw_data(key.raw_data()) else {
+                    return Err(Error::CellUnderflow);
                 };
-                Err(self.inner.finish(err))
+                let value = ok!(V::load_from(&mut value));
+
+                let mut builder = CellBuilder::new();
+                ok!(ok!(f(&key, value)).store_into(&mut builder, &mut Cell::empty_context()));
+                Ok(builder)
+            },
+            &mut Cell::empty_context(),
+        ));
+        Ok(Dict::from_raw(root))
+    }
+}
+
+impl<K: DictKey, V> Dict<K, V> {
+    /// Merges two dictionaries into a new one,
+    /// keeping values from `self` for keys present in both.
+    ///
+    /// Identical subtrees are not traversed.
+    pub fn union_take_left(&self, other: &Self) -> Result<Self, Error> {
+        self.union_take(other, false)
+    }
+
+    /// Merges two dictionaries into a new one,
+    /// keeping values from `other` for keys present in both.
+    ///
+    /// Identical subtrees are not traversed.
+    pub fn union_take_right(&self, other: &Self) -> Result<Self, Error> {
+        self.union_take(other, true)
+    }
+
+    /// Builds a new dictionary from the entries of `self`
+    /// whose keys are not present in `other`.
+    ///
+    /// Both tries are traversed in parallel, so subtrees which don't
+    /// overlap are reused as is, and identical subtrees are skipped.
+    pub fn difference<W>(&self, other: &Dict<K, W>) -> Result<Self, Error> {
+        let root = ok!(dict_difference(
+            self.root.as_ref(),
+            other.root.as_ref(),
+            K::BITS,
+            &mut Cell::empty_context(),
+        ));
+        Ok(Self::from_raw(root))
+    }
+
+    fn union_take(&self, other: &Self, take_right: bool) -> Result<Self, Error> {
+        let root = ok!(dict_merge_subtrees(
+            self.root.as_ref(),
+            other.root.as_ref(),
+            K::BITS,
+            &mut DictMergeOps {
+                keep_left: true,
+                keep_right: true,
+                on_same: SameSubtree::Keep,
+                on_both: &mut |_, left, right| {
+                    let mut builder = CellBuilder::new();
+                    ok!(builder.store_slice(if take_right { right } else { left }));
+                    Ok(Some(builder))
+                },
+                comparator: None,
+            },
+            &mut Cell::empty_context(),
+        ));
+        Ok(Self::from_raw(root))
+    }
+}
+
+impl<K: DictKey, V> Dict<K, V> {
+    /// Returns an iterator over the entries which were added, removed or changed
+    /// in `other` compared to `self`, sorted by key in ascending order.
+    /// The iterator element type is `Result<DictDiff<K, V>>`.
+    ///
+    /// Both tries are traversed lazily and in parallel, so identical subtrees
+    /// are skipped by comparing their representation hashes and only the nodes
+    /// on the paths to the changed entries are visited.
+    ///
+    /// If any of the dictionaries is invalid, finishes after the first invalid
+    /// element, returning an error.
+    pub fn diff<'a>(&'a self, other: &'a Self) -> Result<DiffIter<'a, K, V>, Error>
+    where
+        V: Load<'a>,
+    {
+        Ok(DiffIter::new(self.raw_diff(other)))
+    }
+
+    /// Returns an iterator over the entries which were added, removed or changed
+    /// in `other` compared to `self`, resolving all visited cells with the
+    /// specified context.
+    ///
+    /// See [`diff`] for details.
+    ///
+    /// [`diff`]: Dict::diff
+    pub fn diff_ext<'a>(
+        &'a self,
+        other: &'a Self,
+        context: &'a mut dyn CellContext,
+    ) -> Result<DiffIter<'a, K, V>, Error>
+    where
+        V: Load<'a>,
+    {
+        Ok(DiffIter::new(DiffRawIter::new_ext(
+            self.root.as_deref(),
+            other.root.as_deref(),
+            K::BITS,
+            context,
+        )))
+    }
+
+    /// Returns an iterator over the raw entries which were added, removed or
+    /// changed in `other` compared to `self`, sorted by key bits in ascending order.
+    /// The iterator element type is `Result<(CellBuilder, DiffEntry<CellSlice>)>`.
+    ///
+    /// See [`diff`] for details.
+    ///
+    /// [`diff`]: Dict::diff
+    pub fn raw_diff<'a>(&'a self, other: &'a Self) -> DiffRawIter<'a> {
+        DiffRawIter::new(self.root.as_deref(), other.root.as_deref(), K::BITS)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<K, V> Dict<K, V>
+where
+    K: Store + DictKey + Send,
+    V: Store + Send + Sync,
+{
+    /// Builds a dictionary from the key-value pairs using the rayon thread pool.
+    ///
+    /// Entries are sorted by key and split in half recursively. Each half
+    /// is built independently and then both are merged, reusing their subtrees.
+    /// The resulting dictionary is identical to the one built sequentially.
+    ///
+    /// If the same key occurs multiple times, the last value
+    /// (in the order of the parallel iterator) is used.
+    pub fn from_par_iter<I>(iter: I) -> Result<Self, Error>
+    where
+        I: rayon::iter::IntoParallelIterator<Item = (K, V)>,
+    {
+        use rayon::prelude::*;
+
+        let mut entries = ok!(iter
+            .into_par_iter()
+            .map(|(key, value)| {
+                let mut builder = CellBuilder::new();
+                ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
+                Ok((builder, value))
+            })
+            .collect::<Result<Vec<_>, Error>>());
+
+        // NOTE: All keys have the same length and zero padding so comparing
+        // raw bytes matches the bit order. Sort is stable so the last duplicate
+        // stays the last one.
+        entries.par_sort_by(|(a, _), (b, _)| a.raw_data().cmp(b.raw_data()));
+        entries.dedup_by(|next, prev| {
+            let same = next.0 == prev.0;
+            if same {
+                std::mem::swap(&mut next.1, &mut prev.1);
             }
-            Err(e) => Err(e),
-        })
+            same
+        });
+
+        let root = ok!(build_dict_par(&entries, K::BITS));
+        Ok(Self::from_raw(root))
     }
 }
 
-/// An iterator over the entries across two [`Dict`].
+#[cfg(feature = "rayon")]
+fn build_dict_par<V>(entries: &[(CellBuilder, V)], key_bit_len: u16) -> Result<Option<Cell>, Error>
+where
+    V: Store + Sync,
+{
+    const MIN_CHUNK_LEN: usize = 1024;
+
+    if entries.len() <= MIN_CHUNK_LEN {
+        return build_dict_from_sorted_iter(
+            entries.iter().map(|(key, value)| (key.clone(), value)),
+            key_bit_len,
+            &mut Cell::empty_context(),
+        );
+    }
+
+    let (left, right) = entries.split_at(entries.len() / 2);
+    let (left, right) = rayon::join(
+        || build_dict_par(left, key_bit_len),
+        || build_dict_par(right, key_bit_len),
+    );
+    let (left, right) = (ok!(left), ok!(right));
+
+    // Halves have disjoint keys so there must be no conflicts
+    dict_union(
+        left.as_ref(),
+        right.as_ref(),
+        key_bit_len,
+        |_, _, _| Err(Error::InvalidData),
+        &mut Cell::empty_context(),
+    )
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for Dict<K, V>
+where
+    K: serde::Serialize + Store + DictKey,
+    for<'a> V: serde::Serialize + Load<'a>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::{Error, SerializeMap};
+
+        if serializer.is_human_readable() {
+            let mut ser = serializer.serialize_map(None)?;
+            for ref entry in self.iter() {
+                let (key, value) = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => return Err(Error::custom(e)),
+                };
+                ok!(ser.serialize_entry(key, value));
+            }
+            ser.end()
+        } else {
+            crate::boc::BocRepr::serialize(self, serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for Dict<K, V>
+where
+    K: serde::Deserialize<'de> + std::hash::Hash + Eq + Store + DictKey,
+    V: serde::Deserialize<'de> + Store,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let map = ok!(ahash::HashMap::<K, V>::deserialize(deserializer));
+
+            // TODO: Build from sorted collection
+            let cx = &mut Cell::empty_context();
+            let mut dict = Dict::new();
+            for (key, value) in map {
+                if let Err(e) = dict.set_ext(key, value, cx) {
+                    return Err(serde::de::Error::custom(e));
+                }
+            }
+            Ok(dict)
+        } else {
+            crate::boc::BocRepr::deserialize(deserializer)
+        }
+    }
+}
+
+/// A view into a single entry of a [`Dict`].
 ///
-/// This struct is created by the [`iter_union`] method on [`Dict`].
+/// This struct is created by the [`entry`] method on [`Dict`]. See its documentation for more.
 ///
-/// [`iter_union`]: Dict::iter_union
-pub struct UnionIter<'a, K, V> {
-    inner: UnionRawIter<'a>,
+/// Changes are written into the dictionary when the entry is dropped
+/// or explicitly by [`commit`].
+///
+/// [`entry`]: Dict::entry
+/// [`commit`]: DictEntry::commit
+pub struct DictEntry<'a, K, V>
+where
+    K: Store + DictKey,
+    V: Store,
+{
+    dict: &'a mut Dict<K, V>,
+    key: K,
+    cursor: Option<DictCursor>,
+    value: Option<V>,
+    dirty: bool,
+}
+
+impl<K, V> DictEntry<'_, K, V>
+where
+    K: Store + DictKey,
+    V: Store,
+{
+    /// Returns a reference to this entry's key.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns a reference to this entry's value if it exists.
+    #[inline]
+    pub fn get(&self) -> Option<&V> {
+        self.value.as_ref()
+    }
+
+    /// Ensures a value is in the entry by inserting the default if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_insert(&mut self, default: V) -> &mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the
+    /// default function if empty, and returns a mutable reference to the
+    /// value in the entry.
+    pub fn or_insert_with<F>(&mut self, default: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        self.dirty = true;
+        self.value.get_or_insert_with(default)
+    }
+
+    /// Provides in-place mutable access to an occupied entry.
+    pub fn and_modify<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut V),
+    {
+        if let Some(value) = &mut self.value {
+            self.dirty = true;
+            f(value);
+        }
+        self
+    }
+
+    /// Writes the entry into the dictionary.
+    ///
+    /// Only the path to the entry is rebuilt. Nothing is written
+    /// if the serialized value is the same as the stored one.
+    ///
+    /// Returns whether the dictionary was changed.
+    pub fn commit(mut self) -> Result<bool, Error> {
+        self.write()
+    }
+
+    fn write(&mut self) -> Result<bool, Error> {
+        if !std::mem::take(&mut self.dirty) {
+            return Ok(false);
+        }
+        let (Some(value), Some(cursor)) = (&self.value, self.cursor.take()) else {
+            return Ok(false);
+        };
+
+        let context = &mut Cell::empty_context();
+        let mut builder = CellBuilder::new();
+        ok!(value.store_into(&mut builder, context));
+        if let Some(prev) = ok!(cursor.value()) {
+            if is_same_value(&builder, &prev) {
+                return Ok(false);
+            }
+        }
+
+        self.dict.root = Some(ok!(cursor.set(&builder.as_full_slice(), context)));
+        Ok(true)
+    }
+}
+
+impl<K, V> Drop for DictEntry<'_, K, V>
+where
+    K: Store + DictKey,
+    V: Store,
+{
+    fn drop(&mut self) {
+        _ = self.write();
+    }
+}
+
+/// An iterator over the entries of a [`Dict`].
+///
+/// This struct is created by the [`iter`] method on [`Dict`]. See its documentation for more.
+///
+/// [`iter`]: Dict::iter
+pub struct Iter<'a, K, V> {
+    inner: RawIter<'a>,
     _key: PhantomData<K>,
     _value: PhantomData<V>,
 }
 
-impl<'a, K, V> UnionIter<'a, K, V>
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _key: PhantomData,
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Iter<'a, K, V>
 where
     K: DictKey,
 {
     /// Creates an iterator over the entries of a dictionary.
-    pub fn new(left_root: &'a Option<Cell>, right_root: &'a Option<Cell>) -> Self {
+    pub fn new(root: &'a Option<Cell>) -> Self {
         Self {
-            inner: UnionRawIter::new(left_root, right_root, K::BITS),
+            inner: RawIter::new(root, K::BITS),
             _key: PhantomData,
             _value: PhantomData,
         }
@@ -975,36 +2170,20 @@ where
     }
 }
 
-impl<'a, K, V> Iterator for UnionIter<'a, K, V>
+impl<'a, K, V> Iterator for Iter<'a, K, V>
 where
     K: DictKey,
     V: Load<'a>,
 {
-    type Item = Result<(K, Option<V>, Option<V>), Error>;
+    type Item = Result<(K, V), Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        fn load_opt_value<'a, V: Load<'a>>(
-            value: &mut Option<CellSlice<'a>>,
-        ) -> Result<Option<V>, Error> {
-            match value {
-                Some(mut value) => match V::load_from(&mut value) {
-                    Ok(value) => Ok(Some(value)),
-                    Err(e) => Err(e),
-                },
-                None => Ok(None),
-            }
-        }
-
         Some(match self.inner.next()? {
-            Ok((key, mut left_value, mut right_value)) => {
+            Ok((key, mut value)) => {
                 let err = if let Some(key) = K::from_raw_data(key.raw_data()) {
-                    match (
-                        load_opt_value(&mut left_value),
-                        load_opt_value(&mut right_value),
-                    ) {
-                        (Ok(left), Ok(right)) => return Some(Ok((key, left, right))),
-                        (Err(e), _) => e,
-                        (_, Err(e)) => e,
+                    match V::load_from(&mut value) {
+                        Ok(value) => return Some(Ok((key, value))),
+                        Err(e) => e,
                     }
                 } else {
                     Error::CellUnderflow
@@ -1016,18 +2195,271 @@ where
     }
 }
 
-/// An iterator over the keys of a [`Dict`].
+/// An owning iterator over the entries of a [`Dict`].
 ///
-/// This struct is created by the [`keys`] method on [`Dict`]. See its
-/// documentation for more.
-///
-/// [`keys`]: Dict::keys
-pub struct Keys<'a, K> {
-    inner: RawIter<'a>,
+/// This struct is created by the `into_iter` method on [`Dict`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<K, V> {
+    // NOTE: `inner` must be declared (and therefore dropped) before `root`.
+    inner: RawIter<'static>,
+    _root: Option<Cell>,
     _key: PhantomData<K>,
+    _value: PhantomData<V>,
 }
 
-impl<'a, K> Clone for Keys<'a, K> {
+impl<K, V> IntoIter<K, V>
+where
+    K: DictKey,
+{
+    /// Creates an owning iterator over the entries of a dictionary.
+    pub fn new(root: Option<Cell>) -> Self {
+        // SAFETY: `RawIter` only borrows the cells of the dictionary which are
+        // heap allocated and kept alive by `root` until the iterator is dropped.
+        // The reference to the `Option` itself is not stored.
+        let inner = RawIter::new(unsafe { &*(&root as *const Option<Cell>) }, K::BITS);
+        Self {
+            inner,
+            _root: root,
+            _key: PhantomData,
+            _value: PhantomData,
+        }
+    }
+
+    /// Changes the direction of the iterator to descending.
+    #[inline]
+    pub fn reversed(mut self) -> Self {
+        self.inner = self.inner.reversed();
+        self
+    }
+
+    /// Changes the behavior of the iterator to reverse the high bit.
+    #[inline]
+    pub fn signed(mut self) -> Self {
+        self.inner = self.inner.signed();
+        self
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V>
+where
+    K: DictKey,
+    for<'a> V: Load<'a>,
+{
+    type Item = Result<(K, V), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.inner.next()? {
+            Ok((key, value)) => {
+                // NOTE: Values must not borrow the cells owned by the iterator
+                let mut value: CellSlice<'_> = value;
+                let err = if let Some(key) = K::from_raw_data(key.raw_data()) {
+                    match V::load_from(&mut value) {
+                        Ok(value) => return Some(Ok((key, value))),
+                        Err(e) => e,
+                    }
+                } else {
+                    Error::CellUnderflow
+                };
+                Err(self.inner.finish(err))
+            }
+            Err(e) => Err(e),
+        })
+    }
+}
+
+/// An iterator over the entries across two [`Dict`].
+///
+/// This struct is created by the [`iter_union`] method on [`Dict`].
+///
+/// [`iter_union`]: Dict::iter_union
+pub struct UnionIter<'a, K, V> {
+    inner: UnionRawIter<'a>,
+    _key: PhantomData<K>,
+    _value: PhantomData<V>,
+}
+
+impl<'a, K, V> UnionIter<'a, K, V>
+where
+    K: DictKey,
+{
+    /// Creates an iterator over the entries of a dictionary.
+    pub fn new(left_root: &'a Option<Cell>, right_root: &'a Option<Cell>) -> Self {
+        Self {
+            inner: UnionRawIter::new(left_root, right_root, K::BITS),
+            _key: PhantomData,
+            _value: PhantomData,
+        }
+    }
+
+    /// Changes the direction of the iterator to descending.
+    #[inline]
+    pub fn reversed(mut self) -> Self {
+        self.inner = self.inner.reversed();
+        self
+    }
+
+    /// Changes the behavior of the iterator to reverse the high bit.
+    #[inline]
+    pub fn signed(mut self) -> Self {
+        self.inner = self.inner.signed();
+        self
+    }
+}
+
+impl<'a, K, V> Iterator for UnionIter<'a, K, V>
+where
+    K: DictKey,
+    V: Load<'a>,
+{
+    type Item = Result<(K, Option<V>, Option<V>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        fn load_opt_value<'a, V: Load<'a>>(
+            value: &mut Option<CellSlice<'a>>,
+        ) -> Result<Option<V>, Error> {
+            match value {
+                Some(mut value) => match V::load_from(&mut value) {
+                    Ok(value) => Ok(Some(value)),
+                    Err(e) => Err(e),
+                },
+                None => Ok(None),
+            }
+        }
+
+        Some(match self.inner.next()? {
+            Ok((key, mut left_value, mut right_value)) => {
+                let err = if let Some(key) = K::from_raw_data(key.raw_data()) {
+                    match (
+                        load_opt_value(&mut left_value),
+                        load_opt_value(&mut right_value),
+                    ) {
+                        (Ok(left), Ok(right)) => return Some(Ok((key, left, right))),
+                        (Err(e), _) => e,
+                        (_, Err(e)) => e,
+                    }
+                } else {
+                    Error::CellUnderflow
+                };
+                Err(self.inner.finish(err))
+            }
+            Err(e) => Err(e),
+        })
+    }
+}
+
+/// Dictionary entry which differs between two [`Dict`].
+///
+/// This enum is produced by the [`DiffIter`] iterator.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DictDiff<K, V> {
+    /// The entry is present only in the right dictionary.
+    Added {
+        /// Entry key.
+        key: K,
+        /// Value from the right dictionary.
+        value: V,
+    },
+    /// The entry is present only in the left dictionary.
+    Removed {
+        /// Entry key.
+        key: K,
+        /// Value from the left dictionary.
+        value: V,
+    },
+    /// The entry is present in both dictionaries with different values.
+    Changed {
+        /// Entry key.
+        key: K,
+        /// Value from the left dictionary.
+        old: V,
+        /// Value from the right dictionary.
+        new: V,
+    },
+}
+
+impl<K, V> DictDiff<K, V> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Self::Added { key, .. } | Self::Removed { key, .. } | Self::Changed { key, .. } => key,
+        }
+    }
+}
+
+/// An iterator over the entries which differ between two [`Dict`].
+///
+/// This struct is created by the [`diff`] method on [`Dict`].
+/// See its documentation for more.
+///
+/// [`diff`]: Dict::diff
+pub struct DiffIter<'a, K, V> {
+    inner: DiffRawIter<'a>,
+    _key: PhantomData<K>,
+    _value: PhantomData<V>,
+}
+
+impl<'a, K, V> DiffIter<'a, K, V> {
+    /// Creates a typed iterator over the raw diff entries.
+    pub fn new(inner: DiffRawIter<'a>) -> Self {
+        Self {
+            inner,
+            _key: PhantomData,
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for DiffIter<'a, K, V>
+where
+    K: DictKey,
+    V: Load<'a>,
+{
+    type Item = Result<DictDiff<K, V>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.inner.next()? {
+            Ok((key, entry)) => {
+                let Some(key) = K::from_raw_data(key.raw_data()) else {
+                    return Some(Err(self.inner.finish(Error::CellUnderflow)));
+                };
+
+                let entry = match entry {
+                    DiffEntry::Added(mut value) => {
+                        V::load_from(&mut value).map(|value| DictDiff::Added { key, value })
+                    }
+                    DiffEntry::Removed(mut value) => {
+                        V::load_from(&mut value).map(|value| DictDiff::Removed { key, value })
+                    }
+                    DiffEntry::Changed { mut old, mut new } => {
+                        match (V::load_from(&mut old), V::load_from(&mut new)) {
+                            (Ok(old), Ok(new)) => Ok(DictDiff::Changed { key, old, new }),
+                            (Err(e), _) | (_, Err(e)) => Err(e),
+                        }
+                    }
+                };
+
+                match entry {
+                    Ok(entry) => Ok(entry),
+                    Err(e) => Err(self.inner.finish(e)),
+                }
+            }
+            Err(e) => Err(e),
+        })
+    }
+}
+
+/// An iterator over the keys of a [`Dict`].
+///
+/// This struct is created by the [`keys`] method on [`Dict`]. See its
+/// documentation for more.
+///
+/// [`keys`]: Dict::keys
+pub struct Keys<'a, K> {
+    inner: RawIter<'a>,
+    _key: PhantomData<K>,
+}
+
+impl<'a, K> Clone for Keys<'a, K> {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
@@ -1046,124 +2478,1247 @@ where
             inner: RawIter::new(root, K::BITS),
             _key: PhantomData,
         }
-    }
+    }
+
+    /// Changes the direction of the iterator to descending.
+    #[inline]
+    pub fn reversed(mut self) -> Self {
+        self.inner = self.inner.reversed();
+        self
+    }
+
+    /// Changes the behavior of the iterator to reverse the high bit.
+    #[inline]
+    pub fn signed(mut self) -> Self {
+        self.inner = self.inner.signed();
+        self
+    }
+}
+
+impl<'a, K> Iterator for Keys<'a, K>
+where
+    K: DictKey,
+{
+    type Item = Result<K, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.inner.next()? {
+            Ok((key, _)) => match K::from_raw_data(key.raw_data()) {
+                Some(key) => Ok(key),
+                None => Err(self.inner.finish(Error::CellUnderflow)),
+            },
+            Err(e) => Err(e),
+        })
+    }
+}
+
+/// An iterator over the values of a [`Dict`].
+///
+/// This struct is created by the [`values`] method on [`Dict`]. See its documentation for more.
+///
+/// [`values`]: Dict::values
+pub struct Values<'a, V> {
+    inner: RawValues<'a>,
+    _value: PhantomData<V>,
+}
+
+impl<V> Clone for Values<'_, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<'a, V> Values<'a, V> {
+    /// Creates an iterator over the values of a dictionary.
+    pub fn new(root: &'a Option<Cell>, bit_len: u16) -> Self {
+        Self {
+            inner: RawValues::new(root, bit_len),
+            _value: PhantomData,
+        }
+    }
+
+    /// Changes the direction of the iterator to descending.
+    #[inline]
+    pub fn reversed(mut self) -> Self {
+        self.inner = self.inner.reversed();
+        self
+    }
+
+    /// Changes the behavior of the iterator to reverse the high bit.
+    #[inline]
+    pub fn signed(mut self) -> Self {
+        self.inner = self.inner.signed();
+        self
+    }
+}
+
+impl<'a, V> Iterator for Values<'a, V>
+where
+    V: Load<'a>,
+{
+    type Item = Result<V, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner.next()? {
+            Ok(mut value) => match V::load_from(&mut value) {
+                Ok(value) => Some(Ok(value)),
+                Err(e) => Some(Err(self.inner.finish(e))),
+            },
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// An iterator over the entries of a [`Dict`] with a Merkle proof for each entry.
+///
+/// This struct is created by the [`iter_with_proofs`] method on [`Dict`].
+/// See its documentation for more.
+///
+/// [`iter_with_proofs`]: Dict::iter_with_proofs
+pub struct ProofIter<'a, K, V> {
+    root: Option<&'a Cell>,
+    segments: Vec<ProofIterSegment>,
+    path: Vec<HashBytes>,
+    _key: PhantomData<K>,
+    _value: PhantomData<V>,
+}
+
+struct ProofIterSegment {
+    cell: Cell,
+    prefix: CellBuilder,
+    depth: usize,
+}
+
+impl<'a, K, V> ProofIter<'a, K, V> {
+    /// Creates an iterator over the entries of a dictionary.
+    pub fn new(root: &'a Option<Cell>) -> Self {
+        let mut segments = Vec::new();
+        if let Some(root) = root {
+            segments.push(ProofIterSegment {
+                cell: root.clone(),
+                prefix: CellBuilder::new(),
+                depth: 0,
+            });
+        }
+
+        Self {
+            root: root.as_ref(),
+            segments,
+            path: Vec::new(),
+            _key: PhantomData,
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<K, V> ProofIter<'_, K, V>
+where
+    K: DictKey,
+    for<'a> V: Load<'a>,
+{
+    fn visit(&mut self, segment: ProofIterSegment) -> Result<Option<(K, V, MerkleProof)>, Error> {
+        let Some(root) = self.root else {
+            return Ok(None);
+        };
+
+        // Keep only the path to the current node
+        self.path.truncate(segment.depth);
+        self.path.push(*segment.cell.repr_hash());
+
+        let mut data = ok!(segment.cell.as_slice());
+        let rem_key_bit_len = K::BITS - segment.prefix.bit_len();
+        let label = ok!(read_label(&mut data, rem_key_bit_len));
+
+        let mut key = segment.prefix;
+        ok!(key.store_slice_data(label));
+
+        if label.remaining_bits() != rem_key_bit_len {
+            // Push the right branch first to visit the left one first
+            for bit in [true, false] {
+                let mut prefix = key.clone();
+                ok!(prefix.store_bit(bit));
+                self.segments.push(ProofIterSegment {
+                    cell: ok!(data.get_reference_cloned(bit as u8)),
+                    prefix,
+                    depth: segment.depth + 1,
+                });
+            }
+            return Ok(None);
+        }
+
+        let Some(key) = K::from_raw_data(key.raw_data()) else {
+            return Err(Error::CellUnderflow);
+        };
+        let value = ok!(V::load_from(&mut data));
+
+        let filter = self.path.iter().collect::<ahash::HashSet<&HashBytes>>();
+        let proof = ok!(MerkleProof::create(root.as_ref(), filter).build());
+        Ok(Some((key, value, proof)))
+    }
+}
+
+impl<K, V> Iterator for ProofIter<'_, K, V>
+where
+    K: DictKey,
+    for<'a> V: Load<'a>,
+{
+    type Item = Result<(K, V, MerkleProof), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let segment = self.segments.pop()?;
+            match self.visit(segment) {
+                Ok(Some(entry)) => return Some(Ok(entry)),
+                Ok(None) => continue,
+                Err(e) => {
+                    self.segments.clear();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// Dictionary value which is stored in a child cell of the leaf (`^V`).
+///
+/// Use [`Dict::set_value_cell`] to reuse an already built value cell.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct InRef<V>(pub V);
+
+impl<V> InRef<V> {
+    /// Converts into the underlying value.
+    #[inline]
+    pub fn into_inner(self) -> V {
+        self.0
+    }
+}
+
+impl<V> From<V> for InRef<V> {
+    #[inline]
+    fn from(value: V) -> Self {
+        Self(value)
+    }
+}
+
+impl<V> std::ops::Deref for InRef<V> {
+    type Target = V;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V> std::ops::DerefMut for InRef<V> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<V> ExactSize for InRef<V> {
+    #[inline]
+    fn exact_size(&self) -> CellSliceSize {
+        CellSliceSize { bits: 0, refs: 1 }
+    }
+}
+
+impl<V: Store> Store for InRef<V> {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        let cell = ok!(CellBuilder::build_from_ext(&self.0, context));
+        builder.store_reference(cell)
+    }
+}
+
+impl<'a, V: Load<'a>> Load<'a> for InRef<V> {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        let mut child = ok!(slice.load_reference_as_slice());
+        match V::load_from(&mut child) {
+            Ok(value) => Ok(Self(value)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<K, V> Dict<K, InRef<V>>
+where
+    K: Store + DictKey,
+{
+    /// Returns the cell with the value corresponding to the key.
+    pub fn get_value_cell<Q>(&self, key: Q) -> Result<Option<Cell>, Error>
+    where
+        Q: Borrow<K>,
+    {
+        match ok!(self.get_raw(key)) {
+            Some(mut value) => match value.load_reference_cloned() {
+                Ok(cell) => Ok(Some(cell)),
+                Err(e) => Err(e),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Sets the cell with the value associated with the key in the dictionary.
+    ///
+    /// The cell is stored as is, without rebuilding the value.
+    pub fn set_value_cell<Q>(&mut self, key: Q, cell: Cell) -> Result<bool, Error>
+    where
+        Q: Borrow<K>,
+    {
+        self.set_value_cell_ext(key, cell, &mut Cell::empty_context())
+    }
+
+    /// Sets the cell with the value associated with the key in the dictionary.
+    pub fn set_value_cell_ext<Q>(
+        &mut self,
+        key: Q,
+        cell: Cell,
+        context: &mut dyn CellContext,
+    ) -> Result<bool, Error>
+    where
+        Q: Borrow<K>,
+    {
+        let mut key_builder = CellBuilder::new();
+        ok!(key
+            .borrow()
+            .store_into(&mut key_builder, &mut Cell::empty_context()));
+        dict_insert(
+            &mut self.root,
+            &mut key_builder.as_data_slice(),
+            K::BITS,
+            &cell,
+            SetMode::Set,
+            context,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context;
+
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn dict_set() {
+        let mut dict = Dict::<u32, u16>::new();
+        assert!(dict.set(123, 0xffff).unwrap());
+        assert_eq!(dict.get(123).unwrap(), Some(0xffff));
+
+        assert!(dict.set(123, 0xcafe).unwrap());
+        assert_eq!(dict.get(123).unwrap(), Some(0xcafe));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // takes too long to execute on miri
+    fn dict_set_complex() {
+        let mut dict = Dict::<u32, bool>::new();
+        for i in 0..520 {
+            assert!(dict.set(i, true).unwrap());
+        }
+    }
+
+    #[test]
+    fn dict_entry() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u32>::new();
+        for i in 0..10 {
+            dict.set(i, i)?;
+        }
+
+        let mut entry = dict.entry(5)?;
+        *entry.or_insert(0) += 100;
+        assert!(entry.commit()?);
+
+        let mut entry = dict.entry(20)?;
+        *entry.or_insert(0) += 100;
+        assert!(entry.commit()?);
+
+        let mut entry = dict.entry(3)?;
+        entry.and_modify(|value| *value *= 10).or_insert(1);
+        assert!(entry.commit()?);
+
+        let mut entry = dict.entry(30)?;
+        entry.and_modify(|value| *value *= 10).or_insert(1);
+        assert!(entry.commit()?);
+
+        let mut entry = dict.entry(31)?;
+        entry.and_modify(|value| *value *= 10);
+        assert!(!entry.commit()?);
+
+        assert_eq!(dict.get(5)?, Some(105));
+        assert_eq!(dict.get(20)?, Some(100));
+        assert_eq!(dict.get(3)?, Some(30));
+        assert_eq!(dict.get(30)?, Some(1));
+        assert_eq!(dict.get(31)?, None);
+
+        let mut entry = dict.entry(7)?;
+        assert_eq!(entry.key(), &7);
+        assert_eq!(entry.get(), Some(&7));
+        *entry.or_insert_with(|| unreachable!()) = 70;
+        assert!(entry.commit()?);
+        assert_eq!(dict.get(7)?, Some(70));
+
+        let entry = dict.entry(8)?;
+        assert!(!entry.commit()?);
+
+        // Occupied entries are not rewritten
+        let root = dict.root().clone();
+        let mut entry = dict.entry(9)?;
+        assert_eq!(*entry.or_insert(0), 9);
+        assert!(!entry.commit()?);
+
+        let mut entry = dict.entry(9)?;
+        entry.and_modify(|value| *value *= 1);
+        assert!(!entry.commit()?);
+        assert_eq!(dict.root(), &root);
+
+        // Dropped entries are written
+        let entry = dict.entry(40)?;
+        assert_eq!(entry.get(), None);
+        drop(entry);
+        assert_eq!(dict.root(), &root);
+
+        *dict.entry(40)?.or_insert(0) += 40;
+        dict.entry(41)?.or_insert(41);
+        dict.entry(5)?.and_modify(|value| *value += 1);
+        assert_eq!(dict.get(40)?, Some(40));
+        assert_eq!(dict.get(41)?, Some(41));
+        assert_eq!(dict.get(5)?, Some(106));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_get_or_insert() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u32>::new();
+        dict.set(1, 10)?;
+
+        assert_eq!(dict.get_or_insert(1, 0)?, 10);
+        assert_eq!(dict.get_or_insert(2, 20)?, 20);
+        assert_eq!(dict.get_or_insert_with(2, || unreachable!())?, 20);
+        assert_eq!(dict.get_or_insert_with(3, || 30)?, 30);
+
+        assert_eq!(
+            dict.get_or_try_insert_with(4, || Err(Error::Cancelled)),
+            Err(Error::Cancelled)
+        );
+        assert!(!dict.contains_key(4)?);
+        assert_eq!(dict.get_or_try_insert_with(4, || Ok(40))?, 40);
+
+        for i in 1..=4 {
+            assert_eq!(dict.get(i)?, Some(i * 10));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn dict_update_with() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u32>::new();
+        dict.set(1, 10)?;
+
+        assert!(dict.update_with(1, |value| value.map(|v| v + 5))?);
+        assert_eq!(dict.get(1)?, Some(15));
+
+        assert!(dict.update_with(2, |value| Some(value.unwrap_or_default() + 1))?);
+        assert_eq!(dict.get(2)?, Some(1));
+
+        assert!(!dict.update_with(3, |value| value)?);
+        assert!(!dict.contains_key(3)?);
+
+        assert!(dict.update_with(1, |_| None)?);
+        assert!(!dict.contains_key(1)?);
+
+        // Unchanged values are not rewritten
+        let root = dict.root().clone();
+        assert!(!dict.update_with(2, |value| value)?);
+        assert_eq!(dict.root(), &root);
+
+        // Removed entries are the same as with `remove`
+        let mut expected = Dict::<u32, u32>::new();
+        for i in 0..100 {
+            dict.set(i, i)?;
+            expected.set(i, i)?;
+        }
+        for i in (0..100).step_by(3) {
+            assert!(dict.update_with(i, |_| None)?);
+            expected.remove(i)?;
+            assert_eq!(dict, expected);
+        }
+        for i in 0..100 {
+            dict.update_with(i, |_| None)?;
+        }
+        assert!(dict.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_bulk_insert() -> anyhow::Result<()> {
+        let mut expected = Dict::<u32, u32>::new();
+        for i in 0..100 {
+            expected.set(i * 3, i)?;
+        }
+
+        // Sorted entries into an empty dict
+        let mut dict = Dict::<u32, u32>::new();
+        assert_eq!(dict.bulk_insert((0..100).map(|i| (i * 3, i)))?, 100);
+        assert_eq!(dict, expected);
+        assert_eq!(dict.validate()?.entry_count, 100);
+
+        // Unsorted entries into an empty dict
+        let mut dict = Dict::<u32, u32>::new();
+        assert_eq!(dict.bulk_insert((0..100).rev().map(|i| (i * 3, i)))?, 100);
+        assert_eq!(dict, expected);
+
+        // Entries into a non-empty dict
+        let mut dict = Dict::<u32, u32>::new();
+        dict.bulk_insert((0..50).map(|i| (i * 3, 0)))?;
+        assert_eq!(dict.bulk_insert((0..100).map(|i| (i * 3, i)))?, 50);
+        assert_eq!(dict, expected);
+
+        // Empty entries
+        let mut dict = Dict::<u32, u32>::new();
+        assert_eq!(dict.bulk_insert([])?, 0);
+        assert!(dict.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_union() -> anyhow::Result<()> {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand_xorshift::XorShiftRng::from_seed([2u8; 16]);
+
+        for (left_len, right_len) in [(0, 0), (0, 10), (10, 0), (1, 1), (100, 100), (1000, 10)] {
+            let mut left = Dict::<u32, u32>::new();
+            for _ in 0..left_len {
+                left.set(rng.gen::<u32>() % 2000, rng.gen::<u32>())?;
+            }
+            let mut right = Dict::<u32, u32>::new();
+            for _ in 0..right_len {
+                right.set(rng.gen::<u32>() % 2000, rng.gen::<u32>())?;
+            }
+
+            let mut expected_sum = left.clone();
+            let mut expected_left = left.clone();
+            let mut expected_right = left.clone();
+            for entry in right.iter() {
+                let (key, value) = entry?;
+                expected_right.set(key, value)?;
+                match left.get(key)? {
+                    Some(left_value) => {
+                        expected_sum.set(key, left_value.wrapping_add(value))?;
+                    }
+                    None => {
+                        expected_sum.set(key, value)?;
+                        expected_left.set(key, value)?;
+                    }
+                }
+            }
+
+            let union = left.union(&right, |_, l, r| l.wrapping_add(r))?;
+            assert_eq!(union, expected_sum);
+            union.validate()?;
+
+            assert_eq!(left.union_take_left(&right)?, expected_left);
+            assert_eq!(left.union_take_right(&right)?, expected_right);
+
+            let mut merged = left.clone();
+            merged.merge_with(right.clone(), |_, l, r| l.wrapping_add(r))?;
+            assert_eq!(merged, expected_sum);
+
+            let mut merged = left.clone();
+            merged.union_with(&right, |_, l, r| l.wrapping_add(r))?;
+            assert_eq!(merged, expected_sum);
+        }
+
+        // Conflicts receive the key
+        let left = Dict::<u32, u32>::from_sorted_pairs([(1, 10), (2, 20)])?;
+        let right = Dict::<u32, u32>::from_sorted_pairs([(2, 200), (3, 300)])?;
+        let union = left.union(&right, |key, l, r| key * 1000 + l + r)?;
+        assert_eq!(
+            union.iter().collect::<Result<Vec<_>, _>>()?,
+            [(1, 10), (2, 2220), (3, 300)]
+        );
+
+        // Identical dictionaries
+        assert_eq!(left.union_take_right(&left)?, left);
+        assert_eq!(left.union(&left, |_, l, _| l)?, left);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_intersection_difference() -> anyhow::Result<()> {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand_xorshift::XorShiftRng::from_seed([3u8; 16]);
+
+        for (left_len, right_len) in [(0, 0), (0, 10), (10, 0), (1, 1), (100, 100), (1000, 10)] {
+            let mut left = Dict::<u32, u32>::new();
+            for _ in 0..left_len {
+                left.set(rng.gen::<u32>() % 2000, rng.gen::<u32>())?;
+            }
+            let mut right = Dict::<u32, u16>::new();
+            for _ in 0..right_len {
+                right.set(rng.gen::<u32>() % 2000, rng.gen::<u16>())?;
+            }
+
+            let mut expected_intersection = Dict::<u32, u64>::new();
+            let mut expected_difference = Dict::<u32, u32>::new();
+            for entry in left.iter() {
+                let (key, value) = entry?;
+                match right.get(key)? {
+                    Some(other) => {
+                        expected_intersection.set(key, value as u64 + other as u64)?;
+                    }
+                    None => {
+                        expected_difference.set(key, value)?;
+                    }
+                }
+            }
+
+            let intersection = left.intersection(&right, |_, l, r| l as u64 + r as u64)?;
+            assert_eq!(intersection, expected_intersection);
+            intersection.validate()?;
+
+            let difference = left.difference(&right)?;
+            assert_eq!(difference, expected_difference);
+            difference.validate()?;
+        }
+
+        let dict = Dict::<u32, u32>::from_sorted_pairs([(1, 10), (2, 20), (5, 50)])?;
+        assert!(dict.difference(&dict)?.is_empty());
+        assert_eq!(
+            dict.intersection(&dict, |key, l, _| *key + l)?.get(5)?,
+            Some(55)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_len() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u32>::new();
+        assert!(dict.is_empty());
+        assert_eq!(dict.len()?, 0);
+
+        for i in 0..100 {
+            dict.set(i * 7, i)?;
+            assert!(!dict.is_empty());
+            assert_eq!(dict.len()?, i as u64 + 1);
+        }
+
+        // Values are not parsed
+        let dict = Dict::<u32, u64>::from_raw(dict.root().clone());
+        assert_eq!(dict.len()?, 100);
+        assert!(dict.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_get_with_proof() -> anyhow::Result<()> {
+        let empty = Dict::<u32, u64>::new();
+        assert!(empty.get_with_proof(0)?.is_none());
+        assert_eq!(
+            empty.contains_key_with_proof(0).unwrap_err(),
+            Error::EmptyProof
+        );
+
+        let mut dict = Dict::<u32, u64>::new();
+        for i in 0..1000 {
+            dict.set(i * 2, i as u64)?;
+        }
+        let root = dict.root().clone().unwrap();
+
+        // Present key
+        let (value, proof) = dict.get_with_proof(600)?.unwrap();
+        assert_eq!(value, 300);
+        assert_eq!(proof.hash, *root.repr_hash());
+
+        let proved = Dict::<u32, u64>::from_raw(Some(Cell::virtualize(proof.cell)));
+        assert_eq!(proved.get(600)?, Some(300));
+        assert_eq!(proved.get(0).unwrap_err(), Error::PrunedBranchAccess);
+
+        // Absent key
+        assert!(dict.get_with_proof(601)?.is_none());
+
+        let (contains, proof) = dict.contains_key_with_proof(601)?;
+        assert!(!contains);
+        assert_eq!(proof.hash, *root.repr_hash());
+
+        let proved = Dict::<u32, u64>::from_raw(Some(Cell::virtualize(proof.cell)));
+        assert!(!proved.contains_key(601)?);
+        assert_eq!(proved.get(0).unwrap_err(), Error::PrunedBranchAccess);
+
+        let (contains, proof) = dict.contains_key_with_proof(1998)?;
+        assert!(contains);
+        let proved = Dict::<u32, u64>::from_raw(Some(Cell::virtualize(proof.cell)));
+        assert!(proved.contains_key(1998)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_iter_with_proofs() -> anyhow::Result<()> {
+        let empty = Dict::<u32, u64>::new();
+        assert_eq!(empty.iter_with_proofs().count(), 0);
+        assert!(matches!(
+            empty.batch_get_with_proof(&[1, 2]),
+            Err(Error::EmptyProof)
+        ));
+
+        let mut dict = Dict::<u32, u64>::new();
+        for i in 0..200 {
+            dict.set(i * 2, i as u64)?;
+        }
+        let root = dict.root().clone().unwrap();
+
+        let mut count = 0;
+        for (entry, expected) in dict.iter_with_proofs().zip(dict.iter()) {
+            let (key, value, proof) = entry?;
+            assert_eq!((key, value), expected?);
+            assert_eq!(proof.hash, *root.repr_hash());
+
+            let proved = Dict::<u32, u64>::from_raw(Some(Cell::virtualize(proof.cell)));
+            assert_eq!(proved.get(key)?, Some(value));
+
+            let other = (key + 200) % 400;
+            assert_eq!(proved.get(other).unwrap_err(), Error::PrunedBranchAccess);
+            count += 1;
+        }
+        assert_eq!(count, 200);
+
+        let keys = [0, 3, 100, 398, 1000];
+        let (values, proof) = dict.batch_get_with_proof(&keys)?;
+        assert_eq!(
+            values,
+            [
+                (0, Some(0)),
+                (3, None),
+                (100, Some(50)),
+                (398, Some(199)),
+                (1000, None)
+            ]
+        );
+        assert_eq!(proof.hash, *root.repr_hash());
+
+        let proved = Dict::<u32, u64>::from_raw(Some(Cell::virtualize(proof.cell)));
+        for (key, value) in values {
+            assert_eq!(proved.get(key)?, value);
+        }
+        assert_eq!(proved.get(200).unwrap_err(), Error::PrunedBranchAccess);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_diff() -> anyhow::Result<()> {
+        use std::collections::BTreeMap;
+
+        use rand::{Rng, SeedableRng};
+
+        fn check_diff(left: &BTreeMap<u32, u64>, right: &BTreeMap<u32, u64>) -> anyhow::Result<()> {
+            let mut expected = Vec::new();
+            for (key, old) in left {
+                match right.get(key) {
+                    None => expected.push(DictDiff::Removed {
+                        key: *key,
+                        value: *old,
+                    }),
+                    Some(new) if new != old => expected.push(DictDiff::Changed {
+                        key: *key,
+                        old: *old,
+                        new: *new,
+                    }),
+                    Some(_) => {}
+                }
+            }
+            for (key, new) in right {
+                if !left.contains_key(key) {
+                    expected.push(DictDiff::Added {
+                        key: *key,
+                        value: *new,
+                    });
+                }
+            }
+            expected.sort_by_key(|entry| *entry.key());
+
+            let left = Dict::<u32, u64>::from_sorted_pairs(left.iter().map(|(k, v)| (*k, *v)))?;
+            let right = Dict::<u32, u64>::from_sorted_pairs(right.iter().map(|(k, v)| (*k, *v)))?;
+
+            let diff = left.diff(&right)?.collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(diff, expected);
+
+            let first = left.diff(&right)?.next().transpose()?;
+            assert_eq!(first, expected.first().copied());
+
+            assert!(left.diff(&left)?.next().is_none());
+
+            Ok(())
+        }
+
+        let mut rng = rand_xorshift::XorShiftRng::from_seed([42; 16]);
+        for key_range in [8, 64, 10000, u32::MAX] {
+            let mut left = BTreeMap::new();
+            for _ in 0..200 {
+                left.insert(rng.gen_range(0..key_range), rng.gen::<u64>());
+            }
+
+            let mut right = left.clone();
+            for _ in 0..20 {
+                let key = rng.gen_range(0..key_range);
+                match rng.gen_range(0..3) {
+                    0 => _ = right.remove(&key),
+                    1 => _ = right.insert(key, rng.gen()),
+                    _ => {
+                        if let Some(value) = right.values_mut().nth(rng.gen_range(0..10)) {
+                            *value += 1;
+                        }
+                    }
+                }
+            }
+
+            check_diff(&left, &right)?;
+            check_diff(&right, &left)?;
+            check_diff(&left, &BTreeMap::new())?;
+            check_diff(&BTreeMap::new(), &right)?;
+        }
+
+        // Only the path to the changed entry must be visited
+        let left = Dict::<u32, u64>::from_sorted_pairs((0..1000).map(|i| (i, i as u64)))?;
+        let mut right = left.clone();
+        right.set(123, 0)?;
+
+        let (_, left_proof) = left.get_with_proof(123)?.unwrap();
+        let (_, right_proof) = right.get_with_proof(123)?.unwrap();
+        let left = Dict::<u32, u64>::from_raw(Some(Cell::virtualize(left_proof.cell)));
+        let right = Dict::<u32, u64>::from_raw(Some(Cell::virtualize(right_proof.cell)));
+
+        let diff = left.diff(&right)?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            diff,
+            [DictDiff::Changed {
+                key: 123,
+                old: 123,
+                new: 0
+            }]
+        );
+
+        let raw_diff = left.raw_diff(&right).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(raw_diff.len(), 1);
+        assert!(matches!(raw_diff[0].1, DiffEntry::Changed { .. }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_depth_and_size() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u32>::new();
+        assert_eq!(dict.depth()?, 0);
+        assert_eq!(dict.estimated_size_in_cells(), 0);
+
+        dict.set(0, 0)?;
+        assert_eq!(dict.depth()?, 0);
+        assert_eq!(dict.estimated_size_in_cells(), 1);
+
+        // Complete binary trie
+        for i in 1..256 {
+            dict.set(i, i)?;
+        }
+        assert_eq!(dict.depth()?, 8);
+        assert_eq!(dict.estimated_size_in_cells(), 2 * 256 - 1);
+
+        // Identical values are counted once
+        let value = CellBuilder::build_from(0xdeadbeefu32)?;
+        let mut dict = Dict::<u32, Cell>::new();
+        for i in 0..100 {
+            dict.set(i * 1000, value.clone())?;
+        }
+        assert_eq!(dict.estimated_size_in_cells(), 2 * 100 - 1 + 1);
+        assert!(dict.depth()? >= 7);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_tuple_keys() -> anyhow::Result<()> {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand_xorshift::XorShiftRng::from_seed([7; 16]);
+
+        // Unsigned components
+        let mut keys = (0..500)
+            .map(|_| (rng.gen_range(0..10u32), rng.gen::<u64>()))
+            .collect::<Vec<_>>();
+        let mut dict = Dict::<(u32, u64), u32>::new();
+        for (i, key) in keys.iter().enumerate() {
+            dict.set(key, i as u32)?;
+        }
+        assert_eq!(<(u32, u64)>::BITS, 96);
+
+        keys.sort();
+        keys.dedup();
+        let dict_keys = dict.keys().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(dict_keys, keys);
+        for (i, key) in keys.iter().enumerate() {
+            assert!(dict.contains_key(key)?);
+            assert_eq!(
+                dict.get_next(key, false)?.map(|(k, _)| k),
+                keys.get(i + 1).copied()
+            );
+        }
+
+        // Out message queue shape
+        let mut keys = (0..500)
+            .map(|_| (rng.gen_range(-3..3i32), rng.gen::<u64>()))
+            .collect::<Vec<_>>();
+        let mut dict = Dict::<(i32, u64), u32>::new();
+        for key in &keys {
+            dict.set(key, 0)?;
+        }
+
+        keys.sort();
+        keys.dedup();
+        let dict_keys = dict.keys().signed().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(dict_keys, keys);
+
+        // Three components with unaligned bits
+        let mut keys = (0..500)
+            .map(|_| (rng.gen::<bool>(), rng.gen_range(0..4u8), rng.gen::<u16>()))
+            .collect::<Vec<_>>();
+        let mut dict = Dict::<(bool, u8, u16), u32>::new();
+        for key in &keys {
+            dict.set(key, 0)?;
+        }
+        assert_eq!(<(bool, u8, u16)>::BITS, 25);
+
+        keys.sort();
+        keys.dedup();
+        let dict_keys = dict.keys().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(dict_keys, keys);
+
+        // Transaction index shape
+        let mut keys = (0..500)
+            .map(|_| (rng.gen_range(0..10u32), rng.gen::<HashBytes>()))
+            .collect::<Vec<_>>();
+        let mut dict = Dict::<(u32, HashBytes), u32>::new();
+        for (i, key) in keys.iter().enumerate() {
+            dict.set(key, i as u32)?;
+        }
+        assert_eq!(<(u32, HashBytes)>::BITS, 32 + 256);
+
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(dict.get(key)?, Some(i as u32));
+        }
+        keys.sort();
+        let dict_keys = dict.keys().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(dict_keys, keys);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_split_at() -> anyhow::Result<()> {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand_xorshift::XorShiftRng::from_seed([4u8; 16]);
+
+        let mut dict = Dict::<u32, u32>::new();
+        for _ in 0..1000 {
+            dict.set(rng.gen::<u32>() % 10000, rng.gen::<u32>())?;
+        }
+        let entries = dict.iter().collect::<Result<Vec<_>, _>>()?;
+
+        let mut keys = vec![0, 1, 5000, 9999, 10000, u32::MAX];
+        keys.extend(entries.iter().step_by(100).map(|(key, _)| *key));
+
+        for key in keys {
+            for inclusive in [false, true] {
+                let (left, right) = if inclusive {
+                    dict.split_at_key_inclusive(key)?
+                } else {
+                    dict.split_at(key)?
+                };
+
+                let is_left = |k: u32| if inclusive { k <= key } else { k < key };
+                let expected_left = entries.iter().filter(|(k, _)| is_left(*k));
+                let expected_right = entries.iter().filter(|(k, _)| !is_left(*k));
+
+                assert!(left.iter().map(Result::unwrap).eq(expected_left.copied()));
+                assert!(right.iter().map(Result::unwrap).eq(expected_right.copied()));
+                left.validate()?;
+                right.validate()?;
+
+                assert_eq!(left.union_take_left(&right)?, dict);
+            }
+        }
+
+        let (left, right) = Dict::<u32, u32>::new().split_at(123)?;
+        assert!(left.is_empty() && right.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_get_range() -> anyhow::Result<()> {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand_xorshift::XorShiftRng::from_seed([5u8; 16]);
+
+        let mut dict = Dict::<u32, u32>::new();
+        for _ in 0..1000 {
+            dict.set(rng.gen::<u32>() % 10000, rng.gen::<u32>())?;
+        }
+        let entries = dict.iter().collect::<Result<Vec<_>, _>>()?;
 
-    /// Changes the direction of the iterator to descending.
-    #[inline]
-    pub fn reversed(mut self) -> Self {
-        self.inner = self.inner.reversed();
-        self
-    }
+        let check = |range: (std::ops::Bound<u32>, std::ops::Bound<u32>)| -> anyhow::Result<()> {
+            use std::ops::RangeBounds;
 
-    /// Changes the behavior of the iterator to reverse the high bit.
-    #[inline]
-    pub fn signed(mut self) -> Self {
-        self.inner = self.inner.signed();
-        self
-    }
-}
+            let sub = dict.get_range(range)?;
+            let expected = entries.iter().filter(|(k, _)| range.contains(k));
+            assert!(sub.iter().map(Result::unwrap).eq(expected.copied()));
+            sub.validate()?;
+            Ok(())
+        };
 
-impl<'a, K> Iterator for Keys<'a, K>
-where
-    K: DictKey,
-{
-    type Item = Result<K, Error>;
+        let keys = [0, 1, 5000, 9999, 10000, u32::MAX]
+            .into_iter()
+            .chain(entries.iter().step_by(150).map(|(key, _)| *key))
+            .collect::<Vec<_>>();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        Some(match self.inner.next()? {
-            Ok((key, _)) => match K::from_raw_data(key.raw_data()) {
-                Some(key) => Ok(key),
-                None => Err(self.inner.finish(Error::CellUnderflow)),
-            },
-            Err(e) => Err(e),
-        })
+        for &start in &keys {
+            for &end in &keys {
+                use std::ops::Bound::*;
+                for range in [
+                    (Included(start), Included(end)),
+                    (Included(start), Excluded(end)),
+                    (Excluded(start), Included(end)),
+                    (Included(start), Unbounded),
+                    (Unbounded, Excluded(end)),
+                ] {
+                    check(range)?;
+                }
+            }
+        }
+
+        let full = dict.get_range(..)?;
+        assert!(std::ptr::addr_eq(
+            full.root().as_ref().unwrap().as_ref() as *const DynCell,
+            dict.root().as_ref().unwrap().as_ref() as *const DynCell,
+        ));
+        assert!(dict.get_range(10000..)?.is_empty());
+
+        Ok(())
     }
-}
 
-/// An iterator over the values of a [`Dict`].
-///
-/// This struct is created by the [`values`] method on [`Dict`]. See its documentation for more.
-///
-/// [`values`]: Dict::values
-pub struct Values<'a, V> {
-    inner: RawValues<'a>,
-    _value: PhantomData<V>,
-}
+    #[test]
+    fn dict_values_in_ref() -> anyhow::Result<()> {
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Store, Load)]
+        struct Value {
+            a: u64,
+            b: u32,
+        }
 
-impl<V> Clone for Values<'_, V> {
-    fn clone(&self) -> Self {
-        Self {
-            inner: self.inner.clone(),
-            _value: PhantomData,
+        // Build a raw dictionary with values in references
+        let mut raw = RawDict::<32>::new();
+        let mut typed = Dict::<u32, InRef<Value>>::new();
+        for i in 0..100u32 {
+            let value = Value {
+                a: i as u64 * 1000,
+                b: i,
+            };
+
+            let mut key = CellBuilder::new();
+            key.store_u32(i)?;
+            let mut leaf = CellBuilder::new();
+            leaf.store_reference(CellBuilder::build_from(value)?)?;
+            raw.set(key.as_data_slice(), leaf.as_full_slice())?;
+
+            typed.set(i, InRef(value))?;
+        }
+        assert_eq!(typed.root(), raw.root());
+
+        // Load values from the raw dictionary
+        let parsed = Dict::<u32, InRef<Value>>::from_raw(raw.root().clone());
+        for entry in parsed.iter() {
+            let (key, value) = entry?;
+            assert_eq!(value.a, key as u64 * 1000);
+            assert_eq!(value.into_inner().b, key);
         }
+        assert_eq!(
+            parsed.get(10)?.map(InRef::into_inner).map(|v| v.b),
+            Some(10)
+        );
+
+        // Reuse the value cell
+        let cell = parsed.get_value_cell(10)?.unwrap();
+        let mut other = Dict::<u32, InRef<Value>>::new();
+        other.set_value_cell(123, cell.clone())?;
+        let reused = other.get_value_cell(123)?.unwrap();
+        assert!(std::ptr::addr_eq(
+            reused.as_ref() as *const DynCell,
+            cell.as_ref() as *const DynCell,
+        ));
+        assert_eq!(other.get(123)?, parsed.get(10)?);
+        assert!(other.get_value_cell(10)?.is_none());
+
+        Ok(())
     }
-}
 
-impl<'a, V> Values<'a, V> {
-    /// Creates an iterator over the values of a dictionary.
-    pub fn new(root: &'a Option<Cell>, bit_len: u16) -> Self {
-        Self {
-            inner: RawValues::new(root, bit_len),
-            _value: PhantomData,
+    #[test]
+    fn dict_from_sorted_pairs() -> anyhow::Result<()> {
+        let pairs = (0..1000u32).map(|i| (i * 3, i)).collect::<Vec<_>>();
+
+        let dict = Dict::<u32, u32>::from_sorted_pairs(pairs.iter().copied())?;
+        let mut expected = Dict::<u32, u32>::new();
+        for (key, value) in &pairs {
+            expected.set(key, value)?;
         }
-    }
+        assert_eq!(dict, expected);
+        assert_eq!(dict.validate()?.entry_count, pairs.len());
 
-    /// Changes the direction of the iterator to descending.
-    #[inline]
-    pub fn reversed(mut self) -> Self {
-        self.inner = self.inner.reversed();
-        self
-    }
+        assert!(Dict::<u32, u32>::from_sorted_pairs([])?.is_empty());
 
-    /// Changes the behavior of the iterator to reverse the high bit.
-    #[inline]
-    pub fn signed(mut self) -> Self {
-        self.inner = self.inner.signed();
-        self
+        // Unsorted and duplicate keys are rejected
+        assert!(matches!(
+            Dict::<u32, u32>::from_sorted_pairs([(2, 0), (1, 0)]),
+            Err(Error::InvalidData)
+        ));
+        assert!(matches!(
+            Dict::<u32, u32>::from_sorted_pairs([(1, 0), (1, 0)]),
+            Err(Error::InvalidData)
+        ));
+
+        Ok(())
     }
-}
 
-impl<'a, V> Iterator for Values<'a, V>
-where
-    V: Load<'a>,
-{
-    type Item = Result<V, Error>;
+    #[test]
+    fn dict_extend() -> anyhow::Result<()> {
+        use rand::{Rng, SeedableRng};
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.inner.next()? {
-            Ok(mut value) => match V::load_from(&mut value) {
-                Ok(value) => Some(Ok(value)),
-                Err(e) => Some(Err(self.inner.finish(e))),
-            },
-            Err(e) => Some(Err(e)),
+        let mut rng = rand_xorshift::XorShiftRng::from_seed([1u8; 16]);
+
+        let mut dict = Dict::<u32, u32>::new();
+        for _ in 0..1000 {
+            dict.set(rng.gen::<u32>() % 10000, rng.gen::<u32>())?;
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use anyhow::Context;
+        for batch_size in [0, 1, 2, 10, 100, 1000] {
+            let batch = (0..batch_size)
+                .map(|_| (rng.gen::<u32>() % 20000, rng.gen::<u32>()))
+                .collect::<Vec<_>>();
 
-    use super::*;
-    use crate::prelude::*;
+            let mut expected = dict.clone();
+            for (key, value) in &batch {
+                expected.set(key, value)?;
+            }
+
+            dict.extend(batch)?;
+            assert_eq!(dict, expected);
+            dict.validate()?;
+        }
+
+        // Later duplicates win
+        let mut dict = Dict::<u32, u32>::new();
+        dict.extend([(1, 1), (2, 2), (1, 10), (3, 3), (2, 20)])?;
+        assert_eq!(dict.get(1)?, Some(10));
+        assert_eq!(dict.get(2)?, Some(20));
+        assert_eq!(dict.get(3)?, Some(3));
+
+        Ok(())
+    }
 
     #[test]
-    fn dict_set() {
-        let mut dict = Dict::<u32, u16>::new();
-        assert!(dict.set(123, 0xffff).unwrap());
-        assert_eq!(dict.get(123).unwrap(), Some(0xffff));
+    fn dict_validate() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u32>::new();
+        for i in 0..10 {
+            dict.set(i, i)?;
+        }
+        assert_eq!(dict.validate()?.entry_count, 10);
 
-        assert!(dict.set(123, 0xcafe).unwrap());
-        assert_eq!(dict.get(123).unwrap(), Some(0xcafe));
+        // Leaf with a full label but a 16-bit value
+        let mut builder = CellBuilder::new();
+        builder.store_small_uint(0b10, 2)?;
+        builder.store_small_uint(32, 6)?;
+        builder.store_u32(123)?;
+        builder.store_u16(0xffff)?;
+        let root = builder.build()?;
+
+        let dict = Dict::<u32, u16>::from_raw(Some(root.clone()));
+        assert_eq!(dict.validate()?.entry_count, 1);
+
+        let dict = Dict::<u32, u32>::from_raw(Some(root));
+        assert_eq!(dict.validate().unwrap_err(), Error::CellUnderflow);
+
+        Ok(())
     }
 
     #[test]
-    #[cfg_attr(miri, ignore)] // takes too long to execute on miri
-    fn dict_set_complex() {
-        let mut dict = Dict::<u32, bool>::new();
-        for i in 0..520 {
-            assert!(dict.set(i, true).unwrap());
+    fn dict_verify_consistency() -> anyhow::Result<()> {
+        let mut dict = Dict::<u8, u8>::new();
+        dict.verify_consistency()?;
+        for i in 0..100 {
+            dict.set(i * 2, i)?;
+        }
+        dict.verify_consistency()?;
+        assert!(dict.has_valid_structure());
+
+        let mut single = Dict::<u8, u8>::new();
+        single.set(5, 1)?;
+        let leaf = single.root().clone().unwrap();
+        let check = |root: Cell, expected: DictError| {
+            let dict = Dict::<u8, u8>::from_raw(Some(root));
+            assert_eq!(dict.verify_consistency(), Err(expected));
+            assert!(!dict.has_valid_structure());
+        };
+
+        // Label is longer than the key
+        let mut builder = CellBuilder::new();
+        builder.store_small_uint(0b10, 2)?;
+        builder.store_small_uint(9, 4)?;
+        builder.store_u16(0)?;
+        check(builder.build()?, DictError::InvalidLabel);
+
+        // Fork with a single branch
+        let mut builder = CellBuilder::new();
+        builder.store_zeros(2)?;
+        builder.store_reference(leaf.clone())?;
+        check(builder.build()?, DictError::EmptyBranch);
+
+        // Fork with extra data
+        let mut builder = CellBuilder::new();
+        builder.store_zeros(2)?;
+        builder.store_reference(leaf.clone())?;
+        builder.store_reference(leaf.clone())?;
+        builder.store_bit_one()?;
+        check(builder.build()?, DictError::InvalidFork);
+
+        // Cell with an invalid hash
+        #[cfg(feature = "sync")]
+        {
+            let data: &'static [u8] = Box::leak(leaf.data().to_vec().into_boxed_slice());
+            // SAFETY: hash is intentionally invalid, but the cell is only used for hashing
+            let cell = unsafe { StaticCell::new(data, leaf.bit_len(), &[0; 32]) };
+            let root = Cell::from(std::sync::Arc::new(cell) as std::sync::Arc<DynCell>);
+
+            let dict = Dict::<u8, u8>::from_raw(Some(root));
+            assert!(dict.has_valid_structure());
+            assert_eq!(dict.verify_consistency(), Err(DictError::HashMismatch));
         }
+
+        Ok(())
     }
 
     #[test]
@@ -1345,6 +3900,31 @@ mod tests {
         assert_eq!(signed_range_iter.next(), None);
     }
 
+    #[test]
+    fn dict_trie_iter() -> anyhow::Result<()> {
+        let dict = Dict::<u8, u32>::from_sorted_pairs((0..10u8).map(|i| (i, i as u32)))?;
+
+        let mut count = 0;
+        for (entry, i) in dict.trie_iter().zip(0..) {
+            let (path, key, value) = entry?;
+            let expected_path = (0..8).map(|bit| i & (0x80 >> bit) != 0).collect::<Vec<_>>();
+            assert_eq!(path, expected_path);
+            assert_eq!(key, i);
+            assert_eq!(value, i as u32);
+            count += 1;
+        }
+        assert_eq!(count, 10);
+
+        // Invalid values stop the iterator
+        let dict = Dict::<u8, ()>::from_sorted_pairs((0..10u8).map(|i| (i, ())))?;
+        let dict = Dict::<u8, u32>::from_raw(dict.into_root());
+        let mut iter = dict.trie_iter();
+        assert!(matches!(iter.next(), Some(Err(Error::CellUnderflow))));
+        assert!(iter.next().is_none());
+
+        Ok(())
+    }
+
     #[test]
     fn dict_next_prev_unsigned() {
         let mut dict = Dict::<u32, u32>::new();
@@ -1384,6 +3964,379 @@ mod tests {
         assert_eq!(dict.get_or_prev(100, false).unwrap(), Some((30, 30)));
     }
 
+    #[test]
+    fn dict_mutations_with_context() -> anyhow::Result<()> {
+        #[derive(Default)]
+        struct CountingContext {
+            finalized: usize,
+            loaded: usize,
+        }
+
+        impl CellContext for CountingContext {
+            fn finalize_cell(&mut self, cell: CellParts<'_>) -> Result<Cell, Error> {
+                self.finalized += 1;
+                Cell::empty_context().finalize_cell(cell)
+            }
+
+            fn load_cell(&mut self, cell: Cell, _: LoadMode) -> Result<Cell, Error> {
+                self.loaded += 1;
+                Ok(cell)
+            }
+
+            fn load_dyn_cell<'a>(
+                &mut self,
+                cell: &'a DynCell,
+                _: LoadMode,
+            ) -> Result<&'a DynCell, Error> {
+                self.loaded += 1;
+                Ok(cell)
+            }
+        }
+
+        let dict = Dict::<u32, u32>::from_sorted_pairs((0..10u32).map(|i| (i, i)))?;
+
+        // Update
+        let mut expected = dict.clone();
+        expected.update_with(5, |value| value.map(|v| v + 1))?;
+
+        let mut updated = dict.clone();
+        let context = &mut CountingContext::default();
+        assert!(updated.update_with_ext(5, |value| value.map(|v| v + 1), context)?);
+        assert_eq!(updated, expected);
+        assert!(context.loaded > 0);
+        assert!(context.finalized > 0);
+
+        // Update with removal
+        let mut updated = dict.clone();
+        let context = &mut CountingContext::default();
+        assert!(updated.update_with_ext(5, |_| None, context)?);
+        assert_eq!(updated.get(5)?, None);
+        assert!(context.finalized > 0);
+
+        // Noop update
+        let mut updated = dict.clone();
+        let context = &mut CountingContext::default();
+        assert!(!updated.update_with_ext(100, |_| None, context)?);
+        assert_eq!(updated, dict);
+        assert_eq!(context.finalized, 0);
+
+        // Remove
+        let mut removed = dict.clone();
+        let context = &mut CountingContext::default();
+        assert_eq!(removed.remove_ext(3, context)?, Some(3));
+        assert_eq!(removed.len()?, 9);
+        assert!(context.finalized > 0);
+
+        // Add and set
+        let mut added = dict.clone();
+        let context = &mut CountingContext::default();
+        assert!(!added.add_ext(3, 100, context)?);
+        assert_eq!(context.finalized, 0);
+        assert!(added.add_ext(10, 10, context)?);
+        assert!(added.set_ext(3, 100, context)?);
+        assert_eq!(added.get(3)?, Some(100));
+        assert!(context.finalized > 0);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn dict_from_par_iter() -> anyhow::Result<()> {
+        let pairs = (0..10000u32)
+            .map(|i| (i.wrapping_mul(2654435761), i as u64))
+            .collect::<Vec<_>>();
+
+        let mut expected = Dict::<u32, u64>::new();
+        for (key, value) in &pairs {
+            expected.set(key, value)?;
+        }
+
+        let dict = Dict::<u32, u64>::from_par_iter(pairs.clone())?;
+        assert_eq!(dict, expected);
+        assert_eq!(
+            dict.root().as_ref().map(|c| *c.repr_hash()),
+            expected.root().as_ref().map(|c| *c.repr_hash())
+        );
+
+        // Duplicates keep the last value
+        let dups = vec![(1u32, 1u64), (2, 2), (1, 3), (1, 4)];
+        let dict = Dict::<u32, u64>::from_par_iter(dups)?;
+        assert_eq!(dict.get(1)?, Some(4));
+        assert_eq!(dict.get(2)?, Some(2));
+
+        assert!(Dict::<u32, u64>::from_par_iter(Vec::new())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn dict_map_values() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u64>::new();
+        for i in 0..1000u32 {
+            dict.set(i, if i < 500 { 10 } else { i as u64 * 100 })?;
+        }
+
+        // Values are divided only for the upper half of keys
+        let mapped = dict.map_values(|_, value| Ok(value / 10))?;
+        for entry in mapped.iter() {
+            let (key, value) = entry?;
+            assert_eq!(value, if key < 500 { 1 } else { key as u64 * 10 });
+        }
+        assert_eq!(mapped.len()?, 1000);
+
+        // Same as a dictionary built from the mapped entries
+        let mut expected = Dict::<u32, u64>::new();
+        for entry in dict.iter() {
+            let (key, value) = entry?;
+            expected.set(key, value / 10)?;
+        }
+        assert_eq!(mapped, expected);
+
+        // Stateful closures are allowed
+        let mut calls = 0;
+        let counted = dict.map_values(|_, value| {
+            calls += 1;
+            Ok(value)
+        })?;
+        assert_eq!(calls, 1000);
+        assert_eq!(counted, dict);
+
+        fn same_cell(a: &Cell, b: &Cell) -> bool {
+            std::ptr::addr_eq(a.as_ref() as *const DynCell, b.as_ref() as *const DynCell)
+        }
+
+        // Unchanged values keep the original cells
+        let same = dict.map_values(|_, value| Ok(value))?;
+        assert!(same_cell(
+            same.root().as_ref().unwrap(),
+            dict.root().as_ref().unwrap()
+        ));
+
+        let partial = dict.map_values(|key, value| Ok(if *key == 999 { 0 } else { value }))?;
+        assert_eq!(partial.get(999)?, Some(0));
+        assert_ne!(partial, dict);
+        let left = |dict: &Dict<u32, u64>| {
+            let root = dict.root().as_ref().unwrap();
+            root.reference_cloned(0).unwrap()
+        };
+        assert!(same_cell(&left(&partial), &left(&dict)));
+
+        // Values can change their type
+        let flags = dict.map_values(|key, _| Ok(*key % 2 == 0))?;
+        assert_eq!(flags.get(10)?, Some(true));
+        assert_eq!(flags.get(11)?, Some(false));
+
+        // Errors are propagated
+        assert_eq!(
+            dict.map_values(|_, _| Err::<u64, _>(Error::Cancelled))
+                .unwrap_err(),
+            Error::Cancelled
+        );
+        assert!(Dict::<u32, u64>::new().map_values(|_, v| Ok(v))?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_get_subdict() -> anyhow::Result<()> {
+        let mut outer = Dict::<u32, Dict<u16, u64>>::new();
+        for i in 0..4u32 {
+            let mut inner = Dict::<u16, u64>::new();
+            for j in 0..i as u16 {
+                inner.set(j, (i as u64) << 16 | j as u64)?;
+            }
+            outer.set(i, inner)?;
+        }
+
+        for i in 0..4u32 {
+            let inner = outer.get_subdict::<_, u16, u64>(i)?.unwrap();
+            assert_eq!(inner.len()?, i as u64);
+            for j in 0..i as u16 {
+                assert_eq!(inner.get(j)?, Some((i as u64) << 16 | j as u64));
+            }
+        }
+
+        // Absent outer key
+        assert!(outer.get_subdict::<_, u16, u64>(10)?.is_none());
+
+        // Key length mismatch
+        assert_eq!(
+            outer.get_subdict::<_, u8, u64>(3).unwrap_err(),
+            Error::InvalidData
+        );
+        assert_eq!(
+            outer.get_subdict::<_, u32, u64>(2).unwrap_err(),
+            Error::InvalidData
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_nearest_key() -> anyhow::Result<()> {
+        use rand::{Rng, SeedableRng};
+
+        let mut dict = Dict::<u32, ()>::new();
+        assert_eq!(dict.nearest_key(10)?, None);
+
+        for key in [10, 20, 40] {
+            dict.set(key, ())?;
+        }
+        for (key, expected) in [
+            (0, 10),
+            (10, 10),
+            (15, 10),
+            (16, 20),
+            (20, 20),
+            (30, 20),
+            (31, 40),
+            (100, 40),
+            (u32::MAX, 40),
+        ] {
+            assert_eq!(dict.nearest_key(key)?, Some(expected), "key: {key}");
+        }
+
+        // Compare with the brute force search
+        let mut rng = rand_xorshift::XorShiftRng::from_seed([3u8; 16]);
+        let keys = (0..100).map(|_| rng.gen::<u16>()).collect::<Vec<_>>();
+        let mut dict = Dict::<u16, ()>::new();
+        for key in &keys {
+            dict.set(key, ())?;
+        }
+        for _ in 0..1000 {
+            let key = rng.gen::<u16>();
+            let expected = keys
+                .iter()
+                .copied()
+                .min_by_key(|&k| ((k as i32 - key as i32).abs(), k))
+                .unwrap();
+            assert_eq!(dict.nearest_key(key)?, Some(expected), "key: {key}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_std_iter_traits() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u32>::new();
+        dict.extend((0..100u32).rev().map(|i| (i, i * 2)))?;
+        assert_eq!(dict.len()?, 100);
+        assert_eq!(dict.get(10)?, Some(20));
+
+        let mut count = 0;
+        for entry in &dict {
+            let (key, value) = entry?;
+            assert_eq!(value, key * 2);
+            count += 1;
+        }
+        assert_eq!(count, 100);
+
+        let owned = dict.clone().into_iter().collect::<Result<Vec<_>, _>>()?;
+        let borrowed = dict.iter().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(owned, borrowed);
+
+        let owned_rev = dict.clone().into_iter().reversed();
+        let borrowed_rev = dict.iter().reversed();
+        for (owned, borrowed) in owned_rev.zip(borrowed_rev) {
+            assert_eq!(owned?, borrowed?);
+        }
+
+        assert_eq!(Dict::<u32, u32>::new().into_iter().count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_fold_for_each_find() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u64>::new();
+        assert_eq!(dict.fold(0, |acc, _, value| acc + value)?, 0);
+        assert_eq!(dict.find(|_, _| true)?, None);
+
+        for i in 0..100 {
+            dict.set(i, i as u64 * 2)?;
+        }
+
+        assert_eq!(dict.fold(0, |acc, _, value| acc + value)?, 9900);
+        let keys = dict.fold(Vec::new(), |mut keys, key, _| {
+            keys.push(key);
+            keys
+        })?;
+        assert_eq!(keys, (0..100).collect::<Vec<_>>());
+
+        let mut total = 0;
+        dict.for_each(|key, value| {
+            assert_eq!(value, key as u64 * 2);
+            total += 1;
+            Ok(())
+        })?;
+        assert_eq!(total, 100);
+
+        let mut visited = 0;
+        let res = dict.for_each(|key, _| {
+            visited += 1;
+            if key == 10 {
+                return Err(Error::Cancelled);
+            }
+            Ok(())
+        });
+        assert_eq!(res, Err(Error::Cancelled));
+        assert_eq!(visited, 11);
+
+        assert_eq!(dict.find(|_, value| *value > 50)?, Some((26, 52)));
+        assert_eq!(dict.find(|key, _| *key > 100)?, None);
+
+        // Invalid dict
+        let invalid = Dict::<u32, HashBytes>::from_raw(dict.root().clone());
+        assert!(invalid.fold((), |_, _, _| ()).is_err());
+        assert!(invalid.for_each(|_, _| Ok(())).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dict_next_prev_key() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u32>::new();
+        assert_eq!(dict.next_key(0)?, None);
+        assert_eq!(dict.prev_key(0)?, None);
+
+        for i in 0..100 {
+            dict.set(i * 3, i)?;
+        }
+
+        assert_eq!(dict.next_key(0)?, Some(3));
+        assert_eq!(dict.next_key(4)?, Some(6));
+        assert_eq!(dict.next_key(297)?, None);
+        assert_eq!(dict.prev_key(0)?, None);
+        assert_eq!(dict.prev_key(4)?, Some(3));
+        assert_eq!(dict.prev_key(u32::MAX)?, Some(297));
+
+        // Paginate in both directions
+        let keys = dict.keys().collect::<Result<Vec<_>, _>>()?;
+        let mut forward = vec![0];
+        while let Some(key) = dict.next_key(forward.last().unwrap())? {
+            forward.push(key);
+        }
+        assert_eq!(forward, keys);
+
+        let mut backward = vec![297];
+        while let Some(key) = dict.prev_key(backward.last().unwrap())? {
+            backward.push(key);
+        }
+        backward.reverse();
+        assert_eq!(backward, keys);
+
+        // Raw variants
+        let raw = RawDict::<32>::from(dict.root().clone());
+        let mut key = CellBuilder::new();
+        key.store_u32(150)?;
+        let next = raw.raw_next_key(key.as_data_slice())?.unwrap();
+        assert_eq!(next.as_data_slice().load_u32()?, 153);
+        let prev = raw.raw_prev_key(key.as_data_slice())?.unwrap();
+        assert_eq!(prev.as_data_slice().load_u32()?, 147);
+
+        Ok(())
+    }
+
     #[test]
     fn dict_next_prev_signed() {
         let mut dict = Dict::<i32, i32>::new();
@@ -18,9 +18,9 @@ pub enum Error {
     /// Data does not satisfy some constraints.
     #[error("invalid data")]
     InvalidData,
-    /// Unknown TLB tag.
-    #[error("invalid tag")]
-    InvalidTag,
+    /// Unknown TLB tag. Contains the tag value that was found.
+    #[error("invalid tag {0:#x}")]
+    InvalidTag(u64),
     /// Merkle proof does not contain the root cell.
     #[error("empty proof")]
     EmptyProof,
@@ -42,6 +42,61 @@ pub enum Error {
     /// Presented structure is unbalanced.
     #[error("unbalanced structure")]
     Unbalanced,
+    /// Prefix dictionary key is a prefix of another key.
+    #[error("key is a prefix of another key")]
+    PrefixConflict,
+}
+
+/// Dictionary structure violation.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum DictError {
+    /// Failed to read a dictionary cell.
+    #[error(transparent)]
+    Cell(#[from] Error),
+    /// Edge label is malformed or longer than the remaining key.
+    #[error("invalid edge label")]
+    InvalidLabel,
+    /// Fork is missing one of its branches.
+    #[error("unexpected empty branch")]
+    EmptyBranch,
+    /// Fork contains extra data or references.
+    #[error("invalid fork")]
+    InvalidFork,
+    /// Stored cell hash differs from the computed one.
+    #[error("cell hash mismatch")]
+    HashMismatch,
+}
+
+/// Output actions validation error.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum ActionError {
+    /// Failed to read an action list cell.
+    #[error(transparent)]
+    Cell(#[from] Error),
+    /// Action list contains more than 255 actions.
+    #[error("too many actions")]
+    TooManyActions,
+    /// Action list node was visited twice.
+    #[error("action list contains a cycle")]
+    Cycle,
+    /// Action list node has an unexpected structure.
+    #[error("invalid action list node")]
+    InvalidListNode,
+    /// Action cell contains unknown tag, invalid data or extra data.
+    #[error("invalid action")]
+    InvalidAction,
+    /// Referenced cell is not a valid outbound message.
+    #[error("invalid outbound message")]
+    InvalidMessage,
+    /// Unknown or conflicting send message mode flags.
+    #[error("invalid send message mode")]
+    InvalidSendMode,
+    /// Unknown reserve currency mode flags.
+    #[error("invalid reserve mode")]
+    InvalidReserveMode,
+    /// Currency value is out of range or contains zero extra currencies.
+    #[error("non-canonical currency value")]
+    NonCanonicalValue,
 }
 
 /// Error type for integer parsing related errors.
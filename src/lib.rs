@@ -79,6 +79,28 @@
 //! access a subtotal of values for each subtree.
 //! NOTE: this type is partially implemented due to its complexity.
 //!
+//! ## Tracing
+//!
+//! With the `tracing` feature enabled, some expensive operations are wrapped
+//! into `DEBUG` level spans with the `everscale_types` target:
+//!
+//! | Span name              | Operation                          | Fields                        |
+//! |------------------------|------------------------------------|-------------------------------|
+//! | `boc_decode`           | [`Boc::decode`] and others         | `bytes`, `cells`, `bits`      |
+//! | `boc_encode`           | [`Boc::encode`] and others         | `bytes`, `cells`, `bits`      |
+//! | `merkle_update_apply`  | [`MerkleUpdate::apply_ext`]        | `cells`, `bits`               |
+//! | `merkle_proof_build`   | [`MerkleProofBuilder::build_ext`]  | `cells`, `bits`               |
+//! | `dict_set`             | [`Dict::set`], [`Dict::set_ext`]   | `key_bits`, `cells`, `bits`   |
+//! | `dict_remove`          | [`Dict::remove`] and others        | `key_bits`, `cells`, `bits`   |
+//!
+//! - `bytes` is the BOC size;
+//! - `cells` and `bits` are the number of (unique) cells which were created
+//!   or serialized, and their total data length in bits;
+//! - `key_bits` is the dictionary key length in bits.
+//!
+//! Span names and fields are a part of the public API. Without the feature
+//! no instrumentation code is compiled.
+//!
 //! ## Supported Rust Versions
 //!
 //! This crate is built against the latest stable release. The minimum supported
@@ -104,6 +126,13 @@
 //! [`Dict`]: dict::Dict
 //! [`DictKey`]: dict::DictKey
 //! [`AugDict`]: dict::AugDict
+//! [`Boc::decode`]: boc::Boc::decode
+//! [`Boc::encode`]: boc::Boc::encode
+//! [`MerkleUpdate::apply_ext`]: merkle::MerkleUpdate::apply_ext
+//! [`MerkleProofBuilder::build_ext`]: merkle::MerkleProofBuilder::build_ext
+//! [`Dict::set`]: dict::Dict::set
+//! [`Dict::set_ext`]: dict::Dict::set_ext
+//! [`Dict::remove`]: dict::Dict::remove
 //! [`Load`]: cell::Load
 //! [`Store`]: cell::Store
 /// Prevents using `From::from` for plain error conversion.
@@ -116,6 +145,17 @@ macro_rules! ok {
     };
 }
 
+/// Creates an entered `tracing` span with the specified name and fields.
+///
+/// All spans use the `everscale_types` target. Fields which are unknown
+/// at the span creation can be recorded later using `util::record_cells`.
+#[cfg(feature = "tracing")]
+macro_rules! debug_span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        tracing::debug_span!(target: "everscale_types", $name $(, $field = $value)*).entered()
+    };
+}
+
 #[allow(unused)]
 macro_rules! assert_impl_all {
     ($type:ty: $($trait:path),+ $(,)?) => {
@@ -268,4 +308,165 @@ mod tests {
             }
         );
     }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_spans() -> anyhow::Result<()> {
+        use std::collections::HashMap;
+        use std::sync::{Arc, Mutex};
+
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+
+        use crate::merkle::{MerkleProof, MerkleUpdate};
+
+        type Spans = Vec<(&'static str, HashMap<&'static str, u64>)>;
+
+        #[derive(Default, Clone)]
+        struct Collector(Arc<Mutex<Spans>>);
+
+        impl Collector {
+            fn take(&self, name: &str) -> Vec<HashMap<&'static str, u64>> {
+                let mut spans = self.0.lock().unwrap();
+                let result = spans
+                    .iter()
+                    .filter(|(span_name, _)| *span_name == name)
+                    .map(|(_, fields)| fields.clone())
+                    .collect();
+                spans.clear();
+                result
+            }
+        }
+
+        struct Visitor<'a>(&'a mut HashMap<&'static str, u64>);
+
+        impl Visit for Visitor<'_> {
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                self.0.insert(field.name(), value);
+            }
+
+            fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+        }
+
+        impl tracing::Subscriber for Collector {
+            fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+                metadata.target() == "everscale_types"
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut fields = HashMap::new();
+                span.record(&mut Visitor(&mut fields));
+
+                let mut spans = self.0.lock().unwrap();
+                spans.push((span.metadata().name(), fields));
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, span: &Id, values: &Record<'_>) {
+                let mut spans = self.0.lock().unwrap();
+                let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+                values.record(&mut Visitor(fields));
+            }
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, _: &tracing::Event<'_>) {}
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        fn new_cells(root: &DynCell, old: &DynCell) -> HashMap<&'static str, u64> {
+            let mut old_cells = ahash::HashSet::default();
+            let mut stack = vec![old];
+            while let Some(cell) = stack.pop() {
+                if old_cells.insert(*cell.repr_hash()) {
+                    stack.extend(cell.references());
+                }
+            }
+
+            let (mut cells, mut bits) = (0, 0);
+            let mut stack = vec![root];
+            while let Some(cell) = stack.pop() {
+                if !cell.is_exotic() && old_cells.insert(*cell.repr_hash()) {
+                    cells += 1;
+                    bits += cell.bit_len() as u64;
+                    stack.extend(cell.references());
+                }
+            }
+            HashMap::from([("cells", cells), ("bits", bits)])
+        }
+
+        let collector = Collector::default();
+        let _guard = tracing::subscriber::set_default(collector.clone());
+
+        // BOC
+        let data = decode_base64("te6ccgIDAAwAAQAAAACIAAAEBAABAAEAAQABAAEEBAACAAIAAgACAAIEBAADAAMAAwADAAMEBAAEAAQABAAEAAQEBAAFAAUABQAFAAUEBAAGAAYABgAGAAYEBAAHAAcABwAHAAcEBAAIAAgACAAIAAgEBAAJAAkACQAJAAkEBAAKAAoACgAKAAoEBAALAAsACwALAAsABAAA")?;
+        let cell = Boc::decode(&data)?;
+        assert_eq!(
+            collector.take("boc_decode"),
+            [HashMap::from([
+                ("bytes", data.len() as u64),
+                ("cells", 12),
+                ("bits", 192)
+            ])]
+        );
+
+        let encoded = Boc::encode(&cell);
+        assert_eq!(
+            collector.take("boc_encode"),
+            [HashMap::from([
+                ("bytes", encoded.len() as u64),
+                ("cells", 12),
+                ("bits", 192)
+            ])]
+        );
+
+        // Dict
+        let mut dict = Dict::<u32, u32>::new();
+        for i in 0..10 {
+            dict.set(i, i * 10)?;
+        }
+        let old = dict.root().clone().unwrap();
+        collector.take("");
+
+        dict.set(5, 1)?;
+        let new = dict.root().clone().unwrap();
+        let mut expected = new_cells(new.as_ref(), old.as_ref());
+        expected.insert("key_bits", 32);
+        assert_eq!(collector.take("dict_set"), [expected]);
+
+        let mut removed = dict.clone();
+        removed.remove(5)?;
+        let root = removed.root().clone().unwrap();
+        let mut expected = new_cells(root.as_ref(), new.as_ref());
+        expected.insert("key_bits", 32);
+        assert_eq!(collector.take("dict_remove"), [expected]);
+
+        // Merkle proof
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let tracked = Dict::<u32, u32>::from_raw(Some(usage_tree.track(&old)));
+        tracked.get(3)?;
+        let proof = MerkleProof::create(old.as_ref(), usage_tree).build()?;
+        assert_eq!(
+            collector.take("merkle_proof_build"),
+            [new_cells(proof.cell.as_ref(), Cell::empty_cell_ref())]
+        );
+
+        // Merkle update
+        let mut old_hashes = ahash::HashSet::default();
+        let mut stack = vec![old.as_ref()];
+        while let Some(cell) = stack.pop() {
+            if old_hashes.insert(cell.repr_hash()) {
+                stack.extend(cell.references());
+            }
+        }
+        let update = MerkleUpdate::create(old.as_ref(), new.as_ref(), old_hashes).build()?;
+        collector.take("");
+        assert_eq!(update.apply(&old)?, new);
+        assert_eq!(
+            collector.take("merkle_update_apply"),
+            [new_cells(update.new.as_ref(), Cell::empty_cell_ref())]
+        );
+
+        Ok(())
+    }
 }
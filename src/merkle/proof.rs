@@ -2,7 +2,9 @@ use std::collections::HashMap;
 use std::hash::BuildHasher;
 
 use super::{make_pruned_branch, FilterAction, MerkleFilter};
+use crate::boc::{BocRepr, BocReprError};
 use crate::cell::*;
+use crate::dict::{read_label, Dict, DictKey};
 use crate::error::Error;
 
 /// Non-owning parsed Merkle proof representation.
@@ -108,16 +110,17 @@ impl Load<'_> for MerkleProof {
             return Err(Error::InvalidCell);
         }
 
-        let res = Self {
-            hash: ok!(s.get_u256(8)),
-            depth: ok!(s.get_u16(8 + 256)),
-            cell: ok!(s.get_reference_cloned(0)),
-        };
-        if res.cell.as_ref().hash(0) == &res.hash
-            && res.cell.as_ref().depth(0) == res.depth
-            && s.try_advance(Self::BITS, Self::REFS)
+        let hash = ok!(s.get_u256(8));
+        let depth = ok!(s.get_u16(8 + 256));
+
+        let mut rest = *s;
+        let [cell] = ok!(rest.load_references());
+        if cell.as_ref().hash(0) == &hash
+            && cell.as_ref().depth(0) == depth
+            && rest.try_advance(Self::BITS, 0)
         {
-            Ok(res)
+            *s = rest;
+            Ok(Self { hash, depth, cell })
         } else {
             Err(Error::InvalidCell)
         }
@@ -126,6 +129,251 @@ impl Load<'_> for MerkleProof {
 
 impl Store for MerkleProof {
     fn store_into(&self, b: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        ok!(self.validate());
+        self.store_into_unchecked(b)
+    }
+}
+
+impl MerkleProof {
+    /// The number of data bits that the Merkle proof occupies.
+    pub const BITS: u16 = 8 + 256 + 16;
+    /// The number of references that the Merkle proof occupies.
+    pub const REFS: u8 = 1;
+
+    /// Checks that the stored hash and depth match the child cell.
+    pub fn validate(&self) -> Result<(), Error> {
+        let cell = self.cell.as_ref();
+        if cell.hash(0) == &self.hash && cell.depth(0) == self.depth {
+            Ok(())
+        } else {
+            Err(Error::InvalidData)
+        }
+    }
+
+    /// Checks that the proof is consistent and proves the cell with
+    /// the `expected_hash`. Must be called for proofs received from
+    /// an untrusted source before any further processing.
+    ///
+    /// Also checks that all pruned branches in the proof are pruned
+    /// at the level of this proof.
+    pub fn verify(&self, expected_hash: &HashBytes) -> Result<(), Error> {
+        ok!(self.validate());
+        if &self.hash != expected_hash {
+            return Err(Error::InvalidData);
+        }
+
+        // Pruned branches which are not pruned at the proof level
+        // do not hide any original cells and can't be trusted.
+        for (cell, _, is_pruned) in self.walk() {
+            if !is_pruned && cell.cell_type() == CellType::PrunedBranch {
+                return Err(Error::InvalidCell);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the proof of a dictionary entry and returns its value,
+    /// or `None` if the proof shows that the key is absent.
+    ///
+    /// The proof must be created for the dictionary root cell (e.g. with
+    /// [`Dict::get_with_proof`]) and must reveal the lookup path of the `key`.
+    ///
+    /// Returns [`Error::PrunedBranchAccess`] if the lookup path is not
+    /// revealed and [`Error::InvalidData`] if the proof doesn't match the
+    /// `expected_root_hash`.
+    pub fn verify_dict_value<K, V>(
+        &self,
+        key: &K,
+        expected_root_hash: &HashBytes,
+    ) -> Result<Option<V>, Error>
+    where
+        K: Store + DictKey,
+        for<'a> V: Load<'a>,
+    {
+        ok!(self.verify(expected_root_hash));
+
+        let dict = Dict::<K, V>::from_raw(Some(Cell::virtualize(self.cell.clone())));
+        dict.get(key)
+    }
+
+    /// Returns `true` if the cell with the specified original hash
+    /// is revealed in the proof.
+    pub fn contains_hash(&self, hash: &HashBytes) -> bool {
+        self.find_cell(hash, false)
+    }
+
+    /// Returns `true` if the cell with the specified original hash
+    /// is replaced with a pruned branch in the proof.
+    pub fn is_pruned(&self, hash: &HashBytes) -> bool {
+        self.find_cell(hash, true)
+    }
+
+    /// Returns an iterator over all unique cells of the proof
+    /// which are not pruned, in DFS pre-order.
+    pub fn iter_revealed_cells(&self) -> impl Iterator<Item = &'_ DynCell> + '_ {
+        self.walk()
+            .filter_map(|(cell, _, is_pruned)| (!is_pruned).then_some(cell))
+    }
+
+    /// Returns an iterator over the original hashes and depths
+    /// of all unique pruned branches of the proof, in DFS pre-order.
+    pub fn iter_pruned_branches(&self) -> impl Iterator<Item = (&'_ HashBytes, u16)> + '_ {
+        self.walk()
+            .filter(|(_, _, is_pruned)| *is_pruned)
+            .map(|(cell, merkle_depth, _)| (cell.hash(merkle_depth), cell.depth(merkle_depth)))
+    }
+
+    /// Creates a new proof rooted at the revealed cell with the specified
+    /// original hash. The new proof shares cells with this proof.
+    ///
+    /// NOTE: Cells inside nested Merkle cells are not searched.
+    ///
+    /// Returns [`Error::InvalidData`] if there is no such revealed cell.
+    pub fn extract_subtree(
+        &self,
+        subtree_hash: &HashBytes,
+        context: &mut dyn CellContext,
+    ) -> Result<MerkleProof, Error> {
+        let mut visited = ahash::HashSet::default();
+        let mut stack = vec![self.cell.clone()];
+        while let Some(cell) = stack.pop() {
+            if !visited.insert(*cell.repr_hash()) {
+                continue;
+            }
+
+            if is_pruned_at(cell.as_ref(), 0) {
+                continue;
+            }
+
+            if cell.hash(0) == subtree_hash {
+                return Ok(MerkleProof {
+                    hash: *subtree_hash,
+                    depth: cell.depth(0),
+                    cell,
+                });
+            }
+
+            if cell.descriptor().is_merkle() {
+                continue;
+            }
+
+            let cell = ok!(context.load_dyn_cell(cell.as_ref(), LoadMode::UseGas));
+            stack.extend(cell.references().cloned().rev());
+        }
+
+        Err(Error::InvalidData)
+    }
+
+    /// Creates a usage tree with all cells of the `original` tree
+    /// which are not pruned in the proof.
+    ///
+    /// A proof created from the returned usage tree is the same as this one,
+    /// so the usage tree can be used to extend the proof with more cells.
+    ///
+    /// Returns [`Error::InvalidData`] if the proof doesn't match the `original` tree.
+    pub fn to_usage_tree(&self, original: &Cell) -> Result<UsageTree, Error> {
+        if original.repr_hash() != &self.hash {
+            return Err(Error::InvalidData);
+        }
+
+        let usage_tree = UsageTree::new(UsageTreeMode::OnLoad);
+        let root = usage_tree.track(original);
+
+        let mut visited = ahash::HashSet::default();
+        let mut stack = vec![(self.cell.as_ref(), root.as_ref(), 0u8)];
+        while let Some((cell, tracked, merkle_depth)) = stack.pop() {
+            if !visited.insert(cell.repr_hash()) {
+                continue;
+            }
+
+            if is_pruned_at(cell, merkle_depth) {
+                continue;
+            }
+
+            // NOTE: Loading references of the tracked cell marks them as used
+            let child_merkle_depth = merkle_depth + cell.descriptor().is_merkle() as u8;
+            for (i, child) in cell.references().enumerate() {
+                if is_pruned_at(child, child_merkle_depth) {
+                    continue;
+                }
+
+                match tracked.reference(i as u8) {
+                    Some(tracked) if tracked.hash(0) == child.hash(0) => {
+                        stack.push((child, tracked, child_merkle_depth));
+                    }
+                    _ => return Err(Error::InvalidData),
+                }
+            }
+        }
+
+        Ok(usage_tree)
+    }
+
+    /// Returns the number of unique cells of the proof which are not pruned.
+    pub fn cell_count(&self) -> usize {
+        self.iter_revealed_cells().count()
+    }
+
+    /// Returns the number of unique pruned branches of the proof.
+    pub fn pruned_count(&self) -> usize {
+        self.walk().filter(|(_, _, is_pruned)| *is_pruned).count()
+    }
+
+    /// Returns the total number of data bits of all unique cells
+    /// of the proof which are not pruned.
+    pub fn total_bits(&self) -> u64 {
+        self.iter_revealed_cells()
+            .map(|cell| cell.bit_len() as u64)
+            .sum()
+    }
+
+    fn find_cell(&self, hash: &HashBytes, pruned: bool) -> bool {
+        self.walk().any(|(cell, merkle_depth, is_pruned)| {
+            is_pruned == pruned && cell.hash(merkle_depth) == hash
+        })
+    }
+
+    fn walk(&self) -> ProofWalker<'_> {
+        ProofWalker {
+            visited: Default::default(),
+            stack: vec![(self.cell.as_ref(), 0)],
+        }
+    }
+
+    /// Combines two proofs for the same root into a single proof,
+    /// which reveals cells from both of them.
+    ///
+    /// Pruned branches of one proof are replaced with
+    /// the corresponding subtrees of the other one.
+    pub fn merge(
+        a: &MerkleProof,
+        b: &MerkleProof,
+        context: &mut dyn CellContext,
+    ) -> Result<MerkleProof, Error> {
+        if a.hash != b.hash || a.depth != b.depth {
+            return Err(Error::InvalidData);
+        }
+
+        let mut merger = ProofMerger {
+            cells: Default::default(),
+            context,
+        };
+        let cell = ok!(merger.merge(&a.cell, &b.cell, 0));
+
+        Ok(MerkleProof {
+            hash: a.hash,
+            depth: a.depth,
+            cell,
+        })
+    }
+
+    /// Stores the Merkle proof as is, without checking its consistency.
+    ///
+    /// The resulting cell will fail to load if [`validate`] fails.
+    ///
+    /// [`validate`]: MerkleProof::validate
+    pub fn store_into_unchecked(&self, b: &mut CellBuilder) -> Result<(), Error> {
         if !b.has_capacity(Self::BITS, Self::REFS) {
             return Err(Error::CellOverflow);
         }
@@ -136,13 +384,37 @@ impl Store for MerkleProof {
         ok!(b.store_u16(self.depth));
         b.store_reference(self.cell.clone())
     }
-}
 
-impl MerkleProof {
-    /// The number of data bits that the Merkle proof occupies.
-    pub const BITS: u16 = 8 + 256 + 16;
-    /// The number of references that the Merkle proof occupies.
-    pub const REFS: u8 = 1;
+    /// Encodes the Merkle proof as BOC using an empty cell context.
+    pub fn to_boc(&self) -> Result<Vec<u8>, Error> {
+        BocRepr::encode(self)
+    }
+
+    /// Decodes the Merkle proof from BOC using an empty cell context.
+    pub fn from_boc(data: &[u8]) -> Result<Self, Error> {
+        match BocRepr::decode(data) {
+            Ok(proof) => Ok(proof),
+            Err(BocReprError::InvalidBoc(_)) => Err(Error::InvalidData),
+            Err(BocReprError::InvalidData(e)) => Err(e),
+        }
+    }
+
+    /// Encodes the Merkle proof as BOC using an empty cell context and
+    /// returns the `base64` encoded bytes as a string.
+    #[cfg(any(feature = "base64", test))]
+    pub fn to_base64(&self) -> Result<String, Error> {
+        BocRepr::encode_base64(self)
+    }
+
+    /// Decodes the Merkle proof from a `base64` encoded BOC
+    /// using an empty cell context.
+    #[cfg(any(feature = "base64", test))]
+    pub fn from_base64(data: &str) -> Result<Self, Error> {
+        match crate::util::decode_base64(data) {
+            Ok(data) => Self::from_boc(&data),
+            Err(_) => Err(Error::InvalidData),
+        }
+    }
 
     /// Starts building a Merkle proof for the specified root,
     /// using cells determined by filter.
@@ -178,26 +450,187 @@ impl MerkleProof {
             }
         }
 
-        let mut stack = vec![root.references()];
-        while let Some(last_cells) = stack.last_mut() {
-            match last_cells.next() {
-                Some(child) if child.repr_hash() == child_hash => break,
-                Some(child) => stack.push(child.references()),
-                None => {
-                    stack.pop();
-                }
-            }
+        let mut cells = ahash::HashSet::default();
+        find_cell_path(root, child_hash, &mut cells);
+
+        MerkleProofBuilder::new(root, RootOrChild { cells, child_hash })
+    }
+
+    /// Creates a Merkle proof for the cell at the specified `path`
+    /// of reference indices, starting from the `root`.
+    ///
+    /// Only the cells on the path are included in the proof.
+    ///
+    /// Returns [`Error::InvalidData`] if the path doesn't exist.
+    pub fn create_for_path(
+        root: &DynCell,
+        path: &[u8],
+        context: &mut dyn CellContext,
+    ) -> Result<MerkleProof, Error> {
+        let mut cells =
+            ahash::HashSet::with_capacity_and_hasher(path.len() + 1, Default::default());
+        cells.insert(root.repr_hash());
+
+        let mut cell = root;
+        for &index in path {
+            cell = match cell.reference(index) {
+                Some(child) => child,
+                None => return Err(Error::InvalidData),
+            };
+            cells.insert(cell.repr_hash());
         }
 
-        let mut cells = ahash::HashSet::with_capacity_and_hasher(stack.len(), Default::default());
-        for item in stack {
-            cells.insert(item.cell().repr_hash());
+        MerkleProof::create(root, cells).build_ext(context)
+    }
+
+    /// Creates a single Merkle proof for the lookup paths of all `keys`
+    /// in the dictionary, which is a part of the `root` tree.
+    ///
+    /// `dict` is the root cell of the dictionary. Only ancestors of its
+    /// first occurrence are included in the proof. Paths shared between
+    /// keys are included only once. Missing keys are included as a path
+    /// to the point where the lookup stops, so their absence can be proven.
+    ///
+    /// Proof creation will fail if the dictionary is not found.
+    pub fn create_for_keys<K>(
+        root: &DynCell,
+        dict: &DynCell,
+        keys: &[K],
+    ) -> Result<MerkleProof, Error>
+    where
+        K: Store + DictKey,
+    {
+        let mut cells = ahash::HashSet::<&HashBytes>::default();
+        ok!(collect_dict_key_paths(root, dict, keys, &mut cells));
+        MerkleProof::create(root, cells).build()
+    }
+
+    /// Creates a single Merkle proof for the specified accounts
+    /// of the shard state.
+    ///
+    /// The proof includes the path to the accounts dictionary and the lookup
+    /// paths of all addresses, so the absence of missing accounts
+    /// can be proven as well.
+    #[cfg(feature = "models")]
+    pub fn create_for_accounts(
+        shard_state: &DynCell,
+        account_addrs: &[HashBytes],
+        context: &mut dyn CellContext,
+    ) -> Result<MerkleProof, Error> {
+        let state = ok!(shard_state.parse::<crate::models::ShardStateUnsplit>());
+
+        // shard_accounts: (HashmapAugE 256 ShardAccount DepthBalanceInfo)
+        let accounts = state.accounts.inner().as_ref();
+
+        let mut cells = ahash::HashSet::<&HashBytes>::default();
+        cells.insert(shard_state.repr_hash());
+        cells.insert(accounts.repr_hash());
+        if let Some(dict) = accounts.reference(0) {
+            ok!(collect_key_paths(dict, account_addrs, &mut cells));
         }
 
-        MerkleProofBuilder::new(root, RootOrChild { cells, child_hash })
+        MerkleProof::create(shard_state, cells).build_ext(context)
     }
 }
 
+/// Returns `true` if the cell is a pruned branch of the proof
+/// at the specified Merkle depth.
+fn is_pruned_at(cell: &DynCell, merkle_depth: u8) -> bool {
+    let descriptor = cell.descriptor();
+    descriptor.is_pruned_branch() && descriptor.level_mask().contains(merkle_depth + 1)
+}
+
+/// Collects hashes of all ancestors of the first occurrence of the cell
+/// with the specified hash in the `root` tree (excluding the cell itself).
+///
+/// Returns `false` if there is no such cell.
+fn find_cell_path<'a>(
+    root: &'a DynCell,
+    hash: &HashBytes,
+    cells: &mut ahash::HashSet<&'a HashBytes>,
+) -> bool {
+    // NOTE: Subtrees are visited only once since they can't contain
+    // the cell if it wasn't found there before.
+    let mut visited = ahash::HashSet::default();
+    let mut stack = vec![root.references()];
+    while let Some(last_cells) = stack.last_mut() {
+        match last_cells.next() {
+            Some(child) if child.repr_hash() == hash => {
+                cells.extend(stack.iter().map(|item| item.cell().repr_hash()));
+                return true;
+            }
+            Some(child) if visited.insert(child.repr_hash()) => {
+                stack.push(child.references());
+            }
+            Some(_) => {}
+            None => {
+                stack.pop();
+            }
+        }
+    }
+    false
+}
+
+/// Collects hashes of the path to the `dict` in the `root` tree
+/// and lookup paths of all `keys` in the dictionary.
+fn collect_dict_key_paths<'a, K>(
+    root: &'a DynCell,
+    dict: &'a DynCell,
+    keys: &[K],
+    cells: &mut ahash::HashSet<&'a HashBytes>,
+) -> Result<(), Error>
+where
+    K: Store + DictKey,
+{
+    // Include the path to the dictionary
+    if root.repr_hash() != dict.repr_hash() && !find_cell_path(root, dict.repr_hash(), cells) {
+        return Err(Error::InvalidData);
+    }
+
+    // Include lookup paths of all keys
+    collect_key_paths(dict, keys, cells)
+}
+
+/// Collects hashes of the lookup paths of all `keys` in the dictionary.
+fn collect_key_paths<'a, K>(
+    dict: &'a DynCell,
+    keys: &[K],
+    cells: &mut ahash::HashSet<&'a HashBytes>,
+) -> Result<(), Error>
+where
+    K: Store + DictKey,
+{
+    for key in keys {
+        let mut builder = CellBuilder::new();
+        ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
+        let mut key = builder.as_data_slice();
+
+        let mut data = ok!(CellSlice::new(dict));
+        loop {
+            cells.insert(data.cell().repr_hash());
+
+            let prefix = ok!(read_label(&mut data, key.remaining_bits()));
+            match key.strip_data_prefix(&prefix) {
+                Some(stripped_key) => {
+                    if stripped_key.is_data_empty() || data.remaining_refs() < 2 {
+                        break;
+                    }
+                    key = stripped_key;
+                }
+                None => break,
+            }
+
+            let child_index = ok!(key.load_bit()) as u8;
+            data = match data.cell().reference(child_index) {
+                Some(cell) => ok!(CellSlice::new(cell)),
+                None => return Err(Error::CellUnderflow),
+            };
+        }
+    }
+
+    Ok(())
+}
+
 /// Helper struct to build a Merkle proof.
 pub struct MerkleProofBuilder<'a, F> {
     root: &'a DynCell,
@@ -220,6 +653,13 @@ where
     }
 
     /// Mark whether the different root is ok for this proof.
+    ///
+    /// By default, the filter must include the root cell, otherwise
+    /// the builder fails with [`Error::EmptyProof`]. With this flag set,
+    /// the root cell is always included (with all skipped children pruned)
+    /// regardless of the filter. The [`MerkleProof::hash`] field then records
+    /// the representation hash of the provided root, so the proof cell is still
+    /// valid on its own, and its raw child can be stitched into a larger structure.
     pub fn allow_different_root(mut self, allow: bool) -> Self {
         self.allow_different_root = allow;
         self
@@ -255,6 +695,7 @@ where
             pruned_branches: None,
             context,
             allow_different_root: self.allow_different_root,
+            parent_merkle_depth: 0,
         }
         .build()
     }
@@ -268,6 +709,44 @@ where
     pub fn build(self) -> Result<MerkleProof, Error> {
         self.build_ext(&mut Cell::empty_context())
     }
+
+    /// Builds a Merkle proof child cell using an empty cell context.
+    pub fn build_raw(self) -> Result<Cell, Error> {
+        self.build_raw_ext(&mut Cell::empty_context())
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, F> MerkleProofBuilder<'a, F>
+where
+    F: MerkleFilter + Sync,
+{
+    /// Builds a Merkle proof using the rayon thread pool.
+    ///
+    /// Independent subtrees near the root are processed in parallel,
+    /// the rest is built sequentially on worker threads.
+    /// The resulting proof is identical to the one built by [`build`].
+    ///
+    /// [`build`]: MerkleProofBuilder::build
+    pub fn build_par(self) -> Result<MerkleProof, Error> {
+        let root = self.root;
+        let cell = ok!(self.build_raw_par());
+        Ok(MerkleProof {
+            hash: *root.repr_hash(),
+            depth: root.repr_depth(),
+            cell,
+        })
+    }
+
+    /// Builds a Merkle proof child cell using the rayon thread pool.
+    pub fn build_raw_par(self) -> Result<Cell, Error> {
+        ParBuilderImpl {
+            filter: &self.filter,
+            cells: Default::default(),
+            pruned_branches: None,
+        }
+        .build(self.root, self.allow_different_root)
+    }
 }
 
 /// Helper struct to build a Merkle proof and keep track of all pruned cells.
@@ -279,6 +758,8 @@ pub struct MerkleProofExtBuilder<'a, F> {
 
 impl<'a, F> MerkleProofExtBuilder<'a, F> {
     /// Mark whether the different root is ok for this proof.
+    ///
+    /// See [`MerkleProofBuilder::allow_different_root`].
     pub fn allow_different_root(mut self, allow: bool) -> Self {
         self.allow_different_root = allow;
         self
@@ -302,10 +783,36 @@ where
             pruned_branches: Some(&mut pruned_branches),
             context,
             allow_different_root: self.allow_different_root,
+            parent_merkle_depth: 0,
         };
         let cell = ok!(builder.build());
         Ok((cell, pruned_branches))
     }
+
+    /// Builds a Merkle proof child cell using an empty cell context.
+    pub fn build_raw(self) -> Result<(Cell, ahash::HashMap<&'a HashBytes, bool>), Error> {
+        self.build_raw_ext(&mut Cell::empty_context())
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, F> MerkleProofExtBuilder<'a, F>
+where
+    F: MerkleFilter + Sync,
+{
+    /// Builds a Merkle proof child cell using the rayon thread pool.
+    ///
+    /// See [`MerkleProofBuilder::build_par`].
+    pub fn build_raw_par(self) -> Result<(Cell, ahash::HashMap<&'a HashBytes, bool>), Error> {
+        let builder = ParBuilderImpl {
+            filter: &self.filter,
+            cells: Default::default(),
+            pruned_branches: Some(Default::default()),
+        };
+        let cell = ok!(builder.build(self.root, self.allow_different_root));
+        let pruned_branches = builder.pruned_branches.unwrap_or_default();
+        Ok((cell, into_inner(pruned_branches)))
+    }
 }
 
 struct BuilderImpl<'a, 'b, S = ahash::RandomState> {
@@ -315,6 +822,7 @@ struct BuilderImpl<'a, 'b, S = ahash::RandomState> {
     pruned_branches: Option<&'b mut HashMap<&'a HashBytes, bool, S>>,
     context: &'b mut dyn CellContext,
     allow_different_root: bool,
+    parent_merkle_depth: u8,
 }
 
 impl<'a, 'b, S> BuilderImpl<'a, 'b, S>
@@ -322,6 +830,24 @@ where
     S: BuildHasher + Default,
 {
     fn build(&mut self) -> Result<Cell, Error> {
+        #[cfg(feature = "tracing")]
+        let span = debug_span!(
+            "merkle_proof_build",
+            cells = tracing::field::Empty,
+            bits = tracing::field::Empty,
+        );
+
+        let result = self.build_impl();
+
+        #[cfg(feature = "tracing")]
+        if result.is_ok() {
+            crate::util::record_cells(&span, self.cells.values().map(AsRef::as_ref));
+        }
+
+        result
+    }
+
+    fn build_impl(&mut self) -> Result<Cell, Error> {
         struct Node<'a> {
             references: RefsIter<'a>,
             descriptor: CellDescriptor,
@@ -342,7 +868,7 @@ where
         stack.push(Node {
             references: self.root.references(),
             descriptor: root_descriptor,
-            merkle_depth: root_descriptor.is_merkle() as u8,
+            merkle_depth: self.parent_merkle_depth + root_descriptor.is_merkle() as u8,
             children: CellRefsBuilder::default(),
         });
 
@@ -431,6 +957,218 @@ where
     }
 }
 
+/// DFS pre-order traversal over unique proof cells.
+///
+/// Yields cells with their Merkle depth and whether they are pruned.
+struct ProofWalker<'a> {
+    visited: ahash::HashSet<&'a HashBytes>,
+    stack: Vec<(&'a DynCell, u8)>,
+}
+
+impl<'a> Iterator for ProofWalker<'a> {
+    type Item = (&'a DynCell, u8, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (cell, merkle_depth) = self.stack.pop()?;
+            if !self.visited.insert(cell.repr_hash()) {
+                continue;
+            }
+
+            let is_pruned = is_pruned_at(cell, merkle_depth);
+            if !is_pruned {
+                let child_merkle_depth = merkle_depth + cell.descriptor().is_merkle() as u8;
+                self.stack.extend(
+                    cell.references()
+                        .rev()
+                        .map(|child| (child, child_merkle_depth)),
+                );
+            }
+
+            return Some((cell, merkle_depth, is_pruned));
+        }
+    }
+}
+
+struct ProofMerger<'a> {
+    cells: ahash::HashMap<(HashBytes, HashBytes), Cell>,
+    context: &'a mut dyn CellContext,
+}
+
+impl ProofMerger<'_> {
+    fn merge(&mut self, a: &Cell, b: &Cell, merkle_depth: u8) -> Result<Cell, Error> {
+        // Both cells must represent the same original cell
+        if a.as_ref().hash(merkle_depth) != b.as_ref().hash(merkle_depth) {
+            return Err(Error::InvalidData);
+        }
+
+        if a.repr_hash() == b.repr_hash() || is_pruned_at(b.as_ref(), merkle_depth) {
+            return Ok(a.clone());
+        } else if is_pruned_at(a.as_ref(), merkle_depth) {
+            return Ok(b.clone());
+        }
+
+        let key = (*a.repr_hash(), *b.repr_hash());
+        if let Some(cell) = self.cells.get(&key) {
+            return Ok(cell.clone());
+        }
+
+        let descriptor = a.descriptor();
+        if descriptor.d1 != b.descriptor().d1
+            || descriptor.d2 != b.descriptor().d2
+            || a.bit_len() != b.bit_len()
+            || a.data() != b.data()
+        {
+            return Err(Error::InvalidData);
+        }
+
+        let child_merkle_depth = merkle_depth + descriptor.is_merkle() as u8;
+
+        let mut builder = CellBuilder::new();
+        builder.set_exotic(descriptor.is_exotic());
+        ok!(builder.store_cell_data(a.as_ref()));
+        for (a_child, b_child) in a.references().cloned().zip(b.references().cloned()) {
+            let child = ok!(self.merge(&a_child, &b_child, child_merkle_depth));
+            ok!(builder.store_reference(child));
+        }
+        let cell = ok!(builder.build_ext(self.context));
+
+        self.cells.insert(key, cell.clone());
+        Ok(cell)
+    }
+}
+
+#[cfg(feature = "rayon")]
+struct ParBuilderImpl<'a, 'b> {
+    filter: &'b (dyn MerkleFilter + Sync),
+    cells: std::sync::Mutex<ahash::HashMap<&'a HashBytes, Cell>>,
+    pruned_branches: Option<std::sync::Mutex<ahash::HashMap<&'a HashBytes, bool>>>,
+}
+
+#[cfg(feature = "rayon")]
+impl<'a> ParBuilderImpl<'a, '_> {
+    /// The number of tree levels which are processed in parallel.
+    const PAR_LEVELS: u8 = 8;
+
+    fn build(&self, root: &'a DynCell, allow_different_root: bool) -> Result<Cell, Error> {
+        if !allow_different_root && self.filter.check(root.repr_hash()) == FilterAction::Skip {
+            return Err(Error::EmptyProof);
+        }
+
+        self.build_node(root, 0, Self::PAR_LEVELS)
+    }
+
+    fn build_node(
+        &self,
+        cell: &'a DynCell,
+        parent_merkle_depth: u8,
+        par_levels: u8,
+    ) -> Result<Cell, Error> {
+        use rayon::prelude::*;
+
+        if par_levels == 0 || cell.reference_count() < 2 {
+            return self.build_node_seq(cell, parent_merkle_depth);
+        }
+
+        let descriptor = cell.descriptor();
+        let merkle_depth = parent_merkle_depth + descriptor.is_merkle() as u8;
+
+        let children = ok!((0..cell.reference_count())
+            .into_par_iter()
+            .map(|i| self.build_child(cell, i, merkle_depth, par_levels - 1))
+            .collect::<Result<Vec<_>, Error>>());
+
+        let mut builder = CellBuilder::new();
+        builder.set_exotic(descriptor.is_exotic());
+        _ = builder.store_cell_data(cell);
+        for child in children {
+            ok!(builder.store_reference(child));
+        }
+        let proof_cell = ok!(builder.build_ext(&mut Cell::empty_context()));
+
+        lock(&self.cells).insert(cell.repr_hash(), proof_cell.clone());
+        Ok(proof_cell)
+    }
+
+    fn build_child(
+        &self,
+        cell: &'a DynCell,
+        index: u8,
+        merkle_depth: u8,
+        par_levels: u8,
+    ) -> Result<Cell, Error> {
+        let Some(child) = cell.reference(index) else {
+            return Err(Error::CellUnderflow);
+        };
+
+        let child_repr_hash = child.repr_hash();
+        if let Some(child) = lock(&self.cells).get(child_repr_hash) {
+            // Reused processed cells
+            return Ok(child.clone());
+        }
+
+        match self.filter.check(child_repr_hash) {
+            // Included subtrees are used as is
+            FilterAction::IncludeSubtree => match cell.reference_cloned(index) {
+                Some(child) => Ok(child),
+                None => Err(Error::CellUnderflow),
+            },
+            // Replace all skipped subtrees with pruned branch cells
+            FilterAction::Skip if child.reference_count() > 0 => {
+                let child = ok!(make_pruned_branch_cold(
+                    child,
+                    merkle_depth,
+                    &mut Cell::empty_context()
+                ));
+                if let Some(pruned_branches) = &self.pruned_branches {
+                    lock(pruned_branches).insert(child_repr_hash, false);
+                }
+                Ok(child)
+            }
+            // All other cells will be included in a different branch
+            _ => self.build_node(child, merkle_depth, par_levels),
+        }
+    }
+
+    fn build_node_seq(&self, cell: &'a DynCell, parent_merkle_depth: u8) -> Result<Cell, Error> {
+        let mut pruned_branches = self.pruned_branches.as_ref().map(|_| Default::default());
+
+        let mut builder = BuilderImpl::<ahash::RandomState> {
+            root: cell,
+            filter: self.filter,
+            cells: Default::default(),
+            pruned_branches: pruned_branches.as_mut(),
+            context: &mut Cell::empty_context(),
+            allow_different_root: true,
+            parent_merkle_depth,
+        };
+        let proof_cell = ok!(builder.build_impl());
+
+        // Merge local results into the shared state
+        lock(&self.cells).extend(builder.cells);
+        if let (Some(shared), Some(local)) = (&self.pruned_branches, pruned_branches) {
+            lock(shared).extend(local);
+        }
+
+        Ok(proof_cell)
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn lock<T>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // NOTE: The shared state is always consistent, so poisoning can be ignored
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(feature = "rayon")]
+fn into_inner<T>(mutex: std::sync::Mutex<T>) -> T {
+    mutex
+        .into_inner()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
 #[cold]
 fn make_pruned_branch_cold(
     cell: &DynCell,
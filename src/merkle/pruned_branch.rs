@@ -2,13 +2,25 @@ use crate::cell::*;
 use crate::error::Error;
 
 /// Creates a pruned branch cell with the specified merkle depth.
+///
+/// Only levels below the `merkle_depth` are kept from the original cell,
+/// so it is safe to prune cells which already contain pruned branches
+/// (e.g. when building a proof over a virtualized tree).
+///
+/// Returns [`Error::InvalidCell`] if the pruned branch level would exceed
+/// [`LevelMask::MAX_LEVEL`].
 pub fn make_pruned_branch(
     cell: &DynCell,
     merkle_depth: u8,
     context: &mut dyn CellContext,
 ) -> Result<Cell, Error> {
+    if merkle_depth >= LevelMask::MAX_LEVEL {
+        return Err(Error::InvalidCell);
+    }
+
     let descriptor = cell.descriptor();
-    let cell_level_mask = descriptor.level_mask();
+    let cell_level_mask =
+        LevelMask::new(descriptor.level_mask().to_byte() & ((1u8 << merkle_depth) - 1));
 
     let mut builder = CellBuilder::new();
     let level_mask = LevelMask::new(cell_level_mask.to_byte() | (1 << merkle_depth));
@@ -56,5 +68,14 @@ mod test {
         let virtual_pruned_branch =
             make_pruned_branch(virtual_cell, 0, &mut Cell::empty_context()).unwrap();
         assert_eq!(pruned_branch.as_ref(), virtual_pruned_branch.as_ref());
+
+        // Max level
+        let pruned_branch =
+            make_pruned_branch(cell.as_ref(), 2, &mut Cell::empty_context()).unwrap();
+        assert_eq!(pruned_branch.level_mask(), LevelMask::new(0b100));
+        assert_eq!(
+            make_pruned_branch(cell.as_ref(), 3, &mut Cell::empty_context()),
+            Err(Error::InvalidCell)
+        );
     }
 }
@@ -12,6 +12,26 @@ fn correct_proof_store_load() {
     assert_eq!(parsed, proof);
 }
 
+#[test]
+fn store_inconsistent_proof() -> anyhow::Result<()> {
+    let proof = MerkleProof {
+        hash: HashBytes([0x11; 32]),
+        ..Default::default()
+    };
+    assert!(matches!(proof.validate(), Err(Error::InvalidData)));
+    assert!(matches!(
+        CellBuilder::build_from(&proof),
+        Err(Error::InvalidData)
+    ));
+
+    let mut builder = CellBuilder::new();
+    proof.store_into_unchecked(&mut builder)?;
+    let cell = builder.build()?;
+    assert!(cell.parse::<MerkleProof>().is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_proof() {
     let root = Boc::decode(include_bytes!("simple_proof.boc")).unwrap();
@@ -28,6 +48,90 @@ fn test_proof() {
     assert_eq!(root.as_ref().repr_depth(), virtual_root.repr_depth());
 }
 
+#[test]
+fn verify_proof() -> anyhow::Result<()> {
+    let root = Boc::decode(include_bytes!("simple_proof.boc"))?;
+    let target_hash = root.as_ref().reference(1).unwrap().repr_hash();
+
+    let proof = MerkleProof::create_for_cell(root.as_ref(), target_hash).build()?;
+    proof.verify(root.repr_hash())?;
+
+    // Proof for a different cell
+    assert!(matches!(proof.verify(target_hash), Err(Error::InvalidData)));
+
+    // Inconsistent proof
+    let tampered = MerkleProof {
+        hash: *target_hash,
+        ..proof.clone()
+    };
+    assert!(matches!(
+        tampered.verify(target_hash),
+        Err(Error::InvalidData)
+    ));
+
+    // Round trip through a BOC
+    let cell = CellBuilder::build_from(&proof)?;
+    let parsed = Boc::decode(Boc::encode(cell))?.parse::<MerkleProof>()?;
+    parsed.verify(root.repr_hash())?;
+
+    // Pruned branch of a different level
+    let leaf = CellBuilder::build_from(123u32)?;
+    let pruned = make_pruned_branch(leaf.as_ref(), 1, &mut Cell::empty_context())?;
+    let cell = CellBuilder::build_from(pruned)?;
+    let invalid = MerkleProof {
+        hash: *cell.hash(0),
+        depth: cell.depth(0),
+        cell: cell.clone(),
+    };
+    assert!(matches!(
+        invalid.verify(cell.hash(0)),
+        Err(Error::InvalidCell)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn proof_with_different_root() -> anyhow::Result<()> {
+    let root = Boc::decode(include_bytes!("simple_proof.boc"))?;
+    let child_hash = *root.as_ref().reference(1).unwrap().repr_hash();
+    let filter = std::iter::once(child_hash).collect::<ahash::HashSet<_>>();
+
+    // The filter doesn't include the root
+    assert!(matches!(
+        MerkleProof::create(root.as_ref(), &filter).build(),
+        Err(Error::EmptyProof)
+    ));
+
+    let proof = MerkleProof::create(root.as_ref(), &filter)
+        .allow_different_root(true)
+        .build()?;
+    assert_eq!(proof.hash, *root.repr_hash());
+    assert_eq!(proof.depth, root.repr_depth());
+
+    let cell = CellBuilder::build_from(&proof)?;
+    let parsed = cell.as_ref().parse::<MerkleProof>()?;
+    assert_eq!(parsed, proof);
+
+    let virtual_root = parsed.cell.as_ref().virtualize();
+    assert_eq!(virtual_root.repr_hash(), root.repr_hash());
+    assert_eq!(virtual_root.reference(1).unwrap().repr_hash(), &child_hash);
+
+    // Raw cell is the same as the proof child
+    let raw = MerkleProof::create(root.as_ref(), &filter)
+        .allow_different_root(true)
+        .build_raw()?;
+    assert_eq!(raw.as_ref(), proof.cell.as_ref());
+
+    let (raw, _) = MerkleProof::create(root.as_ref(), &filter)
+        .track_pruned_branches()
+        .allow_different_root(true)
+        .build_raw()?;
+    assert_eq!(raw.as_ref(), proof.cell.as_ref());
+
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)] // takes too long to execute on miri
 fn create_proof_for_deep_cell() {
@@ -98,6 +202,408 @@ fn create_proof_for_dict() {
     assert!(matches!(dict.get(5), Err(Error::PrunedBranchAccess)));
 }
 
+#[test]
+fn merge_dict_proofs() -> anyhow::Result<()> {
+    let mut dict = Dict::<u32, u32>::new();
+    for i in 0..100 {
+        dict.add(i, i * 10)?;
+    }
+    let root = CellBuilder::build_from(dict)?;
+
+    let make_proof = |keys: &[u32]| -> anyhow::Result<MerkleProof> {
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let tracked_cell = usage_tree.track(&root);
+        let tracked_dict = tracked_cell.as_ref().parse::<Dict<u32, u32>>()?;
+        for key in keys {
+            tracked_dict.get(key)?.unwrap();
+        }
+        Ok(MerkleProof::create(tracked_cell.as_ref(), usage_tree).build()?)
+    };
+
+    let a = make_proof(&[0, 1, 2])?;
+    let b = make_proof(&[50, 99])?;
+
+    let merged = MerkleProof::merge(&a, &b, &mut Cell::empty_context())?;
+    merged.verify(root.repr_hash())?;
+
+    // Merged proof contains keys from both proofs
+    let dict = merged.cell.as_ref().virtualize();
+    let dict = dict.parse::<Dict<u32, u32>>()?;
+    for key in [0, 1, 2, 50, 99] {
+        assert_eq!(dict.get(key)?, Some(key * 10));
+    }
+    assert!(matches!(dict.get(25), Err(Error::PrunedBranchAccess)));
+
+    // Merged proof is the same as the proof for all keys
+    let expected = make_proof(&[0, 1, 2, 50, 99])?;
+    assert_eq!(merged.cell.repr_hash(), expected.cell.repr_hash());
+
+    // Merge is symmetric
+    let merged_rev = MerkleProof::merge(&b, &a, &mut Cell::empty_context())?;
+    assert_eq!(merged_rev.cell.repr_hash(), merged.cell.repr_hash());
+
+    // Proofs for different roots can't be merged
+    let other_root = Boc::decode(include_bytes!("simple_proof.boc"))?;
+    let other =
+        MerkleProof::create_for_cell(other_root.as_ref(), other_root.repr_hash()).build()?;
+    assert!(matches!(
+        MerkleProof::merge(&a, &other, &mut Cell::empty_context()),
+        Err(Error::InvalidData)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn proof_contains_hash() -> anyhow::Result<()> {
+    let root = Boc::decode(include_bytes!("simple_proof.boc"))?;
+    let target = root.as_ref().reference(1).unwrap();
+    let other = root.as_ref().reference(0).unwrap();
+
+    let proof = MerkleProof::create_for_cell(root.as_ref(), target.repr_hash()).build()?;
+
+    assert!(proof.contains_hash(root.repr_hash()));
+    assert!(proof.contains_hash(target.repr_hash()));
+    assert!(!proof.is_pruned(root.repr_hash()));
+    assert!(!proof.is_pruned(target.repr_hash()));
+
+    assert!(proof.is_pruned(other.repr_hash()));
+    assert!(!proof.contains_hash(other.repr_hash()));
+
+    assert!(!proof.contains_hash(&HashBytes::ZERO));
+    assert!(!proof.is_pruned(&HashBytes::ZERO));
+
+    Ok(())
+}
+
+#[test]
+fn iter_proof_cells() -> anyhow::Result<()> {
+    let root = Boc::decode(include_bytes!("simple_proof.boc"))?;
+    let target = root.as_ref().reference(1).unwrap();
+    let other = root.as_ref().reference(0).unwrap();
+
+    let proof = MerkleProof::create_for_cell(root.as_ref(), target.repr_hash()).build()?;
+
+    let revealed = proof
+        .iter_revealed_cells()
+        .map(|cell| *cell.hash(0))
+        .collect::<Vec<_>>();
+    assert_eq!(revealed.first(), Some(root.repr_hash()));
+    assert!(revealed.contains(target.repr_hash()));
+    assert!(!revealed.contains(other.repr_hash()));
+
+    let pruned = proof.iter_pruned_branches().collect::<Vec<_>>();
+    assert!(pruned.contains(&(other.repr_hash(), other.repr_depth())));
+    assert!(pruned.iter().all(|(hash, _)| !revealed.contains(hash)));
+
+    // Each revealed or pruned cell is consistent with the membership checks
+    assert!(revealed.iter().all(|hash| proof.contains_hash(hash)));
+    assert!(pruned.iter().all(|(hash, _)| proof.is_pruned(hash)));
+
+    // Counters are consistent with the iterators
+    assert_eq!(proof.cell_count(), revealed.len());
+    assert_eq!(proof.pruned_count(), pruned.len());
+
+    // Proof with the whole tree included has no pruned branches
+    let mut filter = ahash::HashSet::default();
+    let mut stack = vec![root.as_ref()];
+    while let Some(cell) = stack.pop() {
+        filter.insert(*cell.repr_hash());
+        stack.extend(cell.references());
+    }
+    let full = MerkleProof::create(root.as_ref(), &filter).build()?;
+    let stats = root.compute_unique_stats(usize::MAX).unwrap();
+    assert_eq!(full.pruned_count(), 0);
+    assert_eq!(full.cell_count() as u64, stats.cell_count);
+    assert_eq!(full.total_bits(), stats.bit_count);
+
+    Ok(())
+}
+
+#[test]
+fn create_proof_for_shared_cells() -> anyhow::Result<()> {
+    // Each cell references the previous one twice, so there are 2^64 paths
+    let mut shared = Cell::empty_cell();
+    for i in 0..64u32 {
+        let mut builder = CellBuilder::new();
+        builder.store_u32(i)?;
+        builder.store_reference(shared.clone())?;
+        builder.store_reference(shared)?;
+        shared = builder.build()?;
+    }
+
+    let target = CellBuilder::build_from(u64::MAX)?;
+    let mut builder = CellBuilder::new();
+    builder.store_reference(shared)?;
+    builder.store_reference(target.clone())?;
+    let root = builder.build()?;
+
+    let proof = MerkleProof::create_for_cell(root.as_ref(), target.repr_hash()).build()?;
+    proof.verify(root.repr_hash())?;
+    assert!(proof.contains_hash(target.repr_hash()));
+
+    let proof = MerkleProof::create_for_keys::<u32>(root.as_ref(), target.as_ref(), &[])?;
+    proof.verify(root.repr_hash())?;
+
+    Ok(())
+}
+
+#[test]
+fn create_proof_for_keys() -> anyhow::Result<()> {
+    let mut dict = Dict::<u32, u32>::new();
+    for i in 0..1000 {
+        dict.add(i * 2, i)?;
+    }
+    let dict_cell = dict.root().clone().unwrap();
+
+    let mut builder = CellBuilder::new();
+    builder.store_u32(123)?;
+    builder.store_reference(dict_cell.clone())?;
+    let root = builder.build()?;
+
+    // Includes existing and missing keys
+    let keys = [0, 2, 500, 1998, 1001];
+    let proof = MerkleProof::create_for_keys(root.as_ref(), dict_cell.as_ref(), &keys)?;
+    proof.verify(root.repr_hash())?;
+
+    let virtual_root = proof.cell.as_ref().virtualize();
+    let virtual_dict = Dict::<u32, u32>::from_raw(Some(virtual_root.reference_cloned(0).unwrap()));
+    assert_eq!(virtual_dict.get(0)?, Some(0));
+    assert_eq!(virtual_dict.get(2)?, Some(1));
+    assert_eq!(virtual_dict.get(500)?, Some(250));
+    assert_eq!(virtual_dict.get(1998)?, Some(999));
+    assert_eq!(virtual_dict.get(1001)?, None);
+    assert!(matches!(
+        virtual_dict.get(1200),
+        Err(Error::PrunedBranchAccess)
+    ));
+
+    // Same as merged proofs for each key
+    let mut merged = MerkleProof::create_for_keys(root.as_ref(), dict_cell.as_ref(), &keys[..1])?;
+    for key in &keys[1..] {
+        let single = MerkleProof::create_for_keys(root.as_ref(), dict_cell.as_ref(), &[*key])?;
+        merged = MerkleProof::merge(&merged, &single, &mut Cell::empty_context())?;
+    }
+    assert_eq!(merged.cell.repr_hash(), proof.cell.repr_hash());
+
+    // Dictionary must be a part of the root tree
+    let other = CellBuilder::build_from(123u32)?;
+    assert!(matches!(
+        MerkleProof::create_for_keys(other.as_ref(), dict_cell.as_ref(), &keys),
+        Err(Error::InvalidData)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn extract_proof_subtree() -> anyhow::Result<()> {
+    let mut dict = Dict::<u32, u32>::new();
+    for i in 0..1000 {
+        dict.add(i, i * 10)?;
+    }
+    let dict_cell = dict.root().clone().unwrap();
+
+    let mut builder = CellBuilder::new();
+    builder.store_u32(123)?;
+    builder.store_reference(dict_cell.clone())?;
+    let root = builder.build()?;
+
+    let proof = MerkleProof::create_for_keys(root.as_ref(), dict_cell.as_ref(), &[1u32, 900])?;
+
+    // Extract proof for the dictionary
+    let dict_proof = proof.extract_subtree(dict_cell.repr_hash(), &mut Cell::empty_context())?;
+    assert_eq!(dict_proof.hash, *dict_cell.repr_hash());
+    assert_eq!(dict_proof.depth, dict_cell.repr_depth());
+    dict_proof.verify(dict_cell.repr_hash())?;
+
+    let virtual_dict = Dict::<u32, u32>::from_raw(Some(Cell::virtualize(dict_proof.cell.clone())));
+    assert_eq!(virtual_dict.get(1)?, Some(10));
+    assert_eq!(virtual_dict.get(900)?, Some(9000));
+    assert!(matches!(
+        virtual_dict.get(500),
+        Err(Error::PrunedBranchAccess)
+    ));
+
+    // Extracting the root gives the same proof
+    let root_proof = proof.extract_subtree(root.repr_hash(), &mut Cell::empty_context())?;
+    assert_eq!(root_proof, proof);
+
+    // Pruned and unknown cells can't be extracted
+    let (pruned, _) = proof.iter_pruned_branches().next().unwrap();
+    assert!(matches!(
+        proof.extract_subtree(pruned, &mut Cell::empty_context()),
+        Err(Error::InvalidData)
+    ));
+    assert!(matches!(
+        proof.extract_subtree(&HashBytes::ZERO, &mut Cell::empty_context()),
+        Err(Error::InvalidData)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn create_proof_for_path() -> anyhow::Result<()> {
+    let root = Boc::decode(include_bytes!("simple_proof.boc"))?;
+
+    // Find some path to a leaf
+    let mut path = Vec::new();
+    let mut target = root.as_ref();
+    while let Some(child) = target.reference(target.reference_count().saturating_sub(1)) {
+        path.push(target.reference_count() - 1);
+        target = child;
+    }
+    assert!(!path.is_empty());
+
+    let proof = MerkleProof::create_for_path(root.as_ref(), &path, &mut Cell::empty_context())?;
+    proof.verify(root.repr_hash())?;
+    assert!(proof.contains_hash(target.repr_hash()));
+    assert_eq!(
+        proof,
+        MerkleProof::create_for_cell(root.as_ref(), target.repr_hash()).build()?
+    );
+
+    // Empty path
+    let proof = MerkleProof::create_for_path(root.as_ref(), &[], &mut Cell::empty_context())?;
+    proof.verify(root.repr_hash())?;
+    assert_eq!(proof.cell_count(), 1);
+
+    // Invalid paths
+    for path in [
+        &[4u8][..],
+        &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    ] {
+        assert!(matches!(
+            MerkleProof::create_for_path(root.as_ref(), path, &mut Cell::empty_context()),
+            Err(Error::InvalidData)
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn proof_to_usage_tree() -> anyhow::Result<()> {
+    let mut dict = Dict::<u32, u32>::new();
+    for i in 0..1000 {
+        dict.add(i * 2, i)?;
+    }
+    let dict_cell = dict.root().clone().unwrap();
+
+    let mut builder = CellBuilder::new();
+    builder.store_u32(123)?;
+    builder.store_reference(dict_cell.clone())?;
+    let root = builder.build()?;
+
+    let proof = MerkleProof::create_for_keys(root.as_ref(), dict_cell.as_ref(), &[10, 1001])?;
+
+    // Same usage tree produces the same proof
+    let usage_tree = proof.to_usage_tree(&root)?;
+    let rebuilt = MerkleProof::create(root.as_ref(), &usage_tree).build()?;
+    assert_eq!(rebuilt, proof);
+
+    // Usage tree can be extended with more cells
+    let tracked = usage_tree.track(&root);
+    let tracked_dict = Dict::<u32, u32>::from_raw(tracked.reference_cloned(0));
+    assert_eq!(tracked_dict.get(500)?, Some(250));
+
+    let extended = MerkleProof::create(root.as_ref(), &usage_tree).build()?;
+    extended.verify(root.repr_hash())?;
+    let expected =
+        MerkleProof::create_for_keys(root.as_ref(), dict_cell.as_ref(), &[10, 1001, 500])?;
+    assert_eq!(extended, expected);
+
+    // Proof must be created for the same tree
+    assert!(matches!(
+        proof.to_usage_tree(&dict_cell),
+        Err(Error::InvalidData)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn proof_boc_round_trip() -> anyhow::Result<()> {
+    let root = Boc::decode(include_bytes!("simple_proof.boc"))?;
+    let proof = MerkleProof::create_for_path(root.as_ref(), &[0], &mut Cell::empty_context())?;
+
+    let boc = proof.to_boc()?;
+    assert_eq!(boc, Boc::encode(CellBuilder::build_from(&proof)?));
+    assert_eq!(MerkleProof::from_boc(&boc)?, proof);
+
+    let encoded = proof.to_base64()?;
+    assert_eq!(MerkleProof::from_base64(&encoded)?, proof);
+
+    // Invalid data
+    assert!(matches!(
+        MerkleProof::from_boc(&boc[..boc.len() - 1]),
+        Err(Error::InvalidData)
+    ));
+    assert!(matches!(
+        MerkleProof::from_base64("not a boc!"),
+        Err(Error::InvalidData)
+    ));
+
+    // Not a Merkle proof
+    let boc = Boc::encode(CellBuilder::build_from(123u32)?);
+    assert!(matches!(
+        MerkleProof::from_boc(&boc),
+        Err(Error::CellUnderflow)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn verify_proven_dict_value() -> anyhow::Result<()> {
+    let mut dict = Dict::<u32, u64>::new();
+    for i in 0..1000 {
+        dict.add(i * 2, i as u64 * 10)?;
+    }
+    let root_hash = *dict.root().as_ref().unwrap().repr_hash();
+
+    let (value, proof) = dict.get_with_proof(500)?.unwrap();
+    assert_eq!(
+        proof.verify_dict_value::<u32, u64>(&500, &root_hash)?,
+        Some(value)
+    );
+
+    // Proof for multiple keys
+    let dict_cell = dict.root().clone().unwrap();
+    let proof = MerkleProof::create_for_keys(dict_cell.as_ref(), dict_cell.as_ref(), &[10, 1998])?;
+    assert_eq!(
+        proof.verify_dict_value::<u32, u64>(&10, &root_hash)?,
+        Some(50)
+    );
+    assert_eq!(
+        proof.verify_dict_value::<u32, u64>(&1998, &root_hash)?,
+        Some(9990)
+    );
+
+    // Lookup path is not revealed
+    assert!(matches!(
+        proof.verify_dict_value::<u32, u64>(&1200, &root_hash),
+        Err(Error::PrunedBranchAccess)
+    ));
+
+    // Absent key
+    let (found, proof) = dict.contains_key_with_proof(1001)?;
+    assert!(!found);
+    assert_eq!(
+        proof.verify_dict_value::<u32, u64>(&1001, &root_hash)?,
+        None
+    );
+
+    // Different root
+    assert!(matches!(
+        proof.verify_dict_value::<u32, u64>(&1000, &HashBytes::ZERO),
+        Err(Error::InvalidData)
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn proof_with_subtree() -> anyhow::Result<()> {
     let mut dict = Dict::<u32, u32>::new();
@@ -154,3 +660,108 @@ fn proof_with_subtree() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn proof_over_virtualized_proof() -> anyhow::Result<()> {
+    let mut dict = Dict::<u32, u32>::new();
+    for i in 0..100 {
+        dict.add(i, i * 10)?;
+    }
+    let dict_cell = CellBuilder::build_from(&dict)?;
+
+    // Build the first proof for a half of the keys
+    let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+    let tracked = usage_tree.track(&dict_cell);
+    let tracked_dict = tracked.as_ref().parse::<Dict<u32, u32>>()?;
+    for i in 0..50 {
+        tracked_dict.get(i)?.unwrap();
+    }
+    let proof_a = MerkleProof::create(tracked.as_ref(), usage_tree).build()?;
+    let proof_a_cell = CellBuilder::build_from(&proof_a)?;
+
+    // Build the second proof over the virtualized first one
+    let virtual_a = proof_a_cell.as_ref().parse::<MerkleProof>()?.cell;
+    let virtual_a = Cell::virtualize(virtual_a);
+    assert_eq!(virtual_a.repr_hash(), dict_cell.repr_hash());
+
+    let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+    let tracked = usage_tree.track(&virtual_a);
+    let tracked_dict = tracked.as_ref().parse::<Dict<u32, u32>>()?;
+    for i in 0..10 {
+        tracked_dict.get(i)?.unwrap();
+    }
+    let proof_b = MerkleProof::create(tracked.as_ref(), usage_tree).build()?;
+    assert_eq!(proof_b.hash, *dict_cell.repr_hash());
+
+    // Check that the second proof is valid
+    let proof_b_cell = CellBuilder::build_from(&proof_b)?;
+    let proof_b = Boc::decode(Boc::encode(proof_b_cell.as_ref()))?
+        .as_ref()
+        .parse::<MerkleProof>()?;
+
+    let virtual_b = proof_b.cell.as_ref().virtualize();
+    assert_eq!(virtual_b.repr_hash(), dict_cell.repr_hash());
+    assert_eq!(virtual_b.repr_depth(), dict_cell.repr_depth());
+
+    let dict_b = virtual_b.parse::<Dict<u32, u32>>()?;
+    for i in 0..10 {
+        assert_eq!(dict_b.get(i)?, Some(i * 10));
+    }
+    assert!(matches!(dict_b.get(20), Err(Error::PrunedBranchAccess)));
+    assert!(matches!(dict_b.get(70), Err(Error::PrunedBranchAccess)));
+
+    Ok(())
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_proof_matches_sequential() -> anyhow::Result<()> {
+    // Includes paths to the leaves with some of the hashes (~10%)
+    fn collect_paths(cell: &DynCell, filter: &mut ahash::HashSet<HashBytes>) -> bool {
+        let mut included = cell.reference_count() == 0 && cell.repr_hash().0[0] < 26;
+        for child in cell.references() {
+            included |= collect_paths(child, filter);
+        }
+        if included {
+            filter.insert(*cell.repr_hash());
+        }
+        included
+    }
+
+    let dict = Dict::<u32, u32>::from_par_iter((0..20000).map(|i| (i * 7, i)).collect::<Vec<_>>())?;
+    let dict_cell = CellBuilder::build_from(dict)?;
+
+    let mut filter = ahash::HashSet::default();
+    assert!(collect_paths(dict_cell.as_ref(), &mut filter));
+
+    let proof = MerkleProof::create(dict_cell.as_ref(), &filter).build()?;
+    let proof_par = MerkleProof::create(dict_cell.as_ref(), &filter).build_par()?;
+    assert_eq!(proof, proof_par);
+    assert_eq!(proof.cell.repr_hash(), proof_par.cell.repr_hash());
+
+    let (cell, pruned) = MerkleProof::create(dict_cell.as_ref(), &filter)
+        .track_pruned_branches()
+        .build_raw()?;
+    let (cell_par, pruned_par) = MerkleProof::create(dict_cell.as_ref(), &filter)
+        .track_pruned_branches()
+        .build_raw_par()?;
+    assert_eq!(cell.repr_hash(), cell_par.repr_hash());
+    assert!(!pruned.is_empty());
+    assert_eq!(pruned, pruned_par);
+
+    // The root must be included unless a different root is allowed
+    let empty = ahash::HashSet::<HashBytes>::default();
+    assert!(matches!(
+        MerkleProof::create(dict_cell.as_ref(), &empty).build_par(),
+        Err(Error::EmptyProof)
+    ));
+    let proof = MerkleProof::create(dict_cell.as_ref(), &empty)
+        .allow_different_root(true)
+        .build()?;
+    let proof_par = MerkleProof::create(dict_cell.as_ref(), &empty)
+        .allow_different_root(true)
+        .build_par()?;
+    assert_eq!(proof, proof_par);
+
+    Ok(())
+}
@@ -63,21 +63,28 @@ impl Load<'_> for MerkleUpdate {
             return Err(Error::InvalidCell);
         }
 
-        let res = Self {
-            old_hash: ok!(s.get_u256(8)),
-            new_hash: ok!(s.get_u256(8 + 256)),
-            old_depth: ok!(s.get_u16(8 + 256 * 2)),
-            new_depth: ok!(s.get_u16(8 + 256 * 2 + 16)),
-            old: ok!(s.get_reference_cloned(0)),
-            new: ok!(s.get_reference_cloned(1)),
-        };
-        if res.old.as_ref().hash(0) == &res.old_hash
-            && res.old.as_ref().depth(0) == res.old_depth
-            && res.new.as_ref().hash(0) == &res.new_hash
-            && res.new.as_ref().depth(0) == res.new_depth
-            && s.try_advance(Self::BITS, Self::REFS)
+        let old_hash = ok!(s.get_u256(8));
+        let new_hash = ok!(s.get_u256(8 + 256));
+        let old_depth = ok!(s.get_u16(8 + 256 * 2));
+        let new_depth = ok!(s.get_u16(8 + 256 * 2 + 16));
+
+        let mut rest = *s;
+        let [old, new] = ok!(rest.load_references());
+        if old.as_ref().hash(0) == &old_hash
+            && old.as_ref().depth(0) == old_depth
+            && new.as_ref().hash(0) == &new_hash
+            && new.as_ref().depth(0) == new_depth
+            && rest.try_advance(Self::BITS, 0)
         {
-            Ok(res)
+            *s = rest;
+            Ok(Self {
+                old_hash,
+                new_hash,
+                old_depth,
+                new_depth,
+                old,
+                new,
+            })
         } else {
             Err(Error::InvalidCell)
         }
@@ -86,6 +93,38 @@ impl Load<'_> for MerkleUpdate {
 
 impl Store for MerkleUpdate {
     fn store_into(&self, b: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        ok!(self.validate());
+        self.store_into_unchecked(b)
+    }
+}
+
+impl MerkleUpdate {
+    /// The number of data bits that the Merkle update occupies.
+    pub const BITS: u16 = 8 + (256 + 16) * 2;
+    /// The number of references that the Merkle update occupies.
+    pub const REFS: u8 = 2;
+
+    /// Checks that the stored hashes and depths match the child cells.
+    pub fn validate(&self) -> Result<(), Error> {
+        let old = self.old.as_ref();
+        let new = self.new.as_ref();
+        if old.hash(0) == &self.old_hash
+            && old.depth(0) == self.old_depth
+            && new.hash(0) == &self.new_hash
+            && new.depth(0) == self.new_depth
+        {
+            Ok(())
+        } else {
+            Err(Error::InvalidData)
+        }
+    }
+
+    /// Stores the Merkle update as is, without checking its consistency.
+    ///
+    /// The resulting cell will fail to load if [`validate`] fails.
+    ///
+    /// [`validate`]: MerkleUpdate::validate
+    pub fn store_into_unchecked(&self, b: &mut CellBuilder) -> Result<(), Error> {
         if !b.has_capacity(Self::BITS, Self::REFS) {
             return Err(Error::CellOverflow);
         }
@@ -98,13 +137,6 @@ impl Store for MerkleUpdate {
         ok!(b.store_reference(self.old.clone()));
         b.store_reference(self.new.clone())
     }
-}
-
-impl MerkleUpdate {
-    /// The number of data bits that the Merkle update occupies.
-    pub const BITS: u16 = 8 + (256 + 16) * 2;
-    /// The number of references that the Merkle update occupies.
-    pub const REFS: u8 = 2;
 
     /// Starts building a Merkle update between the specified cells,
     /// using old cells determined by filter.
@@ -124,6 +156,13 @@ impl MerkleUpdate {
     /// Tries to apply this Merkle update to the specified cell,
     /// producing a new cell and using an empty cell context.
     pub fn apply_ext(&self, old: &Cell, context: &mut dyn CellContext) -> Result<Cell, Error> {
+        #[cfg(feature = "tracing")]
+        let span = debug_span!(
+            "merkle_update_apply",
+            cells = tracing::field::Empty,
+            bits = tracing::field::Empty,
+        );
+
         if old.as_ref().repr_hash() != &self.old_hash {
             return Err(Error::InvalidData);
         }
@@ -236,12 +275,20 @@ impl MerkleUpdate {
         };
 
         // Apply changed cells
-        let new = Applier {
+        let mut applier = Applier {
             old_cells,
             new_cells: Default::default(),
             context,
-        }
-        .run(self.new.as_ref(), 0)?;
+        };
+        let new = ok!(applier.run(self.new.as_ref(), 0));
+
+        #[cfg(feature = "tracing")]
+        crate::util::record_cells(
+            &span,
+            (applier.new_cells.values())
+                .chain(std::iter::once(&new))
+                .map(AsRef::as_ref),
+        );
 
         if new.as_ref().repr_hash() == &self.new_hash {
             Ok(new)
@@ -653,6 +700,32 @@ mod tests {
         assert_eq!(default, parsed);
     }
 
+    #[test]
+    fn store_inconsistent() -> anyhow::Result<()> {
+        let mut update = MerkleUpdate {
+            new: CellBuilder::build_from(123u32)?,
+            ..Default::default()
+        };
+        assert!(matches!(update.validate(), Err(Error::InvalidData)));
+        assert!(matches!(
+            CellBuilder::build_from(&update),
+            Err(Error::InvalidData)
+        ));
+
+        // Unchecked store produces a cell which fails to load
+        let mut builder = CellBuilder::new();
+        update.store_into_unchecked(&mut builder)?;
+        let cell = builder.build()?;
+        assert!(cell.parse::<MerkleUpdate>().is_err());
+
+        update.new_hash = *update.new.repr_hash();
+        update.validate()?;
+        let cell = CellBuilder::build_from(&update)?;
+        assert_eq!(cell.parse::<MerkleUpdate>()?, update);
+
+        Ok(())
+    }
+
     #[test]
     fn dict_merkle_update() {
         // Create dict with keys 0..10
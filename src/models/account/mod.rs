@@ -7,6 +7,7 @@ use crate::num::*;
 
 use crate::models::currency::CurrencyCollection;
 use crate::models::message::IntAddr;
+use crate::models::transaction::TxPointer;
 use crate::models::Lazy;
 
 /// Amount of unique cells and bits for shard states.
@@ -154,12 +155,55 @@ pub struct ShardAccount {
 
 impl ShardAccount {
     /// Tries to load account data.
+    ///
+    /// Returns `None` for the non-existing account (`account_none$0`).
     pub fn load_account(&self) -> Result<Option<Account>, Error> {
         let OptionalAccount(account) = ok!(self.account.load());
         Ok(account)
     }
+
+    /// Returns `true` if the account doesn't exist (`account_none$0`).
+    ///
+    /// Doesn't parse the account state.
+    pub fn is_empty(&self) -> bool {
+        let cell = self.account.cell.as_ref();
+        !cell.is_exotic()
+            && cell.bit_len() == 1
+            && cell.reference_count() == 0
+            && cell.data()[0] & 0x80 == 0
+    }
+
+    /// Returns a pointer to the last transaction on the account.
+    ///
+    /// Use [`Transaction::prev_trans`] to walk through the previous ones.
+    ///
+    /// [`Transaction::prev_trans`]: crate::models::Transaction::prev_trans
+    pub fn last_trans(&self) -> TxPointer {
+        TxPointer {
+            hash: self.last_trans_hash,
+            lt: self.last_trans_lt,
+        }
+    }
+}
+
+impl Default for ShardAccount {
+    /// Returns an entry for the non-existing account.
+    fn default() -> Self {
+        // SAFETY: `account_none$0` is a single zero bit with a precomputed hash.
+        let cell = unsafe { StaticCell::new(&[0x40], 1, &EMPTY_ACCOUNT_HASH) };
+        Self {
+            account: Lazy::from_raw(Cell::from_static(cell)),
+            last_trans_hash: HashBytes::ZERO,
+            last_trans_lt: 0,
+        }
+    }
 }
 
+const EMPTY_ACCOUNT_HASH: [u8; 32] = [
+    0x90, 0xae, 0xc8, 0x96, 0x5a, 0xfa, 0xbb, 0x16, 0xeb, 0xc3, 0xcb, 0x9b, 0x40, 0x8e, 0xba, 0xe7,
+    0x1b, 0x61, 0x8d, 0x78, 0x78, 0x8b, 0xc8, 0x0d, 0x09, 0x84, 0x35, 0x93, 0xca, 0xc9, 0x8d, 0xa4,
+];
+
 /// A wrapper for `Option<Account>` with customized representation.
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -304,6 +348,30 @@ pub struct Account {
     pub init_code_hash: Option<HashBytes>,
 }
 
+impl Account {
+    /// Returns the hash of the account [`StateInit`].
+    ///
+    /// For frozen accounts returns the stored hash, for active accounts
+    /// computes the hash of the state. Returns `None` for uninit accounts.
+    pub fn state_hash(&self) -> Result<Option<HashBytes>, Error> {
+        match &self.state {
+            AccountState::Uninit => Ok(None),
+            AccountState::Active(state) => state.compute_hash().map(Some),
+            AccountState::Frozen(hash) => Ok(Some(*hash)),
+        }
+    }
+
+    /// Returns `true` if the frozen account can be unfrozen with the specified state.
+    ///
+    /// Always returns `false` for accounts which are not frozen.
+    pub fn can_thaw_with(&self, init: &StateInit) -> Result<bool, Error> {
+        match &self.state {
+            AccountState::Frozen(hash) => Ok(ok!(init.compute_hash()) == *hash),
+            _ => Ok(false),
+        }
+    }
+}
+
 /// State of an existing account.
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -396,6 +464,24 @@ impl Default for StateInit {
 }
 
 impl StateInit {
+    /// Computes the representation hash of the serialized state.
+    ///
+    /// The same hash is used as an account address and as a frozen state hash.
+    pub fn compute_hash(&self) -> Result<HashBytes, Error> {
+        let cell = ok!(CellBuilder::build_from(self));
+        Ok(*cell.repr_hash())
+    }
+
+    /// Returns the representation hash of the code cell.
+    pub fn code_hash(&self) -> Option<&HashBytes> {
+        self.code.as_ref().map(|code| code.repr_hash())
+    }
+
+    /// Returns the representation hash of the data cell.
+    pub fn data_hash(&self) -> Option<&HashBytes> {
+        self.data.as_ref().map(|data| data.repr_hash())
+    }
+
     /// Exact size of this value when it is stored in slice.
     pub const fn exact_size_const(&self) -> CellSliceSize {
         CellSliceSize {
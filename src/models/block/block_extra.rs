@@ -117,11 +117,11 @@ impl<'a> Load<'a> for BlockExtra {
         let tag = ok!(slice.load_u32());
         #[cfg(not(any(feature = "venom", feature = "tycho")))]
         if tag != Self::TAG_V1 {
-            return Err(Error::InvalidTag);
+            return Err(Error::InvalidTag(tag.into()));
         }
         #[cfg(any(feature = "venom", feature = "tycho"))]
         if tag != Self::TAG_V1 && tag != Self::TAG_V2 {
-            return Err(Error::InvalidTag);
+            return Err(Error::InvalidTag(tag.into()));
         }
 
         let in_msg_description = ok!(Lazy::load_from(slice));
@@ -217,7 +217,7 @@ impl<'a> Load<'a> for AccountBlock {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
         match slice.load_small_uint(4) {
             Ok(Self::TAG) => {}
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         }
 
@@ -334,7 +334,7 @@ impl<'a> Load<'a> for McBlockExtra {
         let with_copyleft = match slice.load_u16() {
             Ok(Self::TAG_V1) => false,
             Ok(Self::TAG_V2) => true,
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         };
 
@@ -372,7 +372,7 @@ impl<'a> Load<'a> for McBlockExtra {
 pub type ShardFees = AugDict<ShardIdentFull, ShardFeeCreated, ShardFeeCreated>;
 
 /// [`ShardIdent`] that is stored with terminatino bit.
-#[derive(Clone, Debug, Default, Store, Load)]
+#[derive(Clone, Debug, Default, Store, Load, DictKey)]
 pub struct ShardIdentFull {
     /// Workchain id.
     pub workchain: i32,
@@ -380,16 +380,6 @@ pub struct ShardIdentFull {
     pub prefix: u64,
 }
 
-impl DictKey for ShardIdentFull {
-    const BITS: u16 = 96;
-
-    fn from_raw_data(raw_data: &[u8; 128]) -> Option<Self> {
-        let workchain = i32::from_be_bytes(raw_data[0..4].try_into().unwrap());
-        let prefix = u64::from_be_bytes(raw_data[4..12].try_into().unwrap());
-        Some(Self { workchain, prefix })
-    }
-}
-
 impl TryFrom<ShardIdentFull> for ShardIdent {
     type Error = Error;
 
@@ -516,7 +506,7 @@ impl<'a> Load<'a> for Signature {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
         match slice.load_small_uint(Self::TAG_LEN) {
             Ok(Self::TAG) => {}
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         }
 
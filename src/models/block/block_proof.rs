@@ -52,7 +52,7 @@ impl<'a> Load<'a> for BlockProof {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
         match slice.load_u8() {
             Ok(Self::TAG) => {}
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         }
 
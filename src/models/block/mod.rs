@@ -12,6 +12,7 @@ use crate::util::*;
 
 use crate::models::currency::CurrencyCollection;
 use crate::models::global_version::GlobalVersion;
+use crate::models::message::{ImportFees, InMsg, OutMsg};
 use crate::models::Lazy;
 
 pub use self::block_extra::*;
@@ -71,6 +72,35 @@ impl Block {
         self.extra.load()
     }
 
+    /// Loads inbound message description and returns an iterator over its entries.
+    /// The iterator element type is `Result<(HashBytes, ImportFees, InMsg)>`,
+    /// where the key is a hash of the message cell.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn iter_in_msgs(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<(HashBytes, ImportFees, InMsg), Error>>, Error> {
+        let extra = ok!(self.load_extra());
+        let descr = ok!(extra.load_in_msg_description());
+        Ok(descr.dict().clone().into_iter().map(flatten_aug_entry))
+    }
+
+    /// Loads outbound message description and returns an iterator over its entries.
+    /// The iterator element type is `Result<(HashBytes, CurrencyCollection, OutMsg)>`,
+    /// where the key is a hash of the message cell.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn iter_out_msgs(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<(HashBytes, CurrencyCollection, OutMsg), Error>>, Error>
+    {
+        let extra = ok!(self.load_extra());
+        let descr = ok!(extra.load_out_msg_description());
+        Ok(descr.dict().clone().into_iter().map(flatten_aug_entry))
+    }
+
     /// Builds a data for validators to sign.
     pub fn build_data_for_sign(block_id: &BlockId) -> [u8; Self::DATA_FOR_SIGN_SIZE] {
         let mut data = [0u8; Self::DATA_FOR_SIGN_SIZE];
@@ -81,6 +111,14 @@ impl Block {
     }
 }
 
+#[inline]
+fn flatten_aug_entry<K, A, V>(entry: Result<(K, (A, V)), Error>) -> Result<(K, A, V), Error> {
+    match entry {
+        Ok((key, (aug, value))) => Ok((key, aug, value)),
+        Err(e) => Err(e),
+    }
+}
+
 impl Store for Block {
     fn store_into(
         &self,
@@ -121,7 +159,7 @@ impl<'a> Load<'a> for Block {
         let with_out_msg_queue_updates = match ok!(slice.load_u32()) {
             Self::TAG_V1 => false,
             Self::TAG_V2 => true,
-            _ => return Err(Error::InvalidTag),
+            tag => return Err(Error::InvalidTag(tag.into())),
         };
 
         let global_id = ok!(slice.load_u32()) as i32;
@@ -357,7 +395,7 @@ impl<'a> Load<'a> for BlockInfo {
             Ok(Self::TAG_V1) => false,
             #[cfg(any(feature = "venom", feature = "tycho"))]
             Ok(Self::TAG_V2) => true,
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         };
 
@@ -642,7 +680,7 @@ impl<'a> Load<'a> for ValueFlow {
         let with_copyleft_rewards = match ok!(slice.load_u32()) {
             Self::TAG_V1 => false,
             Self::TAG_V2 => true,
-            _ => return Err(Error::InvalidTag),
+            tag => return Err(Error::InvalidTag(tag.into())),
         };
 
         let fees_collected = ok!(CurrencyCollection::load_from(slice));
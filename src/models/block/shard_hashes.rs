@@ -506,7 +506,7 @@ impl<'a> Load<'a> for ShardDescription {
                 Ok(Self::TAG_V4) => (true, true, true, false),
                 #[cfg(feature = "venom")]
                 Ok(Self::TAG_V5) => (true, false, true, true),
-                Ok(_) => return Err(Error::InvalidTag),
+                Ok(tag) => return Err(Error::InvalidTag(tag.into())),
                 Err(e) => return Err(e),
             };
 
@@ -61,6 +61,29 @@ fn check_block(boc: &[u8], expected_shards: Option<Vec<ShardIdent>>) -> Cell {
         extra.account_blocks.cell.as_ref()
     );
 
+    let in_msgs = extra.load_in_msg_description().unwrap();
+    let mut in_msg_count = 0;
+    for entry in block.iter_in_msgs().unwrap() {
+        let (hash, fees, in_msg) = entry.unwrap();
+        assert_eq!(hash, *in_msg.load_msg_cell().unwrap().repr_hash());
+        assert_eq!(fees, in_msg.compute_fees().unwrap());
+        assert_eq!(in_msgs.get(hash).unwrap(), Some((fees, in_msg)));
+        in_msg_count += 1;
+    }
+    assert_eq!(in_msg_count, in_msgs.iter().count());
+
+    let out_msgs = extra.load_out_msg_description().unwrap();
+    let mut out_msg_count = 0;
+    for entry in block.iter_out_msgs().unwrap() {
+        let (hash, value, out_msg) = entry.unwrap();
+        if let Some(envelope) = out_msg.load_out_msg_envelope().unwrap() {
+            assert_eq!(hash, *envelope.message_hash());
+        }
+        assert_eq!(out_msgs.get(hash).unwrap(), Some((value, out_msg)));
+        out_msg_count += 1;
+    }
+    assert_eq!(out_msg_count, out_msgs.iter().count());
+
     let custom = extra.load_custom().unwrap();
     assert_eq!(expected_shards.is_some(), custom.is_some());
     if let Some(custom) = &custom {
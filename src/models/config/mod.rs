@@ -59,6 +59,13 @@ impl std::ops::DerefMut for BlockchainConfig {
 pub struct BlockchainConfigParams(Dict<u32, Cell>);
 
 impl BlockchainConfigParams {
+    /// Returns the config account address (in masterchain).
+    ///
+    /// Uses [`ConfigParam0`].
+    pub fn get_config_address(&self) -> Result<HashBytes, Error> {
+        ok!(self.get::<ConfigParam0>()).ok_or(Error::CellUnderflow)
+    }
+
     /// Returns the elector account address (in masterchain).
     ///
     /// Uses [`ConfigParam1`].
@@ -107,6 +114,37 @@ impl BlockchainConfigParams {
         self.set_raw(ConfigParam3::ID, ok!(CellBuilder::build_from(address)))
     }
 
+    /// Returns prices for minting extra currencies if specified.
+    ///
+    /// Uses [`ConfigParam6`].
+    pub fn get_mint_prices(&self) -> Result<Option<MintPrices>, Error> {
+        self.get::<ConfigParam6>()
+    }
+
+    /// Updates prices for minting extra currencies.
+    ///
+    /// Uses [`ConfigParam6`].
+    pub fn set_mint_prices(&mut self, prices: &MintPrices) -> Result<bool, Error> {
+        self.set_raw(ConfigParam6::ID, ok!(CellBuilder::build_from(prices)))
+    }
+
+    /// Returns the target amount of minted extra currencies if specified.
+    ///
+    /// Uses [`ConfigParam7`].
+    pub fn get_currencies_to_mint(&self) -> Result<Option<ExtraCurrencyCollection>, Error> {
+        self.get::<ConfigParam7>()
+    }
+
+    /// Updates the target amount of minted extra currencies.
+    ///
+    /// Uses [`ConfigParam7`].
+    pub fn set_currencies_to_mint(
+        &mut self,
+        currencies: &ExtraCurrencyCollection,
+    ) -> Result<bool, Error> {
+        self.set_raw(ConfigParam7::ID, ok!(CellBuilder::build_from(currencies)))
+    }
+
     /// Returns the lowest supported block version and required capabilities.
     ///
     /// Uses [`ConfigParam8`].
@@ -264,6 +302,38 @@ impl BlockchainConfigParams {
         ok!(self.get::<ConfigParam18>()).ok_or(Error::CellUnderflow)
     }
 
+    /// Returns a history of all storage prices sorted by `utime_since`.
+    ///
+    /// Uses [`ConfigParam18`].
+    pub fn storage_prices(&self) -> Result<Vec<StoragePrices>, Error> {
+        let dict = ok!(self.get_storage_prices());
+        let mut prices = ok!(dict.values().collect::<Result<Vec<_>, _>>());
+        prices.sort_by_key(|prices| prices.utime_since);
+        Ok(prices)
+    }
+
+    /// Computes the storage fee for the interval `from_utime..to_utime`.
+    ///
+    /// See [`StoragePrices::compute_storage_fee`] for details.
+    ///
+    /// Uses [`ConfigParam18`].
+    pub fn compute_storage_fee(
+        &self,
+        stats: CellTreeStats,
+        is_masterchain: bool,
+        from_utime: u32,
+        to_utime: u32,
+    ) -> Result<Tokens, Error> {
+        let prices = ok!(self.storage_prices());
+        Ok(StoragePrices::compute_storage_fee(
+            &prices,
+            stats,
+            is_masterchain,
+            from_utime,
+            to_utime,
+        ))
+    }
+
     /// Updates a list with a history of all storage prices.
     ///
     /// Uses [`ConfigParam18`].
@@ -837,7 +907,10 @@ define_config_params! {
     4 => ConfigParam4(HashBytes),
 
     /// Mint new price and mint add price (unused).
-    6 => ConfigParam6(CellSlice<'a>),
+    ///
+    /// Contains [`MintPrices`].
+    #[serde(transparent)]
+    6 => ConfigParam6(MintPrices),
 
     /// Target amount of minted extra currencies.
     #[serde(transparent)]
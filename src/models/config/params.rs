@@ -113,7 +113,7 @@ impl<'a> Load<'a> for WorkchainDescription {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
         match slice.load_u8() {
             Ok(Self::TAG) => {}
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         }
 
@@ -200,7 +200,7 @@ impl<'a> Load<'a> for WorkchainFormat {
         Ok(match ok!(slice.load_small_uint(4)) {
             0x1 => Self::Basic(ok!(WorkchainFormatBasic::load_from(slice))),
             0x0 => Self::Extended(ok!(WorkchainFormatExtended::load_from(slice))),
-            _ => return Err(Error::InvalidTag),
+            tag => return Err(Error::InvalidTag(tag.into())),
         })
     }
 }
@@ -240,6 +240,16 @@ impl WorkchainFormatExtended {
     }
 }
 
+/// Prices for minting extra currencies.
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MintPrices {
+    /// Price for minting a new currency.
+    pub mint_new_price: Tokens,
+    /// Price for minting an additional amount of an existing currency.
+    pub mint_add_price: Tokens,
+}
+
 /// Block creation reward.
 #[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -308,6 +318,70 @@ pub struct StoragePrices {
     pub mc_cell_price_ps: u64,
 }
 
+impl StoragePrices {
+    /// Computes the price (shifted by 16 bits) of storing
+    /// the specified amount of cells and bits for one second.
+    pub fn compute_price_per_second(&self, stats: CellTreeStats, is_masterchain: bool) -> u128 {
+        let (bit_price, cell_price) = if is_masterchain {
+            (self.mc_bit_price_ps, self.mc_cell_price_ps)
+        } else {
+            (self.bit_price_ps, self.cell_price_ps)
+        };
+        (stats.bit_count as u128 * bit_price as u128)
+            .saturating_add(stats.cell_count as u128 * cell_price as u128)
+    }
+
+    /// Computes the storage fee for the interval `from_utime..to_utime`.
+    ///
+    /// The interval is split across price epochs from the history
+    /// of `prices` sorted by `utime_since`. The resulting fee is rounded up
+    /// after the sum of all epochs is divided by `2^16`.
+    pub fn compute_storage_fee(
+        prices: &[Self],
+        stats: CellTreeStats,
+        is_masterchain: bool,
+        from_utime: u32,
+        to_utime: u32,
+    ) -> Tokens {
+        let Some(first) = prices.first() else {
+            return Tokens::ZERO;
+        };
+        if to_utime <= from_utime || from_utime == 0 || to_utime <= first.utime_since {
+            return Tokens::ZERO;
+        }
+
+        // Find the epoch which contains the start of the interval
+        let start = prices
+            .iter()
+            .rposition(|epoch| epoch.utime_since <= from_utime)
+            .unwrap_or_default();
+
+        let mut total = 0u128;
+        let mut upto = std::cmp::max(from_utime, first.utime_since);
+        for (i, epoch) in prices.iter().enumerate().skip(start) {
+            if upto >= to_utime {
+                break;
+            }
+
+            let valid_until = match prices.get(i + 1) {
+                Some(next) => std::cmp::min(to_utime, next.utime_since),
+                None => to_utime,
+            };
+            if upto < valid_until {
+                let delta = (valid_until - upto) as u128;
+                total = total.saturating_add(
+                    epoch
+                        .compute_price_per_second(stats, is_masterchain)
+                        .saturating_mul(delta),
+                );
+            }
+            upto = valid_until;
+        }
+
+        Tokens::new(total.div_ceil(1 << 16))
+    }
+}
+
 /// Gas limits and prices.
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -384,7 +458,7 @@ impl<'a> Load<'a> for GasLimitsPrices {
                     result.delete_due_limit = ok!(slice.load_u64());
                     return Ok(result);
                 }
-                Ok(_) => return Err(Error::InvalidTag),
+                Ok(tag) => return Err(Error::InvalidTag(tag.into())),
                 Err(e) => return Err(e),
             }
         }
@@ -483,7 +557,7 @@ impl<'a> Load<'a> for CatchainConfig {
         let flags = match slice.load_u8() {
             Ok(Self::TAG_V1) => 0,
             Ok(Self::TAG_V2) => ok!(slice.load_u8()),
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         };
         if flags >> 2 != 0 {
@@ -557,7 +631,7 @@ impl<'a> Load<'a> for ConsensusConfig {
                 }
                 (flags, ok!(NonZeroU8::load_from(slice)).into())
             }
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         };
         Ok(Self {
@@ -763,7 +837,7 @@ impl<'a> Load<'a> for ValidatorSet {
         let with_total_weight = match slice.load_u8() {
             Ok(Self::TAG_V1) => false,
             Ok(Self::TAG_V2) => true,
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         };
 
@@ -949,7 +1023,7 @@ impl<'a> Load<'a> for ValidatorDescription {
             Ok(Self::TAG_BASIC) => (false, false),
             Ok(Self::TAG_WITH_ADNL) => (true, false),
             Ok(Self::TAG_WITH_MC_SEQNO) => (true, true),
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         };
 
@@ -957,7 +1031,7 @@ impl<'a> Load<'a> for ValidatorDescription {
             public_key: {
                 match slice.load_u32() {
                     Ok(Self::PUBKEY_TAG) => ok!(slice.load_u256()),
-                    Ok(_) => return Err(Error::InvalidTag),
+                    Ok(tag) => return Err(Error::InvalidTag(tag.into())),
                     Err(e) => return Err(e),
                 }
             },
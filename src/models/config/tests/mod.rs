@@ -2,7 +2,9 @@ use std::num::NonZeroU32;
 
 use super::*;
 use crate::boc::BocRepr;
-use crate::models::{ShardIdent, ShardStateUnsplit};
+use crate::dict::RawDict;
+use crate::models::{Block, ShardIdent, ShardStateUnsplit, TxInfo};
+use crate::num::VarUint248;
 use crate::prelude::Boc;
 
 #[test]
@@ -26,8 +28,13 @@ fn simple_config() {
     assert_eq!(blockchain_config.get::<ConfigParam4>().unwrap(), None);
 
     assert!(blockchain_config.get::<ConfigParam6>().unwrap().is_none());
+    assert!(blockchain_config.get_mint_prices().unwrap().is_none());
 
     assert!(blockchain_config.get::<ConfigParam7>().unwrap().is_some());
+    assert_eq!(
+        blockchain_config.get_currencies_to_mint().unwrap(),
+        blockchain_config.get::<ConfigParam7>().unwrap()
+    );
 
     assert_eq!(
         blockchain_config.get::<ConfigParam8>().unwrap(),
@@ -235,8 +242,11 @@ fn prod_config() {
         let data = Boc::decode(data).unwrap();
         let config = data.parse::<BlockchainConfig>().unwrap();
 
+        assert_eq!(config.get_config_address().unwrap(), [0x55; 32]);
         assert_eq!(config.get_elector_address().unwrap(), [0x33; 32]);
         assert_eq!(config.get_minter_address().unwrap(), [0x00; 32]);
+        assert_eq!(config.get_mint_prices().unwrap(), None);
+        config.get_currencies_to_mint().unwrap();
         assert_eq!(config.get_fee_collector_address().unwrap(), [0x33; 32]);
         config.get_global_version().unwrap();
 
@@ -303,6 +313,182 @@ fn prod_config() {
     check_config(include_bytes!("new_config.boc"));
 }
 
+#[test]
+fn load_dict_from_root() -> anyhow::Result<()> {
+    let config = Boc::decode(include_bytes!("new_config.boc"))?.parse::<BlockchainConfig>()?;
+    let expected = config.get_storage_prices()?;
+    assert!(!expected.is_empty());
+
+    // Param value cell is the dict root itself
+    let param = config.get_raw(ConfigParam18::ID)?.unwrap();
+
+    let prices = Dict::<u32, StoragePrices>::load_from_root(&mut param.clone())?;
+    assert_eq!(prices, expected);
+    let raw = RawDict::<32>::load_from_root(&mut param.clone())?;
+    assert_eq!(raw.root(), expected.root());
+
+    // `HashmapE` layout reads the first bit of the label as a maybe-bit
+    if let Ok(dict) = Dict::<u32, StoragePrices>::load_from(&mut param.clone()) {
+        assert_ne!(dict, expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn storage_fees() -> anyhow::Result<()> {
+    let config = Boc::decode(include_bytes!("new_config.boc"))?.parse::<BlockchainConfig>()?;
+
+    let prices = config.storage_prices()?;
+    assert_eq!(
+        prices,
+        [StoragePrices {
+            utime_since: 0,
+            bit_price_ps: 1,
+            cell_price_ps: 500,
+            mc_bit_price_ps: 1000,
+            mc_cell_price_ps: 500000,
+        }]
+    );
+
+    let stats = CellTreeStats {
+        bit_count: 10000,
+        cell_count: 20,
+    };
+    let from_utime = 1700000000;
+    let to_utime = from_utime + 86400;
+
+    // Empty intervals
+    assert_eq!(
+        config.compute_storage_fee(stats, false, to_utime, from_utime)?,
+        Tokens::ZERO
+    );
+    assert_eq!(
+        config.compute_storage_fee(stats, false, from_utime, from_utime)?,
+        Tokens::ZERO
+    );
+    assert_eq!(
+        config.compute_storage_fee(stats, false, 0, to_utime)?,
+        Tokens::ZERO
+    );
+
+    // Fractions are rounded up
+    let stats = CellTreeStats {
+        bit_count: 1,
+        cell_count: 0,
+    };
+    assert_eq!(
+        config.compute_storage_fee(stats, false, from_utime, from_utime + 1)?,
+        Tokens::new(1)
+    );
+
+    // Multiple price epochs
+    let mut config = config;
+    let make_prices = |utime_since: u32, bit_price_ps: u64| StoragePrices {
+        utime_since,
+        bit_price_ps,
+        cell_price_ps: 0,
+        mc_bit_price_ps: bit_price_ps * 10,
+        mc_cell_price_ps: 0,
+    };
+    config.set_storage_prices(&[make_prices(200, 3), make_prices(100, 1)])?;
+    assert_eq!(
+        config.storage_prices()?,
+        [make_prices(100, 1), make_prices(200, 3)]
+    );
+
+    let stats = CellTreeStats {
+        bit_count: 1 << 16,
+        cell_count: 0,
+    };
+    for (from_utime, to_utime, expected) in [
+        (10, 100, 0),
+        (10, 150, 50),
+        (50, 250, 100 + 50 * 3),
+        (150, 300, 50 + 100 * 3),
+        (250, 260, 10 * 3),
+    ] {
+        let fee = config.compute_storage_fee(stats, false, from_utime, to_utime)?;
+        assert_eq!(fee, Tokens::new(expected));
+        let fee = config.compute_storage_fee(stats, true, from_utime, to_utime)?;
+        assert_eq!(fee, Tokens::new(expected * 10));
+    }
+
+    Ok(())
+}
+
+// NOTE: block fixtures use the non-venom shard state layout
+#[cfg(not(feature = "venom"))]
+#[test]
+fn storage_fees_from_transactions() -> anyhow::Result<()> {
+    let config = Boc::decode(include_bytes!("new_config.boc"))?.parse::<BlockchainConfig>()?;
+
+    let block = Boc::decode(include_bytes!("../../block/tests/simple_shard_block.boc"))?
+        .parse::<Block>()?;
+    assert!(!block.load_info()?.shard.is_masterchain());
+    let old_accounts = block
+        .load_state_update()?
+        .old
+        .parse::<ShardStateUnsplit>()?
+        .load_accounts()?;
+
+    let mut collected = Vec::new();
+    for entry in block.load_extra()?.account_blocks.load()?.iter() {
+        let (account, _, account_block) = entry?;
+
+        // Only the first transaction of the block pays for the storage
+        let (_, _, tx) = account_block.transactions.iter().next().unwrap()?;
+        let tx = tx.load()?;
+        let TxInfo::Ordinary(info) = tx.load_info()? else {
+            continue;
+        };
+        let fees_collected = info.storage_phase.unwrap().storage_fees_collected;
+
+        // Skip accounts which are not present in the block proof
+        let Ok(Some((_, state))) = old_accounts.get(account) else {
+            continue;
+        };
+        let storage = state.load_account()?.unwrap().storage_stat;
+
+        let stats = CellTreeStats {
+            bit_count: storage.used.bits.into_inner(),
+            cell_count: storage.used.cells.into_inner(),
+        };
+        let fee = config.compute_storage_fee(stats, false, storage.last_paid, tx.now)?;
+        assert_eq!(fee, fees_collected);
+        collected.push(fee);
+    }
+    assert_eq!(
+        collected,
+        [71, 6247, 11, 1468].map(Tokens::new),
+        "all transactions with storage fees must be checked"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn mint_params() -> anyhow::Result<()> {
+    let mut config = BlockchainConfig::new_empty(HashBytes([0x55; 32]));
+    assert_eq!(config.get_config_address()?, [0x55; 32]);
+    assert_eq!(config.get_mint_prices()?, None);
+    assert_eq!(config.get_currencies_to_mint()?, None);
+
+    let prices = MintPrices {
+        mint_new_price: Tokens::new(1_000_000_000),
+        mint_add_price: Tokens::new(100_000_000),
+    };
+    config.set_mint_prices(&prices)?;
+    assert_eq!(config.get_mint_prices()?, Some(prices));
+
+    let mut currencies = ExtraCurrencyCollection::new();
+    currencies.as_dict_mut().set(1, VarUint248::new(1000))?;
+    config.set_currencies_to_mint(&currencies)?;
+    assert_eq!(config.get_currencies_to_mint()?, Some(currencies));
+
+    Ok(())
+}
+
 #[test]
 fn create_config() {
     let mut config = BlockchainConfig::new_empty(HashBytes([0x55; 32]));
@@ -128,7 +128,7 @@ impl Store for IntAddr {
 impl<'a> Load<'a> for IntAddr {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
         if !ok!(slice.load_bit()) {
-            return Err(Error::InvalidTag);
+            return Err(Error::InvalidTag(0b0));
         }
 
         Ok(if unlikely(ok!(slice.load_bit())) {
@@ -328,8 +328,9 @@ impl Store for StdAddr {
 
 impl<'a> Load<'a> for StdAddr {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
-        if !ok!(slice.load_bit()) || ok!(slice.load_bit()) {
-            return Err(Error::InvalidTag);
+        let tag = ok!(slice.load_small_uint(2));
+        if tag != 0b10 {
+            return Err(Error::InvalidTag(tag.into()));
         }
 
         Ok(Self {
@@ -340,6 +341,16 @@ impl<'a> Load<'a> for StdAddr {
     }
 }
 
+/// Addresses are stored as is (`addr_std$10 anycast:nothing workchain_id:int8
+/// address:bits256`), so keys always occupy 267 bits.
+///
+/// Keys are ordered by the workchain as an unsigned byte and then by
+/// the address, e.g. basechain (`0`) addresses come before masterchain
+/// (`-1`) addresses. NOTE: signed iteration doesn't change the order of
+/// workchains since the highest bit of all keys is the same.
+///
+/// Addresses with anycast can't be used as keys. Use [`StdAddrKey`]
+/// for a compact 264-bit key ordered by the signed workchain.
 impl crate::dict::DictKey for StdAddr {
     const BITS: u16 = StdAddr::BITS_WITHOUT_ANYCAST;
 
@@ -387,6 +398,97 @@ impl crate::dict::DictKey for StdAddr {
     }
 }
 
+/// Compact standard address dictionary key (`workchain_id:int8 address:bits256`).
+///
+/// Keys always occupy 264 bits. The sign bit of the workchain is inverted,
+/// so keys are ordered by the signed workchain and then by the address,
+/// e.g. masterchain (`-1`) addresses come before basechain (`0`) addresses.
+/// NOTE: this order is only preserved by unsigned iteration.
+#[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct StdAddrKey {
+    /// Workchain id (one-byte range).
+    pub workchain: i8,
+    /// Account id.
+    pub address: HashBytes,
+}
+
+impl StdAddrKey {
+    /// The number of data bits that the key occupies.
+    pub const BITS: u16 = 8 + 256;
+
+    /// Constructs a new address key.
+    #[inline]
+    pub const fn new(workchain: i8, address: HashBytes) -> Self {
+        Self { workchain, address }
+    }
+}
+
+impl std::fmt::Display for StdAddrKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}:{}", self.workchain, self.address))
+    }
+}
+
+impl From<(i8, HashBytes)> for StdAddrKey {
+    #[inline]
+    fn from((workchain, address): (i8, HashBytes)) -> Self {
+        Self::new(workchain, address)
+    }
+}
+
+impl From<StdAddrKey> for StdAddr {
+    #[inline]
+    fn from(value: StdAddrKey) -> Self {
+        Self::new(value.workchain, value.address)
+    }
+}
+
+impl TryFrom<&StdAddr> for StdAddrKey {
+    type Error = Error;
+
+    /// Fails if the address has anycast info.
+    fn try_from(value: &StdAddr) -> Result<Self, Self::Error> {
+        if value.anycast.is_some() {
+            return Err(Error::InvalidData);
+        }
+        Ok(Self::new(value.workchain, value.address))
+    }
+}
+
+impl Store for StdAddrKey {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        if !builder.has_capacity(Self::BITS, 0) {
+            return Err(Error::CellOverflow);
+        }
+        ok!(builder.store_u8(self.workchain as u8 ^ 0x80));
+        builder.store_u256(&self.address)
+    }
+}
+
+impl<'a> Load<'a> for StdAddrKey {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        if !slice.has_remaining(Self::BITS, 0) {
+            return Err(Error::CellUnderflow);
+        }
+        Ok(Self {
+            workchain: (ok!(slice.load_u8()) ^ 0x80) as i8,
+            address: ok!(slice.load_u256()),
+        })
+    }
+}
+
+impl crate::dict::DictKey for StdAddrKey {
+    const BITS: u16 = StdAddrKey::BITS;
+
+    #[inline]
+    fn from_raw_data([workchain, data @ ..]: &[u8; 128]) -> Option<Self> {
+        Some(Self {
+            workchain: (workchain ^ 0x80) as i8,
+            address: HashBytes(data[..32].try_into().unwrap()),
+        })
+    }
+}
+
 #[cfg(feature = "serde")]
 impl serde::Serialize for StdAddr {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -715,11 +817,120 @@ mod tests {
             .unwrap();
         dict.set(StdAddr::new(0, HashBytes([0x20; 32])), 432)
             .unwrap();
+        dict.set(StdAddr::new(1, HashBytes([0x00; 32])), 111)
+            .unwrap();
 
         for entry in dict.iter() {
             let (addr, value) = entry.unwrap();
             println!("{addr}: {value}");
         }
+
+        // Workchains are ordered as unsigned bytes
+        let expected = [
+            (StdAddr::new(0, HashBytes([0x10; 32])), 321),
+            (StdAddr::new(0, HashBytes([0x20; 32])), 432),
+            (StdAddr::new(1, HashBytes([0x00; 32])), 111),
+            (StdAddr::new(-1, HashBytes([0x33; 32])), 123),
+            (StdAddr::new(-1, HashBytes([0x55; 32])), 234),
+        ];
+        let entries = dict.iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(entries, expected);
+
+        let entries = dict
+            .iter()
+            .reversed()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(entries.iter().eq(expected.iter().rev()));
+
+        // Signed iteration doesn't change the order
+        let entries = dict.iter().signed().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(entries, expected);
+
+        assert_eq!(dict.get_min(true).unwrap(), Some(expected[0].clone()));
+        assert_eq!(
+            dict.get(StdAddr::new(-1, HashBytes([0x33; 32]))).unwrap(),
+            Some(123)
+        );
+        assert_eq!(
+            dict.get(StdAddr::new(-1, HashBytes([0x10; 32]))).unwrap(),
+            None
+        );
+
+        // Anycast addresses can't be used as keys
+        let mut addr = StdAddr::new(0, HashBytes([0x10; 32]));
+        addr.anycast = Some(Box::new(
+            Anycast::from_slice(
+                &{
+                    let mut prefix = CellBuilder::new();
+                    prefix.store_u8(0xa5).unwrap();
+                    prefix
+                }
+                .as_data_slice(),
+            )
+            .unwrap(),
+        ));
+        assert!(dict.set(addr, 0).is_err());
+    }
+
+    #[test]
+    fn std_addr_key_dict() {
+        let addr = StdAddrKey::new(-1, HashBytes([0x33; 32]));
+        let cell = CellBuilder::build_from(addr).unwrap();
+        assert_eq!(cell.bit_len(), StdAddrKey::BITS);
+        assert_eq!(cell.data()[0], 0x7f);
+        assert_eq!(cell.parse::<StdAddrKey>().unwrap(), addr);
+
+        let mut dict = Dict::<StdAddrKey, u32>::new();
+        dict.set(StdAddrKey::new(0, HashBytes([0x10; 32])), 321)
+            .unwrap();
+        dict.set(StdAddrKey::new(-1, HashBytes([0x55; 32])), 234)
+            .unwrap();
+        dict.set(StdAddrKey::new(1, HashBytes([0x00; 32])), 111)
+            .unwrap();
+        dict.set(StdAddrKey::new(-1, HashBytes([0x33; 32])), 123)
+            .unwrap();
+        dict.set(StdAddrKey::new(-128, HashBytes([0xff; 32])), 1)
+            .unwrap();
+
+        // Workchains are ordered as signed bytes
+        let expected = [
+            (StdAddrKey::new(-128, HashBytes([0xff; 32])), 1),
+            (StdAddrKey::new(-1, HashBytes([0x33; 32])), 123),
+            (StdAddrKey::new(-1, HashBytes([0x55; 32])), 234),
+            (StdAddrKey::new(0, HashBytes([0x10; 32])), 321),
+            (StdAddrKey::new(1, HashBytes([0x00; 32])), 111),
+        ];
+        let entries = dict.iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(entries, expected);
+
+        let mut sorted = expected;
+        sorted.sort();
+        assert_eq!(sorted, expected);
+
+        assert_eq!(
+            dict.get(StdAddrKey::new(0, HashBytes([0x10; 32]))).unwrap(),
+            Some(321)
+        );
+        assert_eq!(
+            StdAddr::from(expected[1].0),
+            StdAddr::new(-1, HashBytes([0x33; 32]))
+        );
+
+        let mut addr = StdAddr::new(0, HashBytes([0x10; 32]));
+        assert_eq!(StdAddrKey::try_from(&addr).unwrap(), expected[3].0);
+        addr.anycast = Some(Box::new(
+            Anycast::from_slice(
+                &{
+                    let mut prefix = CellBuilder::new();
+                    prefix.store_u8(0xa5).unwrap();
+                    prefix
+                }
+                .as_data_slice(),
+            )
+            .unwrap(),
+        ));
+        assert!(StdAddrKey::try_from(&addr).is_err());
     }
 
     #[test]
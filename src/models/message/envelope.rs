@@ -112,8 +112,13 @@ impl<'a> Load<'a> for IntermediateAddr {
 }
 
 /// Destination prefix length whithin the same workchain.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Store, Load)]
-#[tlb(validate_with = "Self::is_valid")]
+///
+/// # TLB scheme
+///
+/// ```text
+/// interm_addr_regular$0 use_dest_bits:(#<= 96) = IntermediateAddress;
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct IntermediateAddrRegular {
     /// Destination address prefix length in bits.
     use_dest_bits: u8,
@@ -123,6 +128,9 @@ impl IntermediateAddrRegular {
     /// Full address prefix length in bits.
     pub const FULL_BITS: u8 = 96;
 
+    /// Number of bits used to store the prefix length (`#<= 96`).
+    const BITS: u16 = 7;
+
     /// Returns whether the address prefix length is valid.
     pub const fn is_valid(&self) -> bool {
         self.use_dest_bits <= Self::FULL_BITS
@@ -151,6 +159,22 @@ impl IntermediateAddrRegular {
     }
 }
 
+impl Store for IntermediateAddrRegular {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        builder.store_small_uint(self.use_dest_bits, Self::BITS)
+    }
+}
+
+impl<'a> Load<'a> for IntermediateAddrRegular {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        let use_dest_bits = ok!(slice.load_small_uint(Self::BITS));
+        if unlikely(use_dest_bits > Self::FULL_BITS) {
+            return Err(Error::InvalidData);
+        }
+        Ok(Self { use_dest_bits })
+    }
+}
+
 /// Address prefix with a basic workchain id.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Load, Store)]
 pub struct IntermediateAddrSimple {
@@ -235,7 +235,7 @@ impl<'a> Load<'a> for InMsg {
             Self::MSG_IMPORT_IMM => InMsgFinal::load_from(slice).map(Self::Immediate),
             Self::MSG_IMPORT_FIN => InMsgFinal::load_from(slice).map(Self::Final),
             Self::MSG_IMPORT_TR => InMsgTransit::load_from(slice).map(Self::Transit),
-            _ => Err(Error::InvalidTag),
+            tag => Err(Error::InvalidTag(tag.into())),
         }
     }
 }
@@ -139,6 +139,87 @@ impl<I: ExactSize, B: ExactSize> BaseMessage<I, B> {
     }
 }
 
+impl Message<'_> {
+    /// Computes the normalized hash of an external incoming message.
+    ///
+    /// See [`OwnedMessage::normalized_hash`] for details.
+    pub fn normalized_hash(&self) -> Result<HashBytes, Error> {
+        compute_normalized_hash(&self.info, &self.body)
+    }
+
+    /// Computes the representation hash of the message body as a separate cell.
+    ///
+    /// See [`OwnedMessage::body_hash`] for details.
+    pub fn body_hash(&self) -> Result<HashBytes, Error> {
+        compute_body_hash(self.body)
+    }
+}
+
+impl OwnedMessage {
+    /// Computes the normalized hash of an external incoming message.
+    ///
+    /// The hash is computed over a canonical form of the message
+    /// (as described in TEP-467) which is not affected by the fields
+    /// that can be changed without invalidating the message signature:
+    /// - `src` is replaced with `addr_none`;
+    /// - `import_fee` is set to zero;
+    /// - state init is omitted;
+    /// - body is always stored as a reference.
+    ///
+    /// The message itself is left untouched.
+    ///
+    /// Returns [`Error::InvalidData`] for internal and external outgoing messages.
+    pub fn normalized_hash(&self) -> Result<HashBytes, Error> {
+        compute_normalized_hash(&self.info, &self.body)
+    }
+
+    /// Computes the representation hash of the message body as a separate cell.
+    ///
+    /// If the body occupies the whole cell, its hash is returned
+    /// without rebuilding the cell.
+    pub fn body_hash(&self) -> Result<HashBytes, Error> {
+        let (cell, range) = &self.body;
+        if range.is_full(cell.as_ref()) {
+            Ok(*cell.repr_hash())
+        } else {
+            compute_body_hash(ok!(range.apply(cell)))
+        }
+    }
+}
+
+fn compute_body_hash(body: CellSlice<'_>) -> Result<HashBytes, Error> {
+    if body.is_full() {
+        return Ok(*body.cell().repr_hash());
+    }
+
+    let mut builder = CellBuilder::new();
+    ok!(builder.store_slice(body));
+    let cell = ok!(builder.build());
+    Ok(*cell.repr_hash())
+}
+
+fn compute_normalized_hash(info: &MsgInfo, body: &dyn StoreBody) -> Result<HashBytes, Error> {
+    let MsgInfo::ExtIn(info) = info else {
+        return Err(Error::InvalidData);
+    };
+
+    let info = MsgInfo::ExtIn(ExtInMsgInfo {
+        src: None,
+        dst: info.dst.clone(),
+        import_fee: Tokens::ZERO,
+    });
+
+    let context = &mut Cell::empty_context();
+    let mut builder = CellBuilder::new();
+    ok!(info.store_into(&mut builder, context));
+    ok!(builder.store_bit_zero()); // init: nothing$0
+    ok!(builder.store_bit_one()); // body: right$1
+    ok!(body.store_body(true, &mut builder, context));
+
+    let cell = ok!(builder.build_ext(context));
+    Ok(*cell.repr_hash())
+}
+
 impl<I, B> Store for BaseMessage<I, B>
 where
     I: Store + ExactSize,
@@ -279,7 +360,8 @@ impl<'a> LoadBody<'a> for CellSlice<'a> {
 impl<'a> LoadBody<'a> for CellSliceParts {
     fn load_body(from_cell: bool, slice: &mut CellSlice<'a>) -> Result<Self, Error> {
         let body = ok!(if from_cell {
-            slice.load_reference_cloned()
+            let [body] = ok!(slice.load_references());
+            Ok(body)
         } else {
             let slice = slice.load_remaining();
             let mut builder = CellBuilder::new();
@@ -575,7 +657,7 @@ impl<'a> Load<'a> for RelaxedMsgInfo {
                 Err(e) => return Err(e),
             }
         } else {
-            return Err(Error::InvalidTag);
+            return Err(Error::InvalidTag(0b10));
         })
     }
 }
@@ -1044,7 +1126,7 @@ fn store_ext_addr(
 
 fn load_ext_addr(slice: &mut CellSlice<'_>) -> Result<Option<ExtAddr>, Error> {
     if ok!(slice.load_bit()) {
-        return Err(Error::InvalidTag);
+        return Err(Error::InvalidTag(0b1));
     }
 
     if !ok!(slice.load_bit()) {
@@ -1082,9 +1164,10 @@ fn store_opt_int_addr(
 fn load_opt_int_addr(slice: &mut CellSlice<'_>) -> Result<Option<IntAddr>, Error> {
     if ok!(slice.get_bit(0)) {
         IntAddr::load_from(slice).map(Some)
-    } else if ok!(slice.load_small_uint(2)) == 0b00 {
-        Ok(None)
     } else {
-        Err(Error::InvalidTag)
+        match ok!(slice.load_small_uint(2)) {
+            0b00 => Ok(None),
+            tag => Err(Error::InvalidTag(tag.into())),
+        }
     }
 }
@@ -208,7 +208,8 @@ impl<'a> Load<'a> for OutMsg {
             0b110 if ok!(slice.load_bit()) => {
                 OutMsgDequeueShort::load_from(slice).map(Self::DequeueShort)
             }
-            _ => Err(Error::InvalidTag),
+            0b110 => Err(Error::InvalidTag(0b1100)),
+            tag => Err(Error::InvalidTag(tag.into())),
         }
     }
 }
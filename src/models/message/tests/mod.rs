@@ -54,6 +54,147 @@ fn external_message() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn normalized_hash() -> anyhow::Result<()> {
+    let boc = Boc::decode(include_bytes!("external_message.boc"))?;
+    let body = Boc::decode(include_bytes!("external_message_body.boc"))?;
+    let message = boc.parse::<Message>()?;
+    let MsgInfo::ExtIn(info) = &message.info else {
+        anyhow::bail!("expected an external message");
+    };
+
+    // NOTE: Reference hashes are computed independently of this crate
+    // by serializing the TEP-467 canonical form of the message.
+    let expected =
+        "c261afa23ccffbb8cdf2fe1be9f8b5e3ad166f1a61f29946acd8b8f770d70608".parse::<HashBytes>()?;
+    assert_eq!(message.normalized_hash()?, expected);
+
+    // Owned version
+    let owned = boc.parse::<OwnedMessage>()?;
+    assert_eq!(owned.normalized_hash()?, expected);
+
+    // Fields which are not covered by the signature must not affect the hash
+    let deploy = Boc::decode(include_bytes!("internal_message_with_deploy.boc"))?;
+    let deploy = deploy.parse::<Message>()?;
+
+    for with_init in [false, true] {
+        let modified = serialize_message(Message {
+            info: MsgInfo::ExtIn(ExtInMsgInfo {
+                src: Some(ExtAddr::new(8, vec![0xaa]).unwrap()),
+                dst: info.dst.clone(),
+                import_fee: Tokens::new(123),
+            }),
+            init: if with_init { deploy.init.clone() } else { None },
+            body: body.as_slice()?,
+            layout: Some(MessageLayout {
+                init_to_cell: true,
+                body_to_cell: true,
+            }),
+        });
+        assert_ne!(modified.repr_hash(), boc.repr_hash());
+
+        let modified = modified.parse::<Message>()?;
+        assert_eq!(modified.normalized_hash()?, expected);
+    }
+
+    // Inline body must be hashed as a separate cell
+    let small_body = CellBuilder::build_from(0xdeadbeafu32)?;
+    let mut hashes = Vec::new();
+    for body_to_cell in [false, true] {
+        let message = serialize_message(Message {
+            info: MsgInfo::ExtIn(ExtInMsgInfo {
+                src: Some(ExtAddr::new(8, vec![0xaa]).unwrap()),
+                dst: info.dst.clone(),
+                import_fee: Tokens::new(123),
+            }),
+            init: None,
+            body: small_body.as_slice()?,
+            layout: Some(MessageLayout {
+                init_to_cell: false,
+                body_to_cell,
+            }),
+        });
+        hashes.push(message.parse::<Message>()?.normalized_hash()?);
+    }
+    let expected =
+        "2276aa3106db5debdbd380fdcb2fdea0617375697139f0eddba2ca0a7315398a".parse::<HashBytes>()?;
+    assert_eq!(hashes, [expected, expected]);
+
+    // Other message kinds are not supported
+    assert!(deploy.normalized_hash().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn signing_hashes() -> anyhow::Result<()> {
+    use sha2::Digest;
+
+    let boc = Boc::decode(include_bytes!("external_message.boc"))?;
+    let body = Boc::decode(include_bytes!("external_message_body.boc"))?;
+    let message = boc.parse::<Message>()?;
+
+    // Body stored as a reference
+    assert_eq!(message.body_hash()?, *body.repr_hash());
+    assert_eq!(boc.parse::<OwnedMessage>()?.body_hash()?, *body.repr_hash());
+
+    // Inline body is hashed as a separate cell
+    let body_data = [0xaa, 0xde, 0xad, 0xbe, 0xaf];
+    let inline_body = {
+        let mut builder = CellBuilder::new();
+        builder.store_raw(&body_data, 40)?;
+        builder.build()?
+    };
+    let with_inline_body = serialize_message(Message {
+        info: message.info.clone(),
+        init: None,
+        body: inline_body.as_slice()?,
+        layout: Some(MessageLayout {
+            init_to_cell: false,
+            body_to_cell: false,
+        }),
+    });
+
+    // Ordinary cell without references: d1 = 0, d2 = 2 * 40 / 8
+    let mut hasher = sha2::Sha256::new();
+    hasher.update([0, 10]);
+    hasher.update(body_data);
+    let expected = HashBytes::from(hasher.finalize());
+
+    assert_eq!(with_inline_body.parse::<Message>()?.body_hash()?, expected);
+    assert_eq!(
+        with_inline_body.parse::<OwnedMessage>()?.body_hash()?,
+        expected
+    );
+
+    // State init hashes
+    let deploy = Boc::decode(include_bytes!("internal_message_with_deploy.boc"))?;
+    let init = deploy.parse::<Message>()?.init.unwrap();
+    let code = init.code.as_ref().unwrap();
+    let data = init.data.as_ref().unwrap();
+    assert_eq!(
+        init.code_hash(),
+        Some(Boc::decode(Boc::encode(code))?.repr_hash())
+    );
+    assert_eq!(init.data_hash(), Some(data.repr_hash()));
+    assert_eq!(StateInit::default().code_hash(), None);
+
+    // Signable hash
+    let hash = message.body_hash()?;
+    assert_eq!(hash.signable_hash(None), hash);
+
+    let prefix = b"\xff\xffton-connect";
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(prefix);
+    hasher.update(hash.as_slice());
+    assert_eq!(
+        hash.signable_hash(Some(prefix)),
+        HashBytes::from(hasher.finalize())
+    );
+
+    Ok(())
+}
+
 #[test]
 fn external_outgoing() {
     let boc = check_message(include_bytes!("external_out_message.boc"));
@@ -14,6 +14,7 @@ pub use config::*;
 pub use currency::*;
 pub use global_version::*;
 pub use message::*;
+pub use pretty::{pretty_print, DisplayTlb, TlbDisplay, TlbFormatter};
 pub use shard::*;
 pub use transaction::*;
 pub use vm::*;
@@ -24,6 +25,7 @@ pub mod config;
 pub mod currency;
 pub mod global_version;
 pub mod message;
+pub mod pretty;
 pub mod shard;
 pub mod transaction;
 pub mod vm;
@@ -0,0 +1,609 @@
+//! TLB-like pretty printing of models.
+
+use std::fmt::{Display, Write};
+
+use crate::cell::*;
+use crate::dict::Dict;
+use crate::error::Error;
+use crate::num::*;
+
+use crate::models::account::*;
+use crate::models::currency::*;
+use crate::models::message::*;
+use crate::models::transaction::*;
+use crate::models::vm::*;
+use crate::models::Lazy;
+
+#[cfg(test)]
+mod tests;
+
+/// Parses the cell as the specified model and returns its
+/// TLB-like text representation.
+///
+/// Nested lazy cells are printed as their representation hashes
+/// unless `deep` is set.
+pub fn pretty_print<'a, T>(cell: &'a DynCell, deep: bool) -> Result<String, Error>
+where
+    T: Load<'a> + TlbDisplay,
+{
+    let value = ok!(cell.parse::<T>());
+
+    let mut result = String::new();
+    match write!(result, "{}", value.display_tlb(deep)) {
+        Ok(()) => Ok(result),
+        Err(_) => Err(Error::InvalidData),
+    }
+}
+
+impl DynCell {
+    /// Parses the cell as the specified model and returns its
+    /// TLB-like text representation.
+    ///
+    /// See [`pretty_print`].
+    pub fn parse_display<'a, T>(&'a self) -> Result<String, Error>
+    where
+        T: Load<'a> + TlbDisplay,
+    {
+        pretty_print::<T>(self, false)
+    }
+}
+
+/// A model with TLB-like text representation.
+pub trait TlbDisplay {
+    /// Writes the constructor name and all fields of the value.
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result;
+
+    /// Returns an object that implements [`Display`] for printing
+    /// the TLB-like representation of the value.
+    fn display_tlb(&self, deep: bool) -> DisplayTlb<'_, Self> {
+        DisplayTlb { value: self, deep }
+    }
+}
+
+/// Helper struct to print the TLB-like representation of the value.
+#[derive(Clone, Copy)]
+pub struct DisplayTlb<'a, T: ?Sized> {
+    value: &'a T,
+    deep: bool,
+}
+
+impl<T: TlbDisplay + ?Sized> Display for DisplayTlb<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt_tlb(&mut TlbFormatter {
+            f,
+            indent: 0,
+            deep: self.deep,
+        })
+    }
+}
+
+/// Indented TLB-like text writer.
+pub struct TlbFormatter<'a, 'b> {
+    f: &'a mut std::fmt::Formatter<'b>,
+    indent: usize,
+    deep: bool,
+}
+
+impl TlbFormatter<'_, '_> {
+    /// Returns whether nested lazy cells must be loaded and printed.
+    #[inline]
+    pub fn is_deep(&self) -> bool {
+        self.deep
+    }
+
+    /// Writes the constructor name of the current value.
+    #[inline]
+    pub fn constructor(&mut self, name: &str) -> std::fmt::Result {
+        self.f.write_str(name)
+    }
+
+    /// Writes a nested value on the new line.
+    pub fn field<T: TlbDisplay + ?Sized>(&mut self, name: &str, value: &T) -> std::fmt::Result {
+        ok!(self.begin_field(name));
+        self.indent += 1;
+        let res = value.fmt_tlb(self);
+        self.indent -= 1;
+        res
+    }
+
+    /// Writes a simple value on the new line.
+    pub fn field_display<T: Display + ?Sized>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> std::fmt::Result {
+        ok!(self.begin_field(name));
+        write!(self.f, "{value}")
+    }
+
+    /// Writes a cell reference as its representation hash.
+    pub fn field_cell(&mut self, name: &str, cell: &DynCell) -> std::fmt::Result {
+        self.field_display(name, &format_args!("^{}", cell.repr_hash()))
+    }
+
+    /// Writes a cell reference with the specified model.
+    ///
+    /// The model is loaded only if the formatter is deep.
+    pub fn field_cell_as<'a, T>(&mut self, name: &str, cell: &'a DynCell) -> std::fmt::Result
+    where
+        T: Load<'a> + TlbDisplay,
+    {
+        if !self.deep {
+            return self.field_cell(name, cell);
+        }
+
+        match cell.parse::<T>() {
+            Ok(value) => self.field(name, &value),
+            Err(e) => {
+                self.field_display(name, &format_args!("^{} (invalid: {e})", cell.repr_hash()))
+            }
+        }
+    }
+
+    /// Writes a lazy cell.
+    ///
+    /// The model is loaded only if the formatter is deep.
+    pub fn field_lazy<T>(&mut self, name: &str, value: &Lazy<T>) -> std::fmt::Result
+    where
+        for<'a> T: Load<'a> + TlbDisplay,
+    {
+        self.field_cell_as::<T>(name, value.inner().as_ref())
+    }
+
+    fn begin_field(&mut self, name: &str) -> std::fmt::Result {
+        let indent = (self.indent + 1) * 2;
+        write!(self.f, "\n{:indent$}{name}: ", "")
+    }
+}
+
+impl<T: TlbDisplay + ?Sized> TlbDisplay for &T {
+    #[inline]
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        T::fmt_tlb(self, f)
+    }
+}
+
+impl TlbDisplay for CellSlice<'_> {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(write!(f.f, "x{{{}}}", self.display_data()));
+        for i in 0..self.remaining_refs() {
+            if let Ok(cell) = self.get_reference(i) {
+                ok!(write!(f.f, " ^{}", cell.repr_hash()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TlbDisplay for CellSliceParts {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        let (cell, range) = self;
+        match range.apply(cell) {
+            Ok(slice) => slice.fmt_tlb(f),
+            Err(e) => write!(f.f, "^{} (invalid: {e})", cell.repr_hash()),
+        }
+    }
+}
+
+// === Addresses ===
+
+impl TlbDisplay for StdAddr {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("addr_std$10"));
+        ok!(fmt_anycast(f, self.anycast.as_deref()));
+        ok!(f.field_display("workchain_id", &self.workchain));
+        f.field_display("address", &self.address)
+    }
+}
+
+impl TlbDisplay for VarAddr {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("addr_var$11"));
+        ok!(fmt_anycast(f, self.anycast.as_deref()));
+        ok!(f.field_display("addr_len", &self.address_len));
+        ok!(f.field_display("workchain_id", &self.workchain));
+        f.field_display("address", &hex::encode(&self.address))
+    }
+}
+
+impl TlbDisplay for IntAddr {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        match self {
+            Self::Std(addr) => addr.fmt_tlb(f),
+            Self::Var(addr) => addr.fmt_tlb(f),
+        }
+    }
+}
+
+impl TlbDisplay for ExtAddr {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("addr_extern$01"));
+        ok!(f.field_display("len", &self.data_bit_len));
+        f.field_display("external_address", self)
+    }
+}
+
+fn fmt_anycast(f: &mut TlbFormatter<'_, '_>, anycast: Option<&Anycast>) -> std::fmt::Result {
+    match anycast {
+        None => f.field_display("anycast", "nothing$0"),
+        Some(anycast) => f.field_display("anycast", &format_args!("just$1 {anycast}")),
+    }
+}
+
+/// Optional address (`addr_none$00` if empty).
+struct MaybeAddr<'a, T>(Option<&'a T>);
+
+impl<T: TlbDisplay> TlbDisplay for MaybeAddr<'_, T> {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        match self.0 {
+            None => f.constructor("addr_none$00"),
+            Some(addr) => addr.fmt_tlb(f),
+        }
+    }
+}
+
+// === Currencies ===
+
+impl TlbDisplay for CurrencyCollection {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("currencies$_"));
+        ok!(f.field_display("grams", &self.tokens));
+        f.field("other", &self.other)
+    }
+}
+
+impl TlbDisplay for ExtraCurrencyCollection {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("extra_currencies$_"));
+        for entry in self.as_dict().iter() {
+            ok!(match entry {
+                Ok((id, amount)) => f.field_display(&id.to_string(), &amount),
+                Err(e) => f.field_display("invalid", &e),
+            });
+        }
+        Ok(())
+    }
+}
+
+// === Messages ===
+
+impl<I: TlbDisplay, B: TlbDisplay> TlbDisplay for BaseMessage<I, B> {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("message$_"));
+        ok!(f.field("info", &self.info));
+        ok!(match &self.init {
+            None => f.field_display("init", "nothing$0"),
+            Some(init) => f.field("init", init),
+        });
+        f.field("body", &self.body)
+    }
+}
+
+impl TlbDisplay for MsgInfo {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        match self {
+            Self::Int(info) => info.fmt_tlb(f),
+            Self::ExtIn(info) => info.fmt_tlb(f),
+            Self::ExtOut(info) => info.fmt_tlb(f),
+        }
+    }
+}
+
+impl TlbDisplay for RelaxedMsgInfo {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        match self {
+            Self::Int(info) => info.fmt_tlb(f),
+            Self::ExtOut(info) => info.fmt_tlb(f),
+        }
+    }
+}
+
+impl TlbDisplay for IntMsgInfo {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("int_msg_info$0"));
+        ok!(f.field_display("ihr_disabled", &self.ihr_disabled));
+        ok!(f.field_display("bounce", &self.bounce));
+        ok!(f.field_display("bounced", &self.bounced));
+        ok!(f.field("src", &self.src));
+        ok!(f.field("dest", &self.dst));
+        ok!(f.field("value", &self.value));
+        ok!(f.field_display("ihr_fee", &self.ihr_fee));
+        ok!(f.field_display("fwd_fee", &self.fwd_fee));
+        ok!(f.field_display("created_lt", &self.created_lt));
+        f.field_display("created_at", &self.created_at)
+    }
+}
+
+impl TlbDisplay for RelaxedIntMsgInfo {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("int_msg_info$0"));
+        ok!(f.field_display("ihr_disabled", &self.ihr_disabled));
+        ok!(f.field_display("bounce", &self.bounce));
+        ok!(f.field_display("bounced", &self.bounced));
+        ok!(f.field("src", &MaybeAddr(self.src.as_ref())));
+        ok!(f.field("dest", &self.dst));
+        ok!(f.field("value", &self.value));
+        ok!(f.field_display("ihr_fee", &self.ihr_fee));
+        ok!(f.field_display("fwd_fee", &self.fwd_fee));
+        ok!(f.field_display("created_lt", &self.created_lt));
+        f.field_display("created_at", &self.created_at)
+    }
+}
+
+impl TlbDisplay for ExtInMsgInfo {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("ext_in_msg_info$10"));
+        ok!(f.field("src", &MaybeAddr(self.src.as_ref())));
+        ok!(f.field("dest", &self.dst));
+        f.field_display("import_fee", &self.import_fee)
+    }
+}
+
+impl TlbDisplay for ExtOutMsgInfo {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("ext_out_msg_info$11"));
+        ok!(f.field("src", &self.src));
+        ok!(f.field("dest", &MaybeAddr(self.dst.as_ref())));
+        ok!(f.field_display("created_lt", &self.created_lt));
+        f.field_display("created_at", &self.created_at)
+    }
+}
+
+impl TlbDisplay for RelaxedExtOutMsgInfo {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("ext_out_msg_info$11"));
+        ok!(f.field("src", &MaybeAddr(self.src.as_ref())));
+        ok!(f.field("dest", &MaybeAddr(self.dst.as_ref())));
+        ok!(f.field_display("created_lt", &self.created_lt));
+        f.field_display("created_at", &self.created_at)
+    }
+}
+
+// === Accounts ===
+
+impl TlbDisplay for OptionalAccount {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        match &self.0 {
+            None => f.constructor("account_none$0"),
+            Some(account) => account.fmt_tlb(f),
+        }
+    }
+}
+
+impl TlbDisplay for Account {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("account$1"));
+        ok!(f.field("addr", &self.address));
+        ok!(f.field("storage_stat", &self.storage_stat));
+        ok!(f.field_display("last_trans_lt", &self.last_trans_lt));
+        ok!(f.field("balance", &self.balance));
+        ok!(f.field("state", &self.state));
+        match &self.init_code_hash {
+            None => Ok(()),
+            Some(hash) => f.field_display("init_code_hash", hash),
+        }
+    }
+}
+
+impl TlbDisplay for StorageInfo {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("storage_info$_"));
+        ok!(f.field("used", &self.used));
+        ok!(f.field_display("last_paid", &self.last_paid));
+        match &self.due_payment {
+            None => f.field_display("due_payment", "nothing$0"),
+            Some(due) => f.field_display("due_payment", due),
+        }
+    }
+}
+
+impl TlbDisplay for StorageUsed {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("storage_used$_"));
+        ok!(f.field_display("cells", &self.cells));
+        ok!(f.field_display("bits", &self.bits));
+        f.field_display("public_cells", &self.public_cells)
+    }
+}
+
+impl TlbDisplay for AccountState {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        match self {
+            Self::Uninit => f.constructor("account_uninit$00"),
+            Self::Active(state_init) => {
+                ok!(f.constructor("account_active$1"));
+                f.field("state_init", state_init)
+            }
+            Self::Frozen(hash) => {
+                ok!(f.constructor("account_frozen$01"));
+                f.field_display("state_hash", hash)
+            }
+        }
+    }
+}
+
+impl TlbDisplay for AccountStatus {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        f.constructor(match self {
+            Self::Uninit => "acc_state_uninit$00",
+            Self::Frozen => "acc_state_frozen$01",
+            Self::Active => "acc_state_active$10",
+            Self::NotExists => "acc_state_nonexist$11",
+        })
+    }
+}
+
+impl TlbDisplay for StateInit {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("state_init$_"));
+        ok!(match &self.split_depth {
+            None => f.field_display("split_depth", "nothing$0"),
+            Some(depth) => f.field_display("split_depth", &depth.into_bit_len()),
+        });
+        ok!(match &self.special {
+            None => f.field_display("special", "nothing$0"),
+            Some(special) => f.field_display(
+                "special",
+                &format_args!("tick_tock$_ tick:{} tock:{}", special.tick, special.tock),
+            ),
+        });
+        ok!(fmt_maybe_cell(f, "code", self.code.as_deref()));
+        ok!(fmt_maybe_cell(f, "data", self.data.as_deref()));
+        fmt_dict(f, "library", &self.libraries)
+    }
+}
+
+fn fmt_maybe_cell(
+    f: &mut TlbFormatter<'_, '_>,
+    name: &str,
+    cell: Option<&DynCell>,
+) -> std::fmt::Result {
+    match cell {
+        None => f.field_display(name, "nothing$0"),
+        Some(cell) => f.field_cell(name, cell),
+    }
+}
+
+fn fmt_dict<K, V>(f: &mut TlbFormatter<'_, '_>, name: &str, dict: &Dict<K, V>) -> std::fmt::Result {
+    match dict.root() {
+        None => f.field_display(name, "hme_empty$0"),
+        Some(root) => f.field_display(name, &format_args!("hme_root$1 ^{}", root.repr_hash())),
+    }
+}
+
+// === Transactions ===
+
+impl TlbDisplay for Transaction {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("transaction$0111"));
+        ok!(f.field_display("account_addr", &self.account));
+        ok!(f.field_display("lt", &self.lt));
+        ok!(f.field_display("prev_trans_hash", &self.prev_trans_hash));
+        ok!(f.field_display("prev_trans_lt", &self.prev_trans_lt));
+        ok!(f.field_display("now", &self.now));
+        ok!(f.field_display("outmsg_cnt", &self.out_msg_count));
+        ok!(f.field("orig_status", &self.orig_status));
+        ok!(f.field("end_status", &self.end_status));
+        ok!(match &self.in_msg {
+            None => f.field_display("in_msg", "nothing$0"),
+            Some(cell) => f.field_cell_as::<Message<'_>>("in_msg", cell.as_ref()),
+        });
+        ok!(f.field("out_msgs", &OutMsgs(&self.out_msgs)));
+        ok!(f.field("total_fees", &self.total_fees));
+        ok!(f.field_lazy("state_update", &self.state_update));
+        f.field_lazy("description", &self.info)
+    }
+}
+
+/// Outgoing messages dictionary.
+struct OutMsgs<'a>(&'a Dict<Uint15, Cell>);
+
+impl TlbDisplay for OutMsgs<'_> {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return f.constructor("hme_empty$0");
+        }
+
+        ok!(f.constructor("hme_root$1"));
+        for entry in self.0.iter() {
+            ok!(match entry {
+                Ok((index, cell)) => {
+                    f.field_cell_as::<Message<'_>>(&index.to_string(), cell.as_ref())
+                }
+                Err(e) => f.field_display("invalid", &e),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl TlbDisplay for HashUpdate {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        ok!(f.constructor("update_hashes#72"));
+        ok!(f.field_display("old_hash", &self.old));
+        f.field_display("new_hash", &self.new)
+    }
+}
+
+impl TlbDisplay for TxInfo {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        match self {
+            Self::Ordinary(info) => {
+                ok!(f.constructor("trans_ord$0000"));
+                ok!(f.field_display("credit_first", &info.credit_first));
+                ok!(fmt_maybe_debug(
+                    f,
+                    "storage_ph",
+                    info.storage_phase.as_ref()
+                ));
+                ok!(fmt_maybe_debug(f, "credit_ph", info.credit_phase.as_ref()));
+                ok!(f.field_display("compute_ph", &format_args!("{:?}", info.compute_phase)));
+                ok!(fmt_maybe_debug(f, "action", info.action_phase.as_ref()));
+                ok!(f.field_display("aborted", &info.aborted));
+                ok!(fmt_maybe_debug(f, "bounce", info.bounce_phase.as_ref()));
+                f.field_display("destroyed", &info.destroyed)
+            }
+            Self::TickTock(info) => {
+                ok!(f.constructor("trans_tick_tock$001"));
+                ok!(f.field_display("is_tock", &(info.kind == TickTock::Tock)));
+                ok!(f.field_display("storage_ph", &format_args!("{:?}", info.storage_phase)));
+                ok!(f.field_display("compute_ph", &format_args!("{:?}", info.compute_phase)));
+                ok!(fmt_maybe_debug(f, "action", info.action_phase.as_ref()));
+                ok!(f.field_display("aborted", &info.aborted));
+                f.field_display("destroyed", &info.destroyed)
+            }
+        }
+    }
+}
+
+fn fmt_maybe_debug<T: std::fmt::Debug>(
+    f: &mut TlbFormatter<'_, '_>,
+    name: &str,
+    value: Option<&T>,
+) -> std::fmt::Result {
+    match value {
+        None => f.field_display(name, "nothing$0"),
+        Some(value) => f.field_display(name, &format_args!("{value:?}")),
+    }
+}
+
+// === Actions ===
+
+impl TlbDisplay for OutAction {
+    fn fmt_tlb(&self, f: &mut TlbFormatter<'_, '_>) -> std::fmt::Result {
+        match self {
+            Self::SendMsg { mode, out_msg } => {
+                ok!(f.constructor("action_send_msg#0ec3c86d"));
+                ok!(f.field_display("mode", &format_args!("{} {mode:?}", mode.bits())));
+                f.field_lazy("out_msg", out_msg)
+            }
+            Self::SetCode { new_code } => {
+                ok!(f.constructor("action_set_code#ad4de08e"));
+                f.field_cell("new_code", new_code.as_ref())
+            }
+            Self::ReserveCurrency { mode, value } => {
+                ok!(f.constructor("action_reserve_currency#36e6b809"));
+                ok!(f.field_display("mode", &format_args!("{} {mode:?}", mode.bits())));
+                f.field("currency", value)
+            }
+            Self::ChangeLibrary { mode, lib } => {
+                ok!(f.constructor("action_change_library#26fa1dd4"));
+                ok!(f.field_display("mode", &format_args!("{} {mode:?}", *mode as u8)));
+                match lib {
+                    LibRef::Hash(hash) => {
+                        f.field_display("libref", &format_args!("libref_hash$0 {hash}"))
+                    }
+                    LibRef::Cell(cell) => f.field_display(
+                        "libref",
+                        &format_args!("libref_ref$1 ^{}", cell.repr_hash()),
+                    ),
+                }
+            }
+            Self::CopyLeft { license, address } => {
+                ok!(f.constructor("action_copyleft#24486f7a"));
+                ok!(f.field_display("license", license));
+                f.field_display("address", address)
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use super::*;
+use crate::boc::Boc;
+
+fn check_snapshot(actual: &str, expected: &str) {
+    let expected = expected.trim_end();
+    if actual != expected {
+        panic!("snapshot mismatch:\n--- expected\n{expected}\n--- actual\n{actual}\n");
+    }
+}
+
+#[test]
+fn internal_message() {
+    let cell = Boc::decode(include_bytes!(
+        "../../message/tests/internal_message_with_deploy.boc"
+    ))
+    .unwrap();
+    let printed = cell.parse_display::<Message<'_>>().unwrap();
+    check_snapshot(&printed, include_str!("internal_message.txt"));
+}
+
+#[test]
+fn external_message() {
+    let cell = Boc::decode(include_bytes!("../../message/tests/external_message.boc")).unwrap();
+    let printed = pretty_print::<Message<'_>>(cell.as_ref(), false).unwrap();
+    check_snapshot(&printed, include_str!("external_message.txt"));
+}
+
+#[test]
+fn ordinary_transaction() {
+    let cell = Boc::decode(include_bytes!(
+        "../../transaction/tests/ordinary_tx_with_outgoing.boc"
+    ))
+    .unwrap();
+
+    let printed = pretty_print::<Transaction>(cell.as_ref(), false).unwrap();
+    check_snapshot(&printed, include_str!("ordinary_transaction.txt"));
+
+    let printed = pretty_print::<Transaction>(cell.as_ref(), true).unwrap();
+    check_snapshot(&printed, include_str!("ordinary_transaction_deep.txt"));
+}
+
+#[test]
+fn tick_transaction() {
+    let cell = Boc::decode(include_bytes!("../../transaction/tests/tick_tx.boc")).unwrap();
+    let printed = pretty_print::<Transaction>(cell.as_ref(), true).unwrap();
+    check_snapshot(&printed, include_str!("tick_transaction_deep.txt"));
+}
+
+#[test]
+fn account() {
+    let state = Boc::decode(include_bytes!("../../shard/tests/new_zerostate.boc")).unwrap();
+    let state = state.parse::<crate::models::ShardStateUnsplit>().unwrap();
+    let accounts = state.load_accounts().unwrap();
+
+    let (_, _, shard_account) = accounts.iter().next().unwrap().unwrap();
+    let account = shard_account.load_account().unwrap().unwrap();
+    check_snapshot(
+        &account.display_tlb(false).to_string(),
+        include_str!("account.txt"),
+    );
+}
+
+#[test]
+fn out_actions() {
+    let out_msg = Lazy::new(&OwnedRelaxedMessage {
+        info: RelaxedMsgInfo::ExtOut(RelaxedExtOutMsgInfo {
+            src: None,
+            dst: None,
+            created_lt: 0,
+            created_at: 0,
+        }),
+        init: None,
+        body: Default::default(),
+        layout: None,
+    })
+    .unwrap();
+
+    let actions = [
+        OutAction::SendMsg {
+            mode: SendMsgFlags::PAY_FEE_SEPARATELY | SendMsgFlags::IGNORE_ERROR,
+            out_msg,
+        },
+        OutAction::SetCode {
+            new_code: Cell::empty_cell(),
+        },
+        OutAction::ReserveCurrency {
+            mode: ReserveCurrencyFlags::ALL_BUT,
+            value: CurrencyCollection::new(1000),
+        },
+        OutAction::ChangeLibrary {
+            mode: ChangeLibraryMode::AddPublic,
+            lib: LibRef::Hash(HashBytes([0x11; 32])),
+        },
+    ];
+
+    let printed = actions
+        .iter()
+        .map(|action| action.display_tlb(true).to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    check_snapshot(&printed, include_str!("out_actions.txt"));
+}
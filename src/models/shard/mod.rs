@@ -168,6 +168,16 @@ impl ShardStateUnsplit {
         SHARD_ACCOUNTS.get_or_init(|| Lazy::new(&ShardAccounts::new()).unwrap())
     }
 
+    /// Returns the libraries dictionary with publisher tracking helpers.
+    pub fn libraries(&self) -> &Libraries {
+        Libraries::wrap(&self.libraries)
+    }
+
+    /// Returns the mutable libraries dictionary with publisher tracking helpers.
+    pub fn libraries_mut(&mut self) -> &mut Libraries {
+        Libraries::wrap_mut(&mut self.libraries)
+    }
+
     /// Tries to load shard accounts dictionary.
     pub fn load_accounts(&self) -> Result<ShardAccounts, Error> {
         self.accounts.load()
@@ -264,7 +274,7 @@ impl<'a> Load<'a> for ShardStateUnsplit {
             Ok(Self::TAG_V1) => false,
             #[cfg(any(feature = "venom", feature = "tycho"))]
             Ok(Self::TAG_V2) => true,
-            Ok(_) => return Err(Error::InvalidTag),
+            Ok(tag) => return Err(Error::InvalidTag(tag.into())),
             Err(e) => return Err(e),
         };
 
@@ -352,7 +362,8 @@ impl Store for LibDescr {
         ok!(builder.store_small_uint(0, 2));
         ok!(builder.store_reference(self.lib.clone()));
         match self.publishers.root() {
-            Some(root) => builder.store_reference(root.clone()),
+            // NOTE: publishers are stored as a non-empty `Hashmap` inline
+            Some(root) => builder.store_slice(ok!(root.as_slice())),
             None => Err(Error::InvalidData),
         }
     }
@@ -360,8 +371,9 @@ impl Store for LibDescr {
 
 impl<'a> Load<'a> for LibDescr {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
-        if ok!(slice.load_small_uint(2)) != 0 {
-            return Err(Error::InvalidTag);
+        let tag = ok!(slice.load_small_uint(2));
+        if tag != 0 {
+            return Err(Error::InvalidTag(tag.into()));
         }
         Ok(Self {
             lib: ok!(slice.load_reference_cloned()),
@@ -370,6 +382,119 @@ impl<'a> Load<'a> for LibDescr {
     }
 }
 
+/// Dictionary with all shared libraries and their publishers.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Store, Load)]
+#[repr(transparent)]
+pub struct Libraries(Dict<HashBytes, LibDescr>);
+
+impl Libraries {
+    /// Creates an empty libraries dictionary.
+    pub const fn new() -> Self {
+        Self(Dict::new())
+    }
+
+    /// Wraps a reference to the libraries dictionary.
+    pub fn wrap(dict: &Dict<HashBytes, LibDescr>) -> &Self {
+        // SAFETY: `Libraries` is #[repr(transparent)]
+        unsafe { &*(dict as *const Dict<HashBytes, LibDescr> as *const Self) }
+    }
+
+    /// Wraps a mutable reference to the libraries dictionary.
+    pub fn wrap_mut(dict: &mut Dict<HashBytes, LibDescr>) -> &mut Self {
+        // SAFETY: `Libraries` is #[repr(transparent)]
+        unsafe { &mut *(dict as *mut Dict<HashBytes, LibDescr> as *mut Self) }
+    }
+
+    /// Returns `true` if there are no libraries.
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns a reference to the underlying dictionary.
+    pub const fn as_dict(&self) -> &Dict<HashBytes, LibDescr> {
+        &self.0
+    }
+
+    /// Returns a mutable reference to the underlying dictionary.
+    pub fn as_dict_mut(&mut self) -> &mut Dict<HashBytes, LibDescr> {
+        &mut self.0
+    }
+
+    /// Returns the library code with the specified hash.
+    pub fn get_lib(&self, hash: &HashBytes) -> Result<Option<Cell>, Error> {
+        match self.0.get(hash) {
+            Ok(descr) => Ok(descr.map(|descr| descr.lib)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns an iterator over the addresses of masterchain accounts
+    /// which publish the library with the specified hash.
+    ///
+    /// The iterator is empty if there is no such library.
+    pub fn publishers(&self, hash: &HashBytes) -> Result<std::vec::IntoIter<HashBytes>, Error> {
+        let Some(descr) = ok!(self.0.get(hash)) else {
+            return Ok(Vec::new().into_iter());
+        };
+
+        let mut publishers = Vec::new();
+        for publisher in descr.publishers.keys() {
+            publishers.push(ok!(publisher));
+        }
+        Ok(publishers.into_iter())
+    }
+
+    /// Registers the publisher of the library.
+    ///
+    /// Returns `false` if the library was already published by this account.
+    pub fn publish(&mut self, lib: Cell, publisher: HashBytes) -> Result<bool, Error> {
+        let hash = *lib.repr_hash();
+        let descr = match ok!(self.0.get(hash)) {
+            Some(mut descr) => {
+                if !ok!(descr.publishers.add(publisher, ())) {
+                    return Ok(false);
+                }
+                descr
+            }
+            None => {
+                let mut publishers = Dict::new();
+                ok!(publishers.set(publisher, ()));
+                LibDescr { lib, publishers }
+            }
+        };
+
+        ok!(self.0.set(hash, descr));
+        Ok(true)
+    }
+
+    /// Removes the publisher of the library.
+    /// The library is removed when the last publisher leaves.
+    ///
+    /// Returns `false` if the library was not published by this account.
+    pub fn unpublish(&mut self, hash: &HashBytes, publisher: &HashBytes) -> Result<bool, Error> {
+        let Some(mut descr) = ok!(self.0.get(hash)) else {
+            return Ok(false);
+        };
+        if ok!(descr.publishers.remove(publisher)).is_none() {
+            return Ok(false);
+        }
+
+        ok!(if descr.publishers.is_empty() {
+            self.0.remove(hash).map(|_| ())
+        } else {
+            self.0.set(hash, descr).map(|_| ())
+        });
+        Ok(true)
+    }
+}
+
+impl From<Dict<HashBytes, LibDescr>> for Libraries {
+    #[inline]
+    fn from(value: Dict<HashBytes, LibDescr>) -> Self {
+        Self(value)
+    }
+}
+
 /// Processed up to info for externals and internals.
 #[cfg(feature = "tycho")]
 #[derive(Debug, Default, Clone, Store, Load)]
@@ -1,6 +1,7 @@
 use crate::cell::*;
 use crate::dict::{AugDict, AugDictExtra};
 use crate::error::*;
+use crate::num::SplitDepth;
 
 use crate::models::currency::CurrencyCollection;
 use crate::models::ShardAccount;
@@ -12,21 +13,13 @@ pub type ShardAccounts = AugDict<HashBytes, DepthBalanceInfo, ShardAccount>;
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
 pub struct DepthBalanceInfo {
     /// Depth for which the balance was calculated.
-    pub split_depth: u8,
+    ///
+    /// Stored as zero when `None`.
+    pub split_depth: Option<SplitDepth>,
     /// Total balance for a subtree.
     pub balance: CurrencyCollection,
 }
 
-impl DepthBalanceInfo {
-    const SPLIT_DEPTH_BITS: u16 = 5;
-
-    /// Returns `true` if the split depth is valid.
-    #[inline]
-    pub const fn is_valid(&self) -> bool {
-        self.split_depth <= 30
-    }
-}
-
 impl AugDictExtra for DepthBalanceInfo {
     fn comp_add(
         left: &mut CellSlice,
@@ -50,24 +43,24 @@ impl Store for DepthBalanceInfo {
         builder: &mut CellBuilder,
         context: &mut dyn CellContext,
     ) -> Result<(), Error> {
-        if !self.is_valid() {
-            return Err(Error::IntOverflow);
-        }
-        ok!(builder.store_small_uint(self.split_depth, Self::SPLIT_DEPTH_BITS));
+        let split_depth = match self.split_depth {
+            Some(depth) => depth.into_bit_len() as u8,
+            None => 0,
+        };
+        ok!(builder.store_small_uint(split_depth, SplitDepth::BITS));
         self.balance.store_into(builder, context)
     }
 }
 
 impl<'a> Load<'a> for DepthBalanceInfo {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
-        let result = Self {
-            split_depth: ok!(slice.load_small_uint(Self::SPLIT_DEPTH_BITS)),
-            balance: ok!(CurrencyCollection::load_from(slice)),
+        let split_depth = match ok!(slice.load_small_uint(SplitDepth::BITS)) {
+            0 => None,
+            depth => Some(ok!(SplitDepth::new(depth))),
         };
-        if result.is_valid() {
-            Ok(result)
-        } else {
-            Err(Error::IntOverflow)
-        }
+        Ok(Self {
+            split_depth,
+            balance: ok!(CurrencyCollection::load_from(slice)),
+        })
     }
 }
@@ -1,10 +1,10 @@
 use crate::cell::*;
-use crate::dict::{AugDict, AugDictExtra, Dict};
+use crate::dict::{read_label, AugDict, AugDictExtra, Dict};
 use crate::error::Error;
 use crate::num::*;
 
 use crate::models::block::{BlockRef, ShardHashes};
-use crate::models::config::BlockchainConfig;
+use crate::models::config::{BlockchainConfig, ConfigParam0};
 use crate::models::currency::CurrencyCollection;
 
 /// Additional content for masterchain state.
@@ -34,6 +34,90 @@ pub struct McStateExtra {
 impl McStateExtra {
     const TAG: u16 = 0xcc26;
     const BLOCK_STATS_TAG: u8 = 0x17;
+
+    /// Returns the most recent blockchain config.
+    #[inline]
+    pub fn config(&self) -> &BlockchainConfig {
+        &self.config
+    }
+
+    /// Returns the most recent descriptions for all shards.
+    #[inline]
+    pub fn shards(&self) -> &ShardHashes {
+        &self.shards
+    }
+
+    /// Finds the latest key block with seqno less than the specified one.
+    ///
+    /// Subtrees of `prev_blocks` without key blocks are skipped
+    /// using the [`KeyMaxLt`] augmentation.
+    pub fn prev_key_block(&self, seqno: u32) -> Result<Option<BlockRef>, Error> {
+        fn find_prev_key_block(
+            node: &DynCell,
+            prefix: u64,
+            key_bit_len: u16,
+            seqno: u32,
+            context: &mut dyn CellContext,
+        ) -> Result<Option<BlockRef>, Error> {
+            let node = ok!(context.load_dyn_cell(node, LoadMode::Full));
+            let mut data = ok!(node.as_slice());
+            let mut label = ok!(read_label(&mut data, key_bit_len));
+
+            let label_bit_len = label.remaining_bits();
+            let prefix = (prefix << label_bit_len) | ok!(label.load_uint(label_bit_len));
+            let key_bit_len = key_bit_len - label_bit_len;
+
+            // Skip subtrees where all keys are not less than the seqno
+            if prefix << key_bit_len >= seqno as u64 {
+                return Ok(None);
+            }
+
+            // Skip subtrees without key blocks
+            if !ok!(KeyMaxLt::load_from(&mut data)).has_key_block {
+                return Ok(None);
+            }
+
+            if key_bit_len == 0 {
+                let value = ok!(KeyBlockRef::load_from(&mut data));
+                return Ok(value.is_key_block.then_some(value.block_ref));
+            }
+
+            // Visit the subtree with greater keys first
+            for bit in [1, 0] {
+                let child = ok!(data.get_reference(bit));
+                let child_prefix = (prefix << 1) | bit as u64;
+                if let Some(block_ref) = ok!(find_prev_key_block(
+                    child,
+                    child_prefix,
+                    key_bit_len - 1,
+                    seqno,
+                    context
+                )) {
+                    return Ok(Some(block_ref));
+                }
+            }
+            Ok(None)
+        }
+
+        match self.prev_blocks.dict().root() {
+            Some(root) if self.prev_blocks.root_extra().has_key_block => find_prev_key_block(
+                root.as_ref(),
+                0,
+                u32::BITS as u16,
+                seqno,
+                &mut Cell::empty_context(),
+            ),
+            _ => Ok(None),
+        }
+    }
+
+    /// Checks that the config address matches the address from the config param 0.
+    pub fn validate(&self) -> Result<(), Error> {
+        match ok!(self.config.get::<ConfigParam0>()) {
+            Some(address) if address == self.config.address => Ok(()),
+            _ => Err(Error::InvalidData),
+        }
+    }
 }
 
 impl Store for McStateExtra {
@@ -75,11 +159,7 @@ impl Store for McStateExtra {
 
 impl<'a> Load<'a> for McStateExtra {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
-        match slice.load_u16() {
-            Ok(Self::TAG) => {}
-            Ok(_) => return Err(Error::InvalidTag),
-            Err(e) => return Err(e),
-        }
+        ok!(slice.load_prefix_tag(Self::TAG as u64, 16));
 
         let shards = ok!(ShardHashes::load_from(slice));
         let config = ok!(BlockchainConfig::load_from(slice));
@@ -99,9 +179,7 @@ impl<'a> Load<'a> for McStateExtra {
             after_key_block: ok!(child_slice.load_bit()),
             last_key_block: ok!(Option::<BlockRef>::load_from(child_slice)),
             block_create_stats: if flags & 0b01 != 0 {
-                if ok!(child_slice.load_u8()) != Self::BLOCK_STATS_TAG {
-                    return Err(Error::InvalidTag);
-                }
+                ok!(child_slice.load_prefix_tag(Self::BLOCK_STATS_TAG as u64, 8));
                 Some(ok!(Dict::load_from(child_slice)))
             } else {
                 None
@@ -1,5 +1,7 @@
 use super::*;
-use crate::models::Block;
+use crate::dict::AugDict;
+use crate::models::{Account, AccountState, Block, OptionalAccount, ShardAccount, StateInit};
+use crate::num::SplitDepth;
 use crate::prelude::Boc;
 
 fn check_master_state(cell: Cell) {
@@ -16,6 +18,7 @@ fn check_master_state(cell: Cell) {
     for entry in shard_accounts.iter() {
         let (id, depth_balance, shard_state) = entry.unwrap();
         let account = shard_state.load_account().unwrap();
+        assert_eq!(shard_state.is_empty(), account.is_none());
         println!("{id}: {depth_balance:?} {account:#?}");
     }
 
@@ -33,6 +36,7 @@ fn check_master_state(cell: Cell) {
 
     let custom = data.load_custom().unwrap().unwrap();
     println!("custom: {custom:#?}");
+    custom.validate().unwrap();
     assert_eq!(
         CellBuilder::build_from(&custom).unwrap(),
         data.custom.unwrap().cell
@@ -56,5 +60,349 @@ fn new_zerostate() {
     let state_update = block.state_update.load().unwrap();
 
     let new_state = state_update.apply(&zerostate).unwrap();
-    check_master_state(new_state);
+    check_master_state(new_state.clone());
+
+    // Zerostate is the last key block for the first block
+    let new_state = new_state.parse::<ShardStateUnsplit>().unwrap();
+    let custom = new_state.load_custom().unwrap().unwrap();
+
+    let last_key_block = custom.last_key_block.clone().unwrap();
+    assert_eq!(last_key_block.seqno, 0);
+    assert_eq!(
+        custom.prev_key_block(new_state.seqno + 1).unwrap(),
+        Some(last_key_block)
+    );
+    assert_eq!(custom.prev_key_block(0).unwrap(), None);
+}
+
+#[test]
+fn prev_key_block_search() -> anyhow::Result<()> {
+    const BOC: &[u8] = include_bytes!("new_zerostate.boc");
+    let state = Boc::decode(BOC)?.parse::<ShardStateUnsplit>()?;
+    let mut custom = state.load_custom()?.unwrap();
+
+    let key_blocks = [0u32, 5, 6, 77, 1000, 1001, 65536, u32::MAX - 1];
+    let mut prev_blocks = AugDict::<u32, KeyMaxLt, KeyBlockRef>::new();
+    for seqno in (0..1100).chain(65530..65540).chain(u32::MAX - 3..=u32::MAX) {
+        let is_key_block = key_blocks.contains(&seqno);
+        let block_ref = BlockRef {
+            end_lt: seqno as u64 * 1000,
+            seqno,
+            root_hash: HashBytes([seqno as u8; 32]),
+            file_hash: HashBytes([!seqno as u8; 32]),
+        };
+        prev_blocks.set(
+            seqno,
+            KeyMaxLt {
+                has_key_block: is_key_block,
+                max_end_lt: block_ref.end_lt,
+            },
+            KeyBlockRef {
+                is_key_block,
+                block_ref,
+            },
+        )?;
+    }
+    custom.prev_blocks = prev_blocks;
+
+    let expected = |seqno: u32| key_blocks.iter().rev().find(|&&key| key < seqno).copied();
+    for seqno in (0..1200).chain(65530..65540).chain(u32::MAX - 3..=u32::MAX) {
+        let found = custom.prev_key_block(seqno)?;
+        assert_eq!(found.map(|block_ref| block_ref.seqno), expected(seqno));
+    }
+
+    // No key blocks at all
+    custom.prev_blocks = AugDict::new();
+    for seqno in [0, 1, 100] {
+        custom.prev_blocks.set(
+            seqno,
+            KeyMaxLt::default(),
+            KeyBlockRef {
+                is_key_block: false,
+                block_ref: BlockRef {
+                    end_lt: 0,
+                    seqno,
+                    root_hash: HashBytes::ZERO,
+                    file_hash: HashBytes::ZERO,
+                },
+            },
+        )?;
+    }
+    assert_eq!(custom.prev_key_block(u32::MAX)?, None);
+
+    Ok(())
+}
+
+#[test]
+fn libraries() -> anyhow::Result<()> {
+    const BOC: &[u8] = include_bytes!("everscale_zerostate.boc");
+    let state = Boc::decode(BOC)?.parse::<ShardStateUnsplit>()?;
+    assert!(!state.libraries.is_empty());
+
+    for entry in state.libraries.iter() {
+        let (hash, descr) = entry?;
+        assert_eq!(descr.lib.repr_hash(), &hash);
+        assert_eq!(state.libraries().get_lib(&hash)?, Some(descr.lib.clone()));
+
+        // LibDescr round-trip
+        let raw = state.libraries.get_raw(hash)?.unwrap();
+        assert_eq!(
+            CellBuilder::build_from(&descr)?,
+            CellBuilder::build_from(raw)?
+        );
+
+        let publishers = descr.publishers.keys().collect::<Result<Vec<_>, _>>()?;
+        assert!(!publishers.is_empty());
+        assert_eq!(
+            state.libraries().publishers(&hash)?.collect::<Vec<_>>(),
+            publishers
+        );
+    }
+
+    // Mutations
+    let lib = CellBuilder::build_from(0xdeadbeafu32)?;
+    let hash = *lib.repr_hash();
+
+    let mut libraries = Libraries::new();
+    assert_eq!(libraries.get_lib(&hash)?, None);
+    assert_eq!(libraries.publishers(&hash)?.len(), 0);
+    assert!(!libraries.unpublish(&hash, &HashBytes([1; 32]))?);
+
+    assert!(libraries.publish(lib.clone(), HashBytes([1; 32]))?);
+    assert!(!libraries.publish(lib.clone(), HashBytes([1; 32]))?);
+    assert!(libraries.publish(lib.clone(), HashBytes([2; 32]))?);
+    assert_eq!(libraries.get_lib(&hash)?, Some(lib.clone()));
+    assert_eq!(
+        libraries.publishers(&hash)?.collect::<Vec<_>>(),
+        [HashBytes([1; 32]), HashBytes([2; 32])]
+    );
+
+    let decoded = CellBuilder::build_from(&libraries)?.parse::<Libraries>()?;
+    assert_eq!(decoded, libraries);
+    let descr = libraries.as_dict().get(hash)?.unwrap();
+    assert_eq!(CellBuilder::build_from(&descr)?.parse::<LibDescr>()?, descr);
+
+    assert!(!libraries.unpublish(&hash, &HashBytes([3; 32]))?);
+    assert!(libraries.unpublish(&hash, &HashBytes([1; 32]))?);
+    assert_eq!(
+        libraries.publishers(&hash)?.collect::<Vec<_>>(),
+        [HashBytes([2; 32])]
+    );
+    assert!(libraries.unpublish(&hash, &HashBytes([2; 32]))?);
+    assert!(libraries.is_empty());
+
+    // State accessors
+    let mut state =
+        Boc::decode(include_bytes!("everscale_zerostate.boc"))?.parse::<ShardStateUnsplit>()?;
+    assert!(state
+        .libraries_mut()
+        .publish(lib.clone(), HashBytes([1; 32]))?);
+    assert_eq!(state.libraries.get(hash)?.map(|descr| descr.lib), Some(lib));
+
+    Ok(())
+}
+
+#[test]
+fn shard_account_entries() -> anyhow::Result<()> {
+    let boc = Boc::decode(include_bytes!("everscale_zerostate.boc"))?;
+    let state = boc.parse::<ShardStateUnsplit>()?;
+    let accounts = state.load_accounts()?;
+
+    // Existing account
+    let (_, elector) = accounts.get([0x33; 32])?.unwrap();
+    assert!(!elector.is_empty());
+    let account = elector.load_account()?.unwrap();
+    assert_eq!(account.last_trans_lt, elector.last_trans().lt);
+
+    // Non-existing account
+    let empty = ShardAccount::default();
+    assert!(empty.is_empty());
+    assert_eq!(empty.load_account()?, None);
+    assert!(empty.last_trans().is_zero());
+    assert_eq!(
+        empty.account.inner(),
+        &CellBuilder::build_from(OptionalAccount::EMPTY)?
+    );
+
+    let cell = CellBuilder::build_from(&empty)?;
+    let parsed = Boc::decode(Boc::encode(&cell))?.parse::<ShardAccount>()?;
+    assert!(parsed.is_empty());
+    assert_eq!(parsed, empty);
+
+    Ok(())
+}
+
+#[test]
+fn shard_accounts_root_extra() -> anyhow::Result<()> {
+    fn compute_extra(
+        entries: &[(HashBytes, DepthBalanceInfo, ShardAccount)],
+    ) -> (Option<SplitDepth>, u128) {
+        let mut split_depth = None;
+        let mut tokens = 0;
+        for (_, extra, _) in entries {
+            split_depth = std::cmp::max(split_depth, extra.split_depth);
+            tokens += extra.balance.tokens.into_inner();
+        }
+        (split_depth, tokens)
+    }
+
+    let boc = Boc::decode(include_bytes!("everscale_zerostate.boc"))?;
+    let state = boc.parse::<ShardStateUnsplit>()?;
+    let original = state.load_accounts()?;
+
+    let mut entries = original.iter().collect::<Result<Vec<_>, _>>()?;
+    assert!(entries.len() > 2);
+
+    // Use different split depths
+    for (i, (_, extra, _)) in entries.iter_mut().enumerate() {
+        extra.split_depth = SplitDepth::new((i % 31) as u8).ok();
+    }
+
+    // Rebuild accounts
+    let mut accounts = ShardAccounts::new();
+    for (key, extra, account) in &entries {
+        accounts.set(key, extra, account)?;
+    }
+
+    let (split_depth, tokens) = compute_extra(&entries);
+    assert_eq!(accounts.root_extra().split_depth, split_depth);
+    assert_eq!(accounts.root_extra().balance.tokens.into_inner(), tokens);
+    assert_eq!(
+        accounts.root_extra().balance.tokens,
+        original.root_extra().balance.tokens
+    );
+
+    // Remove some accounts
+    for (key, ..) in entries.drain(..2) {
+        assert!(accounts.remove(key)?.is_some());
+    }
+
+    let (split_depth, tokens) = compute_extra(&entries);
+    assert_eq!(accounts.root_extra().split_depth, split_depth);
+    assert_eq!(accounts.root_extra().balance.tokens.into_inner(), tokens);
+
+    // Invalid split depth
+    let mut builder = CellBuilder::new();
+    builder.store_small_uint(31, 5)?;
+    CurrencyCollection::ZERO.store_into(&mut builder, &mut Cell::empty_context())?;
+    let cell = builder.build()?;
+    assert_eq!(
+        cell.parse::<DepthBalanceInfo>(),
+        Err(crate::error::Error::IntOverflow)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn account_proofs() -> anyhow::Result<()> {
+    use crate::merkle::MerkleProof;
+
+    let boc = Boc::decode(include_bytes!("everscale_zerostate.boc"))?;
+    let state = boc.parse::<ShardStateUnsplit>()?;
+    let accounts = state.load_accounts()?;
+
+    let existing = HashBytes([0x33; 32]);
+    let missing = HashBytes([0x11; 32]);
+    let not_included = accounts
+        .keys()
+        .map(|key| key.unwrap())
+        .find(|key| key != &existing)
+        .unwrap();
+
+    let proof = MerkleProof::create_for_accounts(
+        boc.as_ref(),
+        &[existing, missing],
+        &mut Cell::empty_context(),
+    )?;
+    proof.verify(boc.repr_hash())?;
+    assert!(proof.contains_hash(state.accounts.inner().repr_hash()));
+
+    // Same as the proof for dictionary keys
+    let dict = state.accounts.inner().reference(0).unwrap();
+    assert_eq!(
+        proof,
+        MerkleProof::create_for_keys(boc.as_ref(), dict, &[existing, missing])?
+    );
+
+    // Accounts can be read from the proof
+    let virtual_root = proof.cell.as_ref().virtualize();
+    let virtual_accounts = (0..virtual_root.reference_count())
+        .filter_map(|i| virtual_root.reference_cloned(i))
+        .find(|cell| cell.repr_hash() == state.accounts.inner().repr_hash())
+        .unwrap()
+        .parse::<ShardAccounts>()?;
+
+    assert_eq!(virtual_accounts.get(existing)?, accounts.get(existing)?);
+    assert_eq!(virtual_accounts.get(missing)?, None);
+    assert!(virtual_accounts.get(not_included).is_err());
+
+    // Empty accounts dictionary
+    let state = ShardStateUnsplit {
+        accounts: Lazy::new(&ShardAccounts::new())?,
+        ..state
+    };
+    let cell = CellBuilder::build_from(&state)?;
+
+    let proof =
+        MerkleProof::create_for_accounts(cell.as_ref(), &[missing], &mut Cell::empty_context())?;
+    proof.verify(cell.repr_hash())?;
+    assert!(proof.contains_hash(state.accounts.inner().repr_hash()));
+
+    // Not a shard state
+    let cell = CellBuilder::build_from(123u32)?;
+    assert!(MerkleProof::create_for_accounts(
+        cell.as_ref(),
+        &[missing],
+        &mut Cell::empty_context()
+    )
+    .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn frozen_account_state_hash() -> anyhow::Result<()> {
+    let boc = Boc::decode(include_bytes!("everscale_zerostate.boc"))?;
+    let state = boc.parse::<ShardStateUnsplit>()?;
+    let accounts = state.load_accounts()?;
+
+    let (_, elector) = accounts.get([0x33; 32])?.unwrap();
+    let mut account = elector.load_account()?.unwrap();
+    let AccountState::Active(init) = account.state.clone() else {
+        anyhow::bail!("elector must be active");
+    };
+
+    let hash = *CellBuilder::build_from(&init)?.repr_hash();
+    assert_eq!(init.compute_hash()?, hash);
+    assert_eq!(account.state_hash()?, Some(hash));
+    assert!(!account.can_thaw_with(&init)?);
+
+    // Freeze the account
+    account.state = AccountState::Frozen(hash);
+    let cell = CellBuilder::build_from(OptionalAccount(Some(account)))?;
+    let account = Boc::decode(Boc::encode(&cell))?
+        .parse::<OptionalAccount>()?
+        .0
+        .unwrap();
+    assert_eq!(account.state, AccountState::Frozen(hash));
+    assert_eq!(account.state_hash()?, Some(hash));
+    assert!(account.can_thaw_with(&init)?);
+
+    let other = StateInit {
+        data: None,
+        ..init.clone()
+    };
+    assert!(!account.can_thaw_with(&other)?);
+    assert!(!account.can_thaw_with(&StateInit::default())?);
+
+    // Uninit accounts have no state
+    let uninit = Account {
+        state: AccountState::Uninit,
+        ..account
+    };
+    assert_eq!(uninit.state_hash()?, None);
+    assert!(!uninit.can_thaw_with(&init)?);
+
+    Ok(())
 }
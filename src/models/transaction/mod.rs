@@ -63,10 +63,44 @@ impl Transaction {
         }
     }
 
+    /// Returns the representation hash of the incoming message, if present.
+    ///
+    /// The message itself is not parsed.
+    pub fn in_msg_hash(&self) -> Option<HashBytes> {
+        self.in_msg.as_ref().map(|cell| *cell.repr_hash())
+    }
+
     /// Tries to load the detailed transaction info from the lazy cell.
     pub fn load_info(&self) -> Result<TxInfo, Error> {
         self.info.load()
     }
+
+    /// Returns a pointer to the previous transaction on the same account.
+    pub fn prev_trans(&self) -> TxPointer {
+        TxPointer {
+            hash: self.prev_trans_hash,
+            lt: self.prev_trans_lt,
+        }
+    }
+}
+
+/// A reference to the transaction by its hash and logical time.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxPointer {
+    /// The exact hash of the transaction.
+    pub hash: HashBytes,
+    /// The exact logical time of the transaction.
+    pub lt: u64,
+}
+
+impl TxPointer {
+    /// Returns `true` if the pointer doesn't reference any transaction.
+    ///
+    /// The first transaction of the account has a zero previous pointer.
+    pub fn is_zero(&self) -> bool {
+        self.lt == 0 && self.hash == HashBytes::ZERO
+    }
 }
 
 impl Transaction {
@@ -80,6 +114,17 @@ impl Transaction {
             inner: self.out_msgs.raw_values(),
         }
     }
+
+    /// Gets an iterator over the representation hashes of the output messages
+    /// of this transaction, in order by lt. The messages themselves are not parsed.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn out_msg_hashes(&'_ self) -> TxOutMsgHashIter<'_> {
+        TxOutMsgHashIter {
+            inner: self.out_msgs.raw_values(),
+        }
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -207,6 +252,31 @@ impl<'a> Iterator for TxOutMsgIter<'a> {
     }
 }
 
+/// An iterator over the transaction output message hashes.
+///
+/// This struct is created by the [`out_msg_hashes`] method on [`Transaction`].
+/// See its documentation for more.
+///
+/// [`out_msg_hashes`]: Transaction::out_msg_hashes
+#[derive(Clone)]
+pub struct TxOutMsgHashIter<'a> {
+    inner: dict::RawValues<'a>,
+}
+
+impl<'a> Iterator for TxOutMsgHashIter<'a> {
+    type Item = Result<HashBytes, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner.next()? {
+            Ok(value) => match value.get_reference(0) {
+                Ok(cell) => Some(Ok(*cell.repr_hash())),
+                Err(e) => Some(Err(self.inner.finish(e))),
+            },
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
 impl Transaction {
     const TAG: u8 = 0b0111;
 }
@@ -242,11 +312,7 @@ impl Store for Transaction {
 
 impl<'a> Load<'a> for Transaction {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
-        match slice.load_small_uint(4) {
-            Ok(Self::TAG) => {}
-            Ok(_) => return Err(Error::InvalidTag),
-            Err(e) => return Err(e),
-        }
+        ok!(slice.load_prefix_tag(Self::TAG as u64, 4));
 
         let (in_msg, out_msgs) = {
             let slice = &mut ok!(slice.load_reference_as_slice());
@@ -316,8 +382,10 @@ impl<'a> Load<'a> for TxInfo {
                 Ok(info) => Self::Ordinary(info),
                 Err(e) => return Err(e),
             }
+        } else if tag_part == 0b000 {
+            return Err(Error::InvalidTag(0b0001));
         } else {
-            return Err(Error::InvalidTag);
+            return Err(Error::InvalidTag(tag_part.into()));
         })
     }
 }
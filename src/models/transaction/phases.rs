@@ -187,7 +187,7 @@ impl<'a> Load<'a> for ComputePhaseSkipReason {
             Ok(_) => {
                 if ok!(slice.load_bit()) {
                     // 0b11 -> 1
-                    Err(Error::InvalidTag)
+                    Err(Error::InvalidTag(0b111))
                 } else {
                     // 0b11 -> 0
                     Ok(Self::Suspended)
@@ -38,6 +38,19 @@ fn check_tx(boc: &[u8]) -> Cell {
     }
     assert_eq!(out_msg_count, tx.out_msg_count);
 
+    assert_eq!(
+        tx.in_msg_hash(),
+        tx.in_msg.as_ref().map(|cell| *cell.repr_hash())
+    );
+
+    let out_msg_hashes = tx.out_msg_hashes().collect::<Result<Vec<_>, _>>().unwrap();
+    let expected = tx
+        .out_msgs
+        .values()
+        .map(|cell| *cell.unwrap().repr_hash())
+        .collect::<Vec<_>>();
+    assert_eq!(out_msg_hashes, expected);
+
     let info = tx.load_info().unwrap();
     println!("info: {info:#?}");
     assert_eq!(tx.info.cell, CellBuilder::build_from(info).unwrap());
@@ -86,3 +99,33 @@ fn tick_tx() {
 fn tock_tx() {
     check_tx(include_bytes!("tock_tx.boc"));
 }
+
+#[test]
+fn tx_message_hashes() -> anyhow::Result<()> {
+    let tx =
+        Boc::decode(include_bytes!("ordinary_tx_with_outgoing.boc"))?.parse::<Transaction>()?;
+    assert_eq!(
+        tx.in_msg_hash(),
+        Some("8aad0ac9b0d646b950d3ea9eb71d73de4b1956322faa3b2f95c733e851d555fd".parse()?)
+    );
+
+    let out_msg_hashes = tx.out_msg_hashes().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        out_msg_hashes,
+        [
+            "31aed9d7bc3ec460ea7c6c24b1d7c51455def1207f98dfbb3ae4bf8813c0516b"
+                .parse::<HashBytes>()?,
+            "b55fd202d7ff59ef070dfd320acf8de4d81662c5f6611262882b0a74a3917e31".parse()?,
+            "1f91291b044364c69fc572d7bb9fd639041f36af835e0dbaca679f32b88c4247".parse()?,
+            "9c58efada03a8665000ed893a564dae00fe692acca089e63aabf8282fefa893a".parse()?,
+        ]
+    );
+
+    // Tick-tock transactions have no messages
+    let tx = Boc::decode(include_bytes!("tick_tx.boc"))?.parse::<Transaction>()?;
+    assert_eq!(tx.in_msg_hash(), None);
+    assert!(tx.load_in_msg()?.is_none());
+    assert_eq!(tx.out_msg_hashes().count(), 0);
+
+    Ok(())
+}
@@ -1,5 +1,9 @@
 //! VM related models.
 
 pub use self::out_actions::*;
+#[cfg(feature = "bigint")]
+pub use self::stack::*;
 
 mod out_actions;
+#[cfg(feature = "bigint")]
+mod stack;
@@ -1,7 +1,7 @@
 use bitflags::bitflags;
 
 use crate::cell::*;
-use crate::error::Error;
+use crate::error::{ActionError, Error};
 use crate::models::currency::CurrencyCollection;
 use crate::models::message::OwnedRelaxedMessage;
 use crate::models::Lazy;
@@ -45,6 +45,46 @@ impl<'a> Iterator for OutActionsRevIter<'a> {
     }
 }
 
+/// Maximum number of actions in the output actions list.
+pub const MAX_ACTIONS: u16 = 255;
+
+/// Walks the output actions list (`c5`) from its head, validating each
+/// entry with [`OutAction::validate`].
+///
+/// Returns the number of actions in the list.
+pub fn validate_action_list(c5: &DynCell) -> Result<u16, ActionError> {
+    let mut visited = ahash::HashSet::default();
+    let mut count = 0u16;
+
+    let mut cell = c5;
+    loop {
+        let mut slice = ok!(cell.as_slice().map_err(ActionError::Cell));
+        if slice.is_data_empty() && slice.is_refs_empty() {
+            return Ok(count);
+        }
+
+        if count >= MAX_ACTIONS {
+            return Err(ActionError::TooManyActions);
+        }
+        if !visited.insert(cell.repr_hash()) {
+            return Err(ActionError::Cycle);
+        }
+
+        // out_list$_ {n:#} prev:^(OutList n) action:OutAction = OutList (n + 1);
+        let Ok(prev) = slice.load_reference() else {
+            return Err(ActionError::InvalidListNode);
+        };
+        let action = match OutAction::load_from(&mut slice) {
+            Ok(action) if slice.is_data_empty() && slice.is_refs_empty() => action,
+            _ => return Err(ActionError::InvalidAction),
+        };
+        ok!(action.validate());
+
+        count += 1;
+        cell = prev;
+    }
+}
+
 bitflags! {
     /// Mode flags for `SendMsg` output action.
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -187,6 +227,46 @@ impl OutAction {
     const TAG_RESERVE: u32 = 0x36e6b809;
     const TAG_CHANGE_LIB: u32 = 0x26fa1dd4;
     const TAG_COPYLEFT: u32 = 0x24486f7a;
+
+    /// Checks that the action is structurally valid:
+    /// - `SendMsg` has known mode flags (and not both balance flags)
+    ///   and references a valid relaxed message;
+    /// - `ReserveCurrency` has known mode flags and a canonical value
+    ///   (no zero extra currencies).
+    ///
+    /// NOTE: no account state dependent checks are performed.
+    pub fn validate(&self) -> Result<(), ActionError> {
+        match self {
+            Self::SendMsg { mode, out_msg } => {
+                if !SendMsgFlags::all().contains(*mode)
+                    || mode
+                        .contains(SendMsgFlags::ALL_BALANCE | SendMsgFlags::WITH_REMAINING_BALANCE)
+                {
+                    return Err(ActionError::InvalidSendMode);
+                }
+                match out_msg.load() {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err(ActionError::InvalidMessage),
+                }
+            }
+            Self::ReserveCurrency { mode, value } => {
+                if !ReserveCurrencyFlags::all().contains(*mode) {
+                    return Err(ActionError::InvalidReserveMode);
+                }
+                if !value.tokens.is_valid() {
+                    return Err(ActionError::NonCanonicalValue);
+                }
+                for amount in value.other.as_dict().values() {
+                    let amount = ok!(amount.map_err(ActionError::Cell));
+                    if amount.is_zero() || !amount.is_valid() {
+                        return Err(ActionError::NonCanonicalValue);
+                    }
+                }
+                Ok(())
+            }
+            Self::SetCode { .. } | Self::ChangeLibrary { .. } | Self::CopyLeft { .. } => Ok(()),
+        }
+    }
 }
 
 impl Store for OutAction {
@@ -263,7 +343,150 @@ impl<'a> Load<'a> for OutAction {
                 license: ok!(slice.load_u8()),
                 address: ok!(slice.load_u256()),
             },
-            _ => return Err(Error::InvalidTag),
+            _ => return Err(Error::InvalidTag(tag.into())),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::message::{RelaxedExtOutMsgInfo, RelaxedMessage, RelaxedMsgInfo};
+
+    fn build_list(actions: &[OutAction]) -> anyhow::Result<Cell> {
+        let mut head = Cell::empty_cell();
+        for action in actions {
+            let mut builder = CellBuilder::new();
+            builder.store_reference(head)?;
+            action.store_into(&mut builder, &mut Cell::empty_context())?;
+            head = builder.build()?;
+        }
+        Ok(head)
+    }
+
+    fn copyleft() -> OutAction {
+        OutAction::CopyLeft {
+            license: 1,
+            address: HashBytes::ZERO,
+        }
+    }
+
+    #[test]
+    fn validate_actions() -> anyhow::Result<()> {
+        let message = CellBuilder::build_from(RelaxedMessage {
+            info: RelaxedMsgInfo::ExtOut(RelaxedExtOutMsgInfo::default()),
+            init: None,
+            body: Cell::empty_cell_ref().as_slice()?,
+            layout: None,
+        })?;
+
+        let send_msg = OutAction::SendMsg {
+            mode: SendMsgFlags::PAY_FEE_SEPARATELY | SendMsgFlags::IGNORE_ERROR,
+            out_msg: Lazy::from_raw(message),
+        };
+        let reserve = OutAction::ReserveCurrency {
+            mode: ReserveCurrencyFlags::ALL_BUT,
+            value: CurrencyCollection::new(1000),
+        };
+        let set_code = OutAction::SetCode {
+            new_code: Cell::empty_cell(),
+        };
+
+        let list = build_list(&[send_msg, reserve, set_code, copyleft()])?;
+        assert_eq!(validate_action_list(list.as_ref()), Ok(4));
+
+        // Empty list
+        assert_eq!(validate_action_list(Cell::empty_cell_ref()), Ok(0));
+
+        // Invalid send mode
+        let action = OutAction::SendMsg {
+            mode: SendMsgFlags::ALL_BALANCE | SendMsgFlags::WITH_REMAINING_BALANCE,
+            out_msg: Lazy::from_raw(Cell::empty_cell()),
+        };
+        assert_eq!(action.validate(), Err(ActionError::InvalidSendMode));
+
+        // Invalid message
+        let action = OutAction::SendMsg {
+            mode: SendMsgFlags::empty(),
+            out_msg: Lazy::from_raw(Cell::empty_cell()),
+        };
+        assert_eq!(action.validate(), Err(ActionError::InvalidMessage));
+        let list = build_list(&[copyleft(), action])?;
+        assert_eq!(
+            validate_action_list(list.as_ref()),
+            Err(ActionError::InvalidMessage)
+        );
+
+        // Invalid reserve mode
+        let action = OutAction::ReserveCurrency {
+            mode: ReserveCurrencyFlags::from_bits_retain(0x10),
+            value: CurrencyCollection::ZERO,
+        };
+        assert_eq!(action.validate(), Err(ActionError::InvalidReserveMode));
+
+        // Non-canonical extra currencies
+        let mut value = CurrencyCollection::new(1000);
+        value
+            .other
+            .as_dict_mut()
+            .set(1, crate::num::VarUint248::ZERO)?;
+        let action = OutAction::ReserveCurrency {
+            mode: ReserveCurrencyFlags::empty(),
+            value,
+        };
+        assert_eq!(action.validate(), Err(ActionError::NonCanonicalValue));
+
+        // Extra data after the action
+        let mut builder = CellBuilder::new();
+        builder.store_reference(Cell::empty_cell())?;
+        copyleft().store_into(&mut builder, &mut Cell::empty_context())?;
+        builder.store_bit_one()?;
+        let list = builder.build()?;
+        assert_eq!(
+            validate_action_list(list.as_ref()),
+            Err(ActionError::InvalidAction)
+        );
+
+        // Unknown action tag
+        let action = CellBuilder::build_from(0xdeadbeafu32)?;
+        assert!(matches!(
+            action.parse::<OutAction>(),
+            Err(Error::InvalidTag(0xdeadbeaf))
+        ));
+
+        // Missing reference to the previous node
+        let list = CellBuilder::build_from(0xdeadbeafu32)?;
+        assert_eq!(
+            validate_action_list(list.as_ref()),
+            Err(ActionError::InvalidListNode)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_long_action_list() -> anyhow::Result<()> {
+        let actions = (0..MAX_ACTIONS).map(|_| copyleft()).collect::<Vec<_>>();
+        let list = build_list(&actions)?;
+        assert_eq!(validate_action_list(list.as_ref()), Ok(MAX_ACTIONS));
+
+        let mut builder = CellBuilder::new();
+        builder.store_reference(list)?;
+        copyleft().store_into(&mut builder, &mut Cell::empty_context())?;
+        let list = builder.build()?;
+        assert_eq!(
+            validate_action_list(list.as_ref()),
+            Err(ActionError::TooManyActions)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_action_list_with_cycle() -> anyhow::Result<()> {
+        let list = LoopCell(build_list(&[copyleft()])?);
+        assert_eq!(validate_action_list(&list), Err(ActionError::Cycle));
+
+        Ok(())
+    }
+}
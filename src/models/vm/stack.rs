@@ -0,0 +1,505 @@
+use num_bigint::{BigInt, Sign};
+
+use crate::cell::*;
+use crate::error::Error;
+
+/// Maximum number of items in a [`StackValue::Tuple`].
+pub const MAX_TUPLE_LEN: usize = 255;
+
+/// Maximum number of items in a [`VmStack`].
+pub const MAX_STACK_DEPTH: usize = (1 << 24) - 1;
+
+/// A single TVM stack value.
+///
+/// Only a subset of values used by get-methods is supported,
+/// continuations can't be stored or loaded.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum StackValue {
+    /// Null value.
+    Null,
+    /// 257-bit signed integer.
+    Int(BigInt),
+    /// Integer which is not a number.
+    NaN,
+    /// Cell.
+    Cell(Cell),
+    /// Cell slice with its range.
+    Slice(CellSliceParts),
+    /// Cell builder.
+    Builder(CellBuilder),
+    /// Tuple with at most [`MAX_TUPLE_LEN`] items.
+    Tuple(Vec<StackValue>),
+}
+
+impl StackValue {
+    const NULL_TAG: u8 = 0x00;
+    const TINYINT_TAG: u8 = 0x01;
+    const INT_TAG: u16 = 0x0100;
+    const INT_TAG_BITS: u16 = 15;
+    const NAN_TAG: u16 = 0x02ff;
+    const CELL_TAG: u8 = 0x03;
+    const SLICE_TAG: u8 = 0x04;
+    const BUILDER_TAG: u8 = 0x05;
+    const TUPLE_TAG: u8 = 0x07;
+}
+
+impl Store for StackValue {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Null => builder.store_u8(Self::NULL_TAG),
+            Self::Int(value) => match i64::try_from(value) {
+                Ok(value) => {
+                    ok!(builder.store_u8(Self::TINYINT_TAG));
+                    builder.store_u64(value as u64)
+                }
+                Err(_) => {
+                    ok!(builder.store_uint(Self::INT_TAG as u64, Self::INT_TAG_BITS));
+                    store_int257(value, builder)
+                }
+            },
+            Self::NaN => builder.store_u16(Self::NAN_TAG),
+            Self::Cell(cell) => {
+                ok!(builder.store_u8(Self::CELL_TAG));
+                builder.store_reference(cell.clone())
+            }
+            Self::Slice(slice) => builder.store_slice_as_stack_value(slice),
+            Self::Builder(b) => {
+                ok!(builder.store_u8(Self::BUILDER_TAG));
+                let cell = ok!(b.clone().build_ext(context));
+                builder.store_reference(cell)
+            }
+            Self::Tuple(items) => {
+                if items.len() > MAX_TUPLE_LEN {
+                    return Err(Error::IntOverflow);
+                }
+                ok!(builder.store_u8(Self::TUPLE_TAG));
+                ok!(builder.store_u16(items.len() as u16));
+                store_tuple(items, builder, context)
+            }
+        }
+    }
+}
+
+impl<'a> Load<'a> for StackValue {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        match ok!(slice.peek_tag(8)) as u8 {
+            Self::NULL_TAG => {
+                ok!(slice.advance(8, 0));
+                Ok(Self::Null)
+            }
+            Self::TINYINT_TAG => {
+                ok!(slice.advance(8, 0));
+                Ok(Self::Int(BigInt::from(ok!(slice.load_u64()) as i64)))
+            }
+            0x02 => {
+                if slice.load_prefix_tag(Self::NAN_TAG as u64, 16).is_ok() {
+                    return Ok(Self::NaN);
+                }
+                ok!(slice.load_prefix_tag(Self::INT_TAG as u64, Self::INT_TAG_BITS));
+                load_int257(slice).map(Self::Int)
+            }
+            Self::CELL_TAG => {
+                ok!(slice.advance(8, 0));
+                slice.load_reference_cloned().map(Self::Cell)
+            }
+            Self::SLICE_TAG => slice.load_stack_slice().map(Self::Slice),
+            Self::BUILDER_TAG => {
+                ok!(slice.advance(8, 0));
+                let cell = ok!(slice.load_reference());
+                let mut builder = CellBuilder::new();
+                ok!(builder.store_slice(ok!(cell.as_slice())));
+                Ok(Self::Builder(builder))
+            }
+            Self::TUPLE_TAG => {
+                ok!(slice.advance(8, 0));
+                let len = ok!(slice.load_u16()) as usize;
+                if len > MAX_TUPLE_LEN {
+                    return Err(Error::IntOverflow);
+                }
+                let mut items = Vec::with_capacity(len);
+                ok!(load_tuple(slice, len, &mut items));
+                Ok(Self::Tuple(items))
+            }
+            // NOTE: continuations (0x06) are not supported
+            tag => Err(Error::InvalidTag(tag.into())),
+        }
+    }
+}
+
+/// TVM stack.
+///
+/// The last item is the top of the stack.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct VmStack {
+    /// Stack items from the bottom to the top.
+    pub items: Vec<StackValue>,
+}
+
+impl VmStack {
+    /// Creates a new stack from the specified items (from the bottom to the top).
+    pub fn new(items: Vec<StackValue>) -> Self {
+        Self { items }
+    }
+
+    /// Returns the number of items in the stack.
+    pub fn depth(&self) -> usize {
+        self.items.len()
+    }
+}
+
+impl Store for VmStack {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        let depth = self.items.len();
+        if depth > MAX_STACK_DEPTH {
+            return Err(Error::IntOverflow);
+        }
+        ok!(builder.store_uint(depth as u64, 24));
+
+        if let Some((top, rest)) = self.items.split_last() {
+            // vm_stk_cons#_ {n:#} rest:^(VmStackList n) tos:VmStackValue = VmStackList (n + 1);
+            let mut head = Cell::empty_cell();
+            for item in rest {
+                let mut b = CellBuilder::new();
+                ok!(b.store_reference(head));
+                ok!(item.store_into(&mut b, context));
+                head = ok!(b.build_ext(context));
+            }
+            ok!(builder.store_reference(head));
+            ok!(top.store_into(builder, context));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Load<'a> for VmStack {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        let depth = ok!(slice.load_uint(24)) as usize;
+
+        let mut items = Vec::with_capacity(std::cmp::min(depth, MAX_TUPLE_LEN));
+        if depth > 0 {
+            let mut rest = ok!(slice.load_reference_as_slice());
+            items.push(ok!(StackValue::load_from(slice)));
+
+            for _ in 1..depth {
+                let mut next = ok!(rest.load_reference_as_slice());
+                items.push(ok!(StackValue::load_from(&mut rest)));
+                std::mem::swap(&mut rest, &mut next);
+            }
+
+            // vm_stk_nil#_ = VmStackList 0;
+            if !rest.is_data_empty() || !rest.is_refs_empty() {
+                return Err(Error::InvalidData);
+            }
+        }
+
+        items.reverse();
+        Ok(Self { items })
+    }
+}
+
+// vm_tuple_nil$_ = VmTupleRef 0;
+// vm_tuple_leaf$_ entry:^VmStackValue = VmTupleRef 1;
+// vm_tuple_ref$_ {n:#} ref:^(VmTuple (n + 2)) = VmTupleRef (n + 2);
+//
+// vm_tuple_nil$_ = VmTuple 0;
+// vm_tuple_tcons$_ {n:#} head:(VmTupleRef n) tail:^VmStackValue = VmTuple (n + 1);
+
+fn store_tuple(
+    items: &[StackValue],
+    builder: &mut CellBuilder,
+    context: &mut dyn CellContext,
+) -> Result<(), Error> {
+    let Some((tail, head)) = items.split_last() else {
+        return Ok(());
+    };
+
+    match head {
+        [] => {}
+        [item] => ok!(builder.store_reference(ok!(build_value(item, context)))),
+        items => {
+            let mut b = CellBuilder::new();
+            ok!(store_tuple(items, &mut b, context));
+            ok!(builder.store_reference(ok!(b.build_ext(context))));
+        }
+    }
+    builder.store_reference(ok!(build_value(tail, context)))
+}
+
+fn load_tuple(
+    slice: &mut CellSlice<'_>,
+    len: usize,
+    items: &mut Vec<StackValue>,
+) -> Result<(), Error> {
+    if len == 0 {
+        return Ok(());
+    }
+
+    match len - 1 {
+        0 => {}
+        1 => {
+            let mut item = ok!(slice.load_reference_as_slice());
+            items.push(ok!(StackValue::load_from(&mut item)));
+        }
+        n => {
+            let mut head = ok!(slice.load_reference_as_slice());
+            ok!(load_tuple(&mut head, n, items));
+        }
+    }
+
+    let mut tail = ok!(slice.load_reference_as_slice());
+    items.push(ok!(StackValue::load_from(&mut tail)));
+    Ok(())
+}
+
+fn build_value(value: &StackValue, context: &mut dyn CellContext) -> Result<Cell, Error> {
+    let mut builder = CellBuilder::new();
+    ok!(value.store_into(&mut builder, context));
+    builder.build_ext(context)
+}
+
+fn store_int257(value: &BigInt, builder: &mut CellBuilder) -> Result<(), Error> {
+    let bytes = value.to_signed_bytes_be();
+    if bytes.len() > 33 {
+        return Err(Error::IntOverflow);
+    }
+
+    let fill = if value.sign() == Sign::Minus {
+        0xff
+    } else {
+        0x00
+    };
+    let mut buffer = [fill; 33];
+    buffer[33 - bytes.len()..].copy_from_slice(&bytes);
+
+    // All bits above the sign bit must be the same as the sign bit
+    if buffer[0] != fill {
+        return Err(Error::IntOverflow);
+    }
+
+    ok!(builder.store_bit(fill != 0));
+    builder.store_raw(&buffer[1..], 256)
+}
+
+fn load_int257(slice: &mut CellSlice<'_>) -> Result<BigInt, Error> {
+    let is_negative = ok!(slice.load_bit());
+
+    let mut buffer = [if is_negative { 0xff } else { 0x00 }; 33];
+    ok!(slice.load_raw(&mut buffer[1..], 256));
+    Ok(BigInt::from_signed_bytes_be(&buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_value(value: StackValue) -> anyhow::Result<()> {
+        let cell = CellBuilder::build_from(&value)?;
+        let parsed = cell.parse::<StackValue>()?;
+        assert_eq!(parsed, value);
+        Ok(())
+    }
+
+    #[test]
+    fn stack_value_round_trip() -> anyhow::Result<()> {
+        let cell = CellBuilder::build_from((123u32, Cell::empty_cell()))?;
+
+        check_value(StackValue::Null)?;
+        check_value(StackValue::NaN)?;
+        check_value(StackValue::Int(BigInt::from(0)))?;
+        check_value(StackValue::Int(BigInt::from(-123)))?;
+        check_value(StackValue::Int(BigInt::from(i64::MAX)))?;
+        check_value(StackValue::Int(BigInt::from(i64::MIN)))?;
+        check_value(StackValue::Cell(cell.clone()))?;
+
+        let mut slice = cell.as_slice()?;
+        slice.advance(8, 1)?;
+        check_value(StackValue::Slice((cell.clone(), slice.range())))?;
+
+        let mut builder = CellBuilder::new();
+        builder.store_u32(0xdeadbeef)?;
+        builder.store_reference(cell.clone())?;
+        check_value(StackValue::Builder(builder))?;
+
+        check_value(StackValue::Tuple(Vec::new()))?;
+        check_value(StackValue::Tuple(vec![StackValue::Null]))?;
+        check_value(StackValue::Tuple(
+            (0..MAX_TUPLE_LEN as i64)
+                .map(|i| StackValue::Int(BigInt::from(i)))
+                .collect(),
+        ))?;
+        check_value(StackValue::Tuple(vec![
+            StackValue::Int(BigInt::from(1)),
+            StackValue::Tuple(vec![StackValue::Cell(cell), StackValue::NaN]),
+            StackValue::Tuple(Vec::new()),
+        ]))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn stack_value_int257() -> anyhow::Result<()> {
+        let max: BigInt = (BigInt::from(1) << 256) - 1;
+        let min: BigInt = BigInt::from(-1) << 256;
+
+        for value in [
+            BigInt::from(i64::MAX) + 1,
+            BigInt::from(i64::MIN) - 1,
+            max.clone(),
+            min.clone(),
+        ] {
+            let cell = CellBuilder::build_from(StackValue::Int(value.clone()))?;
+            assert_eq!(cell.bit_len(), 15 + 257);
+            check_value(StackValue::Int(value))?;
+        }
+
+        // Tiny ints use a shorter encoding
+        let cell = CellBuilder::build_from(StackValue::Int(BigInt::from(-1)))?;
+        assert_eq!(cell.bit_len(), 8 + 64);
+
+        for value in [max + 1, min - 1] {
+            assert!(matches!(
+                CellBuilder::build_from(StackValue::Int(value)),
+                Err(Error::IntOverflow)
+            ));
+        }
+
+        let too_long = StackValue::Tuple(vec![StackValue::Null; MAX_TUPLE_LEN + 1]);
+        assert!(matches!(
+            CellBuilder::build_from(too_long),
+            Err(Error::IntOverflow)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn vm_stack_round_trip() -> anyhow::Result<()> {
+        let stack = VmStack::default();
+        let cell = CellBuilder::build_from(&stack)?;
+        assert_eq!(cell.bit_len(), 24);
+        assert_eq!(cell.parse::<VmStack>()?, stack);
+
+        let stack = VmStack::new(vec![
+            StackValue::Int(BigInt::from(1)),
+            StackValue::Null,
+            StackValue::Tuple(vec![StackValue::NaN, StackValue::Int(BigInt::from(-2))]),
+            StackValue::Cell(Cell::empty_cell()),
+        ]);
+        assert_eq!(stack.depth(), 4);
+
+        let cell = CellBuilder::build_from(&stack)?;
+        assert_eq!(cell.parse::<VmStack>()?, stack);
+
+        // Top of the stack is stored inline
+        let mut slice = cell.as_slice()?;
+        assert_eq!(slice.load_uint(24)?, 4);
+        slice.advance(0, 1)?;
+        assert_eq!(
+            StackValue::load_from(&mut slice)?,
+            StackValue::Cell(Cell::empty_cell())
+        );
+
+        // Continuations are not supported
+        let mut builder = CellBuilder::new();
+        builder.store_uint(1, 24)?;
+        builder.store_reference(Cell::empty_cell())?;
+        builder.store_u8(0x06)?;
+        let cell = builder.build()?;
+        assert!(matches!(
+            cell.parse::<VmStack>(),
+            Err(Error::InvalidTag(0x06))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn vm_stack_invalid_nil() -> anyhow::Result<()> {
+        let mut rest = CellBuilder::new();
+        rest.store_u8(0xff)?;
+        let rest = rest.build()?;
+
+        let mut builder = CellBuilder::new();
+        builder.store_uint(1, 24)?;
+        builder.store_reference(rest)?;
+        builder.store_u8(0x00)?;
+        let cell = builder.build()?;
+        assert!(matches!(cell.parse::<VmStack>(), Err(Error::InvalidData)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn stack_value_known_encodings() -> anyhow::Result<()> {
+        fn check_encoding(value: StackValue, bit_len: u16, data: &str) -> anyhow::Result<()> {
+            let cell = CellBuilder::build_from(&value)?;
+            assert_eq!(cell.bit_len(), bit_len);
+            assert_eq!(hex::encode(cell.data()), data);
+            assert_eq!(cell.parse::<StackValue>()?, value);
+            Ok(())
+        }
+
+        check_encoding(StackValue::Null, 8, "00")?;
+        check_encoding(StackValue::NaN, 16, "02ff")?;
+        check_encoding(StackValue::Int(BigInt::from(7)), 72, "010000000000000007")?;
+        check_encoding(StackValue::Int(BigInt::from(-1)), 72, "01ffffffffffffffff")?;
+        check_encoding(
+            StackValue::Int(BigInt::from(1) << 64),
+            272,
+            "02000000000000000000000000000000000000000000000000010000000000000000",
+        )?;
+        check_encoding(
+            StackValue::Int(BigInt::from(-1) << 256),
+            272,
+            "02010000000000000000000000000000000000000000000000000000000000000000",
+        )?;
+
+        Ok(())
+    }
+
+    // NOTE: These hashes are not produced by an emulator. They were computed
+    // by a standalone Python implementation of the `VmStack` TL-B scheme
+    // (written independently from this crate) since no emulator-produced
+    // stack BOCs were available at the time.
+    #[test]
+    fn vm_stack_known_hashes() -> anyhow::Result<()> {
+        fn check_hash(stack: VmStack, hash: &str) -> anyhow::Result<()> {
+            let cell = CellBuilder::build_from(&stack)?;
+            assert_eq!(cell.repr_hash().to_string(), hash);
+            assert_eq!(cell.parse::<VmStack>()?, stack);
+            Ok(())
+        }
+
+        check_hash(
+            VmStack::new(vec![
+                StackValue::Int(BigInt::from(1)),
+                StackValue::Null,
+                StackValue::Tuple(vec![StackValue::NaN, StackValue::Int(BigInt::from(-2))]),
+                StackValue::Cell(Cell::empty_cell()),
+            ]),
+            "497fd304835b797ea646921f428749c2e45d681846130f2601e03258c1d25378",
+        )?;
+        check_hash(
+            VmStack::new(vec![
+                StackValue::Int(BigInt::from(1) << 100),
+                StackValue::Tuple(vec![
+                    StackValue::Int(BigInt::from(1)),
+                    StackValue::Int(BigInt::from(2)),
+                    StackValue::Int(BigInt::from(3)),
+                ]),
+                StackValue::Int(BigInt::from(0)),
+            ]),
+            "612e5dbcd819ce7834c689bdce3883d52236c9806d622282a22b2f9f1bd2dc81",
+        )?;
+
+        Ok(())
+    }
+}
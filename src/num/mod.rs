@@ -18,7 +18,11 @@ macro_rules! impl_serde {
             where
                 S: serde::Serializer,
             {
-                self.0.serialize(serializer)
+                if serializer.is_human_readable() {
+                    serializer.collect_str(&self.0)
+                } else {
+                    self.0.serialize(serializer)
+                }
             }
         }
 
@@ -28,30 +32,78 @@ macro_rules! impl_serde {
             where
                 D: serde::Deserializer<'de>,
             {
-                use serde::de::{Error, Unexpected};
+                use serde::de::Error;
 
-                struct Expected;
-
-                impl serde::de::Expected for Expected {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        f.write_str(stringify!($ident))
+                let value = if deserializer.is_human_readable() {
+                    let value = ok!(deserialize_str_or_number(deserializer));
+                    match <$inner>::try_from(value) {
+                        Ok(value) => value,
+                        Err(_) => return Err(out_of_range(value, stringify!($ident))),
                     }
-                }
+                } else {
+                    ok!(<$inner>::deserialize(deserializer))
+                };
 
-                let res = Self::new(ok!(<$inner>::deserialize(deserializer)));
+                let res = Self::new(value);
                 if res.is_valid() {
                     Ok(res)
                 } else {
-                    Err(D::Error::invalid_type(
-                        Unexpected::Other("big number"),
-                        &Expected,
-                    ))
+                    Err(D::Error::custom(format_args!(
+                        "{value} is out of range for {}",
+                        stringify!($ident)
+                    )))
                 }
             }
         }
     };
 }
 
+/// Deserializes an unsigned integer from either a decimal string or a number.
+#[cfg(feature = "serde")]
+fn deserialize_str_or_number<'de, D>(deserializer: D) -> Result<u128, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::{Error, Unexpected, Visitor};
+
+    struct StrOrNumberVisitor;
+
+    impl<'de> Visitor<'de> for StrOrNumberVisitor {
+        type Value = u128;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("an unsigned integer or a string with an unsigned integer")
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            v.parse()
+                .map_err(|e| E::custom(format_args!("invalid number {v:?}: {e}")))
+        }
+
+        fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(v as u128)
+        }
+
+        fn visit_u128<E: Error>(self, v: u128) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+            match u64::try_from(v) {
+                Ok(v) => Ok(v as u128),
+                Err(_) => Err(E::invalid_value(Unexpected::Signed(v), &self)),
+            }
+        }
+    }
+
+    deserializer.deserialize_any(StrOrNumberVisitor)
+}
+
+#[cfg(feature = "serde")]
+fn out_of_range<E: serde::de::Error>(value: u128, ty: &str) -> E {
+    E::custom(format_args!("{value} is out of range for {ty}"))
+}
+
 macro_rules! impl_ops {
     ($ident:ident, $inner:ty) => {
         impl From<$ident> for $inner {
@@ -453,67 +505,7 @@ impl_var_uints! {
 
 impl_serde!(VarUint24, u32);
 impl_serde!(VarUint56, u64);
-
-#[cfg(feature = "serde")]
-impl serde::Serialize for Tokens {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        if serializer.is_human_readable() {
-            serializer.collect_str(&self.0)
-        } else {
-            self.0.serialize(serializer)
-        }
-    }
-}
-
-#[cfg(feature = "serde")]
-impl<'de> serde::Deserialize<'de> for Tokens {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        use serde::de::{Error, Unexpected, Visitor};
-
-        struct Expected;
-
-        impl serde::de::Expected for Expected {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.write_str(stringify!($ident))
-            }
-        }
-
-        struct TokensVisitor;
-
-        impl<'de> Visitor<'de> for TokensVisitor {
-            type Value = u128;
-
-            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-                f.write_str("a string with a number")
-            }
-
-            fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
-                v.parse().map_err(E::custom)
-            }
-        }
-
-        let res = Self::new(ok!(if deserializer.is_human_readable() {
-            deserializer.deserialize_str(TokensVisitor)
-        } else {
-            u128::deserialize(deserializer)
-        }));
-
-        if res.is_valid() {
-            Ok(res)
-        } else {
-            Err(D::Error::invalid_type(
-                Unexpected::Other("big number"),
-                &Expected,
-            ))
-        }
-    }
-}
+impl_serde!(Tokens, u128);
 
 impl Store for VarUint24 {
     fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
@@ -622,6 +614,17 @@ macro_rules! impl_small_uints {
                 Self(value)
             }
 
+            /// Creates a new integer value from a primitive integer.
+            /// Returns `None` if the value doesn't fit into the repr.
+            #[inline]
+            pub const fn new_checked(value: u16) -> Option<Self> {
+                if value <= Self::MAX.0 {
+                    Some(Self(value))
+                } else {
+                    None
+                }
+            }
+
             /// Converts integer into an underlying primitive integer.
             #[inline]
             pub const fn into_inner(self) -> u16 {
@@ -757,11 +760,13 @@ impl SplitDepth {
     pub const BITS: u16 = 5;
 
     /// Creates a new integer value from a primitive integer.
+    ///
+    /// Returns an error if the value is not in range `1..=30`.
     #[inline]
     pub const fn new(value: u8) -> Result<Self, Error> {
         match NonZeroU8::new(value) {
-            Some(value) => Ok(Self(value)),
-            None => Err(Error::IntOverflow),
+            Some(value) if value.get() <= Self::MAX.0.get() => Ok(Self(value)),
+            _ => Err(Error::IntOverflow),
         }
     }
 
@@ -782,6 +787,28 @@ impl SplitDepth {
     }
 }
 
+impl From<SplitDepth> for u8 {
+    #[inline]
+    fn from(value: SplitDepth) -> Self {
+        value.0.get()
+    }
+}
+
+impl TryFrom<u8> for SplitDepth {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl std::fmt::Display for SplitDepth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 impl ExactSize for SplitDepth {
     #[inline]
     fn exact_size(&self) -> CellSliceSize {
@@ -814,7 +841,11 @@ impl serde::Serialize for SplitDepth {
     where
         S: serde::Serializer,
     {
-        self.0.get().serialize(serializer)
+        if serializer.is_human_readable() {
+            serializer.collect_str(&self.0)
+        } else {
+            self.0.get().serialize(serializer)
+        }
     }
 }
 
@@ -824,9 +855,19 @@ impl<'de> serde::Deserialize<'de> for SplitDepth {
     where
         D: serde::Deserializer<'de>,
     {
-        match u8::deserialize(deserializer) {
-            Ok(value) => Self::new(value).map_err(serde::de::Error::custom),
-            Err(e) => Err(e),
+        let value = if deserializer.is_human_readable() {
+            let value = ok!(deserialize_str_or_number(deserializer));
+            match u8::try_from(value) {
+                Ok(value) => value,
+                Err(_) => return Err(out_of_range(value, "SplitDepth")),
+            }
+        } else {
+            ok!(u8::deserialize(deserializer))
+        };
+
+        match Self::new(value) {
+            Ok(value) => Ok(value),
+            Err(_) => Err(out_of_range(value as u128, "SplitDepth")),
         }
     }
 }
@@ -1011,6 +1052,41 @@ mod tests {
         impl_deserialization_tests!(Uint15, 15, 0b11111100110011);
     }
 
+    #[test]
+    fn fixed_len_boundaries() {
+        assert_eq!(Uint15::new_checked(0x7fff), Some(Uint15::MAX));
+        assert_eq!(Uint15::new_checked(0x8000), None);
+        assert_eq!(Uint12::new_checked(0xfff), Some(Uint12::MAX));
+        assert_eq!(Uint12::new_checked(0x1000), None);
+
+        let cell = CellBuilder::build_from(Uint15::MAX).unwrap();
+        assert_eq!(cell.bit_len(), 15);
+        assert_eq!(cell.parse::<Uint15>().unwrap(), Uint15::MAX);
+
+        assert!(CellBuilder::build_from(Uint15::new(0x8000)).is_err());
+    }
+
+    #[test]
+    fn split_depth_boundaries() {
+        assert!(SplitDepth::new(0).is_err());
+        assert_eq!(SplitDepth::new(30).unwrap(), SplitDepth::MAX);
+        assert!(SplitDepth::new(31).is_err());
+        assert_eq!(SplitDepth::MAX.to_string(), "30");
+        assert_eq!(u8::from(SplitDepth::MIN), 1);
+
+        let cell = CellBuilder::build_from(SplitDepth::MAX).unwrap();
+        assert_eq!(cell.bit_len(), 5);
+        assert_eq!(cell.parse::<SplitDepth>().unwrap(), SplitDepth::MAX);
+
+        // Out of range values must not be loaded
+        for value in [0, 31] {
+            let mut builder = CellBuilder::new();
+            builder.store_small_uint(value, SplitDepth::BITS).unwrap();
+            let cell = builder.build().unwrap();
+            assert_eq!(cell.parse::<SplitDepth>(), Err(Error::IntOverflow));
+        }
+    }
+
     #[test]
     fn var_uint24_operations() {
         impl_operation_tests!(VarUint24, check_max_div);
@@ -1055,4 +1131,55 @@ mod tests {
     fn tokens_deserialization() {
         impl_deserialization_tests!(Tokens, 120, 0xabcdef89abcdefdeadbeeffafacafe);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        macro_rules! impl_serde_tests {
+            ($ident:ident, $inner:ty) => {
+                for value in [$ident::ZERO, $ident::new(1), $ident::MAX] {
+                    let inner = <$inner>::from(value);
+
+                    let json = serde_json::to_string(&value).unwrap();
+                    assert_eq!(json, format!("\"{inner}\""));
+                    assert_eq!(serde_json::from_str::<$ident>(&json).unwrap(), value);
+
+                    if let Ok(inner) = u64::try_from(inner as u128) {
+                        let parsed = serde_json::from_str::<$ident>(&inner.to_string()).unwrap();
+                        assert_eq!(parsed, value);
+                    }
+                }
+
+                let too_big = <$inner>::from($ident::MAX) as u128 + 1;
+                let err = serde_json::from_str::<$ident>(&format!("\"{too_big}\"")).unwrap_err();
+                assert!(err.to_string().contains("out of range"), "{err}");
+                if let Ok(too_big) = u64::try_from(too_big) {
+                    let err = serde_json::from_str::<$ident>(&too_big.to_string()).unwrap_err();
+                    assert!(err.to_string().contains("out of range"), "{err}");
+                }
+
+                assert!(serde_json::from_str::<$ident>("-1").is_err());
+                assert!(serde_json::from_str::<$ident>("\"abc\"").is_err());
+            };
+        }
+
+        impl_serde_tests!(VarUint24, u32);
+        impl_serde_tests!(VarUint56, u64);
+        impl_serde_tests!(Tokens, u128);
+        impl_serde_tests!(Uint9, u16);
+        impl_serde_tests!(Uint12, u16);
+        impl_serde_tests!(Uint15, u16);
+
+        for value in [SplitDepth::MIN, SplitDepth::MAX] {
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(json, format!("\"{}\"", value.into_bit_len()));
+            assert_eq!(serde_json::from_str::<SplitDepth>(&json).unwrap(), value);
+
+            let number = value.into_bit_len().to_string();
+            assert_eq!(serde_json::from_str::<SplitDepth>(&number).unwrap(), value);
+        }
+        assert!(serde_json::from_str::<SplitDepth>("0").is_err());
+        assert!(serde_json::from_str::<SplitDepth>("\"31\"").is_err());
+        assert!(serde_json::from_str::<SplitDepth>("256").is_err());
+    }
 }
@@ -309,6 +309,30 @@ impl<'de> serde::Deserialize<'de> for VarUint248 {
                 f.write_str("a formatted 248-bit integer")
             }
 
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(VarUint248::new(v as u128))
+            }
+
+            fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(VarUint248::new(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match u64::try_from(v) {
+                    Ok(v) => self.visit_u64(v),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Signed(v), &self)),
+                }
+            }
+
             fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
             where
                 E: Error,
@@ -327,10 +351,18 @@ impl<'de> serde::Deserialize<'de> for VarUint248 {
             }
         }
 
-        if deserializer.is_human_readable() {
-            deserializer.deserialize_str(VarUint248Visitor)
+        let res = ok!(if deserializer.is_human_readable() {
+            deserializer.deserialize_any(VarUint248Visitor)
         } else {
             <_>::deserialize(deserializer).map(Self)
+        });
+
+        if res.is_valid() {
+            Ok(res)
+        } else {
+            Err(D::Error::custom(format_args!(
+                "{res} is out of range for VarUint248"
+            )))
         }
     }
 }
@@ -1453,4 +1485,30 @@ mod tests {
     fn remainder_by_zero() {
         _ = VarUint248::ONE % 0;
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        for value in [VarUint248::ZERO, VarUint248::ONE, VarUint248::MAX] {
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(json, format!("\"{value}\""));
+            assert_eq!(serde_json::from_str::<VarUint248>(&json).unwrap(), value);
+        }
+
+        assert_eq!(
+            serde_json::from_str::<VarUint248>("12345").unwrap(),
+            VarUint248::new(12345)
+        );
+        assert_eq!(
+            serde_json::from_str::<VarUint248>(&u64::MAX.to_string()).unwrap(),
+            VarUint248::new(u64::MAX as u128)
+        );
+
+        // 2^248
+        let too_big =
+            "\"452312848583266388373324160190187140051835877600158453279131187530910662656\"";
+        let err = serde_json::from_str::<VarUint248>(too_big).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+        assert!(serde_json::from_str::<VarUint248>("-1").is_err());
+    }
 }
@@ -4,6 +4,81 @@ use std::mem::MaybeUninit;
 
 use crate::error::Error;
 
+/// Records the number of cells and their total bit length
+/// as `cells` and `bits` span fields.
+#[cfg(feature = "tracing")]
+pub(crate) fn record_cells<'a, I>(span: &tracing::Span, cells: I)
+where
+    I: IntoIterator<Item = &'a crate::cell::DynCell>,
+{
+    let mut count = 0u64;
+    let mut bits = 0u64;
+    for cell in cells {
+        count += 1;
+        bits += cell.bit_len() as u64;
+    }
+    span.record("cells", count);
+    span.record("bits", bits);
+}
+
+/// Cell context wrapper which counts all finalized cells.
+#[cfg(feature = "tracing")]
+pub(crate) struct TracedContext<'a> {
+    inner: &'a mut dyn crate::cell::CellContext,
+    cells: u64,
+    bits: u64,
+}
+
+#[cfg(feature = "tracing")]
+impl<'a> TracedContext<'a> {
+    pub fn new(inner: &'a mut dyn crate::cell::CellContext) -> Self {
+        Self {
+            inner,
+            cells: 0,
+            bits: 0,
+        }
+    }
+
+    /// Records the number of finalized cells and their total bit length
+    /// as `cells` and `bits` span fields.
+    pub fn record(&self, span: &tracing::Span) {
+        span.record("cells", self.cells);
+        span.record("bits", self.bits);
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl crate::cell::CellContext for TracedContext<'_> {
+    fn finalize_cell(
+        &mut self,
+        cell: crate::cell::CellParts<'_>,
+    ) -> Result<crate::cell::Cell, Error> {
+        self.cells += 1;
+        self.bits += cell.bit_len as u64;
+        self.inner.finalize_cell(cell)
+    }
+
+    fn load_cell(
+        &mut self,
+        cell: crate::cell::Cell,
+        mode: crate::cell::LoadMode,
+    ) -> Result<crate::cell::Cell, Error> {
+        self.inner.load_cell(cell, mode)
+    }
+
+    fn load_dyn_cell<'c>(
+        &mut self,
+        cell: &'c crate::cell::DynCell,
+        mode: crate::cell::LoadMode,
+    ) -> Result<&'c crate::cell::DynCell, Error> {
+        self.inner.load_dyn_cell(cell, mode)
+    }
+
+    fn limits(&self) -> crate::cell::CellSizeLimits {
+        self.inner.limits()
+    }
+}
+
 /// Brings [unlikely](core::intrinsics::unlikely) to stable rust.
 #[inline(always)]
 pub(crate) const fn unlikely(b: bool) -> bool {
@@ -55,26 +130,71 @@ pub(crate) unsafe fn read_be_u64_fast(data_ptr: *const u8, size: usize) -> u64 {
     }
 }
 
+#[cfg(any(feature = "base64", test))]
+mod base64_engines {
+    use base64::alphabet;
+    use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+    use base64::engine::DecodePaddingMode;
+
+    const CONFIG: GeneralPurposeConfig =
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+
+    pub const STANDARD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, CONFIG);
+
+    pub const URL_SAFE: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, CONFIG);
+
+    pub const URL_SAFE_NO_PAD: GeneralPurpose =
+        GeneralPurpose::new(&alphabet::URL_SAFE, CONFIG.with_encode_padding(false));
+}
+
+/// Encodes bytes as a padded `base64` string using the standard alphabet.
 #[cfg(any(feature = "base64", test))]
 #[inline]
-pub(crate) fn encode_base64<T: AsRef<[u8]>>(data: T) -> String {
+pub fn encode_base64<T: AsRef<[u8]>>(data: T) -> String {
     use base64::Engine;
     fn encode_base64_impl(data: &[u8]) -> String {
-        base64::engine::general_purpose::STANDARD.encode(data)
+        base64_engines::STANDARD.encode(data)
     }
     encode_base64_impl(data.as_ref())
 }
 
+/// Decodes a `base64` string using the standard alphabet.
+///
+/// Padding is optional.
 #[cfg(any(feature = "base64", test))]
 #[inline]
-pub(crate) fn decode_base64<T: AsRef<[u8]>>(data: T) -> Result<Vec<u8>, base64::DecodeError> {
+pub fn decode_base64<T: AsRef<[u8]>>(data: T) -> Result<Vec<u8>, base64::DecodeError> {
     use base64::Engine;
     fn decode_base64_impl(data: &[u8]) -> Result<Vec<u8>, base64::DecodeError> {
-        base64::engine::general_purpose::STANDARD.decode(data)
+        base64_engines::STANDARD.decode(data)
     }
     decode_base64_impl(data.as_ref())
 }
 
+/// Encodes bytes as an unpadded `base64` string using the URL-safe alphabet.
+#[cfg(any(feature = "base64", test))]
+#[inline]
+pub fn encode_base64_url<T: AsRef<[u8]>>(data: T) -> String {
+    use base64::Engine;
+    fn encode_base64_url_impl(data: &[u8]) -> String {
+        base64_engines::URL_SAFE_NO_PAD.encode(data)
+    }
+    encode_base64_url_impl(data.as_ref())
+}
+
+/// Decodes a `base64` string using the URL-safe alphabet.
+///
+/// Padding is optional.
+#[cfg(any(feature = "base64", test))]
+#[inline]
+pub fn decode_base64_url_nopad<T: AsRef<[u8]>>(data: T) -> Result<Vec<u8>, base64::DecodeError> {
+    use base64::Engine;
+    fn decode_base64_url_nopad_impl(data: &[u8]) -> Result<Vec<u8>, base64::DecodeError> {
+        base64_engines::URL_SAFE.decode(data)
+    }
+    decode_base64_url_nopad_impl(data.as_ref())
+}
+
 #[cfg(any(feature = "base64", test))]
 #[allow(unused)]
 #[inline]
@@ -87,7 +207,7 @@ pub(crate) fn decode_base64_slice<T: AsRef<[u8]>>(
         data: &[u8],
         target: &mut [u8],
     ) -> Result<(), base64::DecodeSliceError> {
-        base64::engine::general_purpose::STANDARD
+        base64_engines::STANDARD
             .decode_slice(data, target)
             .map(|_| ())
     }
@@ -404,6 +524,48 @@ pub(crate) fn debug_struct_field2_finish(
 mod tests {
     use super::*;
 
+    #[test]
+    fn base64_standard() {
+        const VECTORS: &[(&[u8], &str, &str)] = &[
+            (b"", "", ""),
+            (b"f", "Zg==", "Zg"),
+            (b"fo", "Zm8=", "Zm8"),
+            (b"foo", "Zm9v", "Zm9v"),
+            (b"foob", "Zm9vYg==", "Zm9vYg"),
+            (&[0xfb, 0xff], "+/8=", "+/8"),
+        ];
+
+        for (bytes, padded, unpadded) in VECTORS {
+            assert_eq!(encode_base64(bytes), *padded);
+            assert_eq!(decode_base64(padded).unwrap(), *bytes);
+            assert_eq!(decode_base64(unpadded).unwrap(), *bytes);
+        }
+
+        assert!(decode_base64("-_8").is_err());
+        assert!(decode_base64("Zg!").is_err());
+    }
+
+    #[test]
+    fn base64_url() {
+        const VECTORS: &[(&[u8], &str, &str)] = &[
+            (b"", "", ""),
+            (b"f", "Zg", "Zg=="),
+            (b"fo", "Zm8", "Zm8="),
+            (b"foo", "Zm9v", "Zm9v"),
+            (b"foob", "Zm9vYg", "Zm9vYg=="),
+            (&[0xfb, 0xff], "-_8", "-_8="),
+        ];
+
+        for (bytes, unpadded, padded) in VECTORS {
+            assert_eq!(encode_base64_url(bytes), *unpadded);
+            assert_eq!(decode_base64_url_nopad(unpadded).unwrap(), *bytes);
+            assert_eq!(decode_base64_url_nopad(padded).unwrap(), *bytes);
+        }
+
+        assert!(decode_base64_url_nopad("+/8").is_err());
+        assert!(decode_base64_url_nopad("Zg!").is_err());
+    }
+
     #[test]
     fn parse_bitstring_from_hex_str() {
         let (data, bit_len) = Bitstring::from_hex_str("").unwrap();